@@ -0,0 +1,541 @@
+//! Accessibility tree snapshots for agents
+//!
+//! Chrome's full accessibility tree (CDP `Accessibility.getFullAXTree`) is
+//! pruned into a compact outline of roles, accessible names, values and
+//! states: ignored nodes and unnamed `generic`/`none` wrappers are collapsed
+//! into their children and static text repeating its parent's name is dropped.
+//! Nodes are identified by their backend DOM node id, which stays the same for
+//! an element across snapshots of the same document, so an agent can pick a
+//! node from one snapshot and act on it with [`click_node`]/[`fill_node`].
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Roles an agent can act on
+const INTERACTIVE_ROLES: &[&str] = &[
+    "button",
+    "checkbox",
+    "combobox",
+    "link",
+    "listbox",
+    "menuitem",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "option",
+    "radio",
+    "searchbox",
+    "slider",
+    "spinbutton",
+    "switch",
+    "tab",
+    "textbox",
+    "treeitem",
+];
+
+/// Structural roles that carry nothing on their own when unnamed
+const WRAPPER_ROLES: &[&str] =
+    &["generic", "none", "presentation", "InlineTextBox", "LineBreak", "LayoutTableCell"];
+
+/// Accessible names and values longer than this are cut in the text outline
+const MAX_TEXT_CHARS: usize = 120;
+
+/// Options for [`AccessibilityTree::from_cdp_nodes`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilityOptions {
+    /// Keep only interactive nodes (buttons, links, form controls) as a flat
+    /// list
+    #[serde(default)]
+    pub interactive_only: bool,
+}
+
+/// Node of a pruned accessibility tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxNode {
+    /// Backend DOM node id, used to target the node in [`click_node`] and
+    /// [`fill_node`]; `None` for nodes without a DOM element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// States such as `focused`, `disabled`, `checked`, `collapsed`, `level=2`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<AxNode>,
+}
+
+impl AxNode {
+    /// Whether an agent can click, type into or otherwise act on the node
+    pub fn is_interactive(&self) -> bool {
+        INTERACTIVE_ROLES.contains(&self.role.as_str())
+    }
+}
+
+/// Pruned accessibility tree of a page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityTree {
+    pub nodes: Vec<AxNode>,
+}
+
+/// Node as returned by CDP `Accessibility.getFullAXTree`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdpAxNode {
+    pub node_id: String,
+    #[serde(default)]
+    pub ignored: bool,
+    pub role: Option<CdpAxValue>,
+    pub name: Option<CdpAxValue>,
+    pub description: Option<CdpAxValue>,
+    pub value: Option<CdpAxValue>,
+    #[serde(default)]
+    pub properties: Vec<CdpAxProperty>,
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub child_ids: Vec<String>,
+    #[serde(rename = "backendDOMNodeId")]
+    pub backend_dom_node_id: Option<i64>,
+}
+
+/// CDP `AXValue`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CdpAxValue {
+    pub value: Option<serde_json::Value>,
+}
+
+/// CDP `AXProperty`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CdpAxProperty {
+    pub name: String,
+    pub value: CdpAxValue,
+}
+
+impl CdpAxValue {
+    /// Value as display text; `None` for empty strings
+    fn text(&self) -> Option<String> {
+        let text = match self.value.as_ref()? {
+            serde_json::Value::String(s) => s.split_whitespace().collect::<Vec<_>>().join(" "),
+            serde_json::Value::Null => return None,
+            other => other.to_string(),
+        };
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+impl AccessibilityTree {
+    /// Prune the flat node list of `Accessibility.getFullAXTree`
+    pub fn from_cdp_nodes(nodes: Vec<CdpAxNode>, options: &AccessibilityOptions) -> Self {
+        let by_id: HashMap<&str, &CdpAxNode> =
+            nodes.iter().map(|n| (n.node_id.as_str(), n)).collect();
+        let mut pruned: Vec<AxNode> = nodes
+            .iter()
+            .filter(|n| n.parent_id.as_deref().map_or(true, |p| !by_id.contains_key(p)))
+            .flat_map(|n| prune(&by_id, &n.node_id, None, 0))
+            .collect();
+        if options.interactive_only {
+            let mut flat = Vec::new();
+            collect_interactive(pruned, &mut flat);
+            pruned = flat;
+        }
+        Self { nodes: pruned }
+    }
+
+    /// Find a node by its id
+    pub fn find(&self, id: i64) -> Option<&AxNode> {
+        fn find_in(nodes: &[AxNode], id: i64) -> Option<&AxNode> {
+            nodes.iter().find_map(|n| {
+                if n.id == Some(id) {
+                    Some(n)
+                } else {
+                    find_in(&n.children, id)
+                }
+            })
+        }
+        find_in(&self.nodes, id)
+    }
+
+    /// Number of nodes in the tree
+    pub fn len(&self) -> usize {
+        fn count(nodes: &[AxNode]) -> usize {
+            nodes.iter().map(|n| 1 + count(&n.children)).sum()
+        }
+        count(&self.nodes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Indented outline, one node per line: `[42] button "Send" (disabled)`
+    pub fn to_text(&self) -> String {
+        fn write_nodes(out: &mut String, nodes: &[AxNode], depth: usize) {
+            for node in nodes {
+                out.push_str(&"  ".repeat(depth));
+                if let Some(id) = node.id {
+                    let _ = write!(out, "[{}] ", id);
+                }
+                out.push_str(&node.role);
+                if let Some(name) = &node.name {
+                    let _ = write!(out, " {:?}", truncate(name));
+                }
+                if let Some(value) = &node.value {
+                    let _ = write!(out, " = {:?}", truncate(value));
+                }
+                if !node.states.is_empty() {
+                    let _ = write!(out, " ({})", node.states.join(", "));
+                }
+                out.push('\n');
+                write_nodes(out, &node.children, depth + 1);
+            }
+        }
+        let mut out = String::new();
+        write_nodes(&mut out, &self.nodes, 0);
+        out
+    }
+}
+
+/// Prune the subtree rooted at `id`; collapsed nodes return their children
+fn prune(
+    by_id: &HashMap<&str, &CdpAxNode>,
+    id: &str,
+    parent_name: Option<&str>,
+    depth: usize,
+) -> Vec<AxNode> {
+    // Guard against malformed trees with cycles
+    const MAX_DEPTH: usize = 256;
+    let Some(raw) = by_id.get(id) else {
+        return Vec::new();
+    };
+    if depth > MAX_DEPTH {
+        return Vec::new();
+    }
+
+    let role = raw.role.as_ref().and_then(CdpAxValue::text).unwrap_or_default();
+    let name = raw.name.as_ref().and_then(CdpAxValue::text);
+    let collapsed = raw.ignored
+        || role.is_empty()
+        || (WRAPPER_ROLES.contains(&role.as_str()) && name.is_none());
+    // Text is compared with the nearest name, looking through collapsed wrappers
+    let child_parent_name = if collapsed {
+        parent_name
+    } else {
+        name.as_deref()
+    };
+    let children: Vec<AxNode> = raw
+        .child_ids
+        .iter()
+        .flat_map(|child| prune(by_id, child, child_parent_name, depth + 1))
+        .collect();
+
+    if collapsed {
+        return children;
+    }
+    if role == "StaticText" {
+        // Text already exposed as the name of its link, button or heading
+        return match name {
+            Some(text) if parent_name.map_or(true, |p| !p.contains(text.as_str())) => {
+                vec![AxNode {
+                    id: None,
+                    role: "text".to_string(),
+                    name: Some(text),
+                    value: None,
+                    description: None,
+                    states: Vec::new(),
+                    children: Vec::new(),
+                }]
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    let states = states(&raw.properties);
+    let value = raw.value.as_ref().and_then(CdpAxValue::text);
+    let node = AxNode {
+        id: raw.backend_dom_node_id,
+        role,
+        name,
+        value,
+        description: raw.description.as_ref().and_then(CdpAxValue::text),
+        states,
+        children,
+    };
+
+    // Unnamed, childless containers (empty paragraphs, spacer images) add nothing
+    if node.name.is_none()
+        && node.value.is_none()
+        && node.children.is_empty()
+        && !node.is_interactive()
+    {
+        return Vec::new();
+    }
+    vec![node]
+}
+
+/// Agent-relevant states from CDP properties
+fn states(properties: &[CdpAxProperty]) -> Vec<String> {
+    let mut states = Vec::new();
+    for property in properties {
+        let value = property.value.value.as_ref();
+        let is_true = matches!(value, Some(serde_json::Value::Bool(true)))
+            || value.and_then(|v| v.as_str()) == Some("true");
+        let state = match (property.name.as_str(), value) {
+            ("checked" | "pressed", Some(v)) if v.as_str() == Some("mixed") => {
+                Some("mixed".to_string())
+            }
+            ("checked", _) => Some(if is_true { "checked" } else { "unchecked" }.to_string()),
+            ("pressed", _) if is_true => Some("pressed".to_string()),
+            ("expanded", _) => Some(if is_true { "expanded" } else { "collapsed" }.to_string()),
+            ("level", Some(v)) => Some(format!("level={}", v)),
+            ("invalid", Some(v)) if v.as_str().is_some_and(|s| s != "false") => {
+                Some("invalid".to_string())
+            }
+            ("hasPopup", Some(v)) if v.as_str().is_some_and(|s| s != "false") => {
+                Some(format!("haspopup={}", v.as_str().unwrap_or_default()))
+            }
+            (
+                "disabled" | "focused" | "selected" | "required" | "readonly" | "modal"
+                | "multiselectable",
+                _,
+            ) if is_true => Some(property.name.clone()),
+            _ => None,
+        };
+        states.extend(state);
+    }
+    states
+}
+
+/// Flatten `nodes` into their interactive descendants
+fn collect_interactive(nodes: Vec<AxNode>, out: &mut Vec<AxNode>) {
+    for mut node in nodes {
+        let children = std::mem::take(&mut node.children);
+        if node.is_interactive() {
+            // Options stay nested under their listbox or combobox
+            let (options, rest): (Vec<_>, Vec<_>) =
+                children.into_iter().partition(|c| c.role == "option" || c.role == "menuitem");
+            node.children = options;
+            out.push(node);
+            collect_interactive(rest, out);
+        } else {
+            collect_interactive(children, out);
+        }
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Snapshot the accessibility tree of a page
+#[cfg(feature = "browser-automation")]
+pub async fn snapshot(
+    page: &Page,
+    options: &AccessibilityOptions,
+) -> Result<AccessibilityTree, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::accessibility::GetFullAxTreeParams;
+
+    let response = page.execute(GetFullAxTreeParams::default()).await?;
+    let nodes: Vec<CdpAxNode> = serde_json::from_value(serde_json::to_value(&response.nodes)?)?;
+    Ok(AccessibilityTree::from_cdp_nodes(nodes, options))
+}
+
+/// Scroll the node with backend id `node_id` into view and click its center
+#[cfg(feature = "browser-automation")]
+pub async fn click_node(
+    page: &Page,
+    node_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::dom::{
+        BackendNodeId, GetContentQuadsParams, ScrollIntoViewIfNeededParams,
+    };
+    use chromiumoxide::layout::Point;
+
+    let backend_id = BackendNodeId::new(node_id);
+    page.execute(ScrollIntoViewIfNeededParams::builder().backend_node_id(backend_id).build())
+        .await?;
+    let quads =
+        page.execute(GetContentQuadsParams::builder().backend_node_id(backend_id).build()).await?;
+    let center = quads
+        .quads
+        .iter()
+        .map(|q| q.inner())
+        .filter(|q| q.len() == 8)
+        .map(|q| Point {
+            x: (q[0] + q[2] + q[4] + q[6]) / 4.0,
+            y: (q[1] + q[3] + q[5] + q[7]) / 4.0,
+        })
+        .next()
+        .ok_or_else(|| format!("Node {} is not visible", node_id))?;
+    page.click(center).await?;
+    Ok(())
+}
+
+/// Set the value of the form control with backend id `node_id`, firing
+/// `input` and `change` events; checkboxes and radios take "true"/"false",
+/// selects an option value or label
+#[cfg(feature = "browser-automation")]
+pub async fn fill_node(
+    page: &Page,
+    node_id: i64,
+    value: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    const FILL_FN: &str = r#"function(value) {
+        this.scrollIntoView({block: 'center'});
+        this.focus();
+        if (this instanceof HTMLSelectElement) {
+            const option = Array.from(this.options)
+                .find(o => o.value === value || o.label.trim() === value);
+            if (!option) throw new Error('No option ' + value);
+            this.value = option.value;
+        } else if (this instanceof HTMLInputElement && ['checkbox', 'radio'].includes(this.type)) {
+            this.checked = !['false', '0', 'off', ''].includes(value.toLowerCase());
+        } else if (this instanceof HTMLInputElement || this instanceof HTMLTextAreaElement) {
+            // The native setter keeps framework-controlled inputs in sync
+            const proto = Object.getPrototypeOf(this);
+            Object.getOwnPropertyDescriptor(proto, 'value').set.call(this, value);
+        } else if (this.isContentEditable) {
+            this.textContent = value;
+        } else {
+            throw new Error('Not a form field');
+        }
+        this.dispatchEvent(new Event('input', {bubbles: true}));
+        this.dispatchEvent(new Event('change', {bubbles: true}));
+    }"#;
+
+    call_on_node(page, node_id, FILL_FN, vec![serde_json::json!(value)]).await?;
+    Ok(())
+}
+
+/// Call a JavaScript function with the DOM node `node_id` as `this` and
+/// return its result by value
+#[cfg(feature = "browser-automation")]
+pub async fn call_on_node(
+    page: &Page,
+    node_id: i64,
+    function: &str,
+    args: Vec<serde_json::Value>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::dom::{BackendNodeId, ResolveNodeParams};
+    use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+
+    let resolved = page
+        .execute(ResolveNodeParams::builder().backend_node_id(BackendNodeId::new(node_id)).build())
+        .await?;
+    let object_id = resolved
+        .object
+        .object_id
+        .clone()
+        .ok_or_else(|| format!("Node {} cannot be resolved", node_id))?;
+    let call = CallFunctionOnParams::builder()
+        .function_declaration(function)
+        .object_id(object_id)
+        .arguments(args.into_iter().map(|arg| CallArgument::builder().value(arg).build()))
+        .return_by_value(true)
+        .build()?;
+    let response = page.execute(call).await?;
+    if let Some(exception) = &response.exception_details {
+        let message = exception
+            .exception
+            .as_ref()
+            .and_then(|e| e.description.clone())
+            .unwrap_or_else(|| exception.text.clone());
+        return Err(format!("Call on node {} failed: {}", node_id, message).into());
+    }
+    Ok(response.result.result.value.clone().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cdp_nodes() -> Vec<CdpAxNode> {
+        let text = |v: &str| json!({"type": "string", "value": v});
+        serde_json::from_value(json!([
+            {"nodeId": "1", "ignored": false, "role": {"type": "role", "value": "RootWebArea"},
+             "name": text("Sign in"), "childIds": ["2", "9"], "backendDOMNodeId": 1},
+            {"nodeId": "2", "ignored": false, "role": {"type": "role", "value": "generic"},
+             "name": text(""), "parentId": "1", "childIds": ["3", "5", "6", "7", "8"],
+             "backendDOMNodeId": 10},
+            {"nodeId": "3", "ignored": false, "role": {"type": "role", "value": "heading"},
+             "name": text("Welcome back"), "parentId": "2", "childIds": ["4"],
+             "properties": [{"name": "level", "value": {"type": "integer", "value": 1}}],
+             "backendDOMNodeId": 11},
+            {"nodeId": "4", "ignored": false, "role": {"type": "role", "value": "StaticText"},
+             "name": text("Welcome back"), "parentId": "3", "childIds": [],
+             "backendDOMNodeId": 12},
+            {"nodeId": "5", "ignored": false, "role": {"type": "role", "value": "textbox"},
+             "name": text("Email"), "value": text("ada@example.com"), "parentId": "2",
+             "properties": [
+                {"name": "focusable", "value": {"type": "booleanOrUndefined", "value": true}},
+                {"name": "focused", "value": {"type": "booleanOrUndefined", "value": true}},
+                {"name": "required", "value": {"type": "boolean", "value": true}}],
+             "backendDOMNodeId": 13},
+            {"nodeId": "6", "ignored": false, "role": {"type": "role", "value": "checkbox"},
+             "name": text("Remember me"), "parentId": "2",
+             "properties": [{"name": "checked", "value": {"type": "tristate", "value": "false"}}],
+             "backendDOMNodeId": 14},
+            {"nodeId": "7", "ignored": false, "role": {"type": "role", "value": "button"},
+             "name": text("Sign in"), "parentId": "2",
+             "properties": [{"name": "disabled", "value": {"type": "boolean", "value": true}}],
+             "backendDOMNodeId": 15},
+            {"nodeId": "8", "ignored": true, "role": {"type": "role", "value": "image"},
+             "parentId": "2", "backendDOMNodeId": 16},
+            {"nodeId": "9", "ignored": false, "role": {"type": "role", "value": "paragraph"},
+             "parentId": "1", "childIds": ["10"], "backendDOMNodeId": 17},
+            {"nodeId": "10", "ignored": false, "role": {"type": "role", "value": "StaticText"},
+             "name": text("Forgot   your password?"), "parentId": "9", "backendDOMNodeId": 18}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prune_cdp_tree() {
+        let tree = AccessibilityTree::from_cdp_nodes(cdp_nodes(), &AccessibilityOptions::default());
+
+        // The unnamed generic wrapper and the ignored image are gone
+        assert_eq!(tree.nodes.len(), 1);
+        let root = &tree.nodes[0];
+        let roles: Vec<&str> = root.children.iter().map(|n| n.role.as_str()).collect();
+        assert_eq!(roles, vec!["heading", "textbox", "checkbox", "button", "paragraph"]);
+
+        // Text repeating the heading's name is dropped, other text is kept
+        assert!(root.children[0].children.is_empty());
+        assert_eq!(root.children[4].children[0].name.as_deref(), Some("Forgot your password?"));
+
+        let email = tree.find(13).unwrap();
+        assert_eq!(email.value.as_deref(), Some("ada@example.com"));
+        assert_eq!(email.states, vec!["focused", "required"]);
+        assert_eq!(tree.find(14).unwrap().states, vec!["unchecked"]);
+        assert_eq!(tree.len(), 7);
+
+        let text = tree.to_text();
+        assert!(text.starts_with(
+            "[1] RootWebArea \"Sign in\"\n  [11] heading \"Welcome back\" (level=1)\n"
+        ));
+        assert!(
+            text.contains("  [13] textbox \"Email\" = \"ada@example.com\" (focused, required)\n")
+        );
+        assert!(text.contains("  [15] button \"Sign in\" (disabled)\n"));
+        assert!(text.contains("    text \"Forgot your password?\"\n"));
+    }
+
+    #[test]
+    fn test_interactive_only() {
+        let options = AccessibilityOptions { interactive_only: true };
+        let tree = AccessibilityTree::from_cdp_nodes(cdp_nodes(), &options);
+        let ids: Vec<Option<i64>> = tree.nodes.iter().map(|n| n.id).collect();
+        assert_eq!(ids, vec![Some(13), Some(14), Some(15)]);
+        assert!(tree.nodes.iter().all(AxNode::is_interactive));
+    }
+}
@@ -8,7 +8,8 @@
 
 use regex::Regex;
 use scraper::{Html, Selector};
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 #[cfg(feature = "onnx-integration")]
 use std::fs;
@@ -47,6 +48,11 @@ pub struct NERModel {
 
 static NER_MODEL: OnceLock<Option<NERModel>> = OnceLock::new();
 
+/// Per-language models from `NER_MODEL_PATH_<LANG>` (e.g. `NER_MODEL_PATH_DE`),
+/// keyed by primary language subtag and loaded on first use
+static LANGUAGE_NER_MODELS: OnceLock<Mutex<HashMap<String, Option<Arc<NERModel>>>>> =
+    OnceLock::new();
+
 impl NERModel {
     /// Load NER model from ONNX file
     ///
@@ -55,7 +61,20 @@ impl NERModel {
     /// - Support standard NER model architectures (BERT, DistilBERT)
     /// - Fallback to regex if ONNX feature is disabled or model fails to load
     pub fn load(model_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_for_language(model_path, None)
+    }
+
+    /// Load a NER model for one language.
+    ///
+    /// The tokenizer comes from `NER_TOKENIZER_PATH_<LANG>` when set, falling
+    /// back to `NER_TOKENIZER_PATH`.
+    pub fn load_for_language(
+        model_path: &str,
+        language: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         tracing::info!("Attempting to load NER model from: {}", model_path);
+        #[cfg(not(feature = "onnx-integration"))]
+        let _ = language;
 
         // Check if file exists first
         if !std::path::Path::new(model_path).exists() {
@@ -65,7 +84,7 @@ impl NERModel {
 
         #[cfg(feature = "onnx-integration")]
         {
-            match Self::load_onnx_model(model_path) {
+            match Self::load_onnx_model(model_path, language) {
                 Ok(model) => {
                     tracing::info!("Successfully loaded ONNX NER model");
                     Ok(model)
@@ -88,7 +107,10 @@ impl NERModel {
     }
 
     #[cfg(feature = "onnx-integration")]
-    fn load_onnx_model(model_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    fn load_onnx_model(
+        model_path: &str,
+        language: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         // Load ONNX model using tract
         // Best practice: optimize the model for better performance
         let model =
@@ -96,7 +118,7 @@ impl NERModel {
 
         tracing::debug!("ONNX model loaded and optimized successfully");
 
-        let tokenizer = match Self::load_tokenizer() {
+        let tokenizer = match Self::load_tokenizer(language) {
             Ok(tokenizer) => Some(tokenizer),
             Err(err) => {
                 tracing::warn!("NER tokenizer not available ({}), falling back to regex", err);
@@ -116,10 +138,14 @@ impl NERModel {
     }
 
     #[cfg(feature = "onnx-integration")]
-    fn load_tokenizer() -> Result<Tokenizer, Box<dyn std::error::Error>> {
+    fn load_tokenizer(language: Option<&str>) -> Result<Tokenizer, Box<dyn std::error::Error>> {
         #[allow(clippy::disallowed_methods)]
-        let path = std::env::var("NER_TOKENIZER_PATH")
-            .map_err(|_| "NER_TOKENIZER_PATH not set in environment")?;
+        let path = language
+            .and_then(|lang| {
+                std::env::var(format!("NER_TOKENIZER_PATH_{}", lang.to_ascii_uppercase())).ok()
+            })
+            .or_else(|| std::env::var("NER_TOKENIZER_PATH").ok())
+            .ok_or("NER_TOKENIZER_PATH not set in environment")?;
 
         let tokenizer_path = Path::new(&path);
         if !tokenizer_path.exists() {
@@ -596,13 +622,60 @@ pub fn annotate_html(html: &str) -> Result<Vec<String>, Box<dyn std::error::Erro
         .map(|b| b.text().collect::<String>())
         .unwrap_or_default();
 
-    // Get entities using ML model if available, otherwise regex
-    let entities = get_entities(&body_text);
+    // Pick the NER model for the declared language, or the detected one
+    let html_selector = Selector::parse("html").unwrap();
+    let declared = document
+        .select(&html_selector)
+        .next()
+        .and_then(|html| html.value().attr("lang"))
+        .and_then(crate::language::normalize_tag);
+    let entities = extract_entities(&body_text, declared.as_deref());
 
     // Convert to strings for backward compatibility
     Ok(entities.into_iter().map(|e| e.text).collect())
 }
 
+/// Extract entities with the NER model for the text's language.
+///
+/// Without a `language`, it is detected from the text. Languages with a
+/// `NER_MODEL_PATH_<LANG>` model use it; everything else uses the default model
+/// (`NER_MODEL_PATH`) or the regex fallback.
+pub fn extract_entities(text: &str, language: Option<&str>) -> Vec<Entity> {
+    let language = match language {
+        Some(language) => Some(crate::language::primary_subtag(language)),
+        None => crate::language::detect_language(text).filter(|d| d.reliable).map(|d| d.code),
+    };
+    match language.and_then(|language| language_model(&language)) {
+        Some(model) => model.extract_entities(text),
+        None => get_entities(text),
+    }
+}
+
+/// NER model configured for a language, loading it on first use
+fn language_model(language: &str) -> Option<Arc<NERModel>> {
+    if language.is_empty() {
+        return None;
+    }
+    let models = LANGUAGE_NER_MODELS.get_or_init(Default::default);
+    let mut models = models.lock().unwrap_or_else(|e| e.into_inner());
+    models
+        .entry(language.to_string())
+        .or_insert_with(|| {
+            #[allow(clippy::disallowed_methods)]
+            let path =
+                std::env::var(format!("NER_MODEL_PATH_{}", language.to_ascii_uppercase())).ok()?;
+            tracing::info!("Initializing {} NER model from {}", language, path);
+            match NERModel::load_for_language(&path, Some(language)) {
+                Ok(model) => Some(Arc::new(model)),
+                Err(e) => {
+                    tracing::error!("Failed to load {} NER model: {}", language, e);
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
 /// Get entities using the initialized model or fallback
 fn get_entities(text: &str) -> Vec<Entity> {
     if let Some(Some(model)) = NER_MODEL.get() {
@@ -4,7 +4,7 @@ use crate::kg_integration::insert_snapshot_to_kg;
 use crate::models::SemanticSnapshot;
 use axum::http::HeaderMap;
 use axum::{
-    extract::{ConnectInfo, State},
+    extract::{ConnectInfo, Path, State},
     http::StatusCode,
     routing::{get, post},
     Json, Router,
@@ -21,12 +21,21 @@ use tokio::sync::Mutex;
 pub struct AppState {
     pub kg: Arc<Mutex<crate::kg::KnowledgeGraph>>,
     pub rate_limits: Arc<Mutex<HashMap<String, (u32, Instant)>>>,
+    /// Crawl jobs started through `/crawl`, by job id
+    pub crawls: Arc<Mutex<HashMap<String, crate::crawler::CrawlHandle>>>,
 }
 
 /// Request to parse HTML
 #[derive(serde::Deserialize)]
 pub struct ParseRequest {
     pub html: String,
+    /// URL the HTML was fetched from, used to resolve relative RDFa, microdata and link IRIs;
+    /// outgoing links are inserted into the KG as edges from this page
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Also insert extracted tables into the KG as CSVW rows (requires `base_url`)
+    #[serde(default)]
+    pub insert_tables: bool,
 }
 
 /// Request for LangGraph workflow execution
@@ -41,6 +50,16 @@ pub struct LangGraphRequest {
 pub struct ParseResponse {
     pub title: Option<String>,
     pub entities: Vec<String>,
+    /// RDFa triples extracted from the document (also inserted into the KG)
+    pub rdfa: Vec<crate::kg::Triple>,
+    /// Data tables with typed rows
+    pub tables: Vec<crate::tables::Table>,
+    /// Outgoing links (also inserted into the KG as link-graph edges when `base_url` is set)
+    pub links: Vec<crate::links::Link>,
+    /// Bibliographic record of scholarly pages (inserted as a `schema:ScholarlyArticle`
+    /// when `base_url` is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation: Option<crate::citation::Citation>,
 }
 
 /// Response for LangGraph workflow execution
@@ -73,6 +92,9 @@ pub struct GraphItemsResponse {
 pub struct BrowseRequest {
     pub url: String,
     pub query: String,
+    /// Optional `screenshot` and `pdf` capture options (requires a browser)
+    #[serde(default, flatten)]
+    pub capture: crate::capture::CaptureRequest,
 }
 
 /// Browse response
@@ -81,6 +103,61 @@ pub struct BrowseResponse {
     pub data: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snapshot: Option<SemanticSnapshot>,
+    /// Base64 screenshot, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<crate::capture::Capture>,
+    /// Base64 PDF, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf: Option<crate::capture::Capture>,
+}
+
+/// Feed ingestion request
+#[derive(serde::Deserialize)]
+pub struct FeedsRequest {
+    /// Feed URL, or a page advertising feeds with `<link rel="alternate">`
+    pub url: String,
+    /// Raw RSS/Atom/JSON Feed document; when set, `url` is only used as the feed URL
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Feed ingestion response
+#[derive(serde::Serialize)]
+pub struct FeedsResponse {
+    pub data: String,
+    pub feeds: Vec<crate::feeds::Feed>,
+    pub triples_inserted: usize,
+}
+
+/// Crawl job request
+#[derive(Debug, serde::Deserialize)]
+pub struct CrawlRequest {
+    pub seeds: Vec<String>,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+    /// Domains the crawl may visit (defaults to the seed hosts)
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Minimum delay between requests to the same host, in milliseconds
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+    /// Id of an earlier job to resume from its state file (requires `CRAWL_STATE_DIR`)
+    #[serde(default)]
+    pub resume: Option<String>,
+}
+
+/// Crawl job status response
+#[derive(serde::Serialize)]
+pub struct CrawlResponse {
+    pub data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<crate::crawler::CrawlProgress>,
 }
 
 /// Browse and insert into KG request (2025 best practice)
@@ -163,12 +240,16 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
     let state = AppState {
         kg: Arc::new(Mutex::new(kg)),
         rate_limits: Arc::new(Mutex::new(HashMap::new())),
+        crawls: Arc::new(Mutex::new(HashMap::new())),
     };
     let app = {
         let router = Router::new()
             .route("/parse", post(parse_html))
             .route("/query", post(query_kg))
             .route("/browse", post(browse_url))
+            .route("/feeds", post(ingest_feeds))
+            .route("/crawl", post(start_crawl))
+            .route("/crawl/:id", get(crawl_status).delete(cancel_crawl))
             .route("/langgraph", post(run_langgraph));
         #[cfg(feature = "browser-automation")]
         let router = router.route("/browse_kg", post(browse_url_kg));
@@ -362,6 +443,10 @@ async fn parse_html(
             return Json(ParseResponse {
                 title: None,
                 entities: vec!["Rate limit exceeded".to_string()],
+                rdfa: vec![],
+                tables: vec![],
+                links: vec![],
+                citation: None,
             });
         }
     }
@@ -369,29 +454,82 @@ async fn parse_html(
     // Validate input
     if let Err(e) = crate::security::validate_html_input(&req.html) {
         crate::security::log_action("parse_html", &format!("Validation failed: {}", e));
-        return Json(ParseResponse { title: None, entities: vec![e.to_string()] });
+        return Json(ParseResponse {
+            title: None,
+            entities: vec![e.to_string()],
+            rdfa: vec![],
+            tables: vec![],
+            links: vec![],
+            citation: None,
+        });
     }
 
     // Use parser module
     let mut kg = state.kg.lock().await;
-    match crate::parser::parse_html(&req.html) {
+    match crate::parser::parse_html_with_base(&req.html, req.base_url.as_deref()) {
         Ok(data) => {
             // Insert basic triples to KG
             for micro in &data.microdata {
                 let _ = kg.insert(&micro.item_type, "rdf:type", "schema:Thing");
             }
+            if let Err(e) = kg.insert_triples(&data.rdfa) {
+                tracing::warn!("Failed to insert RDFa triples: {}", e);
+            }
+            if let Some(base_url) = req.base_url.as_deref() {
+                if let Err(e) =
+                    crate::kg_integration::insert_links_to_kg(&data.links, &mut kg, base_url)
+                {
+                    tracing::warn!("Failed to insert links: {}", e);
+                }
+                if let Some(citation) = &data.citation {
+                    if let Err(e) =
+                        crate::kg_integration::insert_citation_to_kg(citation, &mut kg, base_url)
+                    {
+                        tracing::warn!("Failed to insert citation: {}", e);
+                    }
+                }
+            }
+            if req.insert_tables {
+                // Row properties are minted from the document URL, so it is required here
+                match req.base_url.as_deref() {
+                    Some(base_url) => {
+                        if let Err(e) = crate::kg_integration::insert_tables_to_kg(
+                            &data.tables,
+                            &mut kg,
+                            base_url,
+                        ) {
+                            tracing::warn!("Failed to insert tables: {}", e);
+                        }
+                    }
+                    None => tracing::warn!("insert_tables requires base_url; tables not inserted"),
+                }
+            }
             let entities: Vec<String> = data.microdata.into_iter().map(|m| m.item_type).collect();
             crate::security::log_action(
                 "parse_html",
                 &format!("Parsed {} entities", entities.len()),
             );
             tracing::debug!("Parse duration: {:?}", start_time.elapsed());
-            Json(ParseResponse { title: data.title, entities })
+            Json(ParseResponse {
+                title: data.title,
+                entities,
+                rdfa: data.rdfa,
+                tables: data.tables,
+                links: data.links,
+                citation: data.citation,
+            })
         }
         Err(e) => {
             crate::security::log_action("parse_html", &format!("Parse error: {}", e));
             tracing::debug!("Parse duration: {:?}", start_time.elapsed());
-            Json(ParseResponse { title: None, entities: vec![] })
+            Json(ParseResponse {
+                title: None,
+                entities: vec![],
+                rdfa: vec![],
+                tables: vec![],
+                links: vec![],
+                citation: None,
+            })
         }
     }
 }
@@ -536,7 +674,20 @@ async fn query_kg(
     }
 }
 
+/// Status of a failed browse: browser pool backpressure maps to 429 (wait
+/// queue full) and 503 (acquire timeout); other failures keep the 200 error body
+fn browse_error_status(error: &(dyn std::error::Error + Send + Sync + 'static)) -> StatusCode {
+    match error.downcast_ref::<crate::page_pool::PoolError>() {
+        Some(crate::page_pool::PoolError::Saturated { .. }) => StatusCode::TOO_MANY_REQUESTS,
+        Some(crate::page_pool::PoolError::Timeout(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        None => StatusCode::OK,
+    }
+}
+
 /// Handler for browsing with external tools
+///
+/// Browser pool backpressure is reported with [`browse_error_status`] so
+/// clients can back off.
 #[axum::debug_handler]
 #[tracing::instrument(skip(state, addr, headers, _user, req), fields(url = %req.url))]
 async fn browse_url(
@@ -545,7 +696,11 @@ async fn browse_url(
     headers: HeaderMap,
     _user: crate::auth::AuthenticatedUser,
     Json(req): Json<BrowseRequest>,
-) -> Json<BrowseResponse> {
+) -> (StatusCode, Json<BrowseResponse>) {
+    let error = |status: StatusCode, data: String| {
+        (status, Json(BrowseResponse { data, snapshot: None, screenshot: None, pdf: None }))
+    };
+
     // Authentication handled by AuthenticatedUser extractor
 
     // Check rate limit - extract real IP
@@ -555,21 +710,20 @@ async fn browse_url(
         let mut rate_limits = state.rate_limits.lock().await;
         if !check_rate_limit(&mut rate_limits, &ip) {
             crate::security::log_action("browse_url", &format!("Rate limit exceeded for {}", ip));
-            return Json(BrowseResponse {
-                data: "Rate limit exceeded".to_string(),
-                snapshot: None,
-            });
+            return error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string());
         }
     }
 
     // Basic URL validation
     if !req.url.starts_with("http") {
         crate::security::log_action("browse_url", "Invalid URL");
-        return Json(BrowseResponse { data: "Invalid URL".to_string(), snapshot: None });
+        return error(StatusCode::BAD_REQUEST, "Invalid URL".to_string());
     }
 
-    // Use smart browse: chromiumoxide → HTTP fallback (best practice 2025)
-    let browse_result = crate::external::browse_with_best_available(&req.url, &req.query).await;
+    // Use smart browse: chromiumoxide → HTTP fallback (best practice 2025);
+    // captures always go through chromiumoxide
+    let browse_result =
+        crate::external::browse_with_capture(&req.url, &req.query, &req.capture).await;
 
     match browse_result {
         Ok(outcome) => {
@@ -584,13 +738,255 @@ async fn browse_url(
             }
 
             crate::security::log_action("browse_url", &format!("Browsed {} successfully", req.url));
-            Json(BrowseResponse { data: outcome.summary.clone(), snapshot: Some(outcome.snapshot) })
+            (
+                StatusCode::OK,
+                Json(BrowseResponse {
+                    data: outcome.summary,
+                    snapshot: Some(outcome.snapshot),
+                    screenshot: outcome.screenshot,
+                    pdf: outcome.pdf,
+                }),
+            )
         }
         Err(e) => {
             crate::security::log_action("browse_url", &format!("Browse error: {}", e));
-            Json(BrowseResponse { data: format!("Error: {}", e), snapshot: None })
+            error(browse_error_status(e.as_ref()), format!("Error: {}", e))
+        }
+    }
+}
+
+/// Handler for RSS/Atom/JSON Feed ingestion
+///
+/// Fetches the feed at `url` (or the feeds a page advertises), or parses the
+/// supplied `content`, and inserts every item into the KG as a
+/// `schema:BlogPosting`.
+#[axum::debug_handler]
+#[tracing::instrument(skip(state, addr, headers, _user, req), fields(url = %req.url))]
+async fn ingest_feeds(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    _user: crate::auth::AuthenticatedUser,
+    Json(req): Json<FeedsRequest>,
+) -> Json<FeedsResponse> {
+    let error = |data: String| Json(FeedsResponse { data, feeds: vec![], triples_inserted: 0 });
+
+    let ip = extract_ip(&headers, &addr);
+    tracing::debug!("Processing feeds request from IP: {} for URL: {}", ip, req.url);
+    {
+        let mut rate_limits = state.rate_limits.lock().await;
+        if !check_rate_limit(&mut rate_limits, &ip) {
+            crate::security::log_action("ingest_feeds", &format!("Rate limit exceeded for {}", ip));
+            return error("Rate limit exceeded".to_string());
+        }
+    }
+
+    if !req.url.starts_with("http") {
+        crate::security::log_action("ingest_feeds", "Invalid URL");
+        return error("Invalid URL".to_string());
+    }
+
+    let feeds = match &req.content {
+        Some(content) => crate::feeds::parse_feed(content, Some(&req.url)).map(|feed| vec![feed]),
+        None => crate::feeds::fetch_feeds(&req.url).await,
+    };
+    let feeds = match feeds {
+        Ok(feeds) => feeds,
+        Err(e) => {
+            crate::security::log_action("ingest_feeds", &format!("Feed error: {}", e));
+            return error(format!("Error: {}", e));
+        }
+    };
+
+    let mut triples_inserted = 0;
+    {
+        let mut kg = state.kg.lock().await;
+        for feed in &feeds {
+            let feed_url = feed.feed_url.as_deref().unwrap_or(&req.url);
+            match crate::kg_integration::insert_feed_to_kg(feed, &mut kg, feed_url) {
+                Ok(count) => triples_inserted += count,
+                Err(e) => tracing::warn!("Failed to insert feed {}: {}", feed_url, e),
+            }
         }
     }
+
+    let items: usize = feeds.iter().map(|feed| feed.items.len()).sum();
+    crate::security::log_action(
+        "ingest_feeds",
+        &format!("Ingested {} feeds ({} items) from {}", feeds.len(), items, req.url),
+    );
+    Json(FeedsResponse {
+        data: format!(
+            "Ingested {} feeds with {} items from {} ({} triples)",
+            feeds.len(),
+            items,
+            req.url,
+            triples_inserted
+        ),
+        feeds,
+        triples_inserted,
+    })
+}
+
+/// Upper bounds applied to crawl job requests
+const CRAWL_MAX_PAGES: usize = 1000;
+const CRAWL_MAX_CONCURRENCY: usize = 8;
+const CRAWL_DELAY_MS: std::ops::RangeInclusive<u64> = 250..=60_000;
+
+/// How long finished crawl jobs stay available through `GET /crawl/{job_id}`
+const CRAWL_JOB_TTL: Duration = Duration::from_secs(3600);
+
+/// Drop crawl jobs that finished more than [`CRAWL_JOB_TTL`] ago
+fn evict_finished_crawls(crawls: &mut HashMap<String, crate::crawler::CrawlHandle>) {
+    crawls.retain(|_, handle| handle.finished_for().map_or(true, |age| age < CRAWL_JOB_TTL));
+}
+
+/// Handler starting a crawl job
+///
+/// The crawl runs in the background and inserts every page into the KG; poll
+/// `GET /crawl/{job_id}` for progress and `DELETE /crawl/{job_id}` to stop it.
+/// When `CRAWL_STATE_DIR` is set the frontier is saved to `<dir>/<job_id>.json`
+/// and a later request with `resume` set to that id continues the crawl.
+/// `max_pages`, `concurrency` and `delay_ms` are clamped to the server limits.
+#[axum::debug_handler]
+#[tracing::instrument(skip(state, addr, headers, _user, req), fields(seeds = req.seeds.len()))]
+async fn start_crawl(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    _user: crate::auth::AuthenticatedUser,
+    Json(req): Json<CrawlRequest>,
+) -> Json<CrawlResponse> {
+    let error = |data: String| Json(CrawlResponse { data, job_id: None, progress: None });
+
+    let ip = extract_ip(&headers, &addr);
+    tracing::debug!("Processing crawl request from IP: {}", ip);
+    {
+        let mut rate_limits = state.rate_limits.lock().await;
+        if !check_rate_limit(&mut rate_limits, &ip) {
+            crate::security::log_action("start_crawl", &format!("Rate limit exceeded for {}", ip));
+            return error("Rate limit exceeded".to_string());
+        }
+    }
+
+    if req.seeds.is_empty() || req.seeds.iter().any(|seed| !seed.starts_with("http")) {
+        crate::security::log_action("start_crawl", "Invalid seed URLs");
+        return error("Invalid seed URLs".to_string());
+    }
+
+    let job_id = match &req.resume {
+        Some(id) if id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') => id.clone(),
+        Some(_) => return error("Invalid job id".to_string()),
+        None => format!("crawl-{}", uuid::Uuid::new_v4()),
+    };
+    #[allow(clippy::disallowed_methods)]
+    let state_dir = std::env::var("CRAWL_STATE_DIR").ok();
+    if req.resume.is_some() && state_dir.is_none() {
+        return error("Resuming requires CRAWL_STATE_DIR".to_string());
+    }
+
+    let defaults = crate::crawler::CrawlOptions::default();
+    let options = crate::crawler::CrawlOptions {
+        max_depth: req.max_depth.unwrap_or(defaults.max_depth),
+        max_pages: req.max_pages.unwrap_or(defaults.max_pages).min(CRAWL_MAX_PAGES),
+        allowed_domains: req.allowed_domains,
+        concurrency: req
+            .concurrency
+            .unwrap_or(defaults.concurrency)
+            .clamp(1, CRAWL_MAX_CONCURRENCY),
+        delay: req.delay_ms.map_or(defaults.delay, |ms| {
+            Duration::from_millis(ms.clamp(*CRAWL_DELAY_MS.start(), *CRAWL_DELAY_MS.end()))
+        }),
+        state_path: state_dir
+            .map(|dir| std::path::Path::new(&dir).join(format!("{}.json", job_id))),
+        ..defaults
+    };
+
+    let crawler = match crate::crawler::Crawler::new(&req.seeds, options) {
+        Ok(crawler) => crawler,
+        Err(e) => {
+            crate::security::log_action("start_crawl", &format!("Crawl error: {}", e));
+            return error(format!("Error: {}", e));
+        }
+    };
+    let handle = crawler.handle();
+    {
+        let mut crawls = state.crawls.lock().await;
+        evict_finished_crawls(&mut crawls);
+        if crawls
+            .get(&job_id)
+            .is_some_and(|h| h.progress().status == crate::crawler::CrawlStatus::Running)
+        {
+            return error(format!("Crawl {} is already running", job_id));
+        }
+        crawls.insert(job_id.clone(), handle.clone());
+    }
+
+    let kg = Arc::clone(&state.kg);
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        match crawler.run(kg).await {
+            Ok(report) => tracing::info!(
+                "Crawl {} {:?}: {} pages, {} triples",
+                id,
+                report.status,
+                report.pages.len(),
+                report.triples_inserted
+            ),
+            Err(e) => tracing::warn!("Crawl {} failed: {}", id, e),
+        }
+    });
+
+    crate::security::log_action(
+        "start_crawl",
+        &format!("Started crawl {} with {} seeds", job_id, req.seeds.len()),
+    );
+    Json(CrawlResponse {
+        data: format!("Crawl {} started", job_id),
+        job_id: Some(job_id),
+        progress: Some(handle.progress()),
+    })
+}
+
+/// Handler reporting the progress of a crawl job
+#[axum::debug_handler]
+async fn crawl_status(
+    State(state): State<AppState>,
+    _user: crate::auth::AuthenticatedUser,
+    Path(job_id): Path<String>,
+) -> Result<Json<CrawlResponse>, (StatusCode, String)> {
+    let mut crawls = state.crawls.lock().await;
+    evict_finished_crawls(&mut crawls);
+    let handle =
+        crawls.get(&job_id).ok_or((StatusCode::NOT_FOUND, format!("Unknown crawl {}", job_id)))?;
+    let progress = handle.progress();
+    Ok(Json(CrawlResponse {
+        data: format!(
+            "Crawl {} {:?}: {} pages crawled, {} failed, {} queued",
+            job_id, progress.status, progress.pages_crawled, progress.pages_failed, progress.queued
+        ),
+        job_id: Some(job_id),
+        progress: Some(progress),
+    }))
+}
+
+/// Handler cancelling a crawl job; pages in flight still complete
+#[axum::debug_handler]
+async fn cancel_crawl(
+    State(state): State<AppState>,
+    _user: crate::auth::AuthenticatedUser,
+    Path(job_id): Path<String>,
+) -> Result<Json<CrawlResponse>, (StatusCode, String)> {
+    let crawls = state.crawls.lock().await;
+    let handle =
+        crawls.get(&job_id).ok_or((StatusCode::NOT_FOUND, format!("Unknown crawl {}", job_id)))?;
+    handle.cancel();
+    crate::security::log_action("cancel_crawl", &format!("Cancelled crawl {}", job_id));
+    Ok(Json(CrawlResponse {
+        data: format!("Crawl {} cancelled", job_id),
+        job_id: Some(job_id),
+        progress: Some(handle.progress()),
+    }))
 }
 
 /// Handler for browsing URL and inserting into Knowledge Graph (2025 best practice)
@@ -625,7 +1021,17 @@ async fn browse_url_kg(
     headers: HeaderMap,
     _user: crate::auth::AuthenticatedUser,
     Json(req): Json<BrowseKGRequest>,
-) -> Json<BrowseKGResponse> {
+) -> (StatusCode, Json<BrowseKGResponse>) {
+    let error = |status: StatusCode, data: String| {
+        let response = BrowseKGResponse {
+            data,
+            triples_inserted: 0,
+            final_url: req.url.clone(),
+            snapshot: None,
+        };
+        (status, Json(response))
+    };
+
     // Authentication handled by AuthenticatedUser extractor
 
     // Check rate limit - extract real IP
@@ -638,31 +1044,28 @@ async fn browse_url_kg(
                 "browse_url_kg",
                 &format!("Rate limit exceeded for {}", ip),
             );
-            return Json(BrowseKGResponse {
-                data: "Rate limit exceeded".to_string(),
-                triples_inserted: 0,
-                final_url: req.url.clone(),
-                snapshot: None,
-            });
+            return error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string());
         }
     }
 
     // Basic URL validation
     if !req.url.starts_with("http") {
         crate::security::log_action("browse_url_kg", "Invalid URL");
-        return Json(BrowseKGResponse {
-            data: "Invalid URL".to_string(),
-            triples_inserted: 0,
-            final_url: req.url.clone(),
-            snapshot: None,
-        });
+        return error(StatusCode::BAD_REQUEST, "Invalid URL".to_string());
     }
 
-    // Browse and insert into KG
-    let mut kg = state.kg.lock().await;
+    // Browse first and lock the KG only for the insert, so concurrent
+    // requests share the browser pool instead of queueing on the KG
     let options = crate::browser::NavigationOptions::default();
-
-    let browse_result = crate::external::browse_and_insert_kg(&req.url, options, &mut kg).await;
+    let browse_result = match crate::external::browse_with_chromium_full(&req.url, options).await {
+        Ok(semantic_data) => {
+            let mut kg = state.kg.lock().await;
+            crate::kg_integration::insert_semantic_data_to_kg(&semantic_data, &mut kg, &req.url)
+                .map(|count| (semantic_data, count))
+                .map_err(|e| (StatusCode::OK, e.to_string()))
+        }
+        Err(e) => Err((browse_error_status(e.as_ref()), e.to_string())),
+    };
 
     match browse_result {
         Ok((semantic_data, count)) => {
@@ -684,21 +1087,19 @@ async fn browse_url_kg(
             data.push_str(&format!("Microdata items: {}\n", snapshot.microdata.len()));
             data.push_str(&format!("Triples inserted: {}\n", count));
 
-            Json(BrowseKGResponse {
-                data,
-                triples_inserted: count,
-                final_url: snapshot.final_url.clone(),
-                snapshot: Some(snapshot),
-            })
+            (
+                StatusCode::OK,
+                Json(BrowseKGResponse {
+                    data,
+                    triples_inserted: count,
+                    final_url: snapshot.final_url.clone(),
+                    snapshot: Some(snapshot),
+                }),
+            )
         }
-        Err(e) => {
+        Err((status, e)) => {
             crate::security::log_action("browse_url_kg", &format!("Browse error: {}", e));
-            Json(BrowseKGResponse {
-                data: format!("Error browsing and inserting into KG: {}", e),
-                triples_inserted: 0,
-                final_url: req.url.clone(),
-                snapshot: None,
-            })
+            error(status, format!("Error browsing and inserting into KG: {}", e))
         }
     }
 }
@@ -776,6 +1177,19 @@ mod tests {
         let headers3 = HeaderMap::new();
         assert_eq!(extract_ip(&headers3, &addr), "127.0.0.1");
     }
+    #[test]
+    fn test_browse_error_status() {
+        use crate::page_pool::PoolError;
+
+        let saturated: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(PoolError::Saturated { waiting: 32, max_waiting: 32 });
+        assert_eq!(browse_error_status(saturated.as_ref()), StatusCode::TOO_MANY_REQUESTS);
+        let timeout: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(PoolError::Timeout(Duration::from_secs(30)));
+        assert_eq!(browse_error_status(timeout.as_ref()), StatusCode::SERVICE_UNAVAILABLE);
+        let other: Box<dyn std::error::Error + Send + Sync> = "navigation failed".into();
+        assert_eq!(browse_error_status(other.as_ref()), StatusCode::OK);
+    }
 }
 /// Handler for Prometheus metrics endpoint
 #[axum::debug_handler]
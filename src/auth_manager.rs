@@ -33,6 +33,9 @@ pub struct SessionData {
     pub expires_at: Option<u64>,
     /// Session metadata
     pub metadata: HashMap<String, String>,
+    /// Full cookies and web storage, to restore the session in a browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_state: Option<crate::storage_state::StorageState>,
 }
 
 impl SessionData {
@@ -43,6 +46,7 @@ impl SessionData {
             tokens: HashMap::new(),
             expires_at: None,
             metadata: HashMap::new(),
+            storage_state: None,
         }
     }
 
@@ -268,12 +272,18 @@ impl AuthenticationManager {
             }
         }
 
-        // Extract session from cookies
-        let cookies = page.get_cookies().await?;
+        // Keep the full storage state; the name -> value map only has the
+        // cookies of the login site, as the state spans every domain
+        let state = crate::storage_state::export(page).await?;
+        let login_host = url::Url::parse(&config.login_url)?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
         let mut session = SessionData::new();
-        for cookie in cookies {
-            session.cookies.insert(cookie.name, cookie.value);
+        for cookie in state.cookies.iter().filter(|c| c.matches_host(&login_host)) {
+            session.cookies.insert(cookie.name.clone(), cookie.value.clone());
         }
+        session.storage_state = Some(state);
 
         session.metadata.insert("login_url".to_string(), config.login_url.clone());
         session.metadata.insert("username".to_string(), username.to_string());
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use base64::{engine::general_purpose, Engine as _};
 use semantic_browser::kg::KnowledgeGraph;
 use semantic_browser::kg_integration::insert_snapshot_to_kg;
 use semantic_browser::security;
@@ -292,6 +293,11 @@ async fn handle_call_tool(
                 parse_arguments(arguments).map_err(|msg| JsonRpcError::new(-32602, msg, None))?;
             execute_parse_html(args, state).await
         }
+        TOOL_HTML_TO_MARKDOWN => {
+            let args: HtmlToMarkdownArgs =
+                parse_arguments(arguments).map_err(|msg| JsonRpcError::new(-32602, msg, None))?;
+            execute_html_to_markdown(args)
+        }
         TOOL_QUERY_KG => {
             let args: QueryKgArgs =
                 parse_arguments(arguments).map_err(|msg| JsonRpcError::new(-32602, msg, None))?;
@@ -302,6 +308,11 @@ async fn handle_call_tool(
                 parse_arguments(arguments).map_err(|msg| JsonRpcError::new(-32602, msg, None))?;
             execute_browse(args, state).await
         }
+        TOOL_DISCOVER_SITE => {
+            let args: DiscoverSiteArgs =
+                parse_arguments(arguments).map_err(|msg| JsonRpcError::new(-32602, msg, None))?;
+            execute_discover_site(args).await
+        }
         _ => {
             return Err(JsonRpcError::new(-32601, "Unknown tool", Some(json!({ "tool": name }))));
         }
@@ -357,6 +368,15 @@ fn list_tools_result() -> Value {
                         "html": {
                             "type": "string",
                             "description": "Raw HTML content to parse."
+                        },
+                        "baseUrl": {
+                            "type": "string",
+                            "description": "URL of the document, used to resolve relative RDFa, microdata and link IRIs; links are inserted into the knowledge graph as edges from this URL."
+                        },
+                        "insertTables": {
+                            "type": "boolean",
+                            "description": "Insert data tables into the knowledge graph as CSVW rows (requires baseUrl).",
+                            "default": false
                         }
                     },
                     "required": ["html"]
@@ -366,7 +386,52 @@ fn list_tools_result() -> Value {
                     "properties": {
                         "title": { "type": ["string", "null"] },
                         "microdata": { "type": "array", "items": { "type": "object" } },
-                        "jsonLd": { "type": "array", "items": { "type": "object" } }
+                        "jsonLd": { "type": "array", "items": { "type": "object" } },
+                        "rdfa": { "type": "array", "items": { "type": "object" } },
+                        "tables": { "type": "array", "items": { "type": "object" } },
+                        "links": { "type": "array", "items": { "type": "object" } }
+                    }
+                }
+            }),
+            json!({
+                "name": TOOL_HTML_TO_MARKDOWN,
+                "description": "Render HTML as compact Markdown for LLM context, with numbered link references and a token budget.",
+                "annotations": {
+                    "title": "HTML to Markdown",
+                    "readOnlyHint": true,
+                    "openWorldHint": false
+                },
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "html": {
+                            "type": "string",
+                            "description": "Raw HTML content to render."
+                        },
+                        "baseUrl": {
+                            "type": "string",
+                            "description": "URL of the document, used to resolve relative links."
+                        },
+                        "maxTokens": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Approximate token budget; content is cut at block boundaries."
+                        },
+                        "mainContentOnly": {
+                            "type": "boolean",
+                            "description": "Render only <main>/<article> when present.",
+                            "default": false
+                        }
+                    },
+                    "required": ["html"]
+                },
+                "outputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "markdown": { "type": "string" },
+                        "links": { "type": "array", "items": { "type": "object" } },
+                        "estimatedTokens": { "type": "integer" },
+                        "truncated": { "type": "boolean" }
                     }
                 }
             }),
@@ -418,6 +483,29 @@ fn list_tools_result() -> Value {
                             "type": "string",
                             "description": "Optional focus or extraction instruction.",
                             "default": ""
+                        },
+                        "screenshot": {
+                            "type": "object",
+                            "description": "Return a screenshot of the rendered page (needs the headless browser).",
+                            "properties": {
+                                "full_page": { "type": "boolean", "default": false },
+                                "selector": { "type": "string", "description": "Capture only this element." },
+                                "format": { "type": "string", "enum": ["png", "jpeg", "webp"], "default": "png" },
+                                "quality": { "type": "integer", "minimum": 0, "maximum": 100 },
+                                "device_scale_factor": { "type": "number", "exclusiveMinimum": 0, "maximum": 4 }
+                            }
+                        },
+                        "pdf": {
+                            "type": "object",
+                            "description": "Return the page printed to PDF (needs the headless browser).",
+                            "properties": {
+                                "landscape": { "type": "boolean", "default": false },
+                                "print_background": { "type": "boolean", "default": true },
+                                "scale": { "type": "number", "minimum": 0.1, "maximum": 2 },
+                                "paper_width": { "type": "number", "description": "Inches." },
+                                "paper_height": { "type": "number", "description": "Inches." },
+                                "page_ranges": { "type": "string", "description": "For example \"1-5, 8\"." }
+                            }
                         }
                     },
                     "required": ["url"]
@@ -429,6 +517,42 @@ fn list_tools_result() -> Value {
                         "summary": { "type": "string" }
                     }
                 }
+            }),
+            json!({
+                "name": TOOL_DISCOVER_SITE,
+                "description": "List a site's URLs from robots.txt and XML sitemaps (gzip and sitemap indexes included), keeping only URLs robots.txt allows.",
+                "annotations": {
+                    "title": "Discover Site",
+                    "readOnlyHint": true,
+                    "openWorldHint": true
+                },
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "url": {
+                            "type": "string",
+                            "format": "uri",
+                            "description": "Any URL of the site; robots.txt is read from its root."
+                        },
+                        "maxUrls": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Maximum number of URLs to return.",
+                            "default": 100
+                        }
+                    },
+                    "required": ["url"]
+                },
+                "outputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "root": { "type": "string" },
+                        "sitemaps": { "type": "array", "items": { "type": "string" } },
+                        "crawlDelaySecs": { "type": ["number", "null"] },
+                        "totalUrls": { "type": "integer" },
+                        "urls": { "type": "array", "items": { "type": "object" } }
+                    }
+                }
             })
         ]
     })
@@ -442,6 +566,32 @@ fn parse_arguments<T: DeserializeOwned>(value: Option<&Value>) -> Result<T, Stri
 #[derive(Deserialize)]
 struct ParseHtmlArgs {
     html: String,
+    #[serde(default, rename = "baseUrl")]
+    base_url: Option<String>,
+    #[serde(default, rename = "insertTables")]
+    insert_tables: bool,
+}
+
+#[derive(Deserialize)]
+struct HtmlToMarkdownArgs {
+    html: String,
+    #[serde(default, rename = "baseUrl")]
+    base_url: Option<String>,
+    #[serde(default, rename = "maxTokens")]
+    max_tokens: Option<usize>,
+    #[serde(default, rename = "mainContentOnly")]
+    main_content_only: bool,
+}
+
+#[derive(Deserialize)]
+struct DiscoverSiteArgs {
+    url: String,
+    #[serde(default = "default_max_urls", rename = "maxUrls")]
+    max_urls: usize,
+}
+
+fn default_max_urls() -> usize {
+    100
 }
 
 #[derive(Deserialize)]
@@ -454,15 +604,19 @@ struct BrowseArgs {
     url: String,
     #[serde(default)]
     query: String,
+    #[serde(default, flatten)]
+    capture: semantic_browser::capture::CaptureRequest,
 }
 
 const TOOL_PARSE_HTML: &str = "semanticbrowser.parse_html";
+const TOOL_HTML_TO_MARKDOWN: &str = "semanticbrowser.html_to_markdown";
 const TOOL_QUERY_KG: &str = "semanticbrowser.query_kg";
 const TOOL_BROWSE_URL: &str = "semanticbrowser.browse_url";
+const TOOL_DISCOVER_SITE: &str = "semanticbrowser.discover_site";
 
 #[derive(Serialize)]
 struct CallToolSuccess {
-    content: Vec<TextContent>,
+    content: Vec<ToolContent>,
     #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
     structured_content: Option<Value>,
     #[serde(rename = "isError", skip_serializing_if = "Option::is_none")]
@@ -470,15 +624,26 @@ struct CallToolSuccess {
 }
 
 #[derive(Serialize)]
-struct TextContent {
-    #[serde(rename = "type")]
-    content_type: &'static str,
-    text: String,
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ToolContent {
+    Text {
+        text: String,
+    },
+    /// Base64 image
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// Embedded binary resource (`{ uri, mimeType, blob }`)
+    Resource {
+        resource: Value,
+    },
 }
 
 fn success_result(text: String, structured: Option<Value>) -> CallToolSuccess {
     CallToolSuccess {
-        content: vec![TextContent { content_type: "text", text }],
+        content: vec![ToolContent::Text { text }],
         structured_content: structured,
         is_error: None,
     }
@@ -486,7 +651,7 @@ fn success_result(text: String, structured: Option<Value>) -> CallToolSuccess {
 
 fn error_result(message: String) -> CallToolSuccess {
     CallToolSuccess {
-        content: vec![TextContent { content_type: "text", text: message }],
+        content: vec![ToolContent::Text { text: message }],
         structured_content: None,
         is_error: Some(true),
     }
@@ -501,7 +666,13 @@ async fn execute_parse_html(
         return Err(error_result(format!("HTML validation failed: {}", err)));
     }
 
-    let parsed = security::sandbox_parsing(|| semantic_browser::parser::parse_html(&args.html));
+    if args.insert_tables && args.base_url.is_none() {
+        return Err(error_result("insertTables requires baseUrl".to_string()));
+    }
+
+    let parsed = security::sandbox_parsing(|| {
+        semantic_browser::parser::parse_html_with_base(&args.html, args.base_url.as_deref())
+    });
     let data = match parsed {
         Ok(data) => data,
         Err(err) => {
@@ -517,6 +688,32 @@ async fn execute_parse_html(
                 tracing::debug!("Failed to insert microdata into KG: {}", err);
             }
         }
+        if let Err(err) = kg.insert_triples(&data.rdfa) {
+            tracing::debug!("Failed to insert RDFa into KG: {}", err);
+        }
+        if let Some(base_url) = args.base_url.as_deref() {
+            if let Err(err) =
+                semantic_browser::kg_integration::insert_links_to_kg(&data.links, &mut kg, base_url)
+            {
+                tracing::debug!("Failed to insert links into KG: {}", err);
+            }
+            if let Some(citation) = &data.citation {
+                if let Err(err) = semantic_browser::kg_integration::insert_citation_to_kg(
+                    citation, &mut kg, base_url,
+                ) {
+                    tracing::debug!("Failed to insert citation into KG: {}", err);
+                }
+            }
+        }
+        if let (true, Some(base_url)) = (args.insert_tables, args.base_url.as_deref()) {
+            if let Err(err) = semantic_browser::kg_integration::insert_tables_to_kg(
+                &data.tables,
+                &mut kg,
+                base_url,
+            ) {
+                tracing::debug!("Failed to insert tables into KG: {}", err);
+            }
+        }
     }
 
     security::log_action(
@@ -541,18 +738,56 @@ async fn execute_parse_html(
         "title": data.title,
         "microdata": microdata,
         "jsonLd": data.json_ld,
+        "rdfa": data.rdfa,
+        "tables": data.tables,
+        "links": data.links,
+        "citation": data.citation,
     });
 
     let summary = format!(
-        "Parsed HTML. Title: {}. Microdata items: {}. JSON-LD blocks: {}.",
+        "Parsed HTML. Title: {}. Microdata items: {}. JSON-LD blocks: {}. RDFa triples: {}. Tables: {}. Links: {}.",
         data.title.clone().unwrap_or_else(|| "n/a".to_string()),
         data.microdata.len(),
-        data.json_ld.len()
+        data.json_ld.len(),
+        data.rdfa.len(),
+        data.tables.len(),
+        data.links.len()
     );
 
     Ok(success_result(summary, Some(structured)))
 }
 
+fn execute_html_to_markdown(args: HtmlToMarkdownArgs) -> Result<CallToolSuccess, CallToolSuccess> {
+    if let Err(err) = security::validate_html_input(&args.html) {
+        security::log_action("mcp.html_to_markdown", "validation_failed");
+        return Err(error_result(format!("HTML validation failed: {}", err)));
+    }
+
+    let options = semantic_browser::markdown::MarkdownOptions {
+        max_tokens: args.max_tokens,
+        main_content_only: args.main_content_only,
+        base_url: args.base_url,
+    };
+    let page = semantic_browser::markdown::html_to_markdown(
+        &scraper::Html::parse_document(&args.html),
+        &options,
+    );
+
+    security::log_action(
+        "mcp.html_to_markdown",
+        &format!("tokens={} truncated={}", page.estimated_tokens, page.truncated),
+    );
+
+    let structured = json!({
+        "markdown": page.markdown,
+        "links": page.links,
+        "estimatedTokens": page.estimated_tokens,
+        "truncated": page.truncated,
+    });
+
+    Ok(success_result(page.markdown, Some(structured)))
+}
+
 async fn execute_query_kg(
     args: QueryKgArgs,
     state: &ServerState,
@@ -603,6 +838,40 @@ async fn execute_query_kg(
     }
 }
 
+async fn execute_discover_site(args: DiscoverSiteArgs) -> Result<CallToolSuccess, CallToolSuccess> {
+    if !args.url.starts_with("http://") && !args.url.starts_with("https://") {
+        security::log_action("mcp.discover_site", "invalid_url");
+        return Err(error_result("URL must start with http:// or https://".to_string()));
+    }
+
+    match semantic_browser::discovery::Site::discover(&args.url).await {
+        Ok(site) => {
+            let total = site.urls().count();
+            security::log_action("mcp.discover_site", &format!("urls={}", total));
+            let urls: Vec<_> = site.urls().take(args.max_urls).collect();
+            Ok(success_result(
+                format!(
+                    "Discovered {} URLs in {} sitemaps for {}.",
+                    total,
+                    site.sitemaps.len(),
+                    site.root
+                ),
+                Some(json!({
+                    "root": site.root,
+                    "sitemaps": site.sitemaps,
+                    "crawlDelaySecs": site.crawl_delay().map(|d| d.as_secs_f64()),
+                    "totalUrls": total,
+                    "urls": urls,
+                })),
+            ))
+        }
+        Err(err) => {
+            security::log_action("mcp.discover_site", "discovery_error");
+            Err(error_result(format!("Site discovery error: {}", err)))
+        }
+    }
+}
+
 async fn execute_browse(
     args: BrowseArgs,
     state: &ServerState,
@@ -612,7 +881,9 @@ async fn execute_browse(
         return Err(error_result("URL must start with http:// or https://".to_string()));
     }
 
-    match semantic_browser::external::browse_with_best_available(&args.url, &args.query).await {
+    match semantic_browser::external::browse_with_capture(&args.url, &args.query, &args.capture)
+        .await
+    {
         Ok(outcome) => {
             security::log_action("mcp.browse_url", "browse_success");
             {
@@ -624,7 +895,7 @@ async fn execute_browse(
                 }
             }
             let summary_len = outcome.summary.len();
-            Ok(success_result(
+            let mut result = success_result(
                 format!("Browsed {}. Summary length: {} characters.", args.url, summary_len),
                 Some(json!({
                     "url": args.url,
@@ -632,7 +903,24 @@ async fn execute_browse(
                     "summary": outcome.summary,
                     "snapshot": outcome.snapshot
                 })),
-            ))
+            );
+            // Captures travel as content blocks rather than in the structured result
+            if let Some(screenshot) = outcome.screenshot {
+                result.content.push(ToolContent::Image {
+                    data: general_purpose::STANDARD.encode(&screenshot.data),
+                    mime_type: screenshot.media_type,
+                });
+            }
+            if let Some(pdf) = outcome.pdf {
+                result.content.push(ToolContent::Resource {
+                    resource: json!({
+                        "uri": args.url,
+                        "mimeType": pdf.media_type,
+                        "blob": general_purpose::STANDARD.encode(&pdf.data)
+                    }),
+                });
+            }
+            Ok(result)
         }
         Err(err) => {
             security::log_action("mcp.browse_url", "browse_error");
@@ -13,6 +13,8 @@
 #[cfg(feature = "browser-automation")]
 use chromiumoxide::browser::{Browser, BrowserConfig as ChromiumBrowserConfig};
 #[cfg(feature = "browser-automation")]
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+#[cfg(feature = "browser-automation")]
 use chromiumoxide::Page;
 #[cfg(feature = "browser-automation")]
 use futures::StreamExt;
@@ -36,10 +38,34 @@ pub struct BrowserConfig {
     pub block_images: bool,
     /// Default navigation timeout in seconds
     pub timeout_secs: u64,
-    /// Maximum number of concurrent tabs
+    /// Maximum number of pages in use at once
     pub pool_size: usize,
     /// Directory for Chromium user data (profile)
     pub user_data_dir: Option<String>,
+    /// Maximum number of requests waiting for a free page
+    #[serde(default = "default_pool_max_waiting")]
+    pub pool_max_waiting: usize,
+    /// Seconds a request waits for a free page before giving up
+    #[serde(default = "default_pool_acquire_timeout_secs")]
+    pub pool_acquire_timeout_secs: u64,
+    /// Seconds a session's browser context may sit unused before it is disposed
+    #[serde(default = "default_session_idle_timeout_secs")]
+    pub session_idle_timeout_secs: u64,
+    /// JSON file of custom emulation profiles, added to the built-in presets
+    #[serde(default)]
+    pub emulation_profiles_path: Option<String>,
+}
+
+fn default_pool_max_waiting() -> usize {
+    32
+}
+
+fn default_pool_acquire_timeout_secs() -> u64 {
+    30
+}
+
+fn default_session_idle_timeout_secs() -> u64 {
+    900
 }
 
 impl Default for BrowserConfig {
@@ -79,6 +105,24 @@ impl BrowserConfig {
             .parse()
             .unwrap_or(2);
         #[allow(clippy::disallowed_methods)]
+        let pool_max_waiting = std::env::var("BROWSER_POOL_MAX_WAITING")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_pool_max_waiting);
+        #[allow(clippy::disallowed_methods)]
+        let pool_acquire_timeout_secs = std::env::var("BROWSER_POOL_ACQUIRE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_pool_acquire_timeout_secs);
+        #[allow(clippy::disallowed_methods)]
+        let session_idle_timeout_secs = std::env::var("BROWSER_SESSION_IDLE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_session_idle_timeout_secs);
+        #[allow(clippy::disallowed_methods)]
+        let emulation_profiles_path =
+            std::env::var("BROWSER_EMULATION_PROFILES").ok().filter(|v| !v.trim().is_empty());
+        #[allow(clippy::disallowed_methods)]
         let user_data_dir = std::env::var("CHROMIUMOXIDE_USER_DATA_DIR")
             .ok()
             .and_then(|value| {
@@ -112,6 +156,19 @@ impl BrowserConfig {
             timeout_secs,
             pool_size,
             user_data_dir,
+            pool_max_waiting,
+            pool_acquire_timeout_secs,
+            session_idle_timeout_secs,
+            emulation_profiles_path,
+        }
+    }
+
+    /// Limits of the page pool
+    pub fn page_pool_config(&self) -> crate::page_pool::PoolConfig {
+        crate::page_pool::PoolConfig {
+            size: self.pool_size,
+            max_waiting: self.pool_max_waiting,
+            acquire_timeout: std::time::Duration::from_secs(self.pool_acquire_timeout_secs),
         }
     }
 }
@@ -123,12 +180,31 @@ pub struct NavigationOptions {
     pub wait_for_selector: Option<String>,
     /// Custom cookies to set before navigation
     pub cookies: HashMap<String, String>,
+    /// Cookies and web storage to import before navigation, into the session's
+    /// context or, without `session_id`, a context used for this navigation only
+    pub storage_state: Option<crate::storage_state::StorageState>,
+    /// Export the cookies and web storage after navigation
+    pub export_storage_state: bool,
     /// Take screenshot after navigation
     pub take_screenshot: bool,
+    /// Area, format and scale of that screenshot
+    pub screenshot_options: crate::capture::ScreenshotOptions,
+    /// Print the page to PDF after navigation
+    pub print_pdf: Option<crate::capture::PdfOptions>,
+    /// Record the page's network traffic as HAR
+    pub har: Option<crate::har::HarOptions>,
     /// Custom JavaScript to execute after page load
     pub execute_js: Option<String>,
     /// Maximum number of retry attempts on failure (default: 3)
     pub max_retries: u32,
+    /// Text used for `text_content` (default: main article via readability scoring)
+    pub content_mode: crate::readability::ContentMode,
+    /// Run in this session's isolated browser context instead of the shared one
+    /// (see [`crate::browser_context`]); created with default options on first use
+    pub session_id: Option<String>,
+    /// Device, locale and timezone to emulate, e.g. a preset from
+    /// `BrowserPool::emulation_profile`
+    pub emulation: Option<crate::emulation::EmulationProfile>,
 }
 
 impl Default for NavigationOptions {
@@ -136,9 +212,17 @@ impl Default for NavigationOptions {
         Self {
             wait_for_selector: None,
             cookies: HashMap::new(),
+            storage_state: None,
+            export_storage_state: false,
             take_screenshot: false,
+            screenshot_options: crate::capture::ScreenshotOptions::default(),
+            print_pdf: None,
+            har: None,
             execute_js: None,
             max_retries: 3,
+            content_mode: crate::readability::ContentMode::default(),
+            session_id: None,
+            emulation: None,
         }
     }
 }
@@ -152,10 +236,35 @@ pub struct SemanticData {
     pub json_ld: Vec<serde_json::Value>,
     /// Microdata items
     pub microdata: Vec<crate::parser::MicrodataItem>,
-    /// Plain text content (for NER)
+    /// RDFa triples
+    #[serde(default)]
+    pub rdfa: Vec<crate::kg::Triple>,
+    /// Microformats2 items and rels
+    #[serde(default)]
+    pub microformats: crate::microformats::Mf2Document,
+    /// Outgoing links resolved against the final URL
+    #[serde(default)]
+    pub links: Vec<crate::links::Link>,
+    /// Main article (byline, date, lead image) when readability extraction found one
+    #[serde(default)]
+    pub article: Option<crate::readability::Article>,
+    /// Markdown rendering of the main content (see `markdown::DEFAULT_SNAPSHOT_TOKENS`)
+    #[serde(default)]
+    pub markdown: String,
+    /// Plain text content (for NER); the main article text unless
+    /// `NavigationOptions::content_mode` is `FullText`
     pub text_content: String,
-    /// Screenshot bytes (if requested)
+    /// Screenshot bytes (if requested), encoded as `NavigationOptions::screenshot_options`
     pub screenshot: Option<Vec<u8>>,
+    /// PDF rendering of the page (if requested)
+    #[serde(default)]
+    pub pdf: Option<Vec<u8>>,
+    /// Network traffic of the navigation (if requested)
+    #[serde(default)]
+    pub har: Option<crate::har::Har>,
+    /// Cookies and web storage after the navigation (if requested)
+    #[serde(default)]
+    pub storage_state: Option<crate::storage_state::StorageState>,
     /// Final URL after redirects
     pub final_url: String,
 
@@ -172,6 +281,9 @@ pub struct SemanticData {
     pub open_graph: HashMap<String, String>,
     /// Twitter Card meta tags (twitter:card, twitter:site, etc.)
     pub twitter_card: HashMap<String, String>,
+    /// Bibliographic record (`citation_*`, `prism.*`, `DC.*` meta tags)
+    #[serde(default)]
+    pub citation: Option<crate::citation::Citation>,
 }
 
 /// Tab manager for handling multiple browser tabs
@@ -180,6 +292,8 @@ pub struct SemanticData {
 pub struct TabManager {
     browser: Arc<Browser>,
     tabs: HashMap<String, Arc<Page>>,
+    /// Session of each tab opened in an isolated context
+    tab_sessions: HashMap<String, String>,
     active_tab: String,
     max_tabs: usize,
 }
@@ -188,7 +302,13 @@ pub struct TabManager {
 impl TabManager {
     /// Create a new tab manager
     pub fn new(browser: Arc<Browser>, max_tabs: usize) -> Self {
-        Self { browser, tabs: HashMap::new(), active_tab: String::new(), max_tabs }
+        Self {
+            browser,
+            tabs: HashMap::new(),
+            tab_sessions: HashMap::new(),
+            active_tab: String::new(),
+            max_tabs,
+        }
     }
 
     /// Create a new tab and return its ID
@@ -196,13 +316,35 @@ impl TabManager {
         &mut self,
         name: Option<String>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.create_tab_in_context(name, None).await
+    }
+
+    /// Create a new tab in a session's browser context and return its ID
+    ///
+    /// `context` is the session id and its browser context; without one the
+    /// tab shares the default context.
+    pub async fn create_tab_in_context(
+        &mut self,
+        name: Option<String>,
+        context: Option<(String, BrowserContextId)>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        use chromiumoxide::cdp::browser_protocol::target::CreateTargetParams;
+
         if self.tabs.len() >= self.max_tabs {
             return Err("Maximum number of tabs reached".into());
         }
 
         let tab_id = name.unwrap_or_else(|| format!("tab_{}", self.tabs.len()));
-        let page = self.browser.new_page("about:blank").await?;
+        let mut params = CreateTargetParams::new("about:blank");
+        let session_id = context.map(|(session_id, context_id)| {
+            params.browser_context_id = Some(context_id);
+            session_id
+        });
+        let page = self.browser.new_page(params).await?;
         self.tabs.insert(tab_id.clone(), Arc::new(page));
+        if let Some(session_id) = session_id {
+            self.tab_sessions.insert(tab_id.clone(), session_id);
+        }
         self.active_tab = tab_id.clone();
 
         tracing::info!("Created new tab: {}", tab_id);
@@ -252,6 +394,8 @@ impl TabManager {
             return Err(format!("Tab '{}' not found", tab_id).into());
         }
 
+        self.tab_sessions.remove(tab_id);
+
         // Close the page
         if let Some(_page) = self.tabs.remove(tab_id) {
             // Note: page.close() takes ownership, so we need to handle this
@@ -274,6 +418,25 @@ impl TabManager {
         self.tabs.keys().cloned().collect()
     }
 
+    /// Session whose browser context the tab runs in, if any
+    pub fn tab_session(&self, tab_id: &str) -> Option<&str> {
+        self.tab_sessions.get(tab_id).map(String::as_str)
+    }
+
+    /// Drop the tabs of `session_id`, whose pages go away with its context
+    pub async fn close_session_tabs(&mut self, session_id: &str) -> usize {
+        let tab_ids: Vec<String> = self
+            .tab_sessions
+            .iter()
+            .filter(|(_, session)| session.as_str() == session_id)
+            .map(|(tab_id, _)| tab_id.clone())
+            .collect();
+        for tab_id in &tab_ids {
+            let _ = self.close_tab(tab_id).await;
+        }
+        tab_ids.len()
+    }
+
     /// Get the number of active tabs
     pub fn tab_count(&self) -> usize {
         self.tabs.len()
@@ -305,6 +468,14 @@ pub struct BrowserPool {
     config: BrowserConfig,
     browser: Arc<Mutex<Option<Arc<Browser>>>>,
     tab_manager: Arc<Mutex<Option<TabManager>>>,
+    /// Pages checked out per request and reused across requests
+    pages: crate::page_pool::Pool<Page>,
+    /// Isolated browser contexts by session id
+    sessions: Arc<Mutex<crate::browser_context::SessionRegistry<BrowserContextId>>>,
+    /// Cassette pages are served from / recorded to
+    replay: Option<crate::replay::Replay>,
+    /// Device presets and custom emulation profiles by name
+    profiles: crate::emulation::Profiles,
 }
 
 #[cfg(feature = "browser-automation")]
@@ -314,13 +485,30 @@ impl BrowserPool {
         config: BrowserConfig,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("Initializing browser pool with config: {:?}", config);
+        let profiles = match &config.emulation_profiles_path {
+            Some(path) => crate::emulation::Profiles::load(std::path::Path::new(path))?,
+            None => crate::emulation::Profiles::builtin(),
+        };
         Ok(Self {
-            config,
             browser: Arc::new(Mutex::new(None)),
             tab_manager: Arc::new(Mutex::new(None)),
+            pages: crate::page_pool::Pool::new(config.page_pool_config()),
+            sessions: Arc::new(Mutex::new(crate::browser_context::SessionRegistry::new(
+                std::time::Duration::from_secs(config.session_idle_timeout_secs),
+            ))),
+            replay: crate::replay::global().cloned(),
+            profiles,
+            config,
         })
     }
 
+    /// Serve and record page traffic through `replay` instead of the
+    /// cassette configured in the environment
+    pub fn with_replay(mut self, replay: crate::replay::Replay) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
     /// Get or create browser instance (returns reference, not owned)
     async fn ensure_browser_started(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mut browser_lock = self.browser.lock().await;
@@ -388,6 +576,199 @@ impl BrowserPool {
         Ok(())
     }
 
+    /// Running browser, started on first use
+    async fn browser(&self) -> Result<Arc<Browser>, Box<dyn std::error::Error + Send + Sync>> {
+        self.ensure_browser_started().await?;
+        let browser_lock = self.browser.lock().await;
+        browser_lock.as_ref().cloned().ok_or_else(|| "Browser not initialized".into())
+    }
+
+    /// Check a page out of the pool, waiting for one if all are in use
+    ///
+    /// Pages for a session are opened in its browser context rather than taken
+    /// from the idle pages. Fails with a [`PoolError`](crate::page_pool::PoolError)
+    /// when the wait queue is full or no page frees up in time.
+    async fn checkout_page(
+        &self,
+        context: Option<&BrowserContextId>,
+    ) -> Result<(crate::page_pool::Checkout<Page>, Page), Box<dyn std::error::Error + Send + Sync>>
+    {
+        use chromiumoxide::cdp::browser_protocol::target::CreateTargetParams;
+
+        let mut checkout = self.pages.checkout().await?;
+        if checkout.waited() >= std::time::Duration::from_millis(100) {
+            tracing::debug!("Waited {:?} for a browser page", checkout.waited());
+        }
+        let idle = if context.is_none() {
+            checkout.take_idle()
+        } else {
+            None
+        };
+        let page = match idle {
+            Some(page) => page,
+            None => {
+                let mut params = CreateTargetParams::new("about:blank");
+                params.browser_context_id = context.cloned();
+                self.browser().await?.new_page(params).await?
+            }
+        };
+        Ok((checkout, page))
+    }
+
+    /// Hand a page back to the pool, or close it if it should not be reused
+    ///
+    /// Reused pages drop the tab's sessionStorage before going back to
+    /// `about:blank`; cookies and localStorage belong to the context and are
+    /// kept out of the shared one by running such requests in their own.
+    async fn checkin_page(
+        &self,
+        checkout: crate::page_pool::Checkout<Page>,
+        page: Page,
+        reusable: bool,
+    ) {
+        if reusable {
+            let reset = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+                page.evaluate("try { sessionStorage.clear() } catch (e) {}").await?;
+                page.goto("about:blank").await
+            });
+            match reset.await {
+                Ok(Ok(_)) => return checkout.release(page),
+                Ok(Err(e)) => tracing::debug!("Closing browser page that failed to reset: {}", e),
+                Err(_) => tracing::debug!("Closing browser page that timed out resetting"),
+            }
+        }
+        let _ = page.close().await;
+    }
+
+    /// Close the idle pages, e.g. before the browser goes away
+    async fn close_idle_pages(&self) {
+        for page in self.pages.drain_idle() {
+            let _ = page.close().await;
+        }
+    }
+
+    /// Open an isolated browser context for `session_id`
+    ///
+    /// Optional: navigations and tabs with an unknown session id open one with
+    /// default options. Fails if the session is already open with other options.
+    pub async fn open_session(
+        &self,
+        session_id: &str,
+        options: crate::browser_context::ContextOptions,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.dispose_expired_sessions().await;
+        let mut sessions = self.sessions.lock().await;
+        if let Some(existing) = sessions.options(session_id) {
+            if *existing != options {
+                return Err(
+                    format!("Session '{}' is already open with other options", session_id).into()
+                );
+            }
+            sessions.get(session_id);
+            return Ok(());
+        }
+        let browser = self.browser().await?;
+        let context = crate::browser_context::create_context(&browser, &options).await?;
+        tracing::info!("Opened browser context for session {}", session_id);
+        sessions.insert(session_id.to_string(), context, options);
+        Ok(())
+    }
+
+    /// Open `session_id` as [`open_session`](Self::open_session) does and import
+    /// `state` into its context, e.g. cookies saved from an earlier login
+    ///
+    /// The state is imported through a page that is closed afterwards, so only
+    /// cookies and `localStorage` carry over to the session's tabs.
+    pub async fn open_session_with_state(
+        &self,
+        session_id: &str,
+        options: crate::browser_context::ContextOptions,
+        state: &crate::storage_state::StorageState,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.open_session(session_id, options).await?;
+        let context = self.session_context(session_id).await?;
+        let (checkout, page) = self.checkout_page(Some(&context)).await?;
+        let result = crate::storage_state::import(&page, state).await;
+        self.checkin_page(checkout, page, false).await;
+        result
+    }
+
+    /// End `session_id`: close its tabs and dispose its browser context with
+    /// its cookies and storage. Returns whether the session was open.
+    pub async fn end_session(
+        &self,
+        session_id: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(context) = self.sessions.lock().await.remove(session_id) else {
+            return Ok(false);
+        };
+        self.dispose_session(session_id, context).await?;
+        Ok(true)
+    }
+
+    /// Ids of the open sessions
+    pub async fn sessions(&self) -> Vec<String> {
+        self.sessions.lock().await.ids()
+    }
+
+    /// Browser context of `session_id`, opened with default options if needed
+    async fn session_context(
+        &self,
+        session_id: &str,
+    ) -> Result<BrowserContextId, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(context) = self.sessions.lock().await.get(session_id) {
+            return Ok(context);
+        }
+        self.open_session(session_id, Default::default()).await?;
+        self.sessions
+            .lock()
+            .await
+            .get(session_id)
+            .ok_or_else(|| format!("Session '{}' ended while opening", session_id).into())
+    }
+
+    async fn dispose_session(
+        &self,
+        session_id: &str,
+        context: BrowserContextId,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(ref mut manager) = *self.tab_manager.lock().await {
+            manager.close_session_tabs(session_id).await;
+        }
+        let browser = self.browser.lock().await.clone();
+        if let Some(browser) = browser {
+            browser.dispose_browser_context(context).await?;
+        }
+        tracing::info!("Disposed browser context of session {}", session_id);
+        Ok(())
+    }
+
+    /// Dispose the contexts of sessions idle for longer than the timeout
+    async fn dispose_expired_sessions(&self) {
+        let expired = self.sessions.lock().await.take_expired(std::time::Instant::now());
+        for (session_id, context) in expired {
+            tracing::debug!("Session {} idle, disposing its browser context", session_id);
+            if let Err(e) = self.dispose_session(&session_id, context).await {
+                tracing::warn!("Failed to dispose browser context of {}: {}", session_id, e);
+            }
+        }
+    }
+
+    /// Emulation profile by name: a device preset or a custom profile
+    pub fn emulation_profile(&self, name: &str) -> Option<crate::emulation::EmulationProfile> {
+        self.profiles.get(name).cloned()
+    }
+
+    /// Names of the available emulation profiles
+    pub fn emulation_profiles(&self) -> Vec<String> {
+        self.profiles.names()
+    }
+
+    /// Page pool usage: pages in use and idle, queued requests and wait times
+    pub fn pool_metrics(&self) -> crate::page_pool::PoolMetrics {
+        self.pages.metrics()
+    }
+
     /// Navigate to URL and extract semantic data with retry logic
     ///
     /// Best practices 2025: Automatic retry with exponential backoff for resilience.
@@ -418,6 +799,8 @@ impl BrowserPool {
                     }
                     return Ok(data);
                 }
+                // A busy pool is backpressure, not a flaky page: report it right away
+                Err(e) if e.is::<crate::page_pool::PoolError>() => return Err(e),
                 Err(e) => {
                     tracing::warn!("Navigation attempt {} failed for {}: {}", attempt + 1, url, e);
                     last_error = Some(e);
@@ -440,23 +823,124 @@ impl BrowserPool {
         url: &str,
         options: &NavigationOptions,
     ) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
-        // Ensure browser is started
-        self.ensure_browser_started().await?;
+        self.dispose_expired_sessions().await;
+        // Imported state, request cookies and custom JS without a session get a
+        // throwaway context, so the cookies and storage they set never reach the
+        // shared one
+        let isolate = options.storage_state.is_some()
+            || !options.cookies.is_empty()
+            || options.execute_js.is_some();
+        let mut throwaway = None;
+        let context = match &options.session_id {
+            Some(session_id) => Some(self.session_context(session_id).await?),
+            None if isolate => {
+                let browser = self.browser().await?;
+                let context =
+                    crate::browser_context::create_context(&browser, &Default::default()).await?;
+                throwaway = Some(context.clone());
+                Some(context)
+            }
+            None => None,
+        };
+        let result = match self.checkout_page(context.as_ref()).await {
+            Ok((checkout, page)) => {
+                let result = self.record_and_extract(&page, url, options).await;
+                // Pages that failed mid-navigation may be in any state; start afresh
+                // next time. Pages in a session or throwaway context are closed too:
+                // their state lives in the context. So are emulating pages, whose
+                // overrides would leak into the next request.
+                let reusable = result.is_ok() && context.is_none() && options.emulation.is_none();
+                self.checkin_page(checkout, page, reusable).await;
+                result
+            }
+            Err(e) => Err(e),
+        };
+        if let Some(context) = throwaway {
+            if let Err(e) = self.browser().await?.dispose_browser_context(context).await {
+                tracing::debug!("Failed to dispose throwaway browser context: {}", e);
+            }
+        }
+        if let Some(session_id) = &options.session_id {
+            self.sessions.lock().await.get(session_id);
+        }
+        result
+    }
 
-        // Get browser reference
-        let browser_lock = self.browser.lock().await;
-        let browser = browser_lock.as_ref().ok_or("Browser not initialized")?;
+    /// Load `url` in `page` with HAR recording and replay as configured
+    async fn record_and_extract(
+        &self,
+        page: &Page,
+        url: &str,
+        options: &NavigationOptions,
+    ) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(profile) = &options.emulation {
+            crate::emulation::apply(page, profile).await?;
+        }
+        // Imported before recording so its blank origin loads stay out of the HAR and replay
+        if let Some(state) = &options.storage_state {
+            crate::storage_state::import(page, state).await?;
+        }
 
-        let page = browser.new_page("about:blank").await?;
+        // Start recording and intercepting before anything touches the network
+        let har = match &options.har {
+            Some(har_options) => {
+                Some(crate::har::HarCapture::start(page, har_options.clone()).await?)
+            }
+            None => None,
+        };
+        let interception = match &self.replay {
+            Some(replay) => Some(crate::replay::intercept(page, replay.clone()).await?),
+            None => None,
+        };
 
+        let result = self.load_and_extract(page, url, options).await;
+        if let Some(interception) = interception {
+            interception.stop(page).await;
+        }
+
+        // The HAR is kept for failed navigations too; that is when it is most useful
+        let Some(har) = har else {
+            return result;
+        };
+        let title = result.as_ref().ok().and_then(|data| data.title.clone()).unwrap_or_default();
+        // Recording problems are logged rather than hiding the navigation result
+        let har = match har.finish(page, &title).await {
+            Ok(har) => har,
+            Err(e) => {
+                tracing::warn!("HAR recording failed: {}", e);
+                return result;
+            }
+        };
+        if let Some(path) = options.har.as_ref().and_then(|o| o.path.as_ref()) {
+            match har.save(path) {
+                Ok(()) => tracing::info!(
+                    "HAR with {} entries written to {}",
+                    har.log.entries.len(),
+                    path.display()
+                ),
+                Err(e) => tracing::warn!("Failed to write HAR to {}: {}", path.display(), e),
+            }
+        }
+        let mut semantic_data = result?;
+        semantic_data.har = Some(har);
+        Ok(semantic_data)
+    }
+
+    /// Navigate `page` to `url`, wait for it and extract its semantic data
+    async fn load_and_extract(
+        &self,
+        page: &Page,
+        url: &str,
+        options: &NavigationOptions,
+    ) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
         // Set cookies if provided
         for (name, value) in &options.cookies {
-            self.set_cookie(&page, url, name, value).await?;
+            self.set_cookie(page, url, name, value).await?;
         }
 
         // Block resources if configured
         if self.config.block_ads || self.config.block_images {
-            self.setup_resource_blocking(&page).await?;
+            self.setup_resource_blocking(page).await?;
         }
 
         // Navigate to URL
@@ -470,7 +954,7 @@ impl BrowserPool {
         // Wait for selector if specified
         if let Some(selector) = &options.wait_for_selector {
             tracing::debug!("Waiting for selector: {}", selector);
-            self.wait_for_element(&page, selector, timeout).await?;
+            self.wait_for_element(page, selector, timeout).await?;
         }
 
         // Execute custom JS if provided
@@ -480,7 +964,10 @@ impl BrowserPool {
         }
 
         // Extract semantic data
-        let semantic_data = self.extract_semantic_data(&page, url, options.take_screenshot).await?;
+        let mut semantic_data = self.extract_semantic_data(page, url, options).await?;
+        if options.export_storage_state {
+            semantic_data.storage_state = Some(crate::storage_state::export(page).await?);
+        }
 
         tracing::info!("Successfully extracted semantic data from: {}", url);
         Ok(semantic_data)
@@ -490,14 +977,15 @@ impl BrowserPool {
     async fn set_cookie(
         &self,
         page: &Page,
-        _url: &str,
+        url: &str,
         name: &str,
         value: &str,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use chromiumoxide::cdp::browser_protocol::network::SetCookieParams;
 
-        // SetCookieParams takes name and value directly, not CookieParam
-        let params = SetCookieParams::new(name, value);
+        // Without a URL or domain the browser has nowhere to put the cookie
+        let mut params = SetCookieParams::new(name, value);
+        params.url = Some(url.to_string());
 
         page.execute(params).await?;
         Ok(())
@@ -602,7 +1090,7 @@ impl BrowserPool {
         &self,
         page: &Page,
         original_url: &str,
-        take_screenshot: bool,
+        options: &NavigationOptions,
     ) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
         // Get final URL after redirects
         let final_url = page.url().await?.unwrap_or_else(|| original_url.to_string());
@@ -617,6 +1105,11 @@ impl BrowserPool {
         let (
             json_ld,
             microdata,
+            rdfa,
+            microformats,
+            links,
+            article,
+            markdown,
             text_content,
             meta_description,
             meta_keywords,
@@ -624,6 +1117,7 @@ impl BrowserPool {
             canonical_url,
             open_graph,
             twitter_card,
+            citation,
         ) = {
             let document = scraper::Html::parse_document(&html);
 
@@ -639,11 +1133,18 @@ impl BrowserPool {
                 }
             }
 
-            // Extract microdata
-            let microdata = self.extract_microdata(&document)?;
+            // Extract microdata, RDFa and microformats (relative URLs resolve against the
+            // post-redirect URL)
+            let microdata = crate::parser::extract_microdata(&document, Some(&final_url))?;
+            let rdfa = crate::rdfa::extract_rdfa(&document, Some(&final_url));
+            let microformats =
+                crate::microformats::extract_microformats(&document, Some(&final_url));
+            let links = crate::links::extract_links(&document, Some(&final_url));
 
             // Extract text content (for NER and minimalist view)
-            let text_content = self.extract_text_content(&document)?;
+            let (text_content, article) =
+                crate::readability::extract_text(&document, Some(&final_url), options.content_mode);
+            let markdown = crate::markdown::snapshot_markdown(&document, &final_url);
 
             // Phase 1: Extract meta tags
             let meta_description = self.extract_meta_description(&document);
@@ -652,10 +1153,16 @@ impl BrowserPool {
             let canonical_url = self.extract_canonical_url(&document);
             let open_graph = self.extract_open_graph(&document);
             let twitter_card = self.extract_twitter_card(&document);
+            let citation = crate::citation::extract_citation(&document, Some(&final_url));
 
             (
                 json_ld,
                 microdata,
+                rdfa,
+                microformats,
+                links,
+                article,
+                markdown,
                 text_content,
                 meta_description,
                 meta_keywords,
@@ -663,23 +1170,36 @@ impl BrowserPool {
                 canonical_url,
                 open_graph,
                 twitter_card,
+                citation,
             )
         };
         // document is dropped here, before screenshot await
 
-        // Take screenshot if requested
-        let screenshot = if take_screenshot {
-            Some(self.take_screenshot_internal(page).await?)
+        // Take screenshot and PDF if requested
+        let screenshot = if options.take_screenshot {
+            Some(crate::capture::capture_screenshot(page, &options.screenshot_options).await?)
         } else {
             None
         };
+        let pdf = match &options.print_pdf {
+            Some(pdf_options) => Some(crate::capture::print_pdf(page, pdf_options).await?),
+            None => None,
+        };
 
         Ok(SemanticData {
             title,
             json_ld,
             microdata,
+            rdfa,
+            microformats,
+            links,
+            article,
+            markdown,
             text_content,
             screenshot,
+            pdf,
+            har: None,
+            storage_state: None,
             final_url,
             // Phase 1: Meta tags
             meta_description,
@@ -688,67 +1208,10 @@ impl BrowserPool {
             canonical_url,
             open_graph,
             twitter_card,
+            citation,
         })
     }
 
-    /// Extract microdata from HTML document
-    fn extract_microdata(
-        &self,
-        document: &scraper::Html,
-    ) -> Result<Vec<crate::parser::MicrodataItem>, Box<dyn std::error::Error + Send + Sync>> {
-        let itemscope_selector = scraper::Selector::parse("[itemscope]")
-            .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-        let itemprop_selector = scraper::Selector::parse("[itemprop]")
-            .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-
-        let mut microdata = Vec::new();
-        for element in document.select(&itemscope_selector) {
-            let item_type = element.value().attr("itemtype").unwrap_or("").to_string();
-            let mut properties = HashMap::new();
-            for prop in element.select(&itemprop_selector) {
-                let prop_name = prop.value().attr("itemprop").unwrap_or("").to_string();
-                let prop_value = prop.text().collect::<String>();
-                properties.entry(prop_name).or_insert_with(Vec::new).push(prop_value);
-            }
-            microdata.push(crate::parser::MicrodataItem { item_type, properties });
-        }
-
-        Ok(microdata)
-    }
-
-    /// Extract clean text content (exclude nav, footer, ads)
-    fn extract_text_content(
-        &self,
-        document: &scraper::Html,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Try to find main content area
-        let content_selectors = ["main", "article", "[role=\"main\"]", ".content", "#content"];
-
-        for selector_str in &content_selectors {
-            if let Ok(selector) = scraper::Selector::parse(selector_str) {
-                if let Some(element) = document.select(&selector).next() {
-                    let text: String = element.text().collect::<Vec<_>>().join(" ");
-                    // Clean up whitespace
-                    let cleaned = text.split_whitespace().collect::<Vec<_>>().join(" ");
-                    if !cleaned.is_empty() {
-                        return Ok(cleaned);
-                    }
-                }
-            }
-        }
-
-        // Fallback: get body text
-        if let Ok(body_selector) = scraper::Selector::parse("body") {
-            if let Some(body) = document.select(&body_selector).next() {
-                let text: String = body.text().collect::<Vec<_>>().join(" ");
-                let cleaned = text.split_whitespace().collect::<Vec<_>>().join(" ");
-                return Ok(cleaned);
-            }
-        }
-
-        Ok(String::new())
-    }
-
     // Phase 1: Enhanced Meta Tags Extraction Functions (2025 best practices)
 
     /// Extract meta description from HTML
@@ -837,17 +1300,6 @@ impl BrowserPool {
         twitter_data
     }
 
-    /// Take screenshot of current page
-    async fn take_screenshot_internal(
-        &self,
-        page: &Page,
-    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-        use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotParams;
-
-        let screenshot = page.screenshot(CaptureScreenshotParams::default()).await?;
-        Ok(screenshot)
-    }
-
     /// Public method to take screenshot
     pub async fn take_screenshot(
         &self,
@@ -864,17 +1316,30 @@ impl BrowserPool {
         url: &str,
         js_code: &str,
     ) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
-        // Ensure browser is started
-        self.ensure_browser_started().await?;
-
-        // Get browser reference
-        let browser_lock = self.browser.lock().await;
-        let browser = browser_lock.as_ref().ok_or("Browser not initialized")?;
-
-        let page = browser.new_page(url).await?;
+        let (checkout, page) = self.checkout_page(None).await?;
+        let result: Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> = async {
+            page.goto(url).await?;
+            Ok(page.evaluate(js_code).await?.into_value()?)
+        }
+        .await;
+        self.checkin_page(checkout, page, result.is_ok()).await;
+        result
+    }
 
-        let result = page.evaluate(js_code).await?;
-        Ok(result.into_value()?)
+    /// Open a page and snapshot its pruned accessibility tree
+    pub async fn accessibility_tree(
+        &self,
+        url: &str,
+        options: &crate::accessibility::AccessibilityOptions,
+    ) -> Result<crate::accessibility::AccessibilityTree, Box<dyn std::error::Error + Send + Sync>>
+    {
+        let (checkout, page) = self.checkout_page(None).await?;
+        let tree = match page.goto(url).await {
+            Ok(_) => crate::accessibility::snapshot(&page, options).await,
+            Err(e) => Err(e.into()),
+        };
+        self.checkin_page(checkout, page, tree.is_ok()).await;
+        tree
     }
 
     /// Health check for browser pool
@@ -926,7 +1391,7 @@ impl BrowserPool {
         if !self.health_check().await {
             tracing::warn!("Browser unhealthy, attempting restart...");
 
-            // Shutdown existing browser
+            // Shutdown existing browser (this also drops the idle pages)
             let _ = self.shutdown().await;
 
             // Clear the browser reference
@@ -990,6 +1455,24 @@ impl BrowserPool {
         }
     }
 
+    /// Create a new tab in the browser context of `session_id` and return its ID
+    ///
+    /// Ending the session closes the tab.
+    pub async fn create_session_tab(
+        &self,
+        session_id: &str,
+        name: Option<String>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.ensure_tab_manager().await?;
+        let context = self.session_context(session_id).await?;
+        let mut tab_manager = self.tab_manager.lock().await;
+        if let Some(ref mut manager) = *tab_manager {
+            manager.create_tab_in_context(name, Some((session_id.to_string(), context))).await
+        } else {
+            Err("Tab manager not initialized".into())
+        }
+    }
+
     /// Switch to a specific tab
     pub async fn switch_tab(
         &self,
@@ -1071,7 +1554,14 @@ impl BrowserPool {
 
     /// Shutdown the browser pool
     pub async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // Close all tabs first
+        // Session contexts go first, taking their tabs with them
+        let sessions = self.sessions.lock().await.drain();
+        for (session_id, context) in sessions {
+            let dispose = self.dispose_session(&session_id, context);
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(5), dispose).await;
+        }
+
+        // Then the remaining tabs
         let mut tab_manager = self.tab_manager.lock().await;
         if let Some(ref mut manager) = *tab_manager {
             let tab_ids: Vec<String> = manager.list_tabs();
@@ -1080,6 +1570,9 @@ impl BrowserPool {
             }
         }
 
+        // Pooled pages belong to this browser
+        self.close_idle_pages().await;
+
         // Clear the browser reference - Arc will handle cleanup when all references are dropped
         let mut browser_lock = self.browser.lock().await;
         if browser_lock.is_some() {
@@ -1126,6 +1619,19 @@ impl BrowserPool {
         Err("browser-automation feature not enabled".into())
     }
 
+    pub async fn accessibility_tree(
+        &self,
+        _url: &str,
+        _options: &crate::accessibility::AccessibilityOptions,
+    ) -> Result<crate::accessibility::AccessibilityTree, Box<dyn std::error::Error + Send + Sync>>
+    {
+        Err("browser-automation feature not enabled".into())
+    }
+
+    pub fn pool_metrics(&self) -> crate::page_pool::PoolMetrics {
+        crate::page_pool::PoolMetrics::default()
+    }
+
     pub async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         Ok(())
     }
@@ -1143,6 +1649,11 @@ mod tests {
         assert!(!config.block_images);
         assert_eq!(config.timeout_secs, 30);
         assert_eq!(config.pool_size, 2);
+        let pool = config.page_pool_config();
+        assert_eq!(pool.size, 2);
+        assert_eq!(pool.max_waiting, 32);
+        assert_eq!(pool.acquire_timeout, std::time::Duration::from_secs(30));
+        assert_eq!(config.session_idle_timeout_secs, 900);
     }
 
     #[test]
@@ -1150,8 +1661,16 @@ mod tests {
         let options = NavigationOptions::default();
         assert!(options.wait_for_selector.is_none());
         assert!(options.cookies.is_empty());
+        assert!(options.storage_state.is_none());
+        assert!(!options.export_storage_state);
         assert!(!options.take_screenshot);
+        assert_eq!(options.screenshot_options.format, crate::capture::ImageFormat::Png);
+        assert!(options.print_pdf.is_none());
+        assert!(options.har.is_none());
         assert!(options.execute_js.is_none());
+        assert_eq!(options.content_mode, crate::readability::ContentMode::Readability);
+        assert!(options.session_id.is_none());
+        assert!(options.emulation.is_none());
     }
 
     #[tokio::test]
@@ -0,0 +1,189 @@
+//! Isolated browser contexts per session
+//!
+//! Navigations and tabs that carry a session id run in their own
+//! incognito-style browser context (CDP `Target.createBrowserContext`): a
+//! separate cookie jar, storage and cache, optionally with its own proxy and
+//! granted permissions. The context is created on the session's first use and
+//! disposed, closing its pages, when the session is ended or has been idle for
+//! longer than the configured timeout. Requests without a session id keep using
+//! the shared default context.
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::browser::Browser;
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Settings of a session's browser context, fixed when it is created
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextOptions {
+    /// Proxy for the context's traffic, e.g. `http://proxy:3128` or `socks5://127.0.0.1:1080`
+    pub proxy_server: Option<String>,
+    /// Hosts that bypass the proxy, e.g. `localhost,*.internal`
+    pub proxy_bypass_list: Option<String>,
+    /// Permissions granted without prompting, as CDP `PermissionType` names
+    /// (`geolocation`, `notifications`, `clipboardReadWrite`, ...)
+    pub permissions: Vec<String>,
+    /// Origin the permissions apply to (default: all origins)
+    pub permission_origin: Option<String>,
+}
+
+#[derive(Debug)]
+struct Session<C> {
+    context: C,
+    options: ContextOptions,
+    last_used: Instant,
+}
+
+/// Live sessions and their browser contexts
+///
+/// Generic over the context handle so the bookkeeping does not need a browser.
+#[derive(Debug)]
+pub struct SessionRegistry<C> {
+    sessions: HashMap<String, Session<C>>,
+    idle_timeout: Duration,
+}
+
+impl<C: Clone> SessionRegistry<C> {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self { sessions: HashMap::new(), idle_timeout }
+    }
+
+    /// Context of `session_id`, marking the session as used
+    pub fn get(&mut self, session_id: &str) -> Option<C> {
+        let session = self.sessions.get_mut(session_id)?;
+        session.last_used = Instant::now();
+        Some(session.context.clone())
+    }
+
+    /// Options the context of `session_id` was created with
+    pub fn options(&self, session_id: &str) -> Option<&ContextOptions> {
+        self.sessions.get(session_id).map(|s| &s.options)
+    }
+
+    pub fn insert(&mut self, session_id: String, context: C, options: ContextOptions) {
+        self.sessions.insert(session_id, Session { context, options, last_used: Instant::now() });
+    }
+
+    /// Forget `session_id`, returning its context for disposal
+    pub fn remove(&mut self, session_id: &str) -> Option<C> {
+        self.sessions.remove(session_id).map(|s| s.context)
+    }
+
+    /// Forget the sessions idle since before `now - idle_timeout`
+    pub fn take_expired(&mut self, now: Instant) -> Vec<(String, C)> {
+        let expired: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, s)| now.saturating_duration_since(s.last_used) > self.idle_timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| self.sessions.remove(&id).map(|s| (id, s.context)))
+            .collect()
+    }
+
+    /// Forget every session
+    pub fn drain(&mut self) -> Vec<(String, C)> {
+        self.sessions.drain().map(|(id, s)| (id, s.context)).collect()
+    }
+
+    /// Ids of the live sessions, sorted
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.sessions.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+/// Create a browser context with `options` applied
+#[cfg(feature = "browser-automation")]
+pub async fn create_context(
+    browser: &Browser,
+    options: &ContextOptions,
+) -> Result<BrowserContextId, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::browser::{GrantPermissionsParams, PermissionType};
+    use chromiumoxide::cdp::browser_protocol::target::CreateBrowserContextParams;
+
+    // Check the permissions before anything is created
+    let permissions = options
+        .permissions
+        .iter()
+        .map(|p| p.parse::<PermissionType>().map_err(|_| format!("Unknown permission: {}", p)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let params = CreateBrowserContextParams {
+        dispose_on_detach: Some(true),
+        proxy_server: options.proxy_server.clone(),
+        proxy_bypass_list: options.proxy_bypass_list.clone(),
+        origins_with_universal_network_access: None,
+    };
+    let context = browser.create_browser_context(params).await?;
+
+    if !permissions.is_empty() {
+        let grant = GrantPermissionsParams {
+            permissions,
+            origin: options.permission_origin.clone(),
+            browser_context_id: Some(context.clone()),
+        };
+        if let Err(e) = browser.execute(grant).await {
+            let _ = browser.dispose_browser_context(context).await;
+            return Err(e.into());
+        }
+    }
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_registry() {
+        let mut registry = SessionRegistry::new(Duration::from_secs(60));
+        assert!(registry.is_empty());
+
+        let proxied = ContextOptions {
+            proxy_server: Some("socks5://127.0.0.1:1080".to_string()),
+            ..Default::default()
+        };
+        registry.insert("agent-a".to_string(), 1, ContextOptions::default());
+        registry.insert("agent-b".to_string(), 2, proxied.clone());
+        assert_eq!(registry.ids(), vec!["agent-a", "agent-b"]);
+        assert_eq!(registry.get("agent-b"), Some(2));
+        assert_eq!(registry.get("agent-c"), None);
+        assert_eq!(registry.options("agent-b"), Some(&proxied));
+
+        // Nothing is idle yet; a minute later, both are
+        assert!(registry.take_expired(Instant::now()).is_empty());
+        let later = Instant::now() + Duration::from_secs(61);
+        let mut expired = registry.take_expired(later);
+        expired.sort();
+        assert_eq!(expired, vec![("agent-a".to_string(), 1), ("agent-b".to_string(), 2)]);
+        assert!(registry.is_empty());
+
+        registry.insert("agent-a".to_string(), 3, ContextOptions::default());
+        assert_eq!(registry.remove("agent-a"), Some(3));
+        assert_eq!(registry.remove("agent-a"), None);
+        registry.insert("agent-d".to_string(), 4, ContextOptions::default());
+        assert_eq!(registry.drain(), vec![("agent-d".to_string(), 4)]);
+        assert_eq!(registry.len(), 0);
+
+        let options: ContextOptions =
+            serde_json::from_str(r#"{"permissions": ["geolocation"]}"#).unwrap();
+        assert_eq!(options.permissions, vec!["geolocation"]);
+        assert!(options.proxy_server.is_none());
+    }
+}
@@ -0,0 +1,326 @@
+//! Screenshot and PDF capture
+//!
+//! Options for what to capture from a rendered page: the viewport, the whole
+//! scrollable page or a single element, as PNG, JPEG or WebP at a chosen
+//! device scale factor, or the page printed to PDF. Screenshots are taken with
+//! a clip rectangle rather than by resizing the emulated viewport, so they
+//! leave the page's device metrics untouched.
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+
+/// Screenshot image encoding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageFormat {
+    /// MIME type of the encoded image
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    /// Whether the format takes a `quality` setting
+    pub fn is_lossy(&self) -> bool {
+        !matches!(self, ImageFormat::Png)
+    }
+}
+
+/// What to screenshot and how to encode it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotOptions {
+    /// Capture the whole scrollable page instead of the viewport
+    pub full_page: bool,
+    /// Capture only the first element matching this CSS selector
+    pub selector: Option<String>,
+    pub format: ImageFormat,
+    /// Compression quality 0-100 (JPEG and WebP only)
+    pub quality: Option<u8>,
+    /// Pixels per CSS pixel (default: the page's own, usually 1)
+    pub device_scale_factor: Option<f64>,
+}
+
+impl ScreenshotOptions {
+    /// Check the options before anything is sent to the browser
+    pub fn validate(&self) -> Result<(), String> {
+        if self.full_page && self.selector.is_some() {
+            return Err("full_page and selector are mutually exclusive".to_string());
+        }
+        if let Some(quality) = self.quality {
+            if !self.format.is_lossy() {
+                return Err("quality only applies to jpeg and webp screenshots".to_string());
+            }
+            if quality > 100 {
+                return Err(format!("quality must be between 0 and 100, got {}", quality));
+            }
+        }
+        if let Some(scale) = self.device_scale_factor {
+            if !(scale > 0.0 && scale <= 4.0) {
+                return Err(format!(
+                    "device_scale_factor must be greater than 0 and at most 4, got {}",
+                    scale
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How to print the page to PDF
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    pub landscape: bool,
+    /// Print background colours and images (default: true)
+    pub print_background: bool,
+    /// Rendering scale 0.1-2 (default: 1)
+    pub scale: Option<f64>,
+    /// Paper width in inches (default: 8.5)
+    pub paper_width: Option<f64>,
+    /// Paper height in inches (default: 11)
+    pub paper_height: Option<f64>,
+    /// Pages to print, e.g. "1-5, 8"
+    pub page_ranges: Option<String>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            landscape: false,
+            print_background: true,
+            scale: None,
+            paper_width: None,
+            paper_height: None,
+            page_ranges: None,
+        }
+    }
+}
+
+impl PdfOptions {
+    /// Check the options before anything is sent to the browser
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(scale) = self.scale {
+            if !(0.1..=2.0).contains(&scale) {
+                return Err(format!("scale must be between 0.1 and 2, got {}", scale));
+            }
+        }
+        for size in [self.paper_width, self.paper_height].into_iter().flatten() {
+            if size <= 0.0 {
+                return Err(format!("paper size must be positive, got {}", size));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Captures requested alongside a browse
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaptureRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<ScreenshotOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf: Option<PdfOptions>,
+}
+
+impl CaptureRequest {
+    /// Whether nothing needs to be captured
+    pub fn is_empty(&self) -> bool {
+        self.screenshot.is_none() && self.pdf.is_none()
+    }
+
+    /// Check the requested screenshot and PDF options
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(screenshot) = &self.screenshot {
+            screenshot.validate()?;
+        }
+        if let Some(pdf) = &self.pdf {
+            pdf.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Captured image or document; `data` is base64 encoded in JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Capture {
+    pub media_type: String,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl Capture {
+    pub fn screenshot(data: Vec<u8>, format: ImageFormat) -> Self {
+        Self { media_type: format.media_type().to_string(), data }
+    }
+
+    pub fn pdf(data: Vec<u8>) -> Self {
+        Self { media_type: "application/pdf".to_string(), data }
+    }
+}
+
+mod base64_bytes {
+    use base64::{engine::general_purpose, Engine as _};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        general_purpose::STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Take a screenshot of `page`
+#[cfg(feature = "browser-automation")]
+pub async fn capture_screenshot(
+    page: &Page,
+    options: &ScreenshotOptions,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, Viewport};
+    use chromiumoxide::page::ScreenshotParams;
+
+    options.validate()?;
+
+    let format = match options.format {
+        ImageFormat::Png => CaptureScreenshotFormat::Png,
+        ImageFormat::Jpeg => CaptureScreenshotFormat::Jpeg,
+        ImageFormat::Webp => CaptureScreenshotFormat::Webp,
+    };
+    let mut params = ScreenshotParams::builder().format(format);
+    if let Some(quality) = options.quality {
+        params = params.quality(quality);
+    }
+
+    // Clip rectangles are in document coordinates
+    let clip = if let Some(selector) = &options.selector {
+        let element = page.find_element(selector.as_str()).await?;
+        element.scroll_into_view().await?;
+        let bounds = element.bounding_box().await?;
+        let metrics = page.layout_metrics().await?;
+        Some(Viewport {
+            x: bounds.x + metrics.css_layout_viewport.page_x as f64,
+            y: bounds.y + metrics.css_layout_viewport.page_y as f64,
+            width: bounds.width,
+            height: bounds.height,
+            scale: 1.0,
+        })
+    } else if options.full_page {
+        let content = page.layout_metrics().await?.css_content_size;
+        Some(Viewport { x: 0.0, y: 0.0, width: content.width, height: content.height, scale: 1.0 })
+    } else if options.device_scale_factor.is_some() {
+        let viewport = page.layout_metrics().await?.css_visual_viewport;
+        Some(Viewport {
+            x: viewport.page_x,
+            y: viewport.page_y,
+            width: viewport.client_width,
+            height: viewport.client_height,
+            scale: 1.0,
+        })
+    } else {
+        None
+    };
+
+    if let Some(mut clip) = clip {
+        if clip.width < 1.0 || clip.height < 1.0 {
+            return Err("Nothing to capture: the target has no visible area".into());
+        }
+        clip.scale = options.device_scale_factor.unwrap_or(1.0);
+        params = params.clip(clip).capture_beyond_viewport(true);
+    }
+
+    Ok(page.screenshot(params.build()).await?)
+}
+
+/// Print `page` to PDF
+#[cfg(feature = "browser-automation")]
+pub async fn print_pdf(
+    page: &Page,
+    options: &PdfOptions,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
+
+    options.validate()?;
+
+    let params = PrintToPdfParams {
+        landscape: Some(options.landscape),
+        print_background: Some(options.print_background),
+        scale: options.scale,
+        paper_width: options.paper_width,
+        paper_height: options.paper_height,
+        page_ranges: options.page_ranges.clone(),
+        ..Default::default()
+    };
+    Ok(page.pdf(params).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screenshot_options_validation() {
+        let options: ScreenshotOptions =
+            serde_json::from_str(r#"{"format": "jpeg", "quality": 80, "full_page": true}"#)
+                .unwrap();
+        assert_eq!(options.format, ImageFormat::Jpeg);
+        assert_eq!(options.format.media_type(), "image/jpeg");
+        assert!(options.validate().is_ok());
+
+        let defaults: ScreenshotOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(defaults, ScreenshotOptions::default());
+        assert_eq!(defaults.format, ImageFormat::Png);
+
+        let png_quality = ScreenshotOptions { quality: Some(50), ..Default::default() };
+        assert!(png_quality.validate().is_err());
+        let too_good = ScreenshotOptions {
+            format: ImageFormat::Webp,
+            quality: Some(101),
+            ..Default::default()
+        };
+        assert!(too_good.validate().is_err());
+        let both = ScreenshotOptions {
+            full_page: true,
+            selector: Some("#chart".to_string()),
+            ..Default::default()
+        };
+        assert!(both.validate().is_err());
+        let flat = ScreenshotOptions { device_scale_factor: Some(0.0), ..Default::default() };
+        assert!(flat.validate().is_err());
+    }
+
+    #[test]
+    fn test_capture_request_and_encoding() {
+        let request: CaptureRequest =
+            serde_json::from_str(r#"{"pdf": {"landscape": true, "scale": 0.5}}"#).unwrap();
+        assert!(!request.is_empty());
+        assert!(request.screenshot.is_none());
+        let pdf = request.pdf.as_ref().unwrap();
+        assert!(pdf.landscape);
+        assert!(pdf.print_background);
+        assert!(request.validate().is_ok());
+        assert!(CaptureRequest::default().is_empty());
+
+        let bad = PdfOptions { scale: Some(3.0), ..Default::default() };
+        assert!(bad.validate().is_err());
+
+        let capture = Capture::screenshot(vec![0x89, b'P', b'N', b'G'], ImageFormat::Png);
+        let json = serde_json::to_value(&capture).unwrap();
+        assert_eq!(json["media_type"], "image/png");
+        assert_eq!(json["data"], "iVBORw==");
+        let back: Capture = serde_json::from_value(json).unwrap();
+        assert_eq!(back, capture);
+    }
+}
@@ -0,0 +1,319 @@
+//! Character encoding detection for fetched documents
+//!
+//! Implements the WHATWG encoding sniffing algorithm used by browsers: a byte
+//! order mark wins, then the `charset` parameter of the `Content-Type` header,
+//! then a `<meta charset>` / `<meta http-equiv="Content-Type">` declaration
+//! found by prescanning the first 1024 bytes (or the `encoding` of an XML
+//! declaration). Without any declaration, bytes that are valid UTF-8 are read as
+//! UTF-8 and anything else as windows-1252.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252, X_USER_DEFINED};
+
+/// Number of bytes the `<meta>` prescan looks at
+const PRESCAN_BYTES: usize = 1024;
+
+/// Decode a document, returning its text and the encoding used.
+///
+/// Malformed sequences are replaced with U+FFFD; the BOM is stripped.
+pub fn decode(body: &[u8], content_type: Option<&str>) -> (String, &'static Encoding) {
+    let encoding = sniff_encoding(body, content_type);
+    let (text, encoding, _) = encoding.decode(body);
+    (text.into_owned(), encoding)
+}
+
+/// Determine the encoding of a document with the WHATWG sniffing algorithm
+pub fn sniff_encoding(body: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return encoding;
+    }
+    if let Some(encoding) = content_type.and_then(charset_from_content_type) {
+        return encoding;
+    }
+    let head = &body[..body.len().min(PRESCAN_BYTES)];
+    let declared = if head.starts_with(b"<?xml") {
+        xml_declaration(head)
+    } else {
+        prescan(head)
+    };
+    if let Some(encoding) = declared {
+        return encoding;
+    }
+    if std::str::from_utf8(body).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Encoding named by the `charset` parameter of a `Content-Type` value
+pub fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(|c| c == '"' || c == '\'').as_bytes())
+    })
+}
+
+/// A `<meta>` declaration can only name an ASCII-compatible encoding
+fn meta_encoding(label: &[u8]) -> Option<&'static Encoding> {
+    let encoding = Encoding::for_label(label)?;
+    Some(if encoding == UTF_16BE || encoding == UTF_16LE {
+        UTF_8
+    } else if encoding == X_USER_DEFINED {
+        WINDOWS_1252
+    } else {
+        encoding
+    })
+}
+
+/// `encoding="..."` pseudo-attribute of an XML declaration
+fn xml_declaration(head: &[u8]) -> Option<&'static Encoding> {
+    let end = head.iter().position(|&b| b == b'>')?;
+    let decl = &head[..end];
+    let start = find(decl, b"encoding")? + b"encoding".len();
+    let rest = trim_start(&decl[start..]).strip_prefix(b"=")?;
+    let rest = trim_start(rest);
+    let quote = *rest.first().filter(|&&q| q == b'"' || q == b'\'')?;
+    let value = &rest[1..];
+    let value = &value[..value.iter().position(|&b| b == quote)?];
+    meta_encoding(value)
+}
+
+/// Prescan a byte stream for a `<meta>` encoding declaration
+/// (WHATWG HTML, "prescan a byte stream to determine its encoding")
+fn prescan(head: &[u8]) -> Option<&'static Encoding> {
+    let mut pos = 0;
+    while pos < head.len() {
+        let rest = &head[pos..];
+        if rest.starts_with(b"<!--") {
+            pos += find(&rest[4..], b"-->").map_or(rest.len(), |end| end + 7);
+        } else if starts_with_ignore_case(rest, b"<meta")
+            && rest.get(5).is_some_and(|&b| is_space(b) || b == b'/')
+        {
+            pos += 5;
+            if let Some(encoding) = meta_declaration(head, &mut pos) {
+                return Some(encoding);
+            }
+        } else if rest.len() > 2
+            && rest[0] == b'<'
+            && (rest[1].is_ascii_alphabetic() || (rest[1] == b'/' && rest[2].is_ascii_alphabetic()))
+        {
+            pos += rest[1..]
+                .iter()
+                .position(|&b| is_space(b) || b == b'>')
+                .map_or(rest.len(), |p| p + 1);
+            while get_attribute(head, &mut pos).is_some() {}
+        } else if rest.starts_with(b"<!") || rest.starts_with(b"</") || rest.starts_with(b"<?") {
+            pos += rest.iter().position(|&b| b == b'>').map_or(rest.len(), |p| p + 1);
+        } else {
+            pos += 1;
+        }
+    }
+    None
+}
+
+/// Attributes of a `<meta>` tag, starting right after the tag name
+fn meta_declaration(head: &[u8], pos: &mut usize) -> Option<&'static Encoding> {
+    let mut seen = Vec::new();
+    let mut got_pragma = false;
+    // None: no charset yet; Some(false): from `charset`; Some(true): from `content`
+    let mut need_pragma = None;
+    let mut charset = None;
+
+    while let Some((name, value)) = get_attribute(head, pos) {
+        if seen.contains(&name) {
+            continue;
+        }
+        match name.as_slice() {
+            b"http-equiv" => got_pragma |= value.eq_ignore_ascii_case(b"content-type"),
+            b"content" if charset.is_none() => {
+                if let Some(label) = charset_from_meta_content(&value) {
+                    charset = meta_encoding(&label);
+                    need_pragma = Some(true);
+                }
+            }
+            b"charset" => {
+                charset = meta_encoding(&value);
+                need_pragma = Some(false);
+            }
+            _ => {}
+        }
+        seen.push(name);
+    }
+
+    match need_pragma {
+        Some(true) if !got_pragma => None,
+        Some(_) => charset,
+        None => None,
+    }
+}
+
+/// Charset named in a `<meta http-equiv="Content-Type" content="...">` value
+fn charset_from_meta_content(content: &[u8]) -> Option<Vec<u8>> {
+    let mut rest = content;
+    loop {
+        let start = find_ignore_case(rest, b"charset")?;
+        rest = trim_start(&rest[start + b"charset".len()..]);
+        if let Some(value) = rest.strip_prefix(b"=") {
+            rest = trim_start(value);
+            break;
+        }
+    }
+    match rest.first() {
+        Some(&quote @ (b'"' | b'\'')) => {
+            let value = &rest[1..];
+            value.iter().position(|&b| b == quote).map(|end| value[..end].to_vec())
+        }
+        Some(_) => {
+            let end = rest.iter().position(|&b| is_space(b) || b == b';').unwrap_or(rest.len());
+            Some(rest[..end].to_vec())
+        }
+        None => None,
+    }
+}
+
+/// Read one attribute of a tag (WHATWG HTML, "get an attribute"); `None` at the end of the tag
+fn get_attribute(head: &[u8], pos: &mut usize) -> Option<(Vec<u8>, Vec<u8>)> {
+    while *pos < head.len() && (is_space(head[*pos]) || head[*pos] == b'/') {
+        *pos += 1;
+    }
+    if *pos >= head.len() || head[*pos] == b'>' {
+        return None;
+    }
+
+    let mut name = Vec::new();
+    while *pos < head.len() {
+        let b = head[*pos];
+        if (b == b'=' && !name.is_empty()) || is_space(b) || b == b'/' || b == b'>' {
+            break;
+        }
+        name.push(b.to_ascii_lowercase());
+        *pos += 1;
+    }
+    while *pos < head.len() && is_space(head[*pos]) {
+        *pos += 1;
+    }
+    if head.get(*pos) != Some(&b'=') {
+        return Some((name, Vec::new()));
+    }
+    *pos += 1;
+    while *pos < head.len() && is_space(head[*pos]) {
+        *pos += 1;
+    }
+
+    let mut value = Vec::new();
+    match head.get(*pos) {
+        Some(&quote @ (b'"' | b'\'')) => {
+            *pos += 1;
+            while *pos < head.len() && head[*pos] != quote {
+                value.push(head[*pos].to_ascii_lowercase());
+                *pos += 1;
+            }
+            *pos += 1;
+        }
+        _ => {
+            while *pos < head.len() && !is_space(head[*pos]) && head[*pos] != b'>' {
+                value.push(head[*pos].to_ascii_lowercase());
+                *pos += 1;
+            }
+        }
+    }
+    Some((name, value))
+}
+
+fn is_space(b: u8) -> bool {
+    matches!(b, b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| !is_space(b)).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len() && bytes[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn find_ignore_case(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window.eq_ignore_ascii_case(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{ISO_8859_2, SHIFT_JIS, WINDOWS_1251};
+
+    #[test]
+    fn test_sniff_order() {
+        // BOM beats the header, the header beats <meta>
+        let mut bom = b"\xEF\xBB\xBF".to_vec();
+        bom.extend_from_slice(b"<meta charset=\"shift_jis\">");
+        assert_eq!(sniff_encoding(&bom, Some("text/html; charset=windows-1251")), UTF_8);
+        assert_eq!(
+            sniff_encoding(
+                b"<meta charset=\"shift_jis\">",
+                Some("text/html; charset=\"windows-1251\"")
+            ),
+            WINDOWS_1251
+        );
+        assert_eq!(sniff_encoding(b"<meta charset=\"shift_jis\">", Some("text/html")), SHIFT_JIS);
+
+        // Unknown header labels are ignored
+        assert_eq!(
+            sniff_encoding(b"<meta charset=latin2>", Some("text/html; charset=bogus")),
+            ISO_8859_2
+        );
+
+        // No declaration: UTF-8 when valid, windows-1252 otherwise
+        assert_eq!(sniff_encoding("<p>caff\u{e8}</p>".as_bytes(), None), UTF_8);
+        assert_eq!(sniff_encoding(b"<p>caff\xE8</p>", None), WINDOWS_1252);
+    }
+
+    #[test]
+    fn test_prescan_meta_declarations() {
+        let http_equiv =
+            br#"<html><head><META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=windows-1251">"#;
+        assert_eq!(prescan(http_equiv), Some(WINDOWS_1251));
+
+        // `content` without the pragma is ignored
+        assert_eq!(prescan(br#"<meta content="text/html; charset=koi8-r">"#), None);
+
+        // Declarations inside comments and attribute values don't count
+        let commented =
+            br#"<!-- <meta charset="koi8-r"> --><div title="<meta charset=koi8-r>"></div>
+            <meta charset='iso-8859-2'>"#;
+        assert_eq!(prescan(commented), Some(ISO_8859_2));
+
+        // UTF-16 declarations in <meta> mean UTF-8
+        assert_eq!(prescan(b"<meta charset=utf-16le>"), Some(UTF_8));
+
+        assert_eq!(
+            sniff_encoding(
+                br#"<?xml version="1.0" encoding="Shift_JIS"?><rss/>"#,
+                Some("application/xml")
+            ),
+            SHIFT_JIS
+        );
+    }
+
+    #[test]
+    fn test_decode_legacy_encodings() {
+        let (bytes, _, _) =
+            SHIFT_JIS.encode("<meta charset=\"Shift_JIS\"><title>日本語のページ</title>");
+        let (text, encoding) = decode(&bytes, Some("text/html"));
+        assert_eq!(encoding, SHIFT_JIS);
+        assert!(text.contains("日本語のページ"));
+
+        let (bytes, _, _) =
+            WINDOWS_1251.encode("<meta charset=\"windows-1251\"><title>Привет</title>");
+        let (text, encoding) = decode(&bytes, None);
+        assert_eq!(encoding.name(), "windows-1251");
+        assert!(text.contains("Привет"));
+    }
+}
@@ -0,0 +1,483 @@
+//! Bibliographic meta tags
+//!
+//! Academic publishers describe articles with Highwire Press `citation_*` tags
+//! (the format Google Scholar indexes), PRISM `prism.*` tags and Dublin Core
+//! `DC.*`/`DCTERMS.*` tags. [`extract_citation`] merges them into one
+//! [`Citation`] record, preferring Highwire over PRISM over Dublin Core, which
+//! `kg_integration::insert_citation_to_kg` maps to a `schema:ScholarlyArticle`.
+
+use crate::external::normalize_whitespace;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Author of a cited work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitationAuthor {
+    /// Display name, with "Family, Given" turned into "Given Family"
+    pub name: String,
+    /// `citation_author_institution` values following the author
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affiliations: Vec<String>,
+    /// ORCID iD as an `https://orcid.org/` URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+}
+
+/// Entry of the reference list (`citation_reference`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitationReference {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// The tag content as written
+    pub text: String,
+}
+
+/// Bibliographic record of a scholarly page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<CitationAuthor>,
+    /// DOI without resolver prefix (`10.1000/xyz123`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// Journal (or book series) title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_page: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_page: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issn: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    /// Publication date, `YYYY/MM/DD` normalised to ISO 8601 (`YYYY-MM-DD`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publication_date: Option<String>,
+    /// Date the work was first published online
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub online_date: Option<String>,
+    /// Conference (proceedings) title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conference: Option<String>,
+    /// Institution of a dissertation or technical report
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub institution: Option<String>,
+    /// Full-text PDF, resolved against the document URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+    /// Abstract landing page, resolved against the document URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abstract_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abstract_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<CitationReference>,
+}
+
+/// Extract the bibliographic record of a document.
+///
+/// Returns `None` unless the page has Highwire or PRISM tags, or Dublin Core
+/// tags naming a DOI or the journal the work is part of; plain `DC.title` on a
+/// blog post doesn't make it a scholarly article.
+pub fn extract_citation(document: &Html, base_url: Option<&str>) -> Option<Citation> {
+    let selector = Selector::parse("meta[content]").ok()?;
+    let tags: Vec<(String, String)> = document
+        .select(&selector)
+        .filter_map(|meta| {
+            let name = meta.value().attr("name").or_else(|| meta.value().attr("property"))?;
+            let content = normalize_whitespace(meta.value().attr("content")?);
+            (!content.is_empty()).then(|| (name.trim().to_ascii_lowercase(), content))
+        })
+        .collect();
+
+    let mut citation = Citation::default();
+    let highwire = highwire(&tags, &mut citation);
+    let prism = prism(&tags, &mut citation);
+    let dublin_core = dublin_core(&tags, &mut citation);
+    if !highwire
+        && !prism
+        && !(dublin_core && (citation.doi.is_some() || citation.journal.is_some()))
+    {
+        return None;
+    }
+
+    let base = crate::parser::document_base(document, base_url);
+    let resolve = |href: String| match &base {
+        Some(base) => base.join(&href).map(|url| url.to_string()).unwrap_or(href),
+        None => href,
+    };
+    citation.pdf_url = citation.pdf_url.take().map(resolve);
+    citation.abstract_url = citation.abstract_url.take().map(resolve);
+    Some(citation)
+}
+
+/// Highwire Press `citation_*` tags; returns whether any was present
+fn highwire(tags: &[(String, String)], citation: &mut Citation) -> bool {
+    let mut found = false;
+    for (name, content) in tags {
+        let Some(key) = name.strip_prefix("citation_") else {
+            continue;
+        };
+        found = true;
+        let content = content.clone();
+        match key {
+            "title" => set(&mut citation.title, content),
+            "author" => citation.authors.push(author(&content)),
+            // Older pages list every author in one tag
+            "authors" => citation
+                .authors
+                .extend(content.split(';').map(str::trim).filter(|a| !a.is_empty()).map(author)),
+            "author_institution" => {
+                if let Some(author) = citation.authors.last_mut() {
+                    author.affiliations.push(content);
+                }
+            }
+            "author_orcid" => {
+                if let Some(author) = citation.authors.last_mut() {
+                    author.orcid = orcid(&content);
+                }
+            }
+            "doi" => set(&mut citation.doi, normalize_doi(&content).unwrap_or(content)),
+            "journal_title" | "inbook_title" | "book_title" => set(&mut citation.journal, content),
+            "publisher" => set(&mut citation.publisher, content),
+            "volume" => set(&mut citation.volume, content),
+            "issue" => set(&mut citation.issue, content),
+            "firstpage" => set(&mut citation.first_page, content),
+            "lastpage" => set(&mut citation.last_page, content),
+            "issn" | "eissn" => push_unique(&mut citation.issn, content),
+            "isbn" => set(&mut citation.isbn, content),
+            "publication_date" | "date" | "cover_date" | "year" => {
+                set(&mut citation.publication_date, normalize_date(&content))
+            }
+            "online_date" => set(&mut citation.online_date, normalize_date(&content)),
+            "conference_title" | "conference" => set(&mut citation.conference, content),
+            "dissertation_institution" | "technical_report_institution" => {
+                set(&mut citation.institution, content)
+            }
+            "pdf_url" => set(&mut citation.pdf_url, content),
+            "abstract_html_url" => set(&mut citation.abstract_url, content),
+            "abstract" => set(&mut citation.abstract_text, content),
+            "keywords" | "keyword" => keywords(&mut citation.keywords, &content),
+            "language" => set(&mut citation.language, content),
+            "reference" => citation.references.push(reference(content)),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// PRISM `prism.*` tags; returns whether any was present
+fn prism(tags: &[(String, String)], citation: &mut Citation) -> bool {
+    let mut found = false;
+    for (name, content) in tags {
+        let Some(key) = name.strip_prefix("prism.") else {
+            continue;
+        };
+        found = true;
+        let content = content.clone();
+        match key {
+            "doi" => set(&mut citation.doi, normalize_doi(&content).unwrap_or(content)),
+            "publicationname" => set(&mut citation.journal, content),
+            "volume" => set(&mut citation.volume, content),
+            "number" | "issueidentifier" => set(&mut citation.issue, content),
+            "startingpage" => set(&mut citation.first_page, content),
+            "endingpage" => set(&mut citation.last_page, content),
+            "issn" | "eissn" => push_unique(&mut citation.issn, content),
+            "isbn" => set(&mut citation.isbn, content),
+            "publicationdate" | "coverdate" => {
+                set(&mut citation.publication_date, normalize_date(&content))
+            }
+            "onlinedate" => set(&mut citation.online_date, normalize_date(&content)),
+            "keyword" => keywords(&mut citation.keywords, &content),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Dublin Core `DC.*` and `DCTERMS.*` tags; returns whether any was present
+fn dublin_core(tags: &[(String, String)], citation: &mut Citation) -> bool {
+    let mut found = false;
+    for (name, content) in tags {
+        let Some(key) = ["dcterms.", "dc."].iter().find_map(|prefix| name.strip_prefix(prefix))
+        else {
+            continue;
+        };
+        found = true;
+        let content = content.clone();
+        match key {
+            "title" => set(&mut citation.title, content),
+            "creator" | "creator.personalname" | "contributor.author" => {
+                let creator = author(&content);
+                if !citation.authors.iter().any(|a| same_person(&a.name, &creator.name)) {
+                    citation.authors.push(creator);
+                }
+            }
+            // Only DOIs are useful among the identifiers (URLs, ISSNs, local ids...)
+            "identifier" | "identifier.doi" => {
+                if let Some(doi) = normalize_doi(&content) {
+                    set(&mut citation.doi, doi);
+                }
+            }
+            "ispartof" | "relation.ispartof" | "source" | "citation.journaltitle" => {
+                set(&mut citation.journal, content)
+            }
+            "publisher" => set(&mut citation.publisher, content),
+            "issued" | "date.issued" | "date" | "created" | "available" => {
+                set(&mut citation.publication_date, normalize_date(&content))
+            }
+            "abstract" | "description" => set(&mut citation.abstract_text, content),
+            "subject" => keywords(&mut citation.keywords, &content),
+            "language" => set(&mut citation.language, content),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Fill a field unless a higher-priority tag (or an earlier one) already did
+fn set(field: &mut Option<String>, value: String) {
+    if field.is_none() {
+        *field = Some(value);
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+fn keywords(values: &mut Vec<String>, content: &str) {
+    for keyword in content.split([';', ',']).map(str::trim).filter(|k| !k.is_empty()) {
+        push_unique(values, keyword.to_string());
+    }
+}
+
+fn author(name: &str) -> CitationAuthor {
+    CitationAuthor { name: display_name(name), affiliations: Vec::new(), orcid: None }
+}
+
+/// Whether two display names denote the same author: same family name and
+/// first initial ("Alan M. Turing", "A. M. Turing", "alan turing")
+fn same_person(a: &str, b: &str) -> bool {
+    let tokens = |name: &str| -> Vec<String> {
+        name.split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (tokens(a), tokens(b));
+    match (a.first(), a.last(), b.first(), b.last()) {
+        (Some(given_a), Some(family_a), Some(given_b), Some(family_b)) => {
+            family_a == family_b
+                && (a.len() == 1
+                    || b.len() == 1
+                    || given_a.chars().next() == given_b.chars().next())
+        }
+        _ => false,
+    }
+}
+
+/// "Lovelace, Ada" -> "Ada Lovelace"; names with suffixes ("King, Jr.") are kept
+fn display_name(name: &str) -> String {
+    match name.split_once(',') {
+        Some((family, given))
+            if !given.contains(',')
+                && !family.trim().is_empty()
+                && !given.trim().is_empty()
+                && !matches!(
+                    given.trim().trim_end_matches('.').to_ascii_lowercase().as_str(),
+                    "jr" | "sr" | "ii" | "iii" | "iv"
+                ) =>
+        {
+            format!("{} {}", given.trim(), family.trim())
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// `10.`-prefixed DOI from a bare DOI, `doi:` URI or resolver URL
+pub fn normalize_doi(value: &str) -> Option<String> {
+    let value = value.trim();
+    let lower = value.to_ascii_lowercase();
+    let start =
+        ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/"]
+            .iter()
+            .chain(&["info:doi/", "doi:"])
+            .find(|prefix| lower.starts_with(*prefix))
+            .map_or(0, |prefix| prefix.len());
+    let doi = value[start..].trim();
+    (doi.starts_with("10.") && doi.contains('/')).then(|| doi.to_string())
+}
+
+fn orcid(value: &str) -> Option<String> {
+    let id = value
+        .trim()
+        .trim_start_matches("https://orcid.org/")
+        .trim_start_matches("http://orcid.org/");
+    (id.len() == 19 && id.chars().all(|c| c.is_ascii_digit() || c == '-' || c == 'X'))
+        .then(|| format!("https://orcid.org/{}", id))
+}
+
+/// Highwire dates are `YYYY/MM/DD` (or `YYYY/MM`, `YYYY`); anything else is kept
+fn normalize_date(value: &str) -> String {
+    let parts: Vec<&str> = value.split(['/', '-']).collect();
+    let numeric = parts
+        .iter()
+        .all(|p| !p.is_empty() && p.len() <= 4 && p.bytes().all(|b| b.is_ascii_digit()));
+    if !numeric || parts[0].len() != 4 || parts.len() > 3 {
+        return value.to_string();
+    }
+    let mut date = parts[0].to_string();
+    for part in &parts[1..] {
+        date.push_str(&format!("-{:0>2}", part));
+    }
+    date
+}
+
+/// `citation_reference` content: `citation_title=...; citation_doi=...`
+fn reference(text: String) -> CitationReference {
+    let mut title = None;
+    let mut doi = None;
+    for field in text.split(';') {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().trim_start_matches("citation_") {
+            "title" if !value.is_empty() => title = Some(value.to_string()),
+            "doi" => doi = normalize_doi(value),
+            _ => {}
+        }
+    }
+    if doi.is_none() && title.is_none() {
+        doi = normalize_doi(&text);
+    }
+    CitationReference { title, doi, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(head: &str) -> Option<Citation> {
+        let html = format!("<html><head>{}</head><body></body></html>", head);
+        extract_citation(&Html::parse_document(&html), Some("https://journal.example/articles/42"))
+    }
+
+    #[test]
+    fn test_highwire_tags() {
+        let citation = parse(
+            r#"<meta name="citation_title" content="On  Computable Numbers">
+               <meta name="citation_author" content="Turing, Alan M.">
+               <meta name="citation_author_institution" content="King's College, Cambridge">
+               <meta name="citation_author_orcid" content="0000-0002-1825-0097">
+               <meta name="citation_author" content="Ada Lovelace">
+               <meta name="citation_journal_title" content="Proceedings of the LMS">
+               <meta name="citation_volume" content="s2-42">
+               <meta name="citation_issue" content="1">
+               <meta name="citation_firstpage" content="230">
+               <meta name="citation_lastpage" content="265">
+               <meta name="citation_publication_date" content="1937/1/1">
+               <meta name="citation_doi" content="doi:10.1112/plms/s2-42.1.230">
+               <meta name="citation_issn" content="0024-6115">
+               <meta name="citation_pdf_url" content="/articles/42.pdf">
+               <meta name="citation_keywords" content="computability; Entscheidungsproblem">
+               <meta name="citation_reference" content="citation_title=Über formal unentscheidbare Sätze; citation_doi=10.1007/BF01700692">
+               <meta name="citation_reference" content="Church, A. An unsolvable problem (1936)">"#,
+        )
+        .unwrap();
+
+        assert_eq!(citation.title.as_deref(), Some("On Computable Numbers"));
+        let authors: Vec<&str> = citation.authors.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(authors, vec!["Alan M. Turing", "Ada Lovelace"]);
+        assert_eq!(citation.authors[0].affiliations, vec!["King's College, Cambridge"]);
+        assert_eq!(
+            citation.authors[0].orcid.as_deref(),
+            Some("https://orcid.org/0000-0002-1825-0097")
+        );
+        assert_eq!(citation.doi.as_deref(), Some("10.1112/plms/s2-42.1.230"));
+        assert_eq!(citation.journal.as_deref(), Some("Proceedings of the LMS"));
+        assert_eq!(citation.publication_date.as_deref(), Some("1937-01-01"));
+        assert_eq!(
+            (citation.first_page.as_deref(), citation.last_page.as_deref()),
+            (Some("230"), Some("265"))
+        );
+        assert_eq!(citation.pdf_url.as_deref(), Some("https://journal.example/articles/42.pdf"));
+        assert_eq!(citation.keywords, vec!["computability", "Entscheidungsproblem"]);
+        assert_eq!(citation.references.len(), 2);
+        assert_eq!(citation.references[0].doi.as_deref(), Some("10.1007/BF01700692"));
+        assert_eq!(citation.references[1].doi, None);
+    }
+
+    #[test]
+    fn test_prism_and_dublin_core_precedence() {
+        let citation = parse(
+            r#"<meta name="DC.title" content="Dublin Core title">
+               <meta name="DC.creator" content="Hopper, Grace">
+               <meta name="DC.identifier" content="urn:local:123">
+               <meta name="DC.identifier" content="https://doi.org/10.5555/12345678">
+               <meta name="DCTERMS.issued" content="1952-05-01">
+               <meta name="prism.publicationName" content="Journal of the ACM">
+               <meta name="prism.volume" content="1">
+               <meta name="prism.doi" content="10.5555/prism">
+               <meta name="citation_title" content="Highwire title">"#,
+        )
+        .unwrap();
+
+        assert_eq!(citation.title.as_deref(), Some("Highwire title"));
+        assert_eq!(citation.doi.as_deref(), Some("10.5555/prism"));
+        assert_eq!(citation.journal.as_deref(), Some("Journal of the ACM"));
+        assert_eq!(citation.authors[0].name, "Grace Hopper");
+
+        // Dublin Core creators repeating Highwire authors are not added again
+        let both = parse(
+            r#"<meta name="citation_author" content="Hopper, Grace M.">
+               <meta name="DC.creator" content="Hopper, G.">
+               <meta name="DC.creator" content="grace hopper">
+               <meta name="DC.creator" content="Hopper, Howard">"#,
+        )
+        .unwrap();
+        let authors: Vec<&str> = both.authors.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(authors, vec!["Grace M. Hopper", "Howard Hopper"]);
+        assert_eq!(citation.publication_date.as_deref(), Some("1952-05-01"));
+
+        // Dublin Core alone needs a DOI or a journal
+        let dc = parse(r#"<meta name="DC.title" content="My holiday">"#);
+        assert!(dc.is_none());
+        let dc = parse(
+            r#"<meta name="dc.title" content="Paper"><meta name="dc.identifier" content="doi:10.1000/182">"#,
+        )
+        .unwrap();
+        assert_eq!(dc.doi.as_deref(), Some("10.1000/182"));
+
+        assert!(parse(r#"<meta property="og:title" content="Not scholarly">"#).is_none());
+    }
+
+    #[test]
+    fn test_normalizers() {
+        assert_eq!(normalize_doi("https://dx.doi.org/10.1000/xyz"), Some("10.1000/xyz".into()));
+        assert_eq!(normalize_doi("ISBN 978-3-16"), None);
+        assert_eq!(normalize_date("2024/03/05"), "2024-03-05");
+        assert_eq!(normalize_date("2024"), "2024");
+        assert_eq!(normalize_date("March 2024"), "March 2024");
+        assert_eq!(display_name("King, Jr."), "King, Jr.");
+        assert_eq!(display_name("Curie, Marie"), "Marie Curie");
+    }
+}
@@ -0,0 +1,870 @@
+//! Polite multi-page crawler
+//!
+//! [`Crawler`] walks a site breadth-first from one or more seed URLs. Every page
+//! goes through the same extraction as the HTTP browse fallback
+//! ([`crate::external::extract_document`]) and is inserted into the knowledge
+//! graph with [`crate::kg_integration::insert_snapshot_to_kg`]; its anchors feed
+//! the URL frontier.
+//!
+//! Politeness: robots.txt is honoured per host (including `Crawl-delay`), each
+//! host gets its own queue, a bounded number of concurrent requests and a
+//! minimum delay between them, and `rel="nofollow"` links and `<meta name="robots">`
+//! directives are respected. URLs are canonicalised before deduplication, and
+//! the frontier can be persisted to a JSON file so an interrupted crawl resumes
+//! where it stopped.
+
+use crate::discovery::RobotsTxt;
+use crate::kg::KnowledgeGraph;
+use crate::links::LinkSource;
+use crate::models::SemanticSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// Query parameters that only track campaigns and never change the content
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "msclkid", "mc_cid", "mc_eid", "_ga"];
+
+/// Number of finished pages between two writes of the state file
+const SAVE_INTERVAL: usize = 10;
+
+/// Limits and politeness settings of a crawl
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// Link depth followed from the seeds (seeds are depth 0)
+    pub max_depth: usize,
+    /// Maximum number of pages fetched, across resumed runs
+    pub max_pages: usize,
+    /// Domains the crawl may visit, subdomains included; empty restricts the
+    /// crawl to the hosts of the seed URLs
+    pub allowed_domains: Vec<String>,
+    /// Maximum number of pages fetched per host
+    pub max_pages_per_host: Option<usize>,
+    /// Requests in flight across all hosts
+    pub concurrency: usize,
+    /// Requests in flight per host
+    pub per_host_concurrency: usize,
+    /// Minimum delay between two requests to the same host; a longer
+    /// robots.txt `Crawl-delay` takes precedence
+    pub delay: Duration,
+    /// Fetch `/robots.txt` of every host and skip disallowed URLs
+    pub respect_robots: bool,
+    /// Also follow `rel="nofollow"`, `ugc` and `sponsored` links
+    pub follow_nofollow: bool,
+    /// User agent sent with requests and matched against robots.txt groups
+    pub user_agent: String,
+    /// Timeout of a single request
+    pub timeout: Duration,
+    /// JSON file the crawl state is saved to and resumed from
+    pub state_path: Option<PathBuf>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 2,
+            max_pages: 100,
+            allowed_domains: Vec::new(),
+            max_pages_per_host: None,
+            concurrency: 4,
+            per_host_concurrency: 1,
+            delay: Duration::from_secs(1),
+            respect_robots: true,
+            follow_nofollow: false,
+            user_agent: format!("SemanticBrowser/{}", env!("CARGO_PKG_VERSION")),
+            timeout: Duration::from_secs(30),
+            state_path: None,
+        }
+    }
+}
+
+/// A URL waiting in the frontier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlTask {
+    /// Canonical URL
+    pub url: String,
+    pub depth: usize,
+}
+
+/// Outcome of fetching one page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawledPage {
+    pub url: String,
+    /// URL after redirects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    pub depth: usize,
+    /// HTTP status, `None` when the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// New frontier entries found on the page
+    pub links_queued: usize,
+    pub triples_inserted: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CrawledPage {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+/// Persistent crawl state: frontier, seen URLs and finished pages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrawlState {
+    pub frontier: VecDeque<CrawlTask>,
+    /// Canonical URLs already queued or fetched
+    pub seen: HashSet<String>,
+    pub pages: Vec<CrawledPage>,
+    /// URLs dropped because robots.txt disallows them
+    #[serde(default)]
+    pub disallowed: usize,
+}
+
+impl CrawlState {
+    /// Load a state file written by a previous crawl
+    pub fn load(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// Write the state atomically (temporary file, then rename)
+    pub fn save(
+        &self,
+        path: &std::path::Path,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn enqueue(&mut self, url: String, depth: usize) -> bool {
+        if !self.seen.insert(url.clone()) {
+            return false;
+        }
+        self.frontier.push_back(CrawlTask { url, depth });
+        true
+    }
+}
+
+/// Lifecycle of a crawl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrawlStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Live counters of a crawl, readable while it runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlProgress {
+    pub status: CrawlStatus,
+    pub pages_crawled: usize,
+    pub pages_failed: usize,
+    /// URLs waiting in the frontier
+    pub queued: usize,
+    pub triples_inserted: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the crawl stopped running
+    #[serde(skip)]
+    finished_at: Option<std::time::Instant>,
+}
+
+/// Shared view of a running crawl, used to poll progress and cancel it
+#[derive(Debug, Clone)]
+pub struct CrawlHandle {
+    progress: Arc<RwLock<CrawlProgress>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CrawlHandle {
+    pub fn progress(&self) -> CrawlProgress {
+        self.progress.read().map(|p| p.clone()).unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// Time since the crawl stopped running, `None` while it runs
+    pub fn finished_for(&self) -> Option<Duration> {
+        self.progress().finished_at.map(|at| at.elapsed())
+    }
+
+    /// Stop dispatching new requests; pages in flight still complete
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    fn update(&self, f: impl FnOnce(&mut CrawlProgress)) {
+        let mut progress = self.progress.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut progress);
+    }
+}
+
+/// Result of [`Crawler::run`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlReport {
+    pub status: CrawlStatus,
+    /// Every page fetched, including those of previous runs when resumed
+    pub pages: Vec<CrawledPage>,
+    /// URLs left in the frontier
+    pub queued: usize,
+    pub disallowed: usize,
+    pub triples_inserted: usize,
+}
+
+/// Scheduling state of one host
+struct HostState {
+    robots: RobotsTxt,
+    delay: Duration,
+    in_flight: usize,
+    next_fetch: Instant,
+    pages: usize,
+}
+
+/// URLs waiting to be fetched: one FIFO queue per origin, visited round-robin
+#[derive(Debug, Default)]
+struct Frontier {
+    queues: HashMap<String, VecDeque<CrawlTask>>,
+    /// Origins with queued URLs, in dispatch order
+    origins: VecDeque<String>,
+}
+
+impl Frontier {
+    fn push(&mut self, task: CrawlTask) {
+        let Some(origin) = origin_of(&task.url) else {
+            return;
+        };
+        let queue = self.queues.entry(origin.clone()).or_default();
+        if queue.is_empty() {
+            self.origins.push_back(origin);
+        }
+        queue.push_back(task);
+    }
+
+    fn len(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+
+    /// Every queued task, host by host in dispatch order
+    fn tasks(&self) -> VecDeque<CrawlTask> {
+        self.origins.iter().filter_map(|o| self.queues.get(o)).flatten().cloned().collect()
+    }
+}
+
+/// Page fetched and extracted by a worker task
+struct FetchedPage {
+    final_url: String,
+    status: u16,
+    snapshot: Option<SemanticSnapshot>,
+    /// `<meta name="robots" content="noindex">`
+    noindex: bool,
+    /// `<meta name="robots" content="nofollow">`
+    nofollow: bool,
+    /// robots.txt of `final_url` when a redirect changed the origin
+    final_robots: Option<RobotsTxt>,
+}
+
+/// Breadth-first crawler feeding the knowledge graph
+pub struct Crawler {
+    options: CrawlOptions,
+    state: CrawlState,
+    frontier: Frontier,
+    allowed_domains: Vec<String>,
+    handle: CrawlHandle,
+}
+
+impl Crawler {
+    /// Create a crawler for `seeds`.
+    ///
+    /// When `options.state_path` points to an existing state file the crawl
+    /// resumes from it; seeds already seen there are not queued again.
+    pub fn new<S: AsRef<str>>(
+        seeds: &[S],
+        options: CrawlOptions,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = match &options.state_path {
+            Some(path) if path.exists() => {
+                let state = CrawlState::load(path)?;
+                tracing::info!(
+                    "Resuming crawl from {} ({} pages done, {} queued)",
+                    path.display(),
+                    state.pages.len(),
+                    state.frontier.len()
+                );
+                state
+            }
+            _ => CrawlState::default(),
+        };
+
+        let mut allowed_domains: Vec<String> = options
+            .allowed_domains
+            .iter()
+            .map(|d| d.trim_start_matches('.').to_lowercase())
+            .collect();
+        for seed in seeds.iter().map(AsRef::as_ref) {
+            let url =
+                canonicalize_url(seed).ok_or_else(|| format!("Invalid seed URL: {}", seed))?;
+            if options.allowed_domains.is_empty() {
+                if let Some(host) = url::Url::parse(&url)?.host_str() {
+                    if !allowed_domains.iter().any(|d| d == host) {
+                        allowed_domains.push(host.to_string());
+                    }
+                }
+            }
+            state.enqueue(url, 0);
+        }
+        let mut frontier = Frontier::default();
+        for task in std::mem::take(&mut state.frontier) {
+            frontier.push(task);
+        }
+
+        let progress = CrawlProgress {
+            status: CrawlStatus::Running,
+            pages_crawled: state.pages.iter().filter(|p| p.is_success()).count(),
+            pages_failed: state.pages.iter().filter(|p| !p.is_success()).count(),
+            queued: frontier.len(),
+            triples_inserted: state.pages.iter().map(|p| p.triples_inserted).sum(),
+            error: None,
+            finished_at: None,
+        };
+        let handle = CrawlHandle {
+            progress: Arc::new(RwLock::new(progress)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        Ok(Self { options, state, frontier, allowed_domains, handle })
+    }
+
+    /// Handle for polling progress and cancelling the crawl from another task
+    pub fn handle(&self) -> CrawlHandle {
+        self.handle.clone()
+    }
+
+    /// Whether `url` is inside the crawl's domain scope
+    pub fn in_scope(&self, url: &url::Url) -> bool {
+        // Seed hosts match exactly, configured domains include their subdomains
+        let subdomains = !self.options.allowed_domains.is_empty();
+        url.host_str().is_some_and(|host| {
+            self.allowed_domains.iter().any(|d| {
+                host == d
+                    || (subdomains
+                        && host.strip_suffix(d.as_str()).is_some_and(|p| p.ends_with('.')))
+            })
+        })
+    }
+
+    /// Crawl until the frontier is empty, `max_pages` is reached or the crawl
+    /// is cancelled, inserting every indexable page into `kg`.
+    pub async fn run(
+        mut self,
+        kg: Arc<Mutex<KnowledgeGraph>>,
+    ) -> Result<CrawlReport, Box<dyn std::error::Error + Send + Sync>> {
+        let result = self.crawl(&kg).await;
+        let status = match &result {
+            Ok(()) if self.handle.cancelled.load(Ordering::SeqCst) => CrawlStatus::Cancelled,
+            Ok(()) => CrawlStatus::Completed,
+            Err(_) => CrawlStatus::Failed,
+        };
+        let error = result.as_ref().err().map(|e| e.to_string());
+        self.state.frontier = self.frontier.tasks();
+        self.handle.update(|p| {
+            p.status = status;
+            p.queued = self.state.frontier.len();
+            p.error = error;
+            p.finished_at = Some(std::time::Instant::now());
+        });
+        if let Some(path) = &self.options.state_path {
+            self.state.save(path)?;
+        }
+        result?;
+
+        Ok(CrawlReport {
+            status,
+            queued: self.state.frontier.len(),
+            disallowed: self.state.disallowed,
+            triples_inserted: self.state.pages.iter().map(|p| p.triples_inserted).sum(),
+            pages: self.state.pages,
+        })
+    }
+
+    async fn crawl(
+        &mut self,
+        kg: &Mutex<KnowledgeGraph>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let client = reqwest::Client::builder()
+            .user_agent(&self.options.user_agent)
+            .timeout(self.options.timeout)
+            .build()?;
+        let concurrency = self.options.concurrency.max(1);
+        let per_host = self.options.per_host_concurrency.max(1);
+
+        // `None` while the host's robots.txt is being fetched
+        let mut hosts: HashMap<String, Option<HostState>> = HashMap::new();
+        let mut robots_tasks: JoinSet<(String, RobotsTxt)> = JoinSet::new();
+        let mut in_flight: HashMap<String, CrawlTask> = HashMap::new();
+        let mut tasks: JoinSet<(CrawlTask, Result<FetchedPage, String>)> = JoinSet::new();
+        let mut since_save = 0;
+
+        loop {
+            let cancelled = self.handle.cancelled.load(Ordering::SeqCst);
+
+            // New hosts fetch their robots.txt in the background
+            for origin in &self.frontier.origins {
+                if hosts.contains_key(origin) {
+                    continue;
+                }
+                if !self.options.respect_robots {
+                    hosts.insert(origin.clone(), Some(self.host_state(RobotsTxt::default())));
+                    continue;
+                }
+                hosts.insert(origin.clone(), None);
+                let client = client.clone();
+                let origin = origin.clone();
+                robots_tasks.spawn(async move {
+                    let robots = fetch_host_robots(&client, &origin).await;
+                    (origin, robots)
+                });
+            }
+
+            // Visit the hosts round-robin, dispatching from every host that has
+            // a free slot and no pending delay, until nothing more can start
+            let mut dispatched = true;
+            while dispatched && !cancelled {
+                dispatched = false;
+                for _ in 0..self.frontier.origins.len() {
+                    if tasks.len() >= concurrency
+                        || self.state.pages.len() + tasks.len() >= self.options.max_pages
+                    {
+                        break;
+                    }
+                    let Some(origin) = self.frontier.origins.pop_front() else {
+                        break;
+                    };
+                    let Some(Some(host)) = hosts.get_mut(&origin) else {
+                        self.frontier.origins.push_back(origin);
+                        continue;
+                    };
+                    let queue = self.frontier.queues.entry(origin.clone()).or_default();
+
+                    while let Some(task) = queue.front() {
+                        let allowed = url::Url::parse(&task.url).is_ok_and(|url| {
+                            host.robots.is_url_allowed(&self.options.user_agent, &url)
+                        });
+                        if allowed {
+                            break;
+                        }
+                        tracing::debug!("robots.txt disallows {}", task.url);
+                        queue.pop_front();
+                        self.state.disallowed += 1;
+                    }
+                    if self.options.max_pages_per_host.is_some_and(|max| host.pages >= max) {
+                        queue.clear();
+                    }
+                    let now = Instant::now();
+                    if !queue.is_empty() && (host.in_flight >= per_host || host.next_fetch > now) {
+                        self.frontier.origins.push_back(origin);
+                        continue;
+                    }
+                    let Some(task) = queue.pop_front() else {
+                        self.frontier.queues.remove(&origin);
+                        continue;
+                    };
+                    if queue.is_empty() {
+                        self.frontier.queues.remove(&origin);
+                    } else {
+                        self.frontier.origins.push_back(origin);
+                    }
+
+                    host.in_flight += 1;
+                    host.pages += 1;
+                    host.next_fetch = now + host.delay;
+                    dispatched = true;
+                    in_flight.insert(task.url.clone(), task.clone());
+                    let client = client.clone();
+                    let respect_robots = self.options.respect_robots;
+                    tasks.spawn(async move {
+                        let result = fetch_page(&client, &task.url, respect_robots)
+                            .await
+                            .map_err(|e| e.to_string());
+                        (task, result)
+                    });
+                }
+            }
+            self.handle.update(|p| p.queued = self.frontier.len());
+
+            if tasks.is_empty() {
+                let blocked = cancelled
+                    || self.frontier.is_empty()
+                    || self.state.pages.len() >= self.options.max_pages;
+                if blocked {
+                    break;
+                }
+            }
+
+            // Wait for a page or a robots.txt to finish, or for the next host delay to expire
+            let wake = self
+                .frontier
+                .origins
+                .iter()
+                .filter_map(|origin| hosts.get(origin).and_then(Option::as_ref))
+                .filter(|host| host.in_flight < per_host)
+                .map(|host| host.next_fetch)
+                .min()
+                .filter(|wake| *wake > Instant::now());
+            let finished = tokio::select! {
+                finished = tasks.join_next(), if !tasks.is_empty() => finished,
+                robots = robots_tasks.join_next(), if !robots_tasks.is_empty() => {
+                    if let Some((origin, robots)) = robots.transpose()? {
+                        hosts.insert(origin, Some(self.host_state(robots)));
+                    }
+                    None
+                }
+                _ = tokio::time::sleep_until(wake.unwrap_or_else(Instant::now)), if wake.is_some() => None,
+                else => None,
+            };
+            let Some(finished) = finished else {
+                continue;
+            };
+            let (task, result) = finished?;
+            in_flight.remove(&task.url);
+
+            if let Some(Some(host)) = origin_of(&task.url).and_then(|origin| hosts.get_mut(&origin))
+            {
+                host.in_flight -= 1;
+                host.next_fetch = host.next_fetch.max(Instant::now() + host.delay);
+            }
+
+            let page = self.process(task, result, &mut hosts, kg).await;
+            self.handle.update(|p| {
+                if page.is_success() {
+                    p.pages_crawled += 1;
+                } else {
+                    p.pages_failed += 1;
+                }
+                p.triples_inserted += page.triples_inserted;
+            });
+            self.state.pages.push(page);
+
+            since_save += 1;
+            if since_save >= SAVE_INTERVAL {
+                since_save = 0;
+                self.save_with(&in_flight)?;
+            }
+        }
+
+        tracing::info!(
+            "Crawl finished: {} pages, {} queued, {} disallowed by robots.txt",
+            self.state.pages.len(),
+            self.frontier.len(),
+            self.state.disallowed
+        );
+        Ok(())
+    }
+
+    /// Scheduling state of a host whose robots.txt is known, with its request delay
+    fn host_state(&self, robots: RobotsTxt) -> HostState {
+        let delay = robots
+            .crawl_delay(&self.options.user_agent)
+            .map_or(self.options.delay, |d| d.max(self.options.delay));
+        HostState { robots, delay, in_flight: 0, next_fetch: Instant::now(), pages: 0 }
+    }
+
+    /// Record a fetched page, insert it into the KG and queue its links
+    async fn process(
+        &mut self,
+        task: CrawlTask,
+        result: Result<FetchedPage, String>,
+        hosts: &mut HashMap<String, Option<HostState>>,
+        kg: &Mutex<KnowledgeGraph>,
+    ) -> CrawledPage {
+        let mut page = CrawledPage {
+            url: task.url.clone(),
+            final_url: None,
+            depth: task.depth,
+            status: None,
+            title: None,
+            links_queued: 0,
+            triples_inserted: 0,
+            error: None,
+        };
+        let mut fetched = match result {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                tracing::warn!("Failed to crawl {}: {}", task.url, e);
+                page.error = Some(e);
+                return page;
+            }
+        };
+        page.status = Some(fetched.status);
+        // Redirect targets count as seen so they are not fetched twice
+        if let Some(final_url) = canonicalize_url(&fetched.final_url) {
+            self.state.seen.insert(final_url);
+        }
+        page.final_url = Some(fetched.final_url.clone());
+
+        // A redirect may leave the crawl's scope or land on a disallowed path
+        let Ok(final_url) = url::Url::parse(&fetched.final_url) else {
+            page.error = Some(format!("Invalid redirect target {}", fetched.final_url));
+            return page;
+        };
+        if !self.in_scope(&final_url) {
+            page.error = Some(format!("Redirected out of the crawl scope to {}", final_url));
+            return page;
+        }
+        let origin = final_url.origin().ascii_serialization();
+        if let Some(robots) = fetched.final_robots.take() {
+            if !matches!(hosts.get(&origin), Some(Some(_))) {
+                hosts.insert(origin.clone(), Some(self.host_state(robots)));
+            }
+        }
+        let allowed = match hosts.get(&origin) {
+            Some(Some(host)) => host.robots.is_url_allowed(&self.options.user_agent, &final_url),
+            _ => !self.options.respect_robots,
+        };
+        if !allowed {
+            self.state.disallowed += 1;
+            page.error = Some(format!("robots.txt disallows redirect target {}", final_url));
+            return page;
+        }
+
+        let Some(snapshot) = fetched.snapshot else {
+            return page;
+        };
+        page.title = snapshot.title.clone();
+
+        if !fetched.noindex {
+            let mut kg = kg.lock().await;
+            match crate::kg_integration::insert_snapshot_to_kg(
+                &snapshot,
+                &mut kg,
+                &fetched.final_url,
+                None,
+            ) {
+                Ok(count) => page.triples_inserted = count,
+                Err(e) => page.error = Some(format!("KG insertion failed: {}", e)),
+            }
+        }
+
+        if fetched.nofollow || task.depth >= self.options.max_depth {
+            return page;
+        }
+        for link in &snapshot.links {
+            if link.source != LinkSource::Anchor
+                || (link.is_nofollow() && !self.options.follow_nofollow)
+            {
+                continue;
+            }
+            let Some(url) = canonicalize_url(&link.url) else {
+                continue;
+            };
+            let in_scope = url::Url::parse(&url).is_ok_and(|u| self.in_scope(&u));
+            if in_scope && self.state.seen.insert(url.clone()) {
+                self.frontier.push(CrawlTask { url, depth: task.depth + 1 });
+                page.links_queued += 1;
+            }
+        }
+        page
+    }
+
+    /// Save the state with in-flight URLs back at the front of the frontier,
+    /// so a crash does not lose them
+    fn save_with(
+        &self,
+        in_flight: &HashMap<String, CrawlTask>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.options.state_path else {
+            return Ok(());
+        };
+        let mut state = self.state.clone();
+        state.frontier = self.frontier.tasks();
+        for task in in_flight.values() {
+            state.frontier.push_front(task.clone());
+        }
+        state.save(path)
+    }
+}
+
+/// Origin (`scheme://host:port`) of a URL, the key of per-host scheduling
+fn origin_of(url: &str) -> Option<String> {
+    url::Url::parse(url).ok().map(|url| url.origin().ascii_serialization())
+}
+
+/// robots.txt of `origin`
+async fn fetch_host_robots(client: &reqwest::Client, origin: &str) -> RobotsTxt {
+    match url::Url::parse(origin).and_then(|url| url.join("/robots.txt")) {
+        Ok(robots_url) => crate::discovery::fetch_robots(client, &robots_url).await,
+        Err(_) => RobotsTxt::disallow_all(),
+    }
+}
+
+/// Fetch a page and extract it unless it is a binary document (image,
+/// archive, ...). When a redirect changes the origin and `respect_robots` is
+/// set, the robots.txt of the target is fetched too.
+async fn fetch_page(
+    client: &reqwest::Client,
+    url: &str,
+    respect_robots: bool,
+) -> Result<FetchedPage, Box<dyn std::error::Error + Send + Sync>> {
+    use crate::documents::{detect_kind, DocumentKind};
+
+    let document = crate::external::fetch_document(client, url).await?;
+    let final_origin = origin_of(&document.final_url);
+    let final_robots = match final_origin {
+        Some(origin) if respect_robots && Some(&origin) != origin_of(url).as_ref() => {
+            Some(fetch_host_robots(client, &origin).await)
+        }
+        _ => None,
+    };
+    let mut page = FetchedPage {
+        final_url: document.final_url.clone(),
+        status: document.status,
+        snapshot: None,
+        noindex: false,
+        nofollow: false,
+        final_robots,
+    };
+    let kind = detect_kind(document.content_type.as_deref(), &document.body);
+    if !document.is_success() || kind == DocumentKind::Binary {
+        return Ok(page);
+    }
+
+    if kind == DocumentKind::Html {
+        (page.noindex, page.nofollow) = meta_robots(&document.text());
+    }
+    page.snapshot = Some(crate::external::extract_document(
+        &document.body,
+        document.content_type.as_deref(),
+        document.final_url,
+        "",
+    )?);
+    Ok(page)
+}
+
+/// `noindex` and `nofollow` directives of `<meta name="robots">`
+fn meta_robots(html: &str) -> (bool, bool) {
+    let document = scraper::Html::parse_document(html);
+    let selector = scraper::Selector::parse("meta[name][content]").expect("valid selector");
+    let mut directives = (false, false);
+    for meta in document.select(&selector) {
+        let name = meta.value().attr("name").unwrap_or_default();
+        if !name.eq_ignore_ascii_case("robots") {
+            continue;
+        }
+        for directive in meta.value().attr("content").unwrap_or_default().split(',') {
+            match directive.trim().to_ascii_lowercase().as_str() {
+                "noindex" => directives.0 = true,
+                "nofollow" => directives.1 = true,
+                "none" => directives = (true, true),
+                _ => {}
+            }
+        }
+    }
+    directives
+}
+
+/// Canonical form of a URL used for deduplication.
+///
+/// Only `http` and `https` URLs are crawlable. The fragment and tracking
+/// parameters (`utm_*`, `gclid`, ...) are dropped and the remaining query
+/// parameters sorted; host case, default ports and dot segments are already
+/// normalised by URL parsing.
+pub fn canonicalize_url(url: &str) -> Option<String> {
+    let mut url = url::Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.set_fragment(None);
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| {
+            let key = key.to_ascii_lowercase();
+            !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_url() {
+        assert_eq!(
+            canonicalize_url("HTTP://Example.COM:80/a/../b?z=1&utm_source=x&a=2#top").as_deref(),
+            Some("http://example.com/b?a=2&z=1")
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/page?utm_medium=mail&fbclid=1").as_deref(),
+            Some("https://example.com/page")
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com").as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(canonicalize_url("mailto:someone@example.com"), None);
+        assert_eq!(canonicalize_url("/relative"), None);
+    }
+
+    #[test]
+    fn test_scope_and_resume_seeds() {
+        let crawler = Crawler::new(
+            &["https://blog.example.com/"],
+            CrawlOptions { allowed_domains: vec!["example.com".to_string()], ..Default::default() },
+        )
+        .unwrap();
+        let url = |s: &str| url::Url::parse(s).unwrap();
+        assert!(crawler.in_scope(&url("https://example.com/a")));
+        assert!(crawler.in_scope(&url("https://docs.example.com/a")));
+        assert!(!crawler.in_scope(&url("https://notexample.com/a")));
+        assert_eq!(crawler.handle().progress().queued, 1);
+
+        let crawler = Crawler::new(
+            &["https://example.com/#a", "https://example.com/"],
+            CrawlOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(crawler.frontier.len(), 1);
+        assert!(!crawler.in_scope(&url("https://docs.example.com/a")));
+    }
+
+    #[test]
+    fn test_frontier_round_robin() {
+        let mut frontier = Frontier::default();
+        for url in ["https://a.example/1", "https://a.example/2", "https://b.example/1", "nope"] {
+            frontier.push(CrawlTask { url: url.to_string(), depth: 0 });
+        }
+        assert_eq!(frontier.len(), 3);
+        assert_eq!(frontier.origins, ["https://a.example", "https://b.example"]);
+        assert_eq!(frontier.queues["https://a.example"].len(), 2);
+        let urls: Vec<String> = frontier.tasks().into_iter().map(|t| t.url).collect();
+        assert_eq!(urls, ["https://a.example/1", "https://a.example/2", "https://b.example/1"]);
+    }
+
+    #[test]
+    fn test_meta_robots() {
+        assert_eq!(meta_robots(r#"<meta name="ROBOTS" content="noindex, follow">"#), (true, false));
+        assert_eq!(meta_robots(r#"<meta name="robots" content="none">"#), (true, true));
+        assert_eq!(meta_robots("<title>x</title>"), (false, false));
+    }
+}
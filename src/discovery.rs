@@ -0,0 +1,586 @@
+//! Site discovery from robots.txt and XML sitemaps
+//!
+//! [`RobotsTxt`] implements the robots exclusion protocol (RFC 9309): groups
+//! per user agent, `Allow`/`Disallow` rules with `*` and `$` patterns where the
+//! longest match wins, `Crawl-delay` and `Sitemap:` lines. [`parse_sitemap`]
+//! reads sitemaps and sitemap indexes (sitemaps.org 0.9), gzipped or not.
+//! [`Site::discover`] ties both together: it fetches `/robots.txt`, follows the
+//! advertised sitemaps (or `/sitemap.xml`) and exposes the URLs robots.txt
+//! allows, with their `lastmod` and `priority`.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+use std::time::Duration;
+
+/// Largest uncompressed sitemap accepted (the sitemaps.org limit)
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+/// An `Allow` or `Disallow` line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RobotsRule {
+    pub allow: bool,
+    /// Path pattern; `*` matches any sequence and a trailing `$` anchors the end
+    pub pattern: String,
+}
+
+/// Rules shared by consecutive `User-agent` lines
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RobotsGroup {
+    /// Lowercased product tokens (`*` for the default group)
+    pub user_agents: Vec<String>,
+    pub rules: Vec<RobotsRule>,
+    /// `Crawl-delay` in seconds (non-standard, but widely used)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crawl_delay: Option<f64>,
+}
+
+/// Parsed robots.txt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RobotsTxt {
+    pub groups: Vec<RobotsGroup>,
+    /// `Sitemap:` URLs, in file order
+    pub sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    /// Parse a robots.txt body; unknown lines are ignored
+    pub fn parse(text: &str) -> Self {
+        let mut robots = RobotsTxt::default();
+        let mut current: Option<RobotsGroup> = None;
+        // A user-agent line after rules starts a new group
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules || current.is_none() {
+                        robots.groups.extend(current.take());
+                        current = Some(RobotsGroup::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.user_agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // An empty Disallow allows everything and adds no rule
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group
+                            .rules
+                            .push(RobotsRule { allow: key == "allow", pattern: value.to_string() });
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let Some(group) = current.as_mut() {
+                        group.crawl_delay = value.parse().ok().filter(|d: &f64| *d >= 0.0);
+                    }
+                }
+                "sitemap" if !value.is_empty() => robots.sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        robots.groups.extend(current);
+        robots
+    }
+
+    /// robots.txt that disallows everything, used when it cannot be fetched
+    /// (5xx or network error)
+    pub fn disallow_all() -> Self {
+        RobotsTxt {
+            groups: vec![RobotsGroup {
+                user_agents: vec!["*".to_string()],
+                rules: vec![RobotsRule { allow: false, pattern: "/".to_string() }],
+                crawl_delay: None,
+            }],
+            sitemaps: Vec::new(),
+        }
+    }
+
+    /// Groups that apply to `user_agent`: those naming its product token, else `*`
+    fn groups_for(&self, user_agent: &str) -> Vec<&RobotsGroup> {
+        let token = user_agent.split('/').next().unwrap_or_default().trim().to_ascii_lowercase();
+        let specific: Vec<&RobotsGroup> =
+            self.groups.iter().filter(|g| g.user_agents.contains(&token)).collect();
+        if !specific.is_empty() {
+            return specific;
+        }
+        self.groups.iter().filter(|g| g.user_agents.iter().any(|a| a == "*")).collect()
+    }
+
+    /// Whether `user_agent` may fetch `path` (path and query of a URL).
+    ///
+    /// The longest matching pattern decides; on a tie `Allow` wins.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+        let mut best: Option<(usize, bool)> = None;
+        for rule in self.groups_for(user_agent).into_iter().flat_map(|g| &g.rules) {
+            if !pattern_matches(&rule.pattern, path) {
+                continue;
+            }
+            let length = rule.pattern.len();
+            best = match best {
+                Some((best_length, best_allow))
+                    if best_length > length || (best_length == length && best_allow) =>
+                {
+                    Some((best_length, best_allow))
+                }
+                _ => Some((length, rule.allow)),
+            };
+        }
+        best.map_or(true, |(_, allow)| allow)
+    }
+
+    /// Whether `user_agent` may fetch the absolute `url`
+    pub fn is_url_allowed(&self, user_agent: &str, url: &url::Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.is_allowed(user_agent, &path)
+    }
+
+    /// `Crawl-delay` of the groups that apply to `user_agent`
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent)
+            .into_iter()
+            .find_map(|g| g.crawl_delay)
+            .map(Duration::from_secs_f64)
+    }
+}
+
+/// Match a robots.txt path pattern against a path (prefix match unless anchored with `$`)
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut position = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            if !path.starts_with(part) {
+                return false;
+            }
+            position = part.len();
+        } else if i == parts.len() - 1 && anchored {
+            return path.len() >= position + part.len() && path.ends_with(part);
+        } else {
+            match path[position..].find(part) {
+                Some(offset) => position += offset + part.len(),
+                None => return false,
+            }
+        }
+    }
+    !anchored || position == path.len()
+}
+
+/// A `<url>` of a sitemap, or a `<sitemap>` of a sitemap index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    pub loc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastmod: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changefreq: Option<String>,
+    /// 0.0 to 1.0; sitemaps.org defaults a missing value to 0.5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f32>,
+}
+
+/// A parsed sitemap document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// `<urlset>` (or a plain-text sitemap): page URLs
+    UrlSet(Vec<SitemapEntry>),
+    /// `<sitemapindex>`: URLs of further sitemaps
+    Index(Vec<SitemapEntry>),
+}
+
+/// Parse a sitemap, sitemap index or plain-text sitemap, gunzipping it if needed
+pub fn parse_sitemap(body: &[u8]) -> Result<Sitemap, Box<dyn std::error::Error + Send + Sync>> {
+    let mut text = String::new();
+    if body.starts_with(&[0x1f, 0x8b]) {
+        flate2::read::GzDecoder::new(body).take(MAX_SITEMAP_BYTES).read_to_string(&mut text)?;
+    } else {
+        body.take(MAX_SITEMAP_BYTES).read_to_string(&mut text)?;
+    }
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    if !text.starts_with('<') {
+        let entries = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("http://") || line.starts_with("https://"))
+            .map(|loc| SitemapEntry {
+                loc: loc.to_string(),
+                lastmod: None,
+                changefreq: None,
+                priority: None,
+            })
+            .collect();
+        return Ok(Sitemap::UrlSet(entries));
+    }
+
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut is_index = None;
+    let mut entries = Vec::new();
+    let mut entry: Option<SitemapEntry> = None;
+    let mut field = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "urlset" if is_index.is_none() => is_index = Some(false),
+                    "sitemapindex" if is_index.is_none() => is_index = Some(true),
+                    "url" | "sitemap" => {
+                        entry = Some(SitemapEntry {
+                            loc: String::new(),
+                            lastmod: None,
+                            changefreq: None,
+                            priority: None,
+                        })
+                    }
+                    _ => {}
+                }
+                field = name;
+            }
+            Event::Text(t) => {
+                let value = t.unescape()?.trim().to_string();
+                if let Some(entry) = entry.as_mut() {
+                    match field.as_str() {
+                        "loc" => entry.loc = value,
+                        "lastmod" => entry.lastmod = Some(value),
+                        "changefreq" => entry.changefreq = Some(value),
+                        "priority" => entry.priority = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            Event::CData(c) => {
+                if let (Some(entry), "loc") = (entry.as_mut(), field.as_str()) {
+                    entry.loc = String::from_utf8_lossy(&c).trim().to_string();
+                }
+            }
+            Event::End(e) => {
+                if matches!(e.local_name().as_ref(), b"url" | b"sitemap") {
+                    entries.extend(entry.take().filter(|e| !e.loc.is_empty()));
+                }
+                field.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    match is_index {
+        Some(true) => Ok(Sitemap::Index(entries)),
+        Some(false) => Ok(Sitemap::UrlSet(entries)),
+        None => Err("Not a sitemap (expected <urlset> or <sitemapindex>)".into()),
+    }
+}
+
+/// Fetch and parse a robots.txt file.
+///
+/// A missing file (4xx) allows everything; a 5xx response or a failed
+/// request disallows everything, as RFC 9309 asks crawlers to assume a
+/// complete disallow while the server is unreachable.
+pub async fn fetch_robots(client: &reqwest::Client, robots_url: &url::Url) -> RobotsTxt {
+    robots_from(crate::external::fetch_document(client, robots_url.as_str()).await, robots_url)
+}
+
+/// robots.txt for the outcome of fetching `robots_url`
+fn robots_from(
+    fetched: Result<crate::external::FetchedDocument, Box<dyn std::error::Error + Send + Sync>>,
+    robots_url: &url::Url,
+) -> RobotsTxt {
+    match fetched {
+        Ok(document) if document.is_success() => RobotsTxt::parse(&document.text()),
+        Ok(document) if document.status >= 500 => RobotsTxt::disallow_all(),
+        Ok(_) => RobotsTxt::default(),
+        Err(e) => {
+            tracing::warn!("Failed to fetch {}, disallowing the host: {}", robots_url, e);
+            RobotsTxt::disallow_all()
+        }
+    }
+}
+
+/// Limits and identity used by [`Site::discover_with`]
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    /// User agent sent with requests and matched against robots.txt groups
+    pub user_agent: String,
+    /// Maximum number of sitemap documents fetched (indexes included)
+    pub max_sitemaps: usize,
+    /// Maximum number of URLs collected
+    pub max_urls: usize,
+    /// Read `file://` URLs from disk so a local directory can stand in for a
+    /// site. Off by default; leave it off for URLs from untrusted input.
+    pub allow_file_urls: bool,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: format!("SemanticBrowser/{}", env!("CARGO_PKG_VERSION")),
+            max_sitemaps: 50,
+            max_urls: 50_000,
+            allow_file_urls: false,
+        }
+    }
+}
+
+/// A site's robots.txt and the URLs its sitemaps list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Site {
+    /// Site root the robots.txt and default sitemap were resolved against
+    pub root: String,
+    pub user_agent: String,
+    pub robots: RobotsTxt,
+    /// Sitemap documents that were fetched and parsed
+    pub sitemaps: Vec<String>,
+    entries: Vec<SitemapEntry>,
+}
+
+impl Site {
+    /// Discover a site with the default [`DiscoveryOptions`]
+    pub async fn discover(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::discover_with(url, DiscoveryOptions::default()).await
+    }
+
+    /// Fetch `/robots.txt` of the site `url` belongs to and walk its sitemaps
+    /// (falling back to `/sitemap.xml`), following sitemap indexes breadth-first.
+    ///
+    /// Sitemaps on other hosts or with other schemes are skipped. With
+    /// [`DiscoveryOptions::allow_file_urls`] a `file://` directory stands in
+    /// for the site root. A missing robots.txt allows everything; a 5xx
+    /// response or a failed request disallows everything.
+    pub async fn discover_with(
+        url: &str,
+        options: DiscoveryOptions,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let url = url::Url::parse(url)?;
+        let root = match url.scheme() {
+            "http" | "https" => url.join("/")?,
+            "file" if options.allow_file_urls => {
+                if url.path().ends_with('/') {
+                    url.clone()
+                } else {
+                    url.join("./")?
+                }
+            }
+            scheme => return Err(format!("Unsupported URL scheme: {}", scheme).into()),
+        };
+        let client = reqwest::Client::builder().user_agent(&options.user_agent).build()?;
+
+        let robots_url = root.join("robots.txt")?;
+        let robots = robots_from(fetch(&client, &robots_url).await, &robots_url);
+
+        let mut queue: VecDeque<String> = robots
+            .sitemaps
+            .iter()
+            .filter_map(|s| robots_url.join(s).ok())
+            .filter(|u| same_site(&root, u))
+            .map(|u| u.to_string())
+            .collect();
+        if queue.is_empty() {
+            queue.push_back(root.join("sitemap.xml")?.to_string());
+        }
+
+        let mut seen: HashSet<String> = queue.iter().cloned().collect();
+        let mut sitemaps = Vec::new();
+        let mut entries = Vec::new();
+        let mut locs: HashSet<String> = HashSet::new();
+        while let Some(sitemap_url) = queue.pop_front() {
+            if sitemaps.len() >= options.max_sitemaps || entries.len() >= options.max_urls {
+                break;
+            }
+            let document = match fetch(&client, &url::Url::parse(&sitemap_url)?).await {
+                Ok(document) if document.is_success() => document,
+                Ok(document) => {
+                    tracing::debug!("Sitemap {} returned {}", sitemap_url, document.status);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch sitemap {}: {}", sitemap_url, e);
+                    continue;
+                }
+            };
+            let base = url::Url::parse(&document.final_url)?;
+            match parse_sitemap(&document.body) {
+                Ok(Sitemap::Index(children)) => {
+                    for child in children {
+                        let Ok(child) = base.join(&child.loc) else {
+                            continue;
+                        };
+                        if !same_site(&root, &child) {
+                            tracing::debug!("Skipping sitemap {} outside {}", child, root);
+                            continue;
+                        }
+                        if seen.insert(child.to_string()) {
+                            queue.push_back(child.to_string());
+                        }
+                    }
+                }
+                Ok(Sitemap::UrlSet(urls)) => {
+                    for mut entry in urls {
+                        if entries.len() >= options.max_urls {
+                            break;
+                        }
+                        if let Ok(loc) = base.join(&entry.loc) {
+                            entry.loc = loc.to_string();
+                        }
+                        if locs.insert(entry.loc.clone()) {
+                            entries.push(entry);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to parse sitemap {}: {}", sitemap_url, e);
+                    continue;
+                }
+            }
+            sitemaps.push(sitemap_url);
+        }
+
+        tracing::info!(
+            "Discovered {} URLs in {} sitemaps for {}",
+            entries.len(),
+            sitemaps.len(),
+            root
+        );
+        Ok(Site {
+            root: root.to_string(),
+            user_agent: options.user_agent,
+            robots,
+            sitemaps,
+            entries,
+        })
+    }
+
+    /// Sitemap URLs that robots.txt allows for this site's user agent
+    pub fn urls(&self) -> impl Iterator<Item = &SitemapEntry> + '_ {
+        self.entries.iter().filter(|entry| self.is_allowed(&entry.loc))
+    }
+
+    /// Whether robots.txt allows fetching `url`
+    pub fn is_allowed(&self, url: &str) -> bool {
+        url::Url::parse(url).is_ok_and(|url| self.robots.is_url_allowed(&self.user_agent, &url))
+    }
+
+    /// Delay between requests requested by robots.txt
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.robots.crawl_delay(&self.user_agent)
+    }
+}
+
+/// Whether a sitemap URL found in robots.txt or a sitemap index belongs to
+/// the site at `root`: an http(s) URL on the same host, or a file under the
+/// root directory when the root itself is a `file://` URL
+fn same_site(root: &url::Url, url: &url::Url) -> bool {
+    match url.scheme() {
+        "http" | "https" => {
+            matches!(root.scheme(), "http" | "https") && url.host_str() == root.host_str()
+        }
+        "file" => root.scheme() == "file" && url.path().starts_with(root.path()),
+        _ => false,
+    }
+}
+
+/// Fetch a robots.txt or sitemap of the site being discovered; `file://`
+/// URLs only get here when [`DiscoveryOptions::allow_file_urls`] is set
+async fn fetch(
+    client: &reqwest::Client,
+    url: &url::Url,
+) -> Result<crate::external::FetchedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    if url.scheme() == "file" {
+        crate::external::read_local_document(url).await
+    } else {
+        crate::external::fetch_document(client, url.as_str()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robots_groups_and_longest_match() {
+        let robots = RobotsTxt::parse(
+            "User-agent: *\n\
+             Disallow: /private/\n\
+             Allow: /private/public-*.html$\n\
+             Crawl-delay: 2\n\
+             \n\
+             User-agent: BadBot\n\
+             User-agent: OtherBot\n\
+             Disallow: /\n\
+             \n\
+             Sitemap: https://example.com/sitemap.xml # main\n",
+        );
+        assert_eq!(robots.groups.len(), 2);
+        assert_eq!(robots.sitemaps, vec!["https://example.com/sitemap.xml"]);
+
+        let ua = "SemanticBrowser/0.1";
+        assert!(robots.is_allowed(ua, "/"));
+        assert!(!robots.is_allowed(ua, "/private/notes.html"));
+        assert!(robots.is_allowed(ua, "/private/public-1.html"));
+        assert!(!robots.is_allowed(ua, "/private/public-1.html?x=1"));
+        assert_eq!(robots.crawl_delay(ua), Some(Duration::from_secs(2)));
+
+        assert!(!robots.is_allowed("OtherBot/2.0", "/index.html"));
+        assert!(robots.is_allowed("OtherBot/2.0", "/robots.txt"));
+        assert_eq!(robots.crawl_delay("badbot"), None);
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(pattern_matches("/", "/anything"));
+        assert!(pattern_matches("/*.pdf$", "/docs/a.pdf"));
+        assert!(!pattern_matches("/*.pdf$", "/docs/a.pdf.html"));
+        assert!(pattern_matches("/a*b*c", "/a-x-b-y-c-z"));
+        assert!(!pattern_matches("/a*b*c", "/a-x-c-y-b"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exactly"));
+    }
+
+    #[test]
+    fn test_parse_sitemap_and_index() {
+        let urlset = br#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://example.com/?a=1&amp;b=2</loc><lastmod>2025-01-01</lastmod>
+                   <changefreq>daily</changefreq><priority>0.8</priority></url>
+              <url><loc>https://example.com/about</loc></url>
+            </urlset>"#;
+        let Sitemap::UrlSet(entries) = parse_sitemap(urlset).unwrap() else {
+            panic!("expected urlset");
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].loc, "https://example.com/?a=1&b=2");
+        assert_eq!(entries[0].lastmod.as_deref(), Some("2025-01-01"));
+        assert_eq!(entries[0].priority, Some(0.8));
+        assert_eq!(entries[1].priority, None);
+
+        let index = br#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://example.com/posts.xml.gz</loc></sitemap>
+            </sitemapindex>"#;
+        assert!(matches!(parse_sitemap(index).unwrap(), Sitemap::Index(e) if e.len() == 1));
+
+        let text = b"https://example.com/a\nnot a url\nhttps://example.com/b\n";
+        assert!(matches!(parse_sitemap(text).unwrap(), Sitemap::UrlSet(e) if e.len() == 2));
+        assert!(parse_sitemap(b"<html></html>").is_err());
+    }
+}
@@ -0,0 +1,508 @@
+//! Non-HTML documents: PDF, JSON, XML and plain text
+//!
+//! [`detect_kind`] dispatches on the `Content-Type` media type, sniffing the
+//! body when the header is missing or generic (`application/octet-stream`,
+//! `file://` URLs). Each extractor returns an [`ExtractedDocument`] with a
+//! title, searchable text, an LLM-friendly Markdown rendering and the
+//! [`DocumentInfo`] that ends up in `SemanticSnapshot.document` and, through
+//! `kg_integration::insert_document_to_kg`, in the knowledge graph.
+
+use crate::markdown::{estimate_tokens, DEFAULT_SNAPSHOT_TOKENS};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+/// Longest first line used as the title of a text document
+const MAX_TITLE_CHARS: usize = 120;
+
+/// Scalar JSON values listed in the searchable text
+const MAX_JSON_LEAVES: usize = 2000;
+
+/// Kind of document a response holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Html,
+    Pdf,
+    Json,
+    Xml,
+    Text,
+    /// Anything else (images, archives, ...): only the media type is recorded
+    Binary,
+}
+
+/// Metadata of a non-HTML document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentInfo {
+    pub kind: DocumentKind,
+    /// Media type without parameters (`application/pdf`, `application/json`, ...)
+    pub media_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Application that created or produced the document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+    /// Page count (PDF)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<usize>,
+    /// One-line description of the structure ("JSON object with 3 keys: ...")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl DocumentInfo {
+    fn new(kind: DocumentKind, media_type: &str) -> Self {
+        Self {
+            kind,
+            media_type: media_type.to_string(),
+            authors: Vec::new(),
+            keywords: Vec::new(),
+            generator: None,
+            created: None,
+            modified: None,
+            pages: None,
+            summary: None,
+        }
+    }
+}
+
+/// Text and metadata extracted from a non-HTML document
+#[derive(Debug, Clone)]
+pub struct ExtractedDocument {
+    pub info: DocumentInfo,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub language: Option<String>,
+    /// Searchable plain text
+    pub text: String,
+    /// Markdown rendering capped at `markdown::DEFAULT_SNAPSHOT_TOKENS`
+    pub markdown: String,
+    /// The document itself when it is JSON-LD
+    pub json_ld: Vec<serde_json::Value>,
+}
+
+impl ExtractedDocument {
+    fn new(info: DocumentInfo) -> Self {
+        Self {
+            info,
+            title: None,
+            description: None,
+            language: None,
+            text: String::new(),
+            markdown: String::new(),
+            json_ld: Vec::new(),
+        }
+    }
+}
+
+/// Media type of a `Content-Type` value, lowercased and without parameters
+pub fn media_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+/// Decide how a response is extracted
+pub fn detect_kind(content_type: Option<&str>, body: &[u8]) -> DocumentKind {
+    let media_type = content_type.map(media_type).unwrap_or_default();
+    match media_type.as_str() {
+        "application/pdf" | "application/x-pdf" => return DocumentKind::Pdf,
+        "application/json" | "text/json" => return DocumentKind::Json,
+        "application/xml" | "text/xml" => return DocumentKind::Xml,
+        "" | "application/octet-stream" | "binary/octet-stream" => return sniff(body),
+        _ => {}
+    }
+    if media_type.contains("html") {
+        DocumentKind::Html
+    } else if media_type.ends_with("+json") {
+        DocumentKind::Json
+    } else if media_type.ends_with("+xml") {
+        DocumentKind::Xml
+    } else if media_type.starts_with("text/") {
+        DocumentKind::Text
+    } else {
+        DocumentKind::Binary
+    }
+}
+
+/// Guess the kind of an untyped body from its first bytes
+fn sniff(body: &[u8]) -> DocumentKind {
+    if crate::pdf::is_pdf(body) {
+        return DocumentKind::Pdf;
+    }
+    let head = String::from_utf8_lossy(&body[..body.len().min(1024)]);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    let lower = head.to_ascii_lowercase();
+    if head.starts_with('{') || head.starts_with('[') {
+        if serde_json::from_slice::<serde_json::Value>(body).is_ok() {
+            return DocumentKind::Json;
+        }
+    } else if lower.starts_with("<?xml")
+        && !lower.contains("<html")
+        && !lower.contains("<!doctype html")
+    {
+        return DocumentKind::Xml;
+    }
+    if head.starts_with('<') {
+        DocumentKind::Html
+    } else if body.iter().take(1024).any(|&b| b == 0) {
+        DocumentKind::Binary
+    } else {
+        DocumentKind::Text
+    }
+}
+
+/// Extract metadata and text from a PDF
+pub fn extract_pdf(
+    body: &[u8],
+) -> Result<ExtractedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    let pdf = crate::pdf::parse_pdf(body)?;
+    let text = pdf.text();
+
+    let mut info = DocumentInfo::new(DocumentKind::Pdf, "application/pdf");
+    info.authors = pdf.info.author.as_deref().map(split_list).unwrap_or_default();
+    info.keywords = pdf.info.keywords.as_deref().map(split_list).unwrap_or_default();
+    info.generator = pdf.info.producer.clone().or_else(|| pdf.info.creator.clone());
+    info.created = pdf.info.created.clone();
+    info.modified = pdf.info.modified.clone();
+    info.pages = Some(pdf.pages.len());
+    info.summary = Some(format!(
+        "PDF{} document with {} page{}{}",
+        pdf.version.as_deref().map(|v| format!(" {}", v)).unwrap_or_default(),
+        pdf.pages.len(),
+        if pdf.pages.len() == 1 { "" } else { "s" },
+        if pdf.encrypted { " (encrypted)" } else { "" }
+    ));
+
+    let mut document = ExtractedDocument::new(info);
+    document.title = pdf.info.title.clone().or_else(|| first_line(&text));
+    document.description = pdf.info.subject.clone();
+    let mut markdown =
+        document.title.as_deref().map(|t| format!("# {}\n\n", t)).unwrap_or_default();
+    markdown.push_str(&text);
+    document.markdown = truncate_tokens(&markdown);
+    document.text = text;
+    Ok(document)
+}
+
+/// Summarise a JSON document; JSON-LD is returned for KG conversion
+pub fn extract_json(
+    body: &str,
+    media_type: &str,
+) -> Result<ExtractedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    let value: serde_json::Value = serde_json::from_str(body.trim_start_matches('\u{feff}'))?;
+
+    let mut info = DocumentInfo::new(DocumentKind::Json, media_type);
+    info.summary = Some(describe_json(&value));
+    let mut document = ExtractedDocument::new(info);
+
+    if let Some(object) = value.as_object() {
+        document.title = ["title", "name", "headline"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(|v| v.as_str()))
+            .map(str::to_string);
+        document.description =
+            object.get("description").and_then(|v| v.as_str()).map(str::to_string);
+    }
+
+    let mut lines = Vec::new();
+    json_leaves(&value, String::new(), &mut lines);
+    document.text = lines.join("\n");
+
+    let pretty = serde_json::to_string_pretty(&value)?;
+    document.markdown = format!("```json\n{}\n```", truncate_tokens(&pretty));
+
+    let is_json_ld = media_type == "application/ld+json"
+        || value.get("@context").is_some()
+        || value.as_array().is_some_and(|items| items.iter().any(|i| i.get("@context").is_some()));
+    if is_json_ld {
+        document.json_ld.push(value);
+    }
+    Ok(document)
+}
+
+/// Summarise an XML document: root element, element count, text content
+pub fn extract_xml(
+    body: &str,
+    media_type: &str,
+) -> Result<ExtractedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = Reader::from_str(body);
+    let mut root: Option<String> = None;
+    let mut language = None;
+    let mut elements = 0usize;
+    let mut stack: Vec<String> = Vec::new();
+    let mut title: Option<String> = None;
+    let mut texts: Vec<String> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                elements += 1;
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if root.is_none() {
+                    root = Some(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+                    language = xml_lang(&e);
+                }
+                stack.push(name);
+            }
+            Event::Empty(e) => {
+                elements += 1;
+                if root.is_none() {
+                    root = Some(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+                }
+            }
+            Event::End(_) => {
+                stack.pop();
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map(|t| t.into_owned()).unwrap_or_default();
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    continue;
+                }
+                if title.is_none() && stack.last().is_some_and(|n| n.eq_ignore_ascii_case("title"))
+                {
+                    title = Some(text.clone());
+                }
+                texts.push(text);
+            }
+            Event::CData(e) => {
+                let text =
+                    String::from_utf8_lossy(&e).split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    texts.push(text);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let root = root.ok_or("XML document has no root element")?;
+
+    let mut info = DocumentInfo::new(DocumentKind::Xml, media_type);
+    info.summary = Some(format!(
+        "XML document with root <{}> and {} element{}",
+        root,
+        elements,
+        if elements == 1 { "" } else { "s" }
+    ));
+    let mut document = ExtractedDocument::new(info);
+    document.title = title;
+    document.language = language;
+    document.text = texts.join("\n");
+    document.markdown = format!("```xml\n{}\n```", truncate_tokens(body.trim()));
+    Ok(document)
+}
+
+/// Plain text: the first short line doubles as the title
+pub fn extract_text(body: &str, media_type: &str) -> ExtractedDocument {
+    let body = body.trim_start_matches('\u{feff}');
+    let lines = body.lines().count();
+    let mut info = DocumentInfo::new(DocumentKind::Text, media_type);
+    info.summary =
+        Some(format!("Text document with {} line{}", lines, if lines == 1 { "" } else { "s" }));
+    let mut document = ExtractedDocument::new(info);
+    document.title = first_line(body);
+    document.text = body.to_string();
+    document.markdown = truncate_tokens(body.trim());
+    document
+}
+
+/// Document that can't be read as text; only its media type is known
+pub fn binary_document(media_type: &str, size: usize) -> ExtractedDocument {
+    let mut info = DocumentInfo::new(DocumentKind::Binary, media_type);
+    info.summary = Some(format!("{} document ({} bytes)", media_type, size));
+    ExtractedDocument::new(info)
+}
+
+fn xml_lang(element: &quick_xml::events::BytesStart) -> Option<String> {
+    element.attributes().flatten().find_map(|attr| {
+        (attr.key.as_ref() == b"xml:lang")
+            .then(|| String::from_utf8_lossy(&attr.value).into_owned())
+            .filter(|lang| !lang.is_empty())
+    })
+}
+
+fn describe_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(object) => {
+            let keys: Vec<&str> = object.keys().take(10).map(String::as_str).collect();
+            let more = if object.len() > keys.len() {
+                ", ..."
+            } else {
+                ""
+            };
+            format!(
+                "JSON object with {} key{}: {}{}",
+                object.len(),
+                if object.len() == 1 { "" } else { "s" },
+                keys.join(", "),
+                more
+            )
+        }
+        serde_json::Value::Array(items) => {
+            let kinds: std::collections::BTreeSet<&str> = items.iter().map(json_type).collect();
+            format!(
+                "JSON array of {} item{} ({})",
+                items.len(),
+                if items.len() == 1 { "" } else { "s" },
+                kinds.into_iter().collect::<Vec<_>>().join(", ")
+            )
+        }
+        other => format!("JSON {}", json_type(other)),
+    }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// `path: value` lines for the scalar values of a JSON document
+fn json_leaves(value: &serde_json::Value, path: String, lines: &mut Vec<String>) {
+    if lines.len() >= MAX_JSON_LEAVES {
+        return;
+    }
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, child) in object {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                json_leaves(child, path, lines);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                json_leaves(child, format!("{}[{}]", path, i), lines);
+            }
+        }
+        serde_json::Value::String(s) => lines.push(format!("{}: {}", path, s)),
+        serde_json::Value::Null => {}
+        other => lines.push(format!("{}: {}", path, other)),
+    }
+}
+
+/// Split an author or keyword list on `;` (or `,` when there is no `;`)
+fn split_list(value: &str) -> Vec<String> {
+    let separator = if value.contains(';') { ';' } else { ',' };
+    value.split(separator).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+}
+
+fn first_line(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    (line.chars().count() <= MAX_TITLE_CHARS).then(|| line.to_string())
+}
+
+/// Cut text at a line boundary to fit the snapshot token budget
+fn truncate_tokens(text: &str) -> String {
+    if estimate_tokens(text) <= DEFAULT_SNAPSHOT_TOKENS {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for line in text.lines() {
+        if estimate_tokens(&out) + estimate_tokens(line) + 1 > DEFAULT_SNAPSHOT_TOKENS {
+            break;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    if out.is_empty() {
+        out = text.chars().take(DEFAULT_SNAPSHOT_TOKENS * 4).collect();
+    }
+    out.truncate(out.trim_end().len());
+    out.push_str("\n...");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(detect_kind(Some("application/pdf"), b""), DocumentKind::Pdf);
+        assert_eq!(detect_kind(Some("text/html; charset=utf-8"), b""), DocumentKind::Html);
+        assert_eq!(detect_kind(Some("application/problem+json"), b""), DocumentKind::Json);
+        assert_eq!(detect_kind(Some("image/svg+xml"), b""), DocumentKind::Xml);
+        assert_eq!(detect_kind(Some("text/plain"), b"<p>"), DocumentKind::Text);
+        assert_eq!(detect_kind(Some("image/png"), b"\x89PNG"), DocumentKind::Binary);
+
+        // Untyped bodies are sniffed
+        assert_eq!(detect_kind(None, b"%PDF-1.7\n"), DocumentKind::Pdf);
+        assert_eq!(detect_kind(Some("application/octet-stream"), b" [1, 2]"), DocumentKind::Json);
+        assert_eq!(detect_kind(None, b"<?xml version=\"1.0\"?><root/>"), DocumentKind::Xml);
+        assert_eq!(detect_kind(None, b"<!DOCTYPE html><p>hi"), DocumentKind::Html);
+        assert_eq!(detect_kind(None, b"{not json"), DocumentKind::Text);
+        assert_eq!(detect_kind(None, b"Release notes\n"), DocumentKind::Text);
+    }
+
+    #[test]
+    fn test_extract_json_and_json_ld() {
+        let doc = extract_json(
+            r#"{"name": "Widget", "price": 19.5, "tags": ["a", "b"], "stock": null}"#,
+            "application/json",
+        )
+        .unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Widget"));
+        assert_eq!(
+            doc.info.summary.as_deref(),
+            Some("JSON object with 4 keys: name, price, stock, tags")
+        );
+        assert_eq!(doc.text, "name: Widget\nprice: 19.5\ntags[0]: a\ntags[1]: b");
+        assert!(doc.markdown.starts_with("```json\n{\n  \"name\""));
+        assert!(doc.json_ld.is_empty());
+
+        let doc = extract_json(
+            r#"{"@context": "https://schema.org", "@type": "Thing"}"#,
+            "application/json",
+        )
+        .unwrap();
+        assert_eq!(doc.json_ld.len(), 1);
+
+        let doc = extract_json("[1, \"x\", 2]", "application/json").unwrap();
+        assert_eq!(doc.info.summary.as_deref(), Some("JSON array of 3 items (number, string)"));
+        assert!(extract_json("{", "application/json").is_err());
+    }
+
+    #[test]
+    fn test_extract_xml_and_text() {
+        let doc = extract_xml(
+            r#"<?xml version="1.0"?><catalog xml:lang="it"><title>Libri</title>
+               <book id="1"><name>Il nome della rosa</name></book><empty/></catalog>"#,
+            "application/xml",
+        )
+        .unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Libri"));
+        assert_eq!(doc.language.as_deref(), Some("it"));
+        assert_eq!(
+            doc.info.summary.as_deref(),
+            Some("XML document with root <catalog> and 5 elements")
+        );
+        assert_eq!(doc.text, "Libri\nIl nome della rosa");
+
+        let doc = extract_text("\n  Release notes\n\n- fixed a bug\n", "text/plain");
+        assert_eq!(doc.title.as_deref(), Some("Release notes"));
+        assert_eq!(doc.markdown, "Release notes\n\n- fixed a bug");
+
+        let long = "word ".repeat(DEFAULT_SNAPSHOT_TOKENS * 2);
+        let doc = extract_text(
+            &long.replace("word word word word ", "word word word word\n"),
+            "text/plain",
+        );
+        assert!(estimate_tokens(&doc.markdown) <= DEFAULT_SNAPSHOT_TOKENS + 1);
+        assert!(doc.markdown.ends_with("\n..."));
+    }
+}
@@ -0,0 +1,305 @@
+//! Interactive element map
+//!
+//! Lists the visible elements an agent can act on (links, buttons, form
+//! controls, ARIA widgets) with short numeric refs, labels and bounding boxes,
+//! so a model can answer "click 7" instead of guessing a CSS selector. Each
+//! map belongs to one page snapshot: refs resolve to backend DOM node ids of
+//! that snapshot and the executor drops the map when the page navigates.
+//!
+//! For vision models the same refs are drawn onto the page as numbered boxes
+//! ("set-of-marks") before a screenshot is taken, see [`draw_marks`].
+
+use crate::accessibility::{AccessibilityTree, AxNode};
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Element box in CSS pixels, relative to the viewport at snapshot time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// Smallest box around a CDP quad (four x,y corner pairs)
+    pub fn from_quad(quad: &[f64]) -> Option<Self> {
+        if quad.len() != 8 {
+            return None;
+        }
+        let xs = [quad[0], quad[2], quad[4], quad[6]];
+        let ys = [quad[1], quad[3], quad[5], quad[7]];
+        let min = |v: [f64; 4]| v.into_iter().fold(f64::INFINITY, f64::min);
+        let max = |v: [f64; 4]| v.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        let round = |v: f64| (v * 10.0).round() / 10.0;
+        Some(Self {
+            x: round(min(xs)),
+            y: round(min(ys)),
+            width: round(max(xs) - min(xs)),
+            height: round(max(ys) - min(ys)),
+        })
+    }
+
+    /// Whether the box covers any pixels
+    pub fn is_visible(&self) -> bool {
+        self.width >= 1.0 && self.height >= 1.0
+    }
+}
+
+/// Element an agent can act on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractiveElement {
+    /// Ref to pass as `ref` to browser tools, numbered from 1 in document order
+    #[serde(rename = "ref")]
+    pub element_ref: u32,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<String>,
+    pub bounds: BoundingBox,
+    /// Backend DOM node id the ref resolves to
+    #[serde(skip)]
+    pub node_id: i64,
+}
+
+/// Interactive elements of one page snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElementMap {
+    /// URL of the page the refs belong to
+    pub url: String,
+    pub elements: Vec<InteractiveElement>,
+}
+
+impl ElementMap {
+    /// Number the interactive nodes of `tree` that have a visible box in `bounds`
+    pub fn new(url: String, tree: &AccessibilityTree, bounds: &HashMap<i64, BoundingBox>) -> Self {
+        fn collect<'a>(nodes: &'a [AxNode], out: &mut Vec<&'a AxNode>) {
+            for node in nodes {
+                if node.is_interactive() {
+                    out.push(node);
+                }
+                collect(&node.children, out);
+            }
+        }
+        let mut nodes = Vec::new();
+        collect(&tree.nodes, &mut nodes);
+
+        let elements = nodes
+            .into_iter()
+            .filter_map(|node| {
+                let node_id = node.id?;
+                let bounds = *bounds.get(&node_id).filter(|b| b.is_visible())?;
+                Some((node, node_id, bounds))
+            })
+            .zip(1..)
+            .map(|((node, node_id, bounds), element_ref)| InteractiveElement {
+                element_ref,
+                role: node.role.clone(),
+                label: node.name.clone().or_else(|| node.description.clone()),
+                value: node.value.clone(),
+                states: node.states.clone(),
+                bounds,
+                node_id,
+            })
+            .collect();
+        Self { url, elements }
+    }
+
+    /// Backend DOM node id of `element_ref`
+    pub fn resolve(&self, element_ref: u32) -> Option<i64> {
+        self.elements.iter().find(|e| e.element_ref == element_ref).map(|e| e.node_id)
+    }
+
+    /// Elements whose box overlaps a `width` x `height` viewport
+    pub fn in_viewport(&self, width: f64, height: f64) -> Self {
+        let elements = self
+            .elements
+            .iter()
+            .filter(|e| {
+                let b = &e.bounds;
+                b.x < width && b.y < height && b.x + b.width > 0.0 && b.y + b.height > 0.0
+            })
+            .cloned()
+            .collect();
+        Self { url: self.url.clone(), elements }
+    }
+
+    /// One element per line: `[3] button "Sign in" @ 40,120 96x32 (disabled)`
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for element in &self.elements {
+            let _ = write!(out, "[{}] {}", element.element_ref, element.role);
+            if let Some(label) = &element.label {
+                let _ = write!(out, " {:?}", label);
+            }
+            if let Some(value) = &element.value {
+                let _ = write!(out, " = {:?}", value);
+            }
+            let b = &element.bounds;
+            let _ = write!(out, " @ {},{} {}x{}", b.x, b.y, b.width, b.height);
+            if !element.states.is_empty() {
+                let _ = write!(out, " ({})", element.states.join(", "));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Snapshot the visible interactive elements of a page
+#[cfg(feature = "browser-automation")]
+pub async fn snapshot(page: &Page) -> Result<ElementMap, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::dom::{BackendNodeId, GetContentQuadsParams};
+
+    let options = crate::accessibility::AccessibilityOptions { interactive_only: true };
+    let tree = crate::accessibility::snapshot(page, &options).await?;
+    let url = page.url().await?.unwrap_or_default();
+
+    fn node_ids(nodes: &[AxNode], out: &mut Vec<i64>) {
+        for node in nodes {
+            out.extend(node.id);
+            node_ids(&node.children, out);
+        }
+    }
+    let mut ids = Vec::new();
+    node_ids(&tree.nodes, &mut ids);
+
+    // Hidden and detached elements have no content quads and are left out
+    let quads = futures::future::join_all(ids.iter().map(|&id| {
+        page.execute(
+            GetContentQuadsParams::builder().backend_node_id(BackendNodeId::new(id)).build(),
+        )
+    }))
+    .await;
+    let bounds: HashMap<i64, BoundingBox> = ids
+        .into_iter()
+        .zip(quads)
+        .filter_map(|(id, response)| {
+            let response = response.ok()?;
+            let quad = response.quads.first()?;
+            Some((id, BoundingBox::from_quad(quad.inner())?))
+        })
+        .collect();
+
+    Ok(ElementMap::new(url, &tree, &bounds))
+}
+
+/// Id of the overlay element holding the marks
+#[cfg(feature = "browser-automation")]
+const MARKS_OVERLAY_ID: &str = "__semanticbrowser_marks";
+
+/// Overlay a numbered box on each element of `marks`, labelled with its ref
+#[cfg(feature = "browser-automation")]
+pub async fn draw_marks(
+    page: &Page,
+    marks: &ElementMap,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    const DRAW_FN: &str = r#"(function(id, marks) {
+        const colors = ['#e6194b', '#3cb44b', '#4363d8', '#f58231', '#911eb4', '#008080', '#9a6324', '#800000'];
+        document.getElementById(id)?.remove();
+        const root = document.createElement('div');
+        root.id = id;
+        root.style.cssText = 'position:fixed;inset:0;pointer-events:none;z-index:2147483647';
+        for (const m of marks) {
+            const color = colors[m.ref % colors.length];
+            const box = document.createElement('div');
+            box.style.cssText = `position:fixed;left:${m.bounds.x}px;top:${m.bounds.y}px;` +
+                `width:${m.bounds.width}px;height:${m.bounds.height}px;` +
+                `border:2px solid ${color};box-sizing:border-box`;
+            const label = document.createElement('span');
+            label.textContent = m.ref;
+            label.style.cssText = `position:absolute;left:0;top:0;background:${color};color:#fff;` +
+                'font:bold 12px/14px monospace;padding:0 3px';
+            box.appendChild(label);
+            root.appendChild(box);
+        }
+        document.documentElement.appendChild(root);
+    })"#;
+
+    let call = format!(
+        "{}({}, {})",
+        DRAW_FN,
+        serde_json::to_string(MARKS_OVERLAY_ID)?,
+        serde_json::to_string(&marks.elements)?
+    );
+    page.evaluate(call).await?;
+    Ok(())
+}
+
+/// Remove the overlay added by [`draw_marks`]
+#[cfg(feature = "browser-automation")]
+pub async fn clear_marks(page: &Page) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    page.evaluate(format!("document.getElementById({:?})?.remove()", MARKS_OVERLAY_ID)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: i64, role: &str, name: &str) -> AxNode {
+        AxNode {
+            id: Some(id),
+            role: role.to_string(),
+            name: Some(name.to_string()),
+            value: None,
+            description: None,
+            states: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_element_map_refs() {
+        let mut form = node(2, "form", "Search");
+        form.children = vec![node(3, "searchbox", "Query"), node(4, "button", "Go")];
+        let mut root = node(1, "RootWebArea", "Home");
+        root.children = vec![node(5, "link", "Skip to content"), form, node(6, "link", "About")];
+        let tree = AccessibilityTree { nodes: vec![root] };
+
+        let bounds = HashMap::from([
+            (
+                3,
+                BoundingBox::from_quad(&[10.0, 20.0, 210.0, 20.0, 210.0, 52.0, 10.0, 52.0])
+                    .unwrap(),
+            ),
+            (4, BoundingBox { x: 220.0, y: 20.0, width: 48.0, height: 32.0 }),
+            // Off-screen skip links collapse to a zero-sized box
+            (5, BoundingBox { x: 0.0, y: 0.0, width: 0.0, height: 0.0 }),
+            (6, BoundingBox { x: 300.0, y: 24.5, width: 60.0, height: 18.0 }),
+        ]);
+        let map = ElementMap::new("https://example.com/".to_string(), &tree, &bounds);
+
+        let refs: Vec<(u32, i64)> =
+            map.elements.iter().map(|e| (e.element_ref, e.node_id)).collect();
+        assert_eq!(refs, vec![(1, 3), (2, 4), (3, 6)]);
+        assert_eq!(map.resolve(2), Some(4));
+        assert_eq!(map.resolve(4), None);
+        assert_eq!(
+            map.elements[0].bounds,
+            BoundingBox { x: 10.0, y: 20.0, width: 200.0, height: 32.0 }
+        );
+
+        let text = map.to_text();
+        assert!(text.starts_with("[1] searchbox \"Query\" @ 10,20 200x32\n"));
+        assert!(text.ends_with("[3] link \"About\" @ 300,24.5 60x18\n"));
+
+        // Only the elements on screen get a mark
+        let visible = map.in_viewport(280.0, 600.0);
+        let refs: Vec<u32> = visible.elements.iter().map(|e| e.element_ref).collect();
+        assert_eq!(refs, vec![1, 2]);
+        assert_eq!(visible.url, map.url);
+
+        // Refs are serialized for the model, backend node ids are not
+        let json = serde_json::to_value(&map.elements[1]).unwrap();
+        assert_eq!(json["ref"], 2);
+        assert!(json.get("node_id").is_none());
+    }
+}
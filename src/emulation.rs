@@ -0,0 +1,403 @@
+//! Device, locale and timezone emulation
+//!
+//! An [`EmulationProfile`] describes the device and environment a page should
+//! see: viewport and device scale, mobile and touch support, user agent and
+//! `Accept-Language`, locale, timezone, geolocation and preferred color
+//! scheme. Unset fields leave the browser's own value in place.
+//!
+//! [`Profiles`] holds the built-in device presets plus custom profiles loaded
+//! from a JSON file, where each profile may start from a preset (`base`) and
+//! override only what differs. With the `browser-automation` feature
+//! [`apply`] sets a profile on a page through the CDP `Emulation` domain.
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Viewport size in CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Position reported by the Geolocation API
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy radius in meters
+    #[serde(default = "default_accuracy")]
+    pub accuracy: f64,
+}
+
+fn default_accuracy() -> f64 {
+    100.0
+}
+
+/// Value of the `prefers-color-scheme` media feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    NoPreference,
+}
+
+impl ColorScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+            ColorScheme::NoPreference => "no-preference",
+        }
+    }
+}
+
+/// What a page is told about its device and environment, see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmulationProfile {
+    /// Profile this one starts from, resolved by [`Profiles::insert`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    pub viewport: Option<Viewport>,
+    /// Device pixels per CSS pixel
+    pub device_scale_factor: Option<f64>,
+    /// Mobile layout: meta viewport is honoured and scrollbars overlay content
+    pub mobile: Option<bool>,
+    /// Touch events and `navigator.maxTouchPoints`
+    pub touch: Option<bool>,
+    pub user_agent: Option<String>,
+    /// `Accept-Language` header and `navigator.languages`, e.g. `de-DE,de;q=0.9`
+    pub accept_language: Option<String>,
+    /// ICU locale for `Intl` formatting (default: first `accept_language` tag)
+    pub locale: Option<String>,
+    /// IANA timezone, e.g. `Europe/Berlin`
+    pub timezone: Option<String>,
+    /// Needs the `geolocation` permission, see
+    /// [`ContextOptions`](crate::browser_context::ContextOptions)
+    pub geolocation: Option<Geolocation>,
+    pub color_scheme: Option<ColorScheme>,
+}
+
+impl EmulationProfile {
+    /// Built-in device preset by name, see [`PRESETS`]
+    pub fn preset(name: &str) -> Option<Self> {
+        let device = |width, height, scale, mobile, user_agent: Option<&str>| Self {
+            viewport: Some(Viewport { width, height }),
+            device_scale_factor: Some(scale),
+            mobile: Some(mobile),
+            touch: Some(mobile),
+            user_agent: user_agent.map(str::to_string),
+            ..Default::default()
+        };
+        let profile = match name {
+            "desktop" => device(1920, 1080, 1.0, false, None),
+            "desktop-hidpi" => device(1440, 900, 2.0, false, None),
+            "laptop" => device(1366, 768, 1.0, false, None),
+            "iphone-15" => device(393, 852, 3.0, true, Some(IPHONE_USER_AGENT)),
+            "ipad-pro-11" => device(834, 1194, 2.0, true, Some(IPAD_USER_AGENT)),
+            "pixel-7" => device(412, 915, 2.625, true, Some(PIXEL_USER_AGENT)),
+            "galaxy-s23" => device(360, 780, 3.0, true, Some(GALAXY_USER_AGENT)),
+            _ => return None,
+        };
+        Some(profile)
+    }
+
+    /// `self` with its unset fields taken from `base`
+    pub fn merged_over(&self, base: &EmulationProfile) -> Self {
+        Self {
+            base: None,
+            viewport: self.viewport.or(base.viewport),
+            device_scale_factor: self.device_scale_factor.or(base.device_scale_factor),
+            mobile: self.mobile.or(base.mobile),
+            touch: self.touch.or(base.touch),
+            user_agent: self.user_agent.clone().or_else(|| base.user_agent.clone()),
+            accept_language: self.accept_language.clone().or_else(|| base.accept_language.clone()),
+            locale: self.locale.clone().or_else(|| base.locale.clone()),
+            timezone: self.timezone.clone().or_else(|| base.timezone.clone()),
+            geolocation: self.geolocation.or(base.geolocation),
+            color_scheme: self.color_scheme.or(base.color_scheme),
+        }
+    }
+
+    /// Locale to emulate: `locale`, or the first `accept_language` tag
+    pub fn effective_locale(&self) -> Option<String> {
+        self.locale.clone().or_else(|| {
+            let accept = self.accept_language.as_deref()?;
+            let tag = accept.split(',').next()?.split(';').next()?.trim();
+            (!tag.is_empty() && tag != "*").then(|| tag.to_string())
+        })
+    }
+
+    /// Check the profile before anything is sent to the browser
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(viewport) = self.viewport {
+            let valid = 1..=10_000;
+            if !valid.contains(&viewport.width) || !valid.contains(&viewport.height) {
+                return Err(format!(
+                    "viewport must be between 1x1 and 10000x10000, got {}x{}",
+                    viewport.width, viewport.height
+                ));
+            }
+        }
+        if let Some(scale) = self.device_scale_factor {
+            if !(scale > 0.0 && scale <= 4.0) {
+                return Err(format!(
+                    "device_scale_factor must be greater than 0 and at most 4, got {}",
+                    scale
+                ));
+            }
+        }
+        if let Some(geo) = self.geolocation {
+            if !(-90.0..=90.0).contains(&geo.latitude) || !(-180.0..=180.0).contains(&geo.longitude)
+            {
+                return Err(format!(
+                    "geolocation out of range: {}, {}",
+                    geo.latitude, geo.longitude
+                ));
+            }
+            if geo.accuracy < 0.0 {
+                return Err(format!("accuracy must not be negative, got {}", geo.accuracy));
+            }
+        }
+        if self.timezone.as_deref().is_some_and(|tz| tz.trim().is_empty()) {
+            return Err("timezone must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Names of the built-in device presets
+pub const PRESETS: &[&str] =
+    &["desktop", "desktop-hidpi", "laptop", "iphone-15", "ipad-pro-11", "pixel-7", "galaxy-s23"];
+
+const IPHONE_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const IPAD_USER_AGENT: &str = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const PIXEL_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 7) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+const GALAXY_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 14; SM-S911B) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+/// Named emulation profiles: the presets plus custom ones
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profiles {
+    profiles: HashMap<String, EmulationProfile>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Profiles {
+    /// Only the built-in device presets
+    pub fn builtin() -> Self {
+        let profiles = PRESETS
+            .iter()
+            .filter_map(|name| Some((name.to_string(), EmulationProfile::preset(name)?)))
+            .collect();
+        Self { profiles }
+    }
+
+    /// Presets plus the profiles of a JSON file mapping names to profiles
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let data = std::fs::read(path)?;
+        let custom: HashMap<String, EmulationProfile> = serde_json::from_slice(&data)?;
+        let mut profiles = Self::builtin();
+        profiles.extend(custom).map_err(|e| format!("{}: {}", path.display(), e))?;
+        tracing::info!("Loaded emulation profiles from {}", path.display());
+        Ok(profiles)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EmulationProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Names of all profiles, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Add or replace `name`, filling unset fields from its `base` profile
+    pub fn insert(&mut self, name: &str, profile: EmulationProfile) -> Result<(), String> {
+        let profile = match &profile.base {
+            Some(base) => {
+                let base = self
+                    .profiles
+                    .get(base)
+                    .ok_or_else(|| format!("profile '{}': unknown base '{}'", name, base))?;
+                profile.merged_over(base)
+            }
+            None => profile,
+        };
+        profile.validate().map_err(|e| format!("profile '{}': {}", name, e))?;
+        self.profiles.insert(name.to_string(), profile);
+        Ok(())
+    }
+
+    /// Insert `custom` profiles, each after the profile it is based on
+    fn extend(&mut self, mut custom: HashMap<String, EmulationProfile>) -> Result<(), String> {
+        while !custom.is_empty() {
+            let mut ready: Vec<String> = custom
+                .iter()
+                .filter(|(_, p)| {
+                    p.base.as_ref().map_or(true, |base| {
+                        !custom.contains_key(base) && self.profiles.contains_key(base)
+                    })
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+            if ready.is_empty() {
+                // Every remaining profile waits on an unknown or circular base;
+                // take one anyway, failing unless its base is already known
+                let mut names: Vec<String> = custom.keys().cloned().collect();
+                names.sort();
+                ready.push(names.swap_remove(0));
+            }
+            ready.sort();
+            for name in ready {
+                if let Some(profile) = custom.remove(&name) {
+                    self.insert(&name, profile)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Emulate `profile` in `page` until the page is closed
+#[cfg(feature = "browser-automation")]
+pub async fn apply(
+    page: &Page,
+    profile: &EmulationProfile,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::emulation::{
+        MediaFeature, SetDeviceMetricsOverrideParams, SetEmulatedMediaParams,
+        SetGeolocationOverrideParams, SetLocaleOverrideParams, SetTimezoneOverrideParams,
+        SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+    };
+
+    profile.validate()?;
+
+    if profile.viewport.is_some()
+        || profile.device_scale_factor.is_some()
+        || profile.mobile.is_some()
+    {
+        // Zero keeps the window's own size and scale
+        let (width, height) =
+            profile.viewport.map_or((0, 0), |v| (i64::from(v.width), i64::from(v.height)));
+        page.execute(SetDeviceMetricsOverrideParams::new(
+            width,
+            height,
+            profile.device_scale_factor.unwrap_or(0.0),
+            profile.mobile.unwrap_or(false),
+        ))
+        .await?;
+    }
+    if let Some(touch) = profile.touch {
+        let mut params = SetTouchEmulationEnabledParams::new(touch);
+        params.max_touch_points = touch.then_some(5);
+        page.execute(params).await?;
+    }
+    if profile.user_agent.is_some() || profile.accept_language.is_some() {
+        // Accept-Language can only be overridden together with the user agent
+        let user_agent = match &profile.user_agent {
+            Some(user_agent) => user_agent.clone(),
+            None => page.evaluate("navigator.userAgent").await?.into_value::<String>()?,
+        };
+        let mut params = SetUserAgentOverrideParams::new(user_agent);
+        params.accept_language = profile.accept_language.clone();
+        page.execute(params).await?;
+    }
+    if let Some(locale) = profile.effective_locale() {
+        page.execute(SetLocaleOverrideParams { locale: Some(locale) }).await?;
+    }
+    if let Some(timezone) = &profile.timezone {
+        page.execute(SetTimezoneOverrideParams::new(timezone.clone())).await?;
+    }
+    if let Some(geo) = profile.geolocation {
+        page.execute(SetGeolocationOverrideParams {
+            latitude: Some(geo.latitude),
+            longitude: Some(geo.longitude),
+            accuracy: Some(geo.accuracy),
+        })
+        .await?;
+    }
+    if let Some(scheme) = profile.color_scheme {
+        let feature = MediaFeature::new("prefers-color-scheme", scheme.as_str());
+        page.execute(SetEmulatedMediaParams { media: None, features: Some(vec![feature]) }).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let profiles = Profiles::builtin();
+        assert_eq!(profiles.names().len(), PRESETS.len());
+        for name in PRESETS {
+            let preset = profiles.get(name).unwrap();
+            assert!(preset.validate().is_ok(), "{}", name);
+            assert!(preset.viewport.is_some());
+        }
+
+        let iphone = EmulationProfile::preset("iphone-15").unwrap();
+        assert_eq!(iphone.viewport, Some(Viewport { width: 393, height: 852 }));
+        assert_eq!((iphone.mobile, iphone.touch), (Some(true), Some(true)));
+        assert!(iphone.user_agent.unwrap().contains("iPhone"));
+        assert!(EmulationProfile::preset("desktop").unwrap().user_agent.is_none());
+        assert!(EmulationProfile::preset("nokia-3310").is_none());
+    }
+
+    #[test]
+    fn test_custom_profiles() {
+        let json = r#"{
+            "berlin-iphone": {
+                "base": "iphone-15",
+                "accept_language": "de-DE,de;q=0.9,en;q=0.5",
+                "timezone": "Europe/Berlin",
+                "geolocation": { "latitude": 52.52, "longitude": 13.405 }
+            },
+            "berlin-iphone-dark": { "base": "berlin-iphone", "color_scheme": "dark" },
+            "kiosk": { "viewport": { "width": 1080, "height": 1920 }, "locale": "en-GB" }
+        }"#;
+        let mut profiles = Profiles::builtin();
+        profiles.extend(serde_json::from_str(json).unwrap()).unwrap();
+
+        let dark = profiles.get("berlin-iphone-dark").unwrap();
+        assert_eq!(dark.color_scheme, Some(ColorScheme::Dark));
+        assert_eq!(dark.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(dark.device_scale_factor, Some(3.0));
+        assert_eq!(dark.geolocation.unwrap().accuracy, 100.0);
+        assert_eq!(dark.effective_locale().as_deref(), Some("de-DE"));
+        assert!(dark.base.is_none());
+
+        let kiosk = profiles.get("kiosk").unwrap();
+        assert_eq!(kiosk.effective_locale().as_deref(), Some("en-GB"));
+        assert!(kiosk.mobile.is_none());
+
+        let unknown = r#"{ "a": { "base": "b" }, "b": { "base": "a" } }"#;
+        let err = profiles.extend(serde_json::from_str(unknown).unwrap()).unwrap_err();
+        assert!(err.contains("unknown base"), "{}", err);
+
+        let invalid = EmulationProfile { device_scale_factor: Some(0.0), ..Default::default() };
+        assert!(profiles.insert("flat", invalid).is_err());
+        let lost = EmulationProfile {
+            geolocation: Some(Geolocation { latitude: 91.0, longitude: 0.0, accuracy: 1.0 }),
+            ..Default::default()
+        };
+        assert!(lost.validate().is_err());
+    }
+}
@@ -40,42 +40,62 @@ async fn get_browser_pool(
 pub async fn browse_with_chromium(
     url: &str,
     query: &str,
+) -> Result<BrowseOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    browse_with_chromium_options(url, query, crate::browser::NavigationOptions::default()).await
+}
+
+/// Browse URL with chromiumoxide using explicit navigation options
+///
+/// Screenshots and PDFs requested in `options` are returned on the outcome.
+#[cfg(feature = "browser-automation")]
+pub async fn browse_with_chromium_options(
+    url: &str,
+    query: &str,
+    options: crate::browser::NavigationOptions,
 ) -> Result<BrowseOutcome, Box<dyn std::error::Error + Send + Sync>> {
     tracing::info!("Browsing with chromiumoxide: {} (query: {})", url, query);
 
     let pool = get_browser_pool().await?;
-    let options = crate::browser::NavigationOptions::default();
+    let format = options.screenshot_options.format;
 
     let semantic_data = pool.navigate_and_extract(url, options).await?;
 
-    let microdata: Vec<MicrodataSummary> = semantic_data
-        .microdata
-        .iter()
-        .map(|item| MicrodataSummary {
-            item_type: item.item_type.clone(),
-            properties: item.properties.len(),
-        })
-        .collect();
+    let microdata = semantic_data.microdata.iter().map(MicrodataSummary::from).collect();
 
     let snapshot = SemanticSnapshot {
         title: semantic_data.title.clone(),
         description: semantic_data.meta_description.clone(),
         language: semantic_data.language.clone(),
+        detected_language: crate::language::detect_language(&semantic_data.text_content),
         canonical_url: semantic_data.canonical_url.clone(),
         final_url: semantic_data.final_url.clone(),
         keywords: semantic_data.meta_keywords.clone(),
         open_graph: semantic_data.open_graph.clone(),
         twitter_card: semantic_data.twitter_card.clone(),
         json_ld_count: semantic_data.json_ld.len(),
+        json_ld: semantic_data.json_ld.clone(),
         microdata,
+        microdata_items: semantic_data.microdata.clone(),
+        rdfa: semantic_data.rdfa.clone(),
+        microformats: semantic_data.microformats.clone(),
+        links: semantic_data.links.clone(),
+        feed: None,
+        document: None,
+        article: semantic_data.article.clone(),
+        citation: semantic_data.citation.clone(),
+        markdown: semantic_data.markdown.clone(),
         text_preview: build_text_preview(&semantic_data.text_content),
         text_length: semantic_data.text_content.len(),
+        encoding: None,
         query_matches: build_query_matches_from_text(&semantic_data.text_content, query),
     };
 
     let summary = build_summary(url, query, &snapshot);
+    let screenshot =
+        semantic_data.screenshot.map(|data| crate::capture::Capture::screenshot(data, format));
+    let pdf = semantic_data.pdf.map(crate::capture::Capture::pdf);
 
-    Ok(BrowseOutcome { summary, snapshot })
+    Ok(BrowseOutcome { summary, snapshot, screenshot, pdf })
 }
 
 /// Browse URL with chromiumoxide and return full semantic data
@@ -150,6 +170,134 @@ pub async fn browse_and_insert_kg(
     Ok((semantic_data, count))
 }
 
+/// Largest file [`read_local_document`] reads
+pub const MAX_LOCAL_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Raw response of [`fetch_document`] or [`read_local_document`]
+#[derive(Debug, Clone)]
+pub struct FetchedDocument {
+    /// URL after redirects
+    pub final_url: String,
+    /// HTTP status code (200 or 404 for `file://` URLs)
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl FetchedDocument {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Body decoded with its sniffed charset (see [`crate::charset`]),
+    /// replacing invalid sequences
+    pub fn text(&self) -> String {
+        self.decode().0
+    }
+
+    /// Decoded body and the encoding it was decoded with
+    pub fn decode(&self) -> (String, &'static encoding_rs::Encoding) {
+        crate::charset::decode(&self.body, self.content_type.as_deref())
+    }
+}
+
+/// Fetch a URL over HTTP without rendering or extraction.
+///
+/// Only `http` and `https` URLs are fetched: callers pass URLs found in
+/// remote documents (sitemaps, feed links), so other schemes are refused.
+/// Responses go through the process-wide cassette when one is configured
+/// (see [`crate::replay`]).
+pub async fn fetch_document(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<FetchedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    fetch_document_with(client, url, crate::replay::global()).await
+}
+
+/// [`fetch_document`] with an explicit record/replay cassette
+pub async fn fetch_document_with(
+    client: &reqwest::Client,
+    url: &str,
+    replay: Option<&crate::replay::Replay>,
+) -> Result<FetchedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    let parsed = url::Url::parse(url)?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()).into());
+    }
+
+    let request = crate::replay::RecordedRequest::get(parsed.as_str());
+    if let Some(replay) = replay {
+        if let Some(recorded) = replay.lookup(&request)? {
+            return Ok(FetchedDocument {
+                final_url: recorded.final_url.clone().unwrap_or_else(|| parsed.to_string()),
+                status: recorded.status,
+                content_type: recorded.header("content-type").map(str::to_string),
+                body: recorded.body.0,
+            });
+        }
+    }
+
+    let response = client.get(parsed.clone()).send().await?;
+    let final_url = response.url().to_string();
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let body = response.bytes().await?.to_vec();
+
+    if let Some(replay) = replay {
+        replay.record(
+            request,
+            crate::replay::RecordedResponse {
+                status,
+                headers,
+                final_url: (final_url != parsed.as_str()).then(|| final_url.clone()),
+                body: crate::replay::Body(body.clone()),
+            },
+        );
+    }
+    Ok(FetchedDocument { final_url, status, content_type, body })
+}
+
+/// Read a `file://` URL from disk as a [`FetchedDocument`] (a missing file
+/// yields status 404).
+///
+/// Lets a fixture directory stand in for a server; never pass it URLs taken
+/// from remote documents. Files larger than [`MAX_LOCAL_DOCUMENT_BYTES`] are
+/// rejected.
+pub async fn read_local_document(
+    url: &url::Url,
+) -> Result<FetchedDocument, Box<dyn std::error::Error + Send + Sync>> {
+    use tokio::io::AsyncReadExt;
+
+    let path = url.to_file_path().map_err(|_| format!("Invalid file URL: {}", url))?;
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(FetchedDocument {
+                final_url: url.to_string(),
+                status: 404,
+                content_type: None,
+                body: Vec::new(),
+            });
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut body = Vec::new();
+    file.take(MAX_LOCAL_DOCUMENT_BYTES + 1).read_to_end(&mut body).await?;
+    if body.len() as u64 > MAX_LOCAL_DOCUMENT_BYTES {
+        return Err(format!("{} is larger than {} bytes", url, MAX_LOCAL_DOCUMENT_BYTES).into());
+    }
+    Ok(FetchedDocument { final_url: url.to_string(), status: 200, content_type: None, body })
+}
+
 /// Call browser-use to browse a URL and extract semantic data (HTTP fallback)
 ///
 /// This is now the fallback method when chromiumoxide is not available or fails.
@@ -158,14 +306,142 @@ pub async fn browse_with_browser_use(
     url: &str,
     query: &str,
 ) -> Result<BrowseOutcome, Box<dyn std::error::Error + Send + Sync>> {
-    // For now, fetch HTML and parse semantically
+    browse_over_http(url, query, crate::replay::global()).await
+}
+
+/// HTTP fallback browse with an explicit record/replay cassette
+///
+/// With a cassette in `Replay` mode this runs without network access.
+pub async fn browse_over_http(
+    url: &str,
+    query: &str,
+    replay: Option<&crate::replay::Replay>,
+) -> Result<BrowseOutcome, Box<dyn std::error::Error + Send + Sync>> {
     let client = reqwest::Client::new();
-    let response = client.get(url).send().await?;
-    let final_url = response.url().to_string();
-    let html = response.text().await?;
+    let document = fetch_document_with(&client, url, replay).await?;
+
+    let snapshot = extract_document(
+        &document.body,
+        document.content_type.as_deref(),
+        document.final_url,
+        query,
+    )?;
+    let summary = build_summary(url, query, &snapshot);
+
+    Ok(BrowseOutcome { summary, snapshot, screenshot: None, pdf: None })
+}
+
+/// Extract a [`SemanticSnapshot`] from a raw response body.
+///
+/// Dispatches on the content type (sniffing untyped bodies): PDFs are parsed
+/// for their text and document info, JSON, XML and plain text are summarised
+/// (see [`crate::documents`]), and feeds and HTML go through
+/// [`extract_snapshot`]. Text is decoded with the sniffed charset rather than
+/// trusting the header alone.
+pub fn extract_document(
+    body: &[u8],
+    content_type: Option<&str>,
+    final_url: String,
+    query: &str,
+) -> Result<SemanticSnapshot, Box<dyn std::error::Error + Send + Sync>> {
+    use crate::documents::{self, DocumentKind};
+
+    let kind = documents::detect_kind(content_type, body);
+    let media_type = content_type.map(documents::media_type).unwrap_or_else(|| {
+        match kind {
+            DocumentKind::Pdf => "application/pdf",
+            DocumentKind::Json => "application/json",
+            DocumentKind::Xml => "application/xml",
+            DocumentKind::Html => "text/html",
+            DocumentKind::Text => "text/plain",
+            DocumentKind::Binary => "application/octet-stream",
+        }
+        .to_string()
+    });
+
+    match kind {
+        DocumentKind::Pdf => {
+            let document = documents::extract_pdf(body)?;
+            return Ok(document_snapshot(document, final_url, query));
+        }
+        DocumentKind::Binary => {
+            let document = documents::binary_document(&media_type, body.len());
+            return Ok(document_snapshot(document, final_url, query));
+        }
+        _ => {}
+    }
+
+    let (text, encoding) = crate::charset::decode(body, content_type);
+    let is_feed = crate::feeds::detect_format(content_type, &text).is_some();
+    let mut snapshot = match kind {
+        DocumentKind::Json if !is_feed => {
+            document_snapshot(documents::extract_json(&text, &media_type)?, final_url, query)
+        }
+        DocumentKind::Xml if !is_feed => {
+            document_snapshot(documents::extract_xml(&text, &media_type)?, final_url, query)
+        }
+        DocumentKind::Text => {
+            document_snapshot(documents::extract_text(&text, &media_type), final_url, query)
+        }
+        _ => extract_snapshot(&text, content_type, final_url, query)?,
+    };
+    snapshot.encoding = Some(encoding.name().to_string());
+    Ok(snapshot)
+}
+
+/// Snapshot of a non-HTML document; its JSON-LD, if any, is kept for the KG
+fn document_snapshot(
+    document: crate::documents::ExtractedDocument,
+    final_url: String,
+    query: &str,
+) -> SemanticSnapshot {
+    SemanticSnapshot {
+        title: document.title,
+        description: document.description,
+        language: document.language,
+        detected_language: crate::language::detect_language(&document.text),
+        canonical_url: None,
+        final_url,
+        keywords: document.info.keywords.clone(),
+        open_graph: HashMap::new(),
+        twitter_card: HashMap::new(),
+        json_ld_count: document.json_ld.len(),
+        json_ld: document.json_ld,
+        microdata: Vec::new(),
+        microdata_items: Vec::new(),
+        rdfa: Vec::new(),
+        microformats: Default::default(),
+        links: Vec::new(),
+        feed: None,
+        document: Some(document.info),
+        article: None,
+        citation: None,
+        markdown: document.markdown,
+        text_preview: build_text_preview(&document.text),
+        text_length: document.text.len(),
+        encoding: None,
+        query_matches: build_query_matches_from_text(&document.text, query),
+    }
+}
+
+/// Extract a [`SemanticSnapshot`] from a decoded HTML or feed document.
+///
+/// RSS, Atom and JSON Feed documents get a feed snapshot; anything else is
+/// parsed as HTML.
+pub fn extract_snapshot(
+    html: &str,
+    content_type: Option<&str>,
+    final_url: String,
+    query: &str,
+) -> Result<SemanticSnapshot, Box<dyn std::error::Error + Send + Sync>> {
+    // RSS, Atom and JSON Feed documents get a feed snapshot instead of HTML extraction
+    if crate::feeds::detect_format(content_type, html).is_some() {
+        let feed = crate::feeds::parse_feed(html, Some(&final_url))?;
+        return Ok(feed_snapshot(feed, final_url, query));
+    }
 
     // Parse HTML semantically without validation (since external sites may have scripts)
-    let document = scraper::Html::parse_document(&html);
+    let document = scraper::Html::parse_document(html);
 
     // Extract title
     let title_selector = scraper::Selector::parse("title")
@@ -175,34 +451,30 @@ pub async fn browse_with_browser_use(
     // Extract JSON-LD
     let json_ld_selector = scraper::Selector::parse("script[type=\"application/ld+json\"]")
         .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-    let mut json_ld_count = 0usize;
+    let mut json_ld = Vec::new();
     for element in document.select(&json_ld_selector) {
         if let Some(text) = element.text().next() {
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
                 if !value.is_null() {
-                    json_ld_count += 1;
+                    json_ld.push(value);
                 }
             }
         }
     }
+    let json_ld_count = json_ld.len();
 
     // Extract microdata
-    let itemscope_selector = scraper::Selector::parse("[itemscope]")
-        .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-    let itemprop_selector = scraper::Selector::parse("[itemprop]")
-        .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-    let mut microdata = Vec::new();
-    for element in document.select(&itemscope_selector) {
-        let item_type = element.value().attr("itemtype").unwrap_or("").to_string();
-        let mut property_count = 0usize;
-        for prop in element.select(&itemprop_selector) {
-            let prop_value = prop.text().collect::<String>();
-            if !prop_value.trim().is_empty() {
-                property_count += 1;
-            }
-        }
-        microdata.push(MicrodataSummary { item_type, properties: property_count });
-    }
+    let microdata_items = crate::parser::extract_microdata(&document, Some(&final_url))?;
+    let microdata = microdata_items.iter().map(MicrodataSummary::from).collect();
+
+    // Extract RDFa
+    let rdfa = crate::rdfa::extract_rdfa(&document, Some(&final_url));
+
+    // Extract microformats2
+    let microformats = crate::microformats::extract_microformats(&document, Some(&final_url));
+
+    // Extract outgoing links
+    let links = crate::links::extract_links(&document, Some(&final_url));
 
     let description = extract_meta_description(&document);
     let keywords = extract_meta_keywords(&document);
@@ -210,28 +482,81 @@ pub async fn browse_with_browser_use(
     let canonical_url = extract_canonical_url(&document);
     let open_graph = extract_open_graph(&document);
     let twitter_card = extract_twitter_card(&document);
-    let text_content = extract_text_content(&document);
+    let citation = crate::citation::extract_citation(&document, Some(&final_url));
+    let (text_content, article) = crate::readability::extract_text(
+        &document,
+        Some(&final_url),
+        crate::readability::ContentMode::default(),
+    );
+    let markdown = crate::markdown::snapshot_markdown(&document, &final_url);
     let query_matches = build_query_matches_from_document(&document, query);
 
-    let snapshot = SemanticSnapshot {
+    Ok(SemanticSnapshot {
         title,
         description,
         language,
+        detected_language: crate::language::detect_language(&text_content),
         canonical_url,
         final_url,
         keywords,
         open_graph,
         twitter_card,
         json_ld_count,
+        json_ld,
         microdata,
+        microdata_items,
+        rdfa,
+        microformats,
+        links,
+        feed: None,
+        document: None,
+        article,
+        citation,
+        markdown,
         text_preview: build_text_preview(&text_content),
         text_length: text_content.len(),
+        encoding: None,
         query_matches,
-    };
-
-    let summary = build_summary(url, query, &snapshot);
+    })
+}
 
-    Ok(BrowseOutcome { summary, snapshot })
+/// Snapshot of a feed document: item titles and summaries stand in for page text
+fn feed_snapshot(feed: crate::feeds::Feed, final_url: String, query: &str) -> SemanticSnapshot {
+    let text = feed
+        .items
+        .iter()
+        .flat_map(|item| [item.title.as_deref(), item.summary.as_deref()])
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(". ");
+
+    SemanticSnapshot {
+        title: feed.title.clone(),
+        description: feed.description.clone(),
+        language: feed.language.clone(),
+        detected_language: crate::language::detect_language(&text),
+        canonical_url: None,
+        final_url,
+        keywords: Vec::new(),
+        open_graph: HashMap::new(),
+        twitter_card: HashMap::new(),
+        json_ld_count: 0,
+        json_ld: Vec::new(),
+        microdata: Vec::new(),
+        microdata_items: Vec::new(),
+        rdfa: Vec::new(),
+        microformats: Default::default(),
+        links: Vec::new(),
+        feed: Some(feed),
+        document: None,
+        article: None,
+        citation: None,
+        markdown: String::new(),
+        text_preview: build_text_preview(&text),
+        text_length: text.len(),
+        encoding: None,
+        query_matches: build_query_matches_from_text(&text, query),
+    }
 }
 
 /// Smart browse: Try chromiumoxide first, fallback to HTTP
@@ -250,6 +575,8 @@ pub async fn browse_with_best_available(
                 tracing::info!("Successfully browsed with chromiumoxide");
                 return Ok(result);
             }
+            // Shed load when the page pool is saturated instead of moving it to HTTP
+            Err(e) if e.is::<crate::page_pool::PoolError>() => return Err(e),
             Err(e) => {
                 tracing::warn!("Chromiumoxide failed: {}, falling back to HTTP", e);
             }
@@ -261,6 +588,38 @@ pub async fn browse_with_best_available(
     browse_with_browser_use(url, query).await
 }
 
+/// Smart browse that can also capture a screenshot and/or PDF of the page
+///
+/// Without captures this is [`browse_with_best_available`]. Captures need a
+/// rendered page, so there is no HTTP fallback when any are requested.
+pub async fn browse_with_capture(
+    url: &str,
+    query: &str,
+    capture: &crate::capture::CaptureRequest,
+) -> Result<BrowseOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    if capture.is_empty() {
+        return browse_with_best_available(url, query).await;
+    }
+    capture.validate()?;
+
+    #[cfg(feature = "browser-automation")]
+    {
+        let options = crate::browser::NavigationOptions {
+            take_screenshot: capture.screenshot.is_some(),
+            screenshot_options: capture.screenshot.clone().unwrap_or_default(),
+            print_pdf: capture.pdf.clone(),
+            ..Default::default()
+        };
+        browse_with_chromium_options(url, query, options).await
+    }
+
+    #[cfg(not(feature = "browser-automation"))]
+    {
+        let _ = (url, query);
+        Err("Screenshot and PDF capture require the browser-automation feature".into())
+    }
+}
+
 /// Call browser-use Python library using PyO3 (if available) or subprocess fallback
 ///
 /// DEPRECATED: Use browse_with_chromium or browse_with_best_available instead.
@@ -496,8 +855,12 @@ pub async fn run_langgraph_workflow(
                             .collect::<Vec<_>>()
                             .join(" ");
 
-                        let entities =
-                            crate::annotator::annotate_html(&html_content).unwrap_or_default();
+                        let language = snapshot.effective_language();
+                        let entities: Vec<String> =
+                            crate::annotator::extract_entities(&html_content, language.as_deref())
+                                .into_iter()
+                                .map(|entity| entity.text)
+                                .collect();
 
                         let mut new_state = state.clone();
                         new_state.results.insert(
@@ -714,7 +1077,8 @@ fn build_text_preview(text: &str) -> String {
     preview
 }
 
-fn normalize_whitespace(text: &str) -> String {
+/// Collapse runs of whitespace into single spaces
+pub(crate) fn normalize_whitespace(text: &str) -> String {
     text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
@@ -732,7 +1096,6 @@ fn tokenize_query(query: &str) -> Vec<String> {
         .collect()
 }
 
-#[cfg(feature = "browser-automation")]
 fn build_query_matches_from_text(text: &str, query: &str) -> Vec<QueryMatch> {
     let tokens = tokenize_query(query);
     if tokens.is_empty() {
@@ -897,28 +1260,6 @@ fn extract_twitter_card(document: &scraper::Html) -> HashMap<String, String> {
     twitter
 }
 
-fn extract_text_content(document: &scraper::Html) -> String {
-    let selectors = ["main", "article", "[role='main']", ".content", "#content"];
-    for selector_str in &selectors {
-        if let Ok(selector) = scraper::Selector::parse(selector_str) {
-            if let Some(element) = document.select(&selector).next() {
-                let text = normalize_whitespace(&element.text().collect::<Vec<_>>().join(" "));
-                if !text.is_empty() {
-                    return text;
-                }
-            }
-        }
-    }
-
-    if let Ok(body_selector) = scraper::Selector::parse("body") {
-        if let Some(body) = document.select(&body_selector).next() {
-            return normalize_whitespace(&body.text().collect::<Vec<_>>().join(" "));
-        }
-    }
-
-    String::new()
-}
-
 const PY_BROWSER_TIMEOUT: Duration = Duration::from_secs(45);
 
 /// Spawn a Python subprocess to drive a headless browser via browser-use/Playwright.
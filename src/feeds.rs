@@ -0,0 +1,722 @@
+//! RSS, Atom and JSON Feed ingestion
+//!
+//! Parses RSS 2.0 (and RSS 1.0/RDF), Atom 1.0 and JSON Feed 1.x documents into
+//! a common [`Feed`] model. Dates are normalized to RFC 3339, relative links are
+//! resolved against the feed URL, and HTML descriptions are reduced to text.
+//! [`fetch_feeds`] also follows `<link rel="alternate">` feed discovery when it
+//! is given an HTML page. Items are mapped to `schema:BlogPosting` resources by
+//! `kg_integration::insert_feed_to_kg`.
+
+use crate::external::normalize_whitespace;
+use crate::links::Link;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of feeds fetched from the `<link rel="alternate">` entries of a page
+const MAX_DISCOVERED_FEEDS: usize = 5;
+
+/// Namespace of RSS 1.0 elements, which live inside a generic `rdf:RDF` root
+const RSS1_NAMESPACE: &str = "http://purl.org/rss/1.0/";
+
+/// Media types advertised for feeds in `<link type>` and `Content-Type`
+const FEED_MEDIA_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/rdf+xml",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    JsonFeed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedAuthor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// A feed entry (RSS `<item>`, Atom `<entry>`, JSON Feed item)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedItem {
+    /// `guid` / `id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Absolute URL of the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// Plain-text summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Plain-text full content, when the feed carries it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Publication date (RFC 3339 when parseable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    /// Last update (RFC 3339 when parseable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<FeedAuthor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feed {
+    pub format: FeedFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Web site the feed belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// URL the feed was fetched from (or its self link)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<FeedAuthor>,
+    pub items: Vec<FeedItem>,
+}
+
+/// Whether a `Content-Type` value names a feed media type
+pub fn is_feed_content_type(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+    FEED_MEDIA_TYPES.iter().any(|t| media_type.eq_ignore_ascii_case(t))
+}
+
+/// Detect the feed format of a response body.
+///
+/// `text/html` responses are never feeds; anything else (including the generic
+/// `application/xml`, `text/xml` and `application/json` types many servers use)
+/// is sniffed from the body.
+pub fn detect_format(content_type: Option<&str>, body: &str) -> Option<FeedFormat> {
+    if content_type.is_some_and(|ct| ct.to_ascii_lowercase().contains("html")) {
+        return None;
+    }
+    let body = body.trim_start_matches('\u{feff}').trim_start();
+    if body.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(body).ok()?;
+        let version = value.get("version")?.as_str()?;
+        return version.contains("jsonfeed.org/version/").then_some(FeedFormat::JsonFeed);
+    }
+    if !body.starts_with('<') {
+        return None;
+    }
+    let mut reader = Reader::from_str(body);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let name = e.local_name();
+                return match name.as_ref() {
+                    b"rss" => Some(FeedFormat::Rss),
+                    // Any RDF/XML document has an `rdf:RDF` root; RSS 1.0 declares
+                    // its namespace or opens with a `<channel>`
+                    b"RDF" => {
+                        let rss_namespace = e
+                            .attributes()
+                            .flatten()
+                            .any(|attr| attr.value.as_ref() == RSS1_NAMESPACE.as_bytes());
+                        (rss_namespace || first_child_is_channel(&mut reader))
+                            .then_some(FeedFormat::Rss)
+                    }
+                    b"feed" => Some(FeedFormat::Atom),
+                    _ => None,
+                };
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Whether the next element read is a `<channel>`
+fn first_child_is_channel(reader: &mut Reader<&[u8]>) -> bool {
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                return e.local_name().as_ref() == b"channel";
+            }
+            Ok(Event::Eof) | Err(_) => return false,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Feed URLs advertised by a page through `<link rel="alternate" type="...">`.
+///
+/// Only `http(s)` URLs are returned: the page is untrusted and must not point
+/// the fetcher at `file://` or other local resources.
+pub fn discover_feeds(links: &[Link]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for link in links {
+        let advertised = link.has_rel("alternate")
+            && link.media_type.as_deref().is_some_and(is_feed_content_type)
+            && url::Url::parse(&link.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if advertised && !urls.contains(&link.url) {
+            urls.push(link.url.clone());
+        }
+    }
+    urls
+}
+
+/// Parse an RSS, Atom or JSON Feed document.
+///
+/// `feed_url` is the URL the document was fetched from; it is used to resolve
+/// relative links and as the default `feed_url`.
+pub fn parse_feed(
+    body: &str,
+    feed_url: Option<&str>,
+) -> Result<Feed, Box<dyn std::error::Error + Send + Sync>> {
+    let mut feed = match detect_format(None, body) {
+        Some(FeedFormat::JsonFeed) => parse_json_feed(body)?,
+        Some(FeedFormat::Rss) => parse_rss(&parse_xml(body)?),
+        Some(FeedFormat::Atom) => parse_atom(&parse_xml(body)?),
+        None => return Err("Not an RSS, Atom or JSON Feed document".into()),
+    };
+
+    if feed.feed_url.is_none() {
+        feed.feed_url = feed_url.map(str::to_string);
+    }
+    let base = feed.feed_url.as_deref().or(feed_url).and_then(|u| url::Url::parse(u).ok());
+    if let Some(base) = &base {
+        let resolve = |value: &mut Option<String>| {
+            if let Some(resolved) = value.as_deref().and_then(|v| base.join(v).ok()) {
+                *value = Some(resolved.to_string());
+            }
+        };
+        resolve(&mut feed.link);
+        for item in &mut feed.items {
+            resolve(&mut item.link);
+            resolve(&mut item.image);
+            for author in &mut item.authors {
+                resolve(&mut author.url);
+            }
+        }
+    }
+    Ok(feed)
+}
+
+/// Fetch the feeds at `url`: the document itself when it is a feed, otherwise
+/// the feeds its `<link rel="alternate">` elements advertise.
+pub async fn fetch_feeds(url: &str) -> Result<Vec<Feed>, Box<dyn std::error::Error + Send + Sync>> {
+    let client = reqwest::Client::new();
+    let (final_url, content_type, body) = fetch(&client, url).await?;
+    if detect_format(content_type.as_deref(), &body).is_some() {
+        return Ok(vec![parse_feed(&body, Some(&final_url))?]);
+    }
+
+    let discovered = {
+        let document = scraper::Html::parse_document(&body);
+        discover_feeds(&crate::links::extract_links(&document, Some(&final_url)))
+    };
+    tracing::debug!("Discovered {} feeds on {}", discovered.len(), final_url);
+
+    let mut feeds = Vec::new();
+    for feed_url in discovered.into_iter().take(MAX_DISCOVERED_FEEDS) {
+        match fetch(&client, &feed_url).await {
+            Ok((final_url, _, body)) => match parse_feed(&body, Some(&final_url)) {
+                Ok(feed) => feeds.push(feed),
+                Err(e) => tracing::warn!("Failed to parse feed {}: {}", feed_url, e),
+            },
+            Err(e) => tracing::warn!("Failed to fetch feed {}: {}", feed_url, e),
+        }
+    }
+    Ok(feeds)
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<(String, Option<String>, String), Box<dyn std::error::Error + Send + Sync>> {
+    let document = crate::external::fetch_document(client, url).await?;
+    if !document.is_success() {
+        return Err(format!("HTTP {} for {}", document.status, url).into());
+    }
+    let body = document.text();
+    Ok((document.final_url, document.content_type, body))
+}
+
+fn parse_rss(root: &XmlElement) -> Feed {
+    let channel = root.child("channel");
+    // RSS 1.0 (RDF) lists items next to the channel rather than inside it
+    let items = channel
+        .map(|c| c.children_named("item").collect::<Vec<_>>())
+        .filter(|items| !items.is_empty())
+        .unwrap_or_else(|| root.children_named("item").collect());
+
+    let items = items
+        .into_iter()
+        .map(|item| {
+            let description = item.child_text("description");
+            let content = item.child_text("content:encoded").map(|html| html_to_text(&html));
+            FeedItem {
+                id: item.child_text("guid").or_else(|| item.attr("rdf:about").map(str::to_string)),
+                title: item.child_text("title").map(|t| html_to_text(&t)),
+                link: item.child_text("link"),
+                summary: description.map(|html| html_to_text(&html)),
+                content,
+                published: item
+                    .child_text("pubDate")
+                    .or_else(|| item.child_text("dc:date"))
+                    .map(|d| normalize_date(&d)),
+                updated: None,
+                authors: item
+                    .children_named("author")
+                    .chain(item.children_named("dc:creator"))
+                    .filter_map(|a| a.text_value())
+                    .map(|a| rss_author(&a))
+                    .collect(),
+                categories: item
+                    .children_named("category")
+                    .chain(item.children_named("dc:subject"))
+                    .filter_map(XmlElement::text_value)
+                    .collect(),
+                image: item
+                    .children_named("enclosure")
+                    .find(|e| e.attr("type").is_some_and(|t| t.starts_with("image/")))
+                    .or_else(|| item.child("media:content"))
+                    .or_else(|| item.child("media:thumbnail"))
+                    .and_then(|e| e.attr("url"))
+                    .map(str::to_string),
+            }
+        })
+        .collect();
+
+    let channel_text = |name: &str| channel.and_then(|c| c.child_text(name));
+    Feed {
+        format: FeedFormat::Rss,
+        title: channel_text("title"),
+        description: channel_text("description").map(|d| html_to_text(&d)),
+        link: channel_text("link"),
+        feed_url: channel
+            .and_then(|c| c.children_named("atom:link").find(|l| l.attr("rel") == Some("self")))
+            .and_then(|l| l.attr("href"))
+            .map(str::to_string),
+        language: channel_text("language").or_else(|| channel_text("dc:language")),
+        updated: channel_text("lastBuildDate")
+            .or_else(|| channel_text("pubDate"))
+            .map(|d| normalize_date(&d)),
+        authors: channel_text("managingEditor").map(|a| rss_author(&a)).into_iter().collect(),
+        items,
+    }
+}
+
+/// RSS authors are `email (Name)` or just a name
+fn rss_author(value: &str) -> FeedAuthor {
+    match value.split_once('(') {
+        Some((email, name)) if email.contains('@') => FeedAuthor {
+            name: Some(name.trim_end_matches(')').trim().to_string()),
+            url: None,
+            email: Some(email.trim().to_string()),
+        },
+        _ if value.contains('@') && !value.contains(' ') => {
+            FeedAuthor { name: None, url: None, email: Some(value.to_string()) }
+        }
+        _ => FeedAuthor { name: Some(value.to_string()), url: None, email: None },
+    }
+}
+
+fn parse_atom(root: &XmlElement) -> Feed {
+    let link = |element: &XmlElement, rel: &str| {
+        element
+            .local_children("link")
+            .find(|l| l.attr("rel").unwrap_or("alternate") == rel)
+            .and_then(|l| l.attr("href"))
+            .map(str::to_string)
+    };
+    let authors = |element: &XmlElement| {
+        element
+            .local_children("author")
+            .map(|a| FeedAuthor {
+                name: a.local_child_text("name"),
+                url: a.local_child_text("uri"),
+                email: a.local_child_text("email"),
+            })
+            .collect::<Vec<_>>()
+    };
+    let text =
+        |element: &XmlElement, name: &str| element.local_children(name).next().and_then(atom_text);
+
+    let items = root
+        .local_children("entry")
+        .map(|entry| FeedItem {
+            id: entry.local_child_text("id"),
+            title: text(entry, "title"),
+            link: link(entry, "alternate"),
+            summary: text(entry, "summary"),
+            content: text(entry, "content"),
+            published: entry.local_child_text("published").map(|d| normalize_date(&d)),
+            updated: entry.local_child_text("updated").map(|d| normalize_date(&d)),
+            authors: authors(entry),
+            categories: entry
+                .local_children("category")
+                .filter_map(|c| c.attr("label").or_else(|| c.attr("term")))
+                .map(str::to_string)
+                .collect(),
+            image: entry
+                .local_children("link")
+                .find(|l| {
+                    l.attr("rel") == Some("enclosure")
+                        && l.attr("type").is_some_and(|t| t.starts_with("image/"))
+                })
+                .and_then(|l| l.attr("href"))
+                .map(str::to_string),
+        })
+        .collect();
+
+    Feed {
+        format: FeedFormat::Atom,
+        title: text(root, "title"),
+        description: text(root, "subtitle"),
+        link: link(root, "alternate"),
+        feed_url: link(root, "self"),
+        language: root.attr("xml:lang").map(str::to_string),
+        updated: root.local_child_text("updated").map(|d| normalize_date(&d)),
+        authors: authors(root),
+        items,
+    }
+}
+
+/// Text of an Atom text construct (`type="text"`, `"html"` or `"xhtml"`)
+fn atom_text(element: &XmlElement) -> Option<String> {
+    let text = match element.attr("type") {
+        Some("html") => html_to_text(&element.text),
+        Some("xhtml") => normalize_whitespace(&element.text_content()),
+        _ => normalize_whitespace(&element.text),
+    };
+    Some(text).filter(|t| !t.is_empty())
+}
+
+fn parse_json_feed(body: &str) -> Result<Feed, Box<dyn std::error::Error + Send + Sync>> {
+    let value: serde_json::Value = serde_json::from_str(body)?;
+    let string = |value: &serde_json::Value, key: &str| {
+        value.get(key).and_then(|v| v.as_str()).map(str::to_string).filter(|s| !s.is_empty())
+    };
+    // Version 1.1 uses `authors`, 1.0 a single `author`
+    let authors = |value: &serde_json::Value| {
+        let list = match value.get("authors").and_then(|a| a.as_array()) {
+            Some(list) => list.iter().collect::<Vec<_>>(),
+            None => value.get("author").into_iter().collect(),
+        };
+        list.into_iter()
+            .map(|a| FeedAuthor { name: string(a, "name"), url: string(a, "url"), email: None })
+            .filter(|a| a.name.is_some() || a.url.is_some())
+            .collect::<Vec<_>>()
+    };
+
+    let items = value
+        .get("items")
+        .and_then(|i| i.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| FeedItem {
+                    id: item.get("id").and_then(|id| match id {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    }),
+                    title: string(item, "title"),
+                    link: string(item, "url").or_else(|| string(item, "external_url")),
+                    summary: string(item, "summary"),
+                    content: string(item, "content_text")
+                        .or_else(|| string(item, "content_html").map(|html| html_to_text(&html))),
+                    published: string(item, "date_published").map(|d| normalize_date(&d)),
+                    updated: string(item, "date_modified").map(|d| normalize_date(&d)),
+                    authors: authors(item),
+                    categories: item
+                        .get("tags")
+                        .and_then(|t| t.as_array())
+                        .map(|tags| {
+                            tags.iter().filter_map(|t| t.as_str()).map(str::to_string).collect()
+                        })
+                        .unwrap_or_default(),
+                    image: string(item, "image").or_else(|| string(item, "banner_image")),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Feed {
+        format: FeedFormat::JsonFeed,
+        title: string(&value, "title"),
+        description: string(&value, "description"),
+        link: string(&value, "home_page_url"),
+        feed_url: string(&value, "feed_url"),
+        language: string(&value, "language"),
+        updated: None,
+        authors: authors(&value),
+        items,
+    })
+}
+
+/// RFC 822/2822 (RSS) and RFC 3339 (Atom, JSON Feed) dates to RFC 3339
+fn normalize_date(value: &str) -> String {
+    let value = value.trim();
+    chrono::DateTime::parse_from_rfc2822(value)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(value))
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+fn html_to_text(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(html);
+    normalize_whitespace(&fragment.root_element().text().collect::<String>())
+}
+
+/// Minimal element tree; feeds are small enough to hold in memory
+#[derive(Debug, Default)]
+struct XmlElement {
+    /// Qualified name as written (`dc:creator`, `entry`, ...)
+    name: String,
+    attributes: Vec<(String, String)>,
+    /// Direct text and CDATA content
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Children matched by local name, for namespaced formats (Atom) that may use a prefix
+    fn local_children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.local_name() == name)
+    }
+
+    fn text_value(&self) -> Option<String> {
+        Some(self.text.trim().to_string()).filter(|t| !t.is_empty())
+    }
+
+    fn child_text(&self, name: &str) -> Option<String> {
+        self.children.iter().filter(|c| c.name == name).find_map(XmlElement::text_value)
+    }
+
+    fn local_child_text(&self, name: &str) -> Option<String> {
+        self.children.iter().filter(|c| c.local_name() == name).find_map(XmlElement::text_value)
+    }
+
+    fn text_content(&self) -> String {
+        let mut text = self.text.clone();
+        for child in &self.children {
+            text.push(' ');
+            text.push_str(&child.text_content());
+        }
+        text
+    }
+}
+
+fn parse_xml(body: &str) -> Result<XmlElement, Box<dyn std::error::Error + Send + Sync>> {
+    let element = |e: &quick_xml::events::BytesStart<'_>| XmlElement {
+        name: String::from_utf8_lossy(e.name().as_ref()).into_owned(),
+        attributes: e
+            .attributes()
+            .flatten()
+            .map(|a| {
+                let value = a
+                    .unescape_value()
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&a.value).into_owned());
+                (String::from_utf8_lossy(a.key.as_ref()).into_owned(), value)
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut reader = Reader::from_str(body.trim_start_matches('\u{feff}'));
+    let mut stack: Vec<XmlElement> = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => stack.push(element(&e)),
+            Event::Empty(e) => {
+                let child = element(&e);
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(child),
+                    None => return Ok(child),
+                }
+            }
+            Event::End(_) => {
+                let Some(done) = stack.pop() else {
+                    return Err("Unbalanced XML end tag".into());
+                };
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(done),
+                    None => return Ok(done),
+                }
+            }
+            Event::Text(t) => {
+                if let Some(current) = stack.last_mut() {
+                    // HTML entities such as &nbsp; are not XML; keep them verbatim
+                    match t.unescape() {
+                        Ok(text) => current.text.push_str(&text),
+                        Err(_) => current.text.push_str(&String::from_utf8_lossy(&t)),
+                    }
+                }
+            }
+            Event::CData(c) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&c));
+                }
+            }
+            Event::Eof => return Err("Unexpected end of XML document".into()),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let rss = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"
+                 xmlns:atom="http://www.w3.org/2005/Atom">
+              <channel>
+                <title>Example Blog</title>
+                <link>https://blog.example/</link>
+                <atom:link rel="self" href="https://blog.example/feed.xml"/>
+                <description>Notes &amp; news</description>
+                <item>
+                  <title>Hello</title>
+                  <link>/posts/hello</link>
+                  <guid isPermaLink="false">post-1</guid>
+                  <pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+                  <dc:creator>Ann Lee</dc:creator>
+                  <category>rust</category>
+                  <description><![CDATA[<p>First <b>post</b></p>]]></description>
+                </item>
+              </channel>
+            </rss>"#;
+        let feed = parse_feed(rss, None).unwrap();
+        assert_eq!(feed.format, FeedFormat::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Example Blog"));
+        assert_eq!(feed.description.as_deref(), Some("Notes & news"));
+        assert_eq!(feed.feed_url.as_deref(), Some("https://blog.example/feed.xml"));
+
+        let item = &feed.items[0];
+        assert_eq!(item.link.as_deref(), Some("https://blog.example/posts/hello"));
+        assert_eq!(item.id.as_deref(), Some("post-1"));
+        assert_eq!(item.published.as_deref(), Some("2025-06-10T04:00:00+00:00"));
+        assert_eq!(item.authors[0].name.as_deref(), Some("Ann Lee"));
+        assert_eq!(item.categories, vec!["rust"]);
+        assert_eq!(item.summary.as_deref(), Some("First post"));
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+              <title>Atom Site</title>
+              <link href="https://atom.example/"/>
+              <link rel="self" href="https://atom.example/atom.xml"/>
+              <updated>2025-01-02T03:04:05Z</updated>
+              <entry>
+                <id>urn:uuid:1</id>
+                <title type="html">Fish &amp;amp; Chips</title>
+                <link rel="alternate" href="https://atom.example/fish"/>
+                <published>2025-01-01T00:00:00Z</published>
+                <author><name>Bo</name><uri>https://bo.example/</uri></author>
+                <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>Tasty</p></div></content>
+              </entry>
+            </feed>"#;
+        let feed = parse_feed(atom, None).unwrap();
+        assert_eq!(feed.format, FeedFormat::Atom);
+        assert_eq!(feed.link.as_deref(), Some("https://atom.example/"));
+        assert_eq!(feed.feed_url.as_deref(), Some("https://atom.example/atom.xml"));
+        assert_eq!(feed.language.as_deref(), Some("en"));
+
+        let entry = &feed.items[0];
+        assert_eq!(entry.title.as_deref(), Some("Fish & Chips"));
+        assert_eq!(entry.link.as_deref(), Some("https://atom.example/fish"));
+        assert_eq!(entry.published.as_deref(), Some("2025-01-01T00:00:00+00:00"));
+        assert_eq!(entry.authors[0].url.as_deref(), Some("https://bo.example/"));
+        assert_eq!(entry.content.as_deref(), Some("Tasty"));
+    }
+
+    #[test]
+    fn test_parse_json_feed_and_discovery() {
+        let json = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "JSON Site",
+            "home_page_url": "https://json.example/",
+            "items": [{
+                "id": "1",
+                "url": "https://json.example/1",
+                "content_html": "<p>Body</p>",
+                "date_published": "2025-03-04T05:06:07-05:00",
+                "authors": [{ "name": "Cy" }],
+                "tags": ["a", "b"]
+            }]
+        }"#;
+        let feed = parse_feed(json, Some("https://json.example/feed.json")).unwrap();
+        assert_eq!(feed.format, FeedFormat::JsonFeed);
+        assert_eq!(feed.feed_url.as_deref(), Some("https://json.example/feed.json"));
+        assert_eq!(feed.items[0].content.as_deref(), Some("Body"));
+        assert_eq!(feed.items[0].authors[0].name.as_deref(), Some("Cy"));
+
+        assert_eq!(detect_format(Some("text/html"), json), None);
+
+        // Only RSS 1.0 flavoured RDF/XML is a feed
+        let rss1 = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://purl.org/rss/1.0/"><channel rdf:about="https://r.example/"/></rdf:RDF>"#;
+        assert_eq!(detect_format(None, rss1), Some(FeedFormat::Rss));
+        let bare = r#"<rdf:RDF><channel><title>t</title></channel></rdf:RDF>"#;
+        assert_eq!(detect_format(None, bare), Some(FeedFormat::Rss));
+        let foaf = r##"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns:foaf="http://xmlns.com/foaf/0.1/"><foaf:Person rdf:about="#me"/></rdf:RDF>"##;
+        assert_eq!(detect_format(Some("application/rdf+xml"), foaf), None);
+        assert!(parse_feed("<html><body></body></html>", None).is_err());
+
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="/rss">
+            <link rel="alternate" hreflang="fr" href="/fr/">
+            </head></html>"#;
+        let document = scraper::Html::parse_document(html);
+        let links = crate::links::extract_links(&document, Some("https://site.example/"));
+        assert_eq!(discover_feeds(&links), vec!["https://site.example/rss"]);
+
+        // Local and non-web schemes are never followed
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="file:///etc/passwd">
+            <link rel="alternate" type="application/atom+xml" href="ftp://site.example/atom">
+            <link rel="alternate" type="application/feed+json" href="http://site.example/feed.json">
+            </head></html>"#;
+        let document = scraper::Html::parse_document(html);
+        let links = crate::links::extract_links(&document, Some("https://site.example/"));
+        assert_eq!(discover_feeds(&links), vec!["http://site.example/feed.json"]);
+    }
+}
@@ -0,0 +1,939 @@
+//! HAR recording of browser navigation
+//!
+//! Records the network traffic of a page as a HAR 1.2 log (requests,
+//! responses, headers, timings and optionally bodies) so failed runs can be
+//! inspected in any HAR viewer. Sensitive headers are redacted before they
+//! are stored and logs are capped by entry count and body size.
+//!
+//! [`HarRecorder`] builds the log from network events and holds no browser
+//! state; with the `browser-automation` feature [`HarCapture`] feeds it from
+//! CDP `Network.*` events of a page.
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Replacement value of redacted headers
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers redacted by default: credentials and cookies
+pub const REDACTED_HEADERS: &[&str] =
+    &["cookie", "set-cookie", "authorization", "proxy-authorization"];
+
+/// What to record and how much
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarOptions {
+    /// Store response (and request) bodies (default: false)
+    pub include_bodies: bool,
+    /// Largest single body that is stored (default: 256 KiB)
+    pub max_body_bytes: usize,
+    /// Bodies stored across the whole log (default: 8 MiB)
+    pub max_total_body_bytes: usize,
+    /// Requests recorded; later ones are counted but dropped (default: 1000)
+    pub max_entries: usize,
+    /// Headers whose values are replaced with [`REDACTED`], case-insensitive
+    pub redact_headers: Vec<String>,
+    /// Also write the log to this file, including for failed navigations
+    pub path: Option<PathBuf>,
+}
+
+impl Default for HarOptions {
+    fn default() -> Self {
+        Self {
+            include_bodies: false,
+            max_body_bytes: 256 * 1024,
+            max_total_body_bytes: 8 * 1024 * 1024,
+            max_entries: 1000,
+            redact_headers: REDACTED_HEADERS.iter().map(|s| s.to_string()).collect(),
+            path: None,
+        }
+    }
+}
+
+/// HAR file root
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub pages: Vec<HarPage>,
+    pub entries: Vec<HarEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPage {
+    pub started_date_time: String,
+    pub id: String,
+    pub title: String,
+    pub page_timings: HarPageTimings,
+}
+
+/// Page load timings; not measured, so left empty as HAR allows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPageTimings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_content_load: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_load: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pageref: Option<String>,
+    pub started_date_time: String,
+    /// Total time in milliseconds
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: HarCache,
+    pub timings: HarTimings,
+    #[serde(
+        rename = "serverIPAddress",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub server_ip_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HarCache {}
+
+/// Request phases in milliseconds; -1 when a phase does not apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub blocked: f64,
+    pub dns: f64,
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    pub ssl: f64,
+}
+
+impl Har {
+    /// Write the log as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// Connection timing of a response, as CDP `Network.ResourceTiming`:
+/// `request_time` in seconds, the rest in milliseconds after it, -1 if unused
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkTiming {
+    pub request_time: f64,
+    pub dns_start: f64,
+    pub dns_end: f64,
+    pub connect_start: f64,
+    pub connect_end: f64,
+    pub ssl_start: f64,
+    pub ssl_end: f64,
+    pub send_start: f64,
+    pub send_end: f64,
+    pub receive_headers_end: f64,
+}
+
+/// Request as seen when it is sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestSent {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HarNameValue>,
+    pub has_post_data: bool,
+    /// Wall clock time in seconds since the epoch
+    pub wall_time: f64,
+    /// Monotonic time in seconds, the clock of all other events
+    pub timestamp: f64,
+}
+
+/// Response headers and metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseReceived {
+    pub status: i64,
+    pub status_text: String,
+    pub headers: Vec<HarNameValue>,
+    pub mime_type: String,
+    /// Negotiated protocol, e.g. `h2` or `http/1.1`
+    pub protocol: Option<String>,
+    pub remote_ip_address: Option<String>,
+    pub timing: Option<NetworkTiming>,
+}
+
+#[derive(Debug)]
+struct PendingEntry {
+    request_id: String,
+    request: RequestSent,
+    response: Option<ResponseReceived>,
+    redirect_url: String,
+    finished: Option<f64>,
+    encoded_length: Option<f64>,
+    error: Option<String>,
+    post_data: Option<HarPostData>,
+    content: Option<(String, bool)>,
+    body_comment: Option<String>,
+}
+
+/// Builds a HAR log from network events
+#[derive(Debug)]
+pub struct HarRecorder {
+    options: HarOptions,
+    entries: Vec<PendingEntry>,
+    /// Request id -> latest entry (redirects reuse the request id)
+    latest: HashMap<String, usize>,
+    dropped: usize,
+    body_bytes: usize,
+}
+
+impl HarRecorder {
+    pub fn new(options: HarOptions) -> Self {
+        Self { options, entries: Vec::new(), latest: HashMap::new(), dropped: 0, body_bytes: 0 }
+    }
+
+    /// A request is sent; `redirect` is the response that redirected to it
+    pub fn request_will_be_sent(
+        &mut self,
+        request_id: &str,
+        mut request: RequestSent,
+        redirect: Option<ResponseReceived>,
+    ) {
+        // A redirect completes the previous hop of the same request id
+        if let Some(mut response) = redirect {
+            response.headers = redact(response.headers, &self.options.redact_headers);
+            if let Some(entry) = self.entry_mut(request_id) {
+                entry.redirect_url = request.url.clone();
+                entry.finished = Some(request.timestamp);
+                entry.response = Some(response);
+            }
+        }
+        if self.entries.len() >= self.options.max_entries {
+            self.dropped += 1;
+            self.latest.remove(request_id);
+            return;
+        }
+        request.headers = redact(request.headers, &self.options.redact_headers);
+        self.latest.insert(request_id.to_string(), self.entries.len());
+        self.entries.push(PendingEntry {
+            request_id: request_id.to_string(),
+            request,
+            response: None,
+            redirect_url: String::new(),
+            finished: None,
+            encoded_length: None,
+            error: None,
+            post_data: None,
+            content: None,
+            body_comment: None,
+        });
+    }
+
+    pub fn response_received(&mut self, request_id: &str, mut response: ResponseReceived) {
+        response.headers = redact(response.headers, &self.options.redact_headers);
+        if let Some(entry) = self.entry_mut(request_id) {
+            entry.response = Some(response);
+        }
+    }
+
+    /// The response body arrived; `encoded_length` is the transfer size
+    pub fn loading_finished(&mut self, request_id: &str, timestamp: f64, encoded_length: f64) {
+        if let Some(entry) = self.entry_mut(request_id) {
+            entry.finished = Some(timestamp);
+            entry.encoded_length = Some(encoded_length);
+        }
+    }
+
+    pub fn loading_failed(&mut self, request_id: &str, timestamp: f64, error: &str) {
+        if let Some(entry) = self.entry_mut(request_id) {
+            entry.finished = Some(timestamp);
+            entry.error = Some(error.to_string());
+        }
+    }
+
+    /// Requests whose bodies should be fetched: (request id, has a response body)
+    pub fn wanted_bodies(&self) -> Vec<(String, bool)> {
+        if !self.options.include_bodies {
+            return Vec::new();
+        }
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(index, entry)| self.latest.get(&entry.request_id) == Some(index))
+            .filter_map(|(_, entry)| {
+                let response_body = entry.error.is_none()
+                    && entry.encoded_length.is_some()
+                    && entry.response.is_some();
+                (response_body || entry.request.has_post_data)
+                    .then(|| (entry.request_id.clone(), response_body))
+            })
+            .collect()
+    }
+
+    pub fn set_request_body(&mut self, request_id: &str, post_data: String) {
+        let mime_type = self
+            .entry_mut(request_id)
+            .and_then(|entry| {
+                entry
+                    .request
+                    .headers
+                    .iter()
+                    .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+                    .map(|h| h.value.clone())
+            })
+            .unwrap_or_default();
+        if self.reserve_body(post_data.len()).is_err() {
+            return;
+        }
+        if let Some(entry) = self.entry_mut(request_id) {
+            entry.post_data = Some(HarPostData { mime_type, text: post_data });
+        }
+    }
+
+    /// Store a response body, unless it is over the size limits
+    pub fn set_response_body(&mut self, request_id: &str, body: String, base64_encoded: bool) {
+        let stored = self.reserve_body(body.len());
+        if let Some(entry) = self.entry_mut(request_id) {
+            match stored {
+                Ok(()) => entry.content = Some((body, base64_encoded)),
+                Err(reason) => entry.body_comment = Some(reason),
+            }
+        }
+    }
+
+    fn reserve_body(&mut self, len: usize) -> Result<(), String> {
+        if len > self.options.max_body_bytes {
+            return Err(format!(
+                "body omitted: {} bytes exceeds the {} byte limit",
+                len, self.options.max_body_bytes
+            ));
+        }
+        if self.body_bytes + len > self.options.max_total_body_bytes {
+            return Err(format!(
+                "body omitted: log body limit of {} bytes reached",
+                self.options.max_total_body_bytes
+            ));
+        }
+        self.body_bytes += len;
+        Ok(())
+    }
+
+    fn entry_mut(&mut self, request_id: &str) -> Option<&mut PendingEntry> {
+        let index = *self.latest.get(request_id)?;
+        self.entries.get_mut(index)
+    }
+
+    /// The log so far, as one page titled `title`
+    pub fn to_har(&self, title: &str) -> Har {
+        const PAGE_ID: &str = "page_1";
+
+        let entries: Vec<HarEntry> = self.entries.iter().map(|e| e.to_entry(PAGE_ID)).collect();
+        let pages = entries
+            .first()
+            .map(|first| HarPage {
+                started_date_time: first.started_date_time.clone(),
+                id: PAGE_ID.to_string(),
+                title: title.to_string(),
+                page_timings: HarPageTimings::default(),
+            })
+            .into_iter()
+            .collect();
+        let comment = (self.dropped > 0).then(|| {
+            format!(
+                "{} requests not recorded (limit of {} entries)",
+                self.dropped, self.options.max_entries
+            )
+        });
+
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                pages,
+                entries,
+                comment,
+            },
+        }
+    }
+}
+
+impl PendingEntry {
+    fn to_entry(&self, pageref: &str) -> HarEntry {
+        let http_version = self
+            .response
+            .as_ref()
+            .and_then(|r| r.protocol.as_deref())
+            .map(http_version)
+            .unwrap_or_default();
+        let (timings, time) = har_timings(
+            self.response.as_ref().and_then(|r| r.timing.as_ref()),
+            self.request.timestamp,
+            self.finished,
+        );
+
+        let query_string = url::Url::parse(&self.request.url)
+            .map(|url| {
+                url.query_pairs()
+                    .map(|(name, value)| HarNameValue {
+                        name: name.into_owned(),
+                        value: value.into_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let request = HarRequest {
+            method: self.request.method.clone(),
+            url: self.request.url.clone(),
+            http_version: http_version.clone(),
+            cookies: Vec::new(),
+            headers: self.request.headers.clone(),
+            query_string,
+            body_size: self.post_data.as_ref().map_or(0, |p| p.text.len() as i64),
+            post_data: self.post_data.clone(),
+            headers_size: -1,
+        };
+
+        let (text, encoding) = match &self.content {
+            Some((text, base64)) => (Some(text.clone()), base64.then(|| "base64".to_string())),
+            None => (None, None),
+        };
+        let size = match &self.content {
+            Some((text, true)) => {
+                use base64::{engine::general_purpose, Engine as _};
+                general_purpose::STANDARD.decode(text).map_or(-1, |bytes| bytes.len() as i64)
+            }
+            Some((text, false)) => text.len() as i64,
+            None => -1,
+        };
+        let response = match &self.response {
+            Some(response) => HarResponse {
+                status: response.status,
+                status_text: response.status_text.clone(),
+                http_version,
+                cookies: Vec::new(),
+                headers: response.headers.clone(),
+                content: HarContent {
+                    size,
+                    mime_type: response.mime_type.clone(),
+                    text,
+                    encoding,
+                    comment: self.body_comment.clone(),
+                },
+                redirect_url: self.redirect_url.clone(),
+                headers_size: -1,
+                body_size: self.encoded_length.map_or(-1, |len| len as i64),
+            },
+            // Failed before any response: HAR uses status 0
+            None => HarResponse {
+                status: 0,
+                status_text: String::new(),
+                http_version,
+                cookies: Vec::new(),
+                headers: Vec::new(),
+                content: HarContent {
+                    size: 0,
+                    mime_type: "x-unknown".to_string(),
+                    text: None,
+                    encoding: None,
+                    comment: None,
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: -1,
+            },
+        };
+
+        HarEntry {
+            pageref: Some(pageref.to_string()),
+            started_date_time: date_time(self.request.wall_time),
+            time,
+            request,
+            response,
+            cache: HarCache {},
+            timings,
+            server_ip_address: self.response.as_ref().and_then(|r| r.remote_ip_address.clone()),
+            comment: self.error.clone().or_else(|| {
+                self.finished
+                    .is_none()
+                    .then(|| "incomplete: still loading when recording stopped".to_string())
+            }),
+        }
+    }
+}
+
+/// Replace the values of `names` headers with [`REDACTED`]
+fn redact(headers: Vec<HarNameValue>, names: &[String]) -> Vec<HarNameValue> {
+    headers
+        .into_iter()
+        .map(|header| {
+            if names.iter().any(|name| name.eq_ignore_ascii_case(&header.name)) {
+                HarNameValue { name: header.name, value: REDACTED.to_string() }
+            } else {
+                header
+            }
+        })
+        .collect()
+}
+
+/// Headers from a CDP `Network.Headers` object; multi-valued headers are
+/// joined with newlines there and split into one entry per value here
+pub fn headers_from_json(headers: &serde_json::Value) -> Vec<HarNameValue> {
+    let Some(map) = headers.as_object() else {
+        return Vec::new();
+    };
+    map.iter()
+        .flat_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            value
+                .split('\n')
+                .map(|v| HarNameValue { name: name.clone(), value: v.to_string() })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// HAR spelling of a CDP protocol name
+fn http_version(protocol: &str) -> String {
+    match protocol.to_ascii_lowercase().as_str() {
+        "h2" | "http/2" | "http/2.0" => "HTTP/2".to_string(),
+        "h3" | "http/3" => "HTTP/3".to_string(),
+        other => other.to_ascii_uppercase(),
+    }
+}
+
+/// ISO 8601 with milliseconds, as HAR expects
+fn date_time(wall_time: f64) -> String {
+    chrono::DateTime::from_timestamp_millis((wall_time * 1000.0) as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// HAR timings and total time for a request sent at `started` (seconds)
+fn har_timings(
+    timing: Option<&NetworkTiming>,
+    started: f64,
+    finished: Option<f64>,
+) -> (HarTimings, f64) {
+    let round = |ms: f64| (ms * 1000.0).round() / 1000.0;
+    let elapsed = finished.map_or(0.0, |f| ((f - started) * 1000.0).max(0.0));
+
+    let Some(t) = timing else {
+        // Cached, failed or data: requests have no connection timing
+        let timings = HarTimings {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: round(elapsed),
+            ssl: -1.0,
+        };
+        return (timings, round(elapsed));
+    };
+
+    let span = |start: f64, end: f64| {
+        if start >= 0.0 && end >= start {
+            end - start
+        } else {
+            -1.0
+        }
+    };
+    let queued = ((t.request_time - started) * 1000.0).max(0.0);
+    let first_activity =
+        [t.dns_start, t.connect_start, t.send_start].into_iter().find(|v| *v >= 0.0).unwrap_or(0.0);
+    let blocked = queued + first_activity;
+    let dns = span(t.dns_start, t.dns_end);
+    let connect = span(t.connect_start, t.connect_end);
+    let ssl = span(t.ssl_start, t.ssl_end);
+    let send = (t.send_end - t.send_start).max(0.0);
+    let wait = (t.receive_headers_end - t.send_end).max(0.0);
+    let receive =
+        finished.map_or(0.0, |f| (f - t.request_time) * 1000.0 - t.receive_headers_end).max(0.0);
+    // ssl is part of connect in HAR, so it is not added again
+    let total = blocked + dns.max(0.0) + connect.max(0.0) + send + wait + receive;
+
+    let timings = HarTimings {
+        blocked: round(blocked),
+        dns: round(dns),
+        connect: round(connect),
+        send: round(send),
+        wait: round(wait),
+        receive: round(receive),
+        ssl: round(ssl),
+    };
+    (timings, round(total))
+}
+
+/// Records the network traffic of a page into a [`HarRecorder`]
+#[cfg(feature = "browser-automation")]
+pub struct HarCapture {
+    recorder: std::sync::Arc<std::sync::Mutex<HarRecorder>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "browser-automation")]
+impl HarCapture {
+    /// Start listening; call before navigating
+    pub async fn start(
+        page: &Page,
+        options: HarOptions,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        use chromiumoxide::cdp::browser_protocol::network::{
+            EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+        };
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        enum Event {
+            Request(Arc<EventRequestWillBeSent>),
+            Response(Arc<EventResponseReceived>),
+            Finished(Arc<EventLoadingFinished>),
+            Failed(Arc<EventLoadingFailed>),
+        }
+
+        let mut events = futures::stream::select_all([
+            page.event_listener::<EventRequestWillBeSent>().await?.map(Event::Request).boxed(),
+            page.event_listener::<EventResponseReceived>().await?.map(Event::Response).boxed(),
+            page.event_listener::<EventLoadingFinished>().await?.map(Event::Finished).boxed(),
+            page.event_listener::<EventLoadingFailed>().await?.map(Event::Failed).boxed(),
+        ]);
+
+        let recorder = Arc::new(std::sync::Mutex::new(HarRecorder::new(options)));
+        let task = tokio::spawn({
+            let recorder = Arc::clone(&recorder);
+            async move {
+                while let Some(event) = events.next().await {
+                    let Ok(mut recorder) = recorder.lock() else {
+                        break;
+                    };
+                    match event {
+                        Event::Request(e) => recorder.request_will_be_sent(
+                            e.request_id.inner(),
+                            RequestSent {
+                                method: e.request.method.clone(),
+                                url: e.request.url.clone(),
+                                headers: headers_from_json(e.request.headers.inner()),
+                                has_post_data: e.request.has_post_data.unwrap_or(false),
+                                wall_time: *e.wall_time.inner(),
+                                timestamp: *e.timestamp.inner(),
+                            },
+                            e.redirect_response.as_ref().map(response_received),
+                        ),
+                        Event::Response(e) => recorder.response_received(
+                            e.request_id.inner(),
+                            response_received(&e.response),
+                        ),
+                        Event::Finished(e) => recorder.loading_finished(
+                            e.request_id.inner(),
+                            *e.timestamp.inner(),
+                            e.encoded_data_length,
+                        ),
+                        Event::Failed(e) => recorder.loading_failed(
+                            e.request_id.inner(),
+                            *e.timestamp.inner(),
+                            &e.error_text,
+                        ),
+                    }
+                }
+            }
+        });
+
+        Ok(Self { recorder, task })
+    }
+
+    /// Stop listening, fetch bodies if requested and return the log
+    pub async fn finish(
+        self,
+        page: &Page,
+        title: &str,
+    ) -> Result<Har, Box<dyn std::error::Error + Send + Sync>> {
+        use chromiumoxide::cdp::browser_protocol::network::{
+            GetRequestPostDataParams, GetResponseBodyParams, RequestId,
+        };
+
+        self.task.abort();
+        let wanted =
+            self.recorder.lock().map_err(|_| "HAR recorder lock poisoned")?.wanted_bodies();
+
+        for (request_id, response_body) in wanted {
+            let id = RequestId::new(request_id.clone());
+            // Bodies Chrome already evicted or never kept are left out
+            let post_data = page.execute(GetRequestPostDataParams::new(id.clone())).await.ok();
+            let body = if response_body {
+                page.execute(GetResponseBodyParams::new(id)).await.ok()
+            } else {
+                None
+            };
+            let mut recorder = self.recorder.lock().map_err(|_| "HAR recorder lock poisoned")?;
+            if let Some(post_data) = post_data {
+                recorder.set_request_body(&request_id, post_data.result.post_data.clone());
+            }
+            if let Some(body) = body {
+                recorder.set_response_body(
+                    &request_id,
+                    body.result.body.clone(),
+                    body.result.base64_encoded,
+                );
+            }
+        }
+
+        let har = self.recorder.lock().map_err(|_| "HAR recorder lock poisoned")?.to_har(title);
+        Ok(har)
+    }
+}
+
+#[cfg(feature = "browser-automation")]
+fn response_received(
+    response: &chromiumoxide::cdp::browser_protocol::network::Response,
+) -> ResponseReceived {
+    ResponseReceived {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        headers: headers_from_json(response.headers.inner()),
+        mime_type: response.mime_type.clone(),
+        protocol: response.protocol.clone(),
+        remote_ip_address: response.remote_ip_address.clone(),
+        timing: response.timing.as_ref().map(|t| NetworkTiming {
+            request_time: t.request_time,
+            dns_start: t.dns_start,
+            dns_end: t.dns_end,
+            connect_start: t.connect_start,
+            connect_end: t.connect_end,
+            ssl_start: t.ssl_start,
+            ssl_end: t.ssl_end,
+            send_start: t.send_start,
+            send_end: t.send_end,
+            receive_headers_end: t.receive_headers_end,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, value: &str) -> HarNameValue {
+        HarNameValue { name: name.to_string(), value: value.to_string() }
+    }
+
+    fn request(url: &str, timestamp: f64) -> RequestSent {
+        RequestSent {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: vec![header("Accept", "text/html"), header("Cookie", "session=s3cr3t")],
+            has_post_data: false,
+            wall_time: 1_700_000_000.0 + timestamp,
+            timestamp,
+        }
+    }
+
+    fn response(status: i64, headers: Vec<HarNameValue>) -> ResponseReceived {
+        ResponseReceived {
+            status,
+            status_text: "OK".to_string(),
+            headers,
+            mime_type: "text/html".to_string(),
+            protocol: Some("h2".to_string()),
+            remote_ip_address: Some("93.184.216.34".to_string()),
+            timing: Some(NetworkTiming {
+                request_time: 10.001,
+                dns_start: 0.0,
+                dns_end: 4.0,
+                connect_start: 4.0,
+                connect_end: 20.0,
+                ssl_start: 8.0,
+                ssl_end: 20.0,
+                send_start: 20.0,
+                send_end: 21.0,
+                receive_headers_end: 60.0,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_har_entries_redaction_and_timings() {
+        let options = HarOptions { include_bodies: true, ..Default::default() };
+        let mut recorder = HarRecorder::new(options);
+
+        // http -> https redirect, then the page, then a request that fails
+        recorder.request_will_be_sent("1", request("http://example.com/?q=a+b", 10.0), None);
+        let mut moved = response(301, vec![header("Location", "https://example.com/?q=a+b")]);
+        moved.timing = None;
+        recorder.request_will_be_sent(
+            "1",
+            request("https://example.com/?q=a+b", 10.0),
+            Some(moved),
+        );
+        recorder.response_received(
+            "1",
+            response(
+                200,
+                headers_from_json(&serde_json::json!({
+                    "content-type": "text/html",
+                    "set-cookie": "a=1\nb=2"
+                })),
+            ),
+        );
+        recorder.loading_finished("1", 10.081, 1256.0);
+        recorder.request_will_be_sent("2", request("https://cdn.example.com/app.js", 10.1), None);
+        recorder.loading_failed("2", 10.2, "net::ERR_NAME_NOT_RESOLVED");
+
+        assert_eq!(recorder.wanted_bodies(), vec![("1".to_string(), true)]);
+        recorder.set_response_body("1", "<html></html>".to_string(), false);
+
+        let har = recorder.to_har("Example Domain");
+        let log = &har.log;
+        assert_eq!(log.version, "1.2");
+        assert_eq!(log.pages[0].title, "Example Domain");
+        assert_eq!(log.entries.len(), 3);
+
+        let redirect = &log.entries[0];
+        assert_eq!(redirect.response.status, 301);
+        assert_eq!(redirect.response.redirect_url, "https://example.com/?q=a+b");
+        assert_eq!(redirect.started_date_time, "2023-11-14T22:13:30.000Z");
+
+        let page = &log.entries[1];
+        assert_eq!(page.request.http_version, "HTTP/2");
+        assert_eq!(page.request.query_string, vec![header("q", "a b")]);
+        assert!(page.request.headers.contains(&header("Cookie", REDACTED)));
+        assert!(page.request.headers.contains(&header("Accept", "text/html")));
+        let set_cookies: Vec<_> =
+            page.response.headers.iter().filter(|h| h.name == "set-cookie").collect();
+        assert_eq!(set_cookies.len(), 2);
+        assert!(set_cookies.iter().all(|h| h.value == REDACTED));
+        assert_eq!(page.response.content.text.as_deref(), Some("<html></html>"));
+        assert_eq!(page.response.content.size, 13);
+        assert_eq!(page.response.body_size, 1256);
+        assert_eq!(page.server_ip_address.as_deref(), Some("93.184.216.34"));
+
+        // 1ms queued, 4 dns, 16 connect (12 of it TLS), 1 send, 39 wait, 20 receive
+        let t = &page.timings;
+        assert_eq!((t.blocked, t.dns, t.connect, t.ssl), (1.0, 4.0, 16.0, 12.0));
+        assert_eq!((t.send, t.wait, t.receive), (1.0, 39.0, 20.0));
+        assert_eq!(page.time, 81.0);
+
+        let failed = &log.entries[2];
+        assert_eq!(failed.response.status, 0);
+        assert_eq!(failed.comment.as_deref(), Some("net::ERR_NAME_NOT_RESOLVED"));
+
+        let json = serde_json::to_value(&har).unwrap();
+        assert_eq!(json["log"]["entries"][1]["response"]["redirectURL"], "");
+        assert_eq!(json["log"]["entries"][1]["serverIPAddress"], "93.184.216.34");
+        assert!(json["log"]["entries"][1]["request"]["queryString"].is_array());
+    }
+
+    #[test]
+    fn test_har_limits() {
+        let options = HarOptions {
+            include_bodies: true,
+            max_entries: 2,
+            max_body_bytes: 10,
+            max_total_body_bytes: 15,
+            ..Default::default()
+        };
+        let mut recorder = HarRecorder::new(options);
+        for (id, url) in
+            [("a", "https://a.test/"), ("b", "https://b.test/"), ("c", "https://c.test/")]
+        {
+            recorder.request_will_be_sent(id, request(url, 1.0), None);
+            recorder.response_received(id, response(200, Vec::new()));
+            recorder.loading_finished(id, 1.5, 100.0);
+        }
+        recorder.set_response_body("a", "x".repeat(11), false);
+        recorder.set_response_body("b", "y".repeat(8), false);
+
+        let har = recorder.to_har("");
+        assert_eq!(har.log.entries.len(), 2);
+        assert_eq!(
+            har.log.comment.as_deref(),
+            Some("1 requests not recorded (limit of 2 entries)")
+        );
+        let a = &har.log.entries[0].response.content;
+        assert!(a.text.is_none());
+        assert!(a.comment.as_deref().unwrap().contains("exceeds the 10 byte limit"));
+        assert_eq!(har.log.entries[1].response.content.text.as_deref(), Some("yyyyyyyy"));
+
+        // Without include_bodies nothing is fetched
+        let recorder = HarRecorder::new(HarOptions::default());
+        assert!(recorder.wanted_bodies().is_empty());
+    }
+}
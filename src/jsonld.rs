@@ -0,0 +1,578 @@
+//! JSON-LD to RDF conversion
+//!
+//! Turns the JSON-LD blocks collected by `parser::extract_json_ld` and
+//! `BrowserPool::extract_semantic_data` into [`Triple`]s that can be inserted
+//! into the [`KnowledgeGraph`](crate::kg::KnowledgeGraph).
+//!
+//! This is a pragmatic subset of the JSON-LD 1.1 expansion algorithm that
+//! covers what real pages publish:
+//! - `@context` as string, object or array (`@vocab`, `@base`, `@language`,
+//!   prefixes and expanded term definitions with `@id`/`@type`/`@language`)
+//! - `@graph`, `@id`, `@type`, `@reverse`, `@list`/`@set` (flattened)
+//! - nested node objects (blank nodes when they carry no `@id`)
+//! - value objects with `@value`, `@language` and `@type`
+//!
+//! Remote contexts are never fetched. The schema.org context is recognised by
+//! URL, and blocks without a usable vocabulary fall back to schema.org, which
+//! mirrors how search engines consume embedded JSON-LD. All schema.org IRIs
+//! are normalised to `https://schema.org/` so they line up with the `schema:`
+//! prefix used across the knowledge graph.
+
+use crate::kg::{Term, Triple};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+const SCHEMA_ORG: &str = "https://schema.org/";
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Maximum nesting depth processed before giving up on a branch
+const MAX_DEPTH: usize = 32;
+
+/// Result of converting one or more JSON-LD blocks
+#[derive(Debug, Clone, Default)]
+pub struct JsonLdGraph {
+    /// All triples produced by the conversion
+    pub triples: Vec<Triple>,
+    /// Subjects of the top-level nodes (what the page is "about")
+    pub roots: Vec<Term>,
+}
+
+/// Convert a list of JSON-LD blocks (one per `<script>` tag) to RDF
+///
+/// `base_url` is the document URL used to resolve relative `@id` values.
+/// Blank node labels are unique across all blocks in the returned graph.
+pub fn to_rdf(blocks: &[Value], base_url: &str) -> JsonLdGraph {
+    let mut converter =
+        Converter { graph: JsonLdGraph::default(), next_blank: 0, labels: HashMap::new() };
+
+    for block in blocks {
+        // Each script tag is its own JSON-LD document: blank node labels are scoped to it
+        converter.labels.clear();
+        let context = Context::initial(base_url);
+        converter.document(block, &context);
+    }
+
+    converter.graph
+}
+
+/// Active context (see JSON-LD 1.1, section 4.1)
+#[derive(Debug, Clone)]
+struct Context {
+    base: String,
+    vocab: Option<String>,
+    language: Option<String>,
+    terms: HashMap<String, TermDefinition>,
+}
+
+#[derive(Debug, Clone)]
+struct TermDefinition {
+    id: String,
+    /// `@id`, `@vocab` or a datatype IRI
+    type_mapping: Option<String>,
+    /// `Some(None)` means the term explicitly opts out of the default language
+    language: Option<Option<String>>,
+    reverse: bool,
+}
+
+impl Context {
+    fn initial(base_url: &str) -> Self {
+        Self { base: base_url.to_string(), vocab: None, language: None, terms: HashMap::new() }
+    }
+
+    /// Process a local `@context` value on top of this context
+    fn merge(&self, local: &Value) -> Self {
+        let mut ctx = self.clone();
+        match local {
+            Value::Null => {
+                ctx = Context::initial(&self.base);
+            }
+            Value::String(url) => {
+                if is_schema_org(url) {
+                    ctx.vocab = Some(SCHEMA_ORG.to_string());
+                } else {
+                    tracing::debug!("Ignoring remote JSON-LD context {}", url);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    ctx = ctx.merge(item);
+                }
+            }
+            Value::Object(map) => {
+                if let Some(base) = map.get("@base").and_then(Value::as_str) {
+                    ctx.base = resolve_iri(&self.base, base);
+                }
+                match map.get("@vocab") {
+                    Some(Value::String(vocab)) => {
+                        let expanded = ctx.expand_iri(vocab, true);
+                        ctx.vocab = Some(normalize_schema_org(&expanded));
+                    }
+                    Some(Value::Null) => ctx.vocab = None,
+                    _ => {}
+                }
+                match map.get("@language") {
+                    Some(Value::String(lang)) => ctx.language = Some(lang.to_lowercase()),
+                    Some(Value::Null) => ctx.language = None,
+                    _ => {}
+                }
+                for (term, definition) in map {
+                    if term.starts_with('@') {
+                        continue;
+                    }
+                    if let Some(def) = ctx.define_term(term, definition, map) {
+                        ctx.terms.insert(term.clone(), def);
+                    } else {
+                        ctx.terms.remove(term);
+                    }
+                }
+            }
+            _ => {}
+        }
+        ctx
+    }
+
+    fn define_term(
+        &self,
+        term: &str,
+        definition: &Value,
+        local: &Map<String, Value>,
+    ) -> Option<TermDefinition> {
+        // Resolve the IRI of a term defined in the same local context (prefix chains)
+        let expand = |value: &str| -> String {
+            if let Some((prefix, suffix)) = value.split_once(':') {
+                if !suffix.starts_with("//") && prefix != term {
+                    if let Some(Value::String(prefix_iri)) = local.get(prefix) {
+                        return format!("{}{}", prefix_iri, suffix);
+                    }
+                    if let Some(Value::Object(obj)) = local.get(prefix) {
+                        if let Some(prefix_iri) = obj.get("@id").and_then(Value::as_str) {
+                            return format!("{}{}", prefix_iri, suffix);
+                        }
+                    }
+                }
+            }
+            self.expand_iri(value, true)
+        };
+
+        match definition {
+            Value::Null => None,
+            Value::String(id) => Some(TermDefinition {
+                id: normalize_schema_org(&expand(id)),
+                type_mapping: None,
+                language: None,
+                reverse: false,
+            }),
+            Value::Object(obj) => {
+                let reverse = obj.get("@reverse").and_then(Value::as_str);
+                let id = match (reverse, obj.get("@id").and_then(Value::as_str)) {
+                    (Some(rev), _) => expand(rev),
+                    (None, Some(id)) => expand(id),
+                    (None, None) => expand(term),
+                };
+                let type_mapping = obj.get("@type").and_then(Value::as_str).map(|t| match t {
+                    "@id" | "@vocab" => t.to_string(),
+                    other => expand(other),
+                });
+                let language = match obj.get("@language") {
+                    Some(Value::String(lang)) => Some(Some(lang.to_lowercase())),
+                    Some(Value::Null) => Some(None),
+                    _ => None,
+                };
+                Some(TermDefinition {
+                    id: normalize_schema_org(&id),
+                    type_mapping,
+                    language,
+                    reverse: reverse.is_some(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// IRI expansion (JSON-LD 1.1, section 4.3)
+    ///
+    /// `vocab` selects vocabulary-relative expansion (property names, types)
+    /// instead of document-relative resolution against `@base`.
+    fn expand_iri(&self, value: &str, vocab: bool) -> String {
+        if value.starts_with('@') {
+            return value.to_string();
+        }
+        if vocab {
+            if let Some(def) = self.terms.get(value) {
+                return def.id.clone();
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" {
+                return value.to_string();
+            }
+            if !suffix.starts_with("//") {
+                if let Some(def) = self.terms.get(prefix) {
+                    return format!("{}{}", def.id, suffix);
+                }
+            }
+            if is_absolute_iri(value) {
+                return normalize_schema_org(value);
+            }
+        }
+        if vocab {
+            let vocab_iri = self.vocab.as_deref().unwrap_or(SCHEMA_ORG);
+            return format!("{}{}", vocab_iri, value);
+        }
+        resolve_iri(&self.base, value)
+    }
+}
+
+struct Converter {
+    graph: JsonLdGraph,
+    next_blank: usize,
+    /// Document blank node labels (`_:x`) mapped to batch-unique labels
+    labels: HashMap<String, String>,
+}
+
+impl Converter {
+    fn document(&mut self, value: &Value, ctx: &Context) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.document(item, ctx);
+                }
+            }
+            Value::Object(map) => {
+                let ctx = match map.get("@context") {
+                    Some(local) => ctx.merge(local),
+                    None => ctx.clone(),
+                };
+                let only_graph = map.keys().all(|k| k == "@context" || k == "@graph");
+                if only_graph {
+                    if let Some(graph) = map.get("@graph") {
+                        for node in as_array(graph) {
+                            if let Some(subject) = self.node(node, &ctx, 0) {
+                                self.graph.roots.push(subject);
+                            }
+                        }
+                        return;
+                    }
+                }
+                if let Some(subject) = self.node(value, &ctx, 0) {
+                    self.graph.roots.push(subject);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn fresh_blank(&mut self) -> Term {
+        let label = format!("jsonld{}", self.next_blank);
+        self.next_blank += 1;
+        Term::BlankNode(label)
+    }
+
+    fn subject_for(&mut self, id: Option<&str>, ctx: &Context) -> Term {
+        match id {
+            Some(id) if id.starts_with("_:") => {
+                if let Some(label) = self.labels.get(id) {
+                    return Term::BlankNode(label.clone());
+                }
+                let term = self.fresh_blank();
+                if let Term::BlankNode(label) = &term {
+                    self.labels.insert(id.to_string(), label.clone());
+                }
+                term
+            }
+            Some(id) => Term::Iri(normalize_schema_org(&ctx.expand_iri(id, false))),
+            None => self.fresh_blank(),
+        }
+    }
+
+    /// Process a node object and return its subject
+    fn node(&mut self, value: &Value, ctx: &Context, depth: usize) -> Option<Term> {
+        if depth > MAX_DEPTH {
+            tracing::debug!("JSON-LD nesting deeper than {}, truncating", MAX_DEPTH);
+            return None;
+        }
+        let map = value.as_object()?;
+        let local_ctx;
+        let ctx = match map.get("@context") {
+            Some(local) => {
+                local_ctx = ctx.merge(local);
+                &local_ctx
+            }
+            None => ctx,
+        };
+
+        let subject = self.subject_for(map.get("@id").and_then(Value::as_str), ctx);
+
+        for (key, value) in map {
+            match key.as_str() {
+                "@context" | "@id" => {}
+                "@type" => {
+                    for ty in as_array(value) {
+                        if let Some(ty) = ty.as_str() {
+                            let iri = ctx.expand_iri(ty, true);
+                            self.graph.triples.push(Triple::new(
+                                subject.clone(),
+                                RDF_TYPE,
+                                Term::Iri(iri),
+                            ));
+                        }
+                    }
+                }
+                "@graph" => {
+                    for node in as_array(value) {
+                        self.node(node, ctx, depth + 1);
+                    }
+                }
+                "@reverse" => {
+                    if let Some(reverse) = value.as_object() {
+                        for (property, values) in reverse {
+                            let predicate = ctx.expand_iri(property, true);
+                            for object in self.values(values, None, ctx, depth) {
+                                if object.is_resource() {
+                                    self.graph.triples.push(Triple::new(
+                                        object,
+                                        predicate.clone(),
+                                        subject.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+                other if other.starts_with('@') => {}
+                property => {
+                    let definition = ctx.terms.get(property).cloned();
+                    let predicate = ctx.expand_iri(property, true);
+                    if !is_absolute_iri(&predicate) {
+                        continue;
+                    }
+                    let reverse = definition.as_ref().map(|d| d.reverse).unwrap_or(false);
+                    for object in self.values(value, definition.as_ref(), ctx, depth) {
+                        if reverse {
+                            if object.is_resource() {
+                                self.graph.triples.push(Triple::new(
+                                    object,
+                                    predicate.clone(),
+                                    subject.clone(),
+                                ));
+                            }
+                        } else {
+                            self.graph.triples.push(Triple::new(
+                                subject.clone(),
+                                predicate.clone(),
+                                object,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(subject)
+    }
+
+    /// Convert a property value (scalar, value object, node object or array) to terms
+    fn values(
+        &mut self,
+        value: &Value,
+        definition: Option<&TermDefinition>,
+        ctx: &Context,
+        depth: usize,
+    ) -> Vec<Term> {
+        let mut out = Vec::new();
+        match value {
+            Value::Null => {}
+            Value::Array(items) => {
+                for item in items {
+                    out.extend(self.values(item, definition, ctx, depth));
+                }
+            }
+            Value::Bool(b) => {
+                out.push(Term::typed_literal(b.to_string(), format!("{}boolean", XSD)))
+            }
+            Value::Number(n) => {
+                let datatype = if n.is_i64() || n.is_u64() {
+                    "integer"
+                } else {
+                    "double"
+                };
+                out.push(Term::typed_literal(n.to_string(), format!("{}{}", XSD, datatype)));
+            }
+            Value::String(s) => {
+                let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+                match type_mapping {
+                    Some("@id") => out.push(self.subject_for(Some(s), ctx)),
+                    Some("@vocab") => out.push(Term::Iri(ctx.expand_iri(s, true))),
+                    Some(datatype) => out.push(Term::typed_literal(s.clone(), datatype)),
+                    None => {
+                        let language = match definition.and_then(|d| d.language.clone()) {
+                            Some(lang) => lang,
+                            None => ctx.language.clone(),
+                        };
+                        match language {
+                            Some(lang) => out.push(Term::language_literal(s.clone(), lang)),
+                            None => out.push(Term::literal(s.clone())),
+                        }
+                    }
+                }
+            }
+            Value::Object(map) => {
+                if let Some(literal) = map.get("@value") {
+                    let text = match literal {
+                        Value::String(s) => s.clone(),
+                        Value::Null => return out,
+                        other => other.to_string(),
+                    };
+                    if let Some(ty) = map.get("@type").and_then(Value::as_str) {
+                        out.push(Term::typed_literal(text, ctx.expand_iri(ty, true)));
+                    } else if let Some(lang) = map.get("@language").and_then(Value::as_str) {
+                        out.push(Term::language_literal(text, lang.to_lowercase()));
+                    } else {
+                        // Reuse scalar handling so numbers and booleans keep their xsd type
+                        out.extend(self.values(literal, None, ctx, depth));
+                    }
+                } else if let Some(list) = map.get("@list").or_else(|| map.get("@set")) {
+                    out.extend(self.values(list, definition, ctx, depth));
+                } else if let Some(node) = self.node(value, ctx, depth + 1) {
+                    out.push(node);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn as_array(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    }
+}
+
+fn is_schema_org(url: &str) -> bool {
+    let trimmed = url.trim_end_matches('/');
+    matches!(
+        trimmed,
+        "http://schema.org"
+            | "https://schema.org"
+            | "http://www.schema.org"
+            | "https://www.schema.org"
+    ) || trimmed.ends_with("schema.org/docs/jsonldcontext.json")
+        || trimmed.ends_with("schema.org/docs/jsonldcontext.jsonld")
+}
+
+pub(crate) fn normalize_schema_org(iri: &str) -> String {
+    for prefix in ["http://schema.org/", "http://www.schema.org/", "https://www.schema.org/"] {
+        if let Some(rest) = iri.strip_prefix(prefix) {
+            return format!("{}{}", SCHEMA_ORG, rest);
+        }
+    }
+    iri.to_string()
+}
+
+pub(crate) fn is_absolute_iri(value: &str) -> bool {
+    match value.split_once(':') {
+        Some((scheme, _)) => {
+            !scheme.is_empty()
+                && scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
+
+fn resolve_iri(base: &str, value: &str) -> String {
+    match url::Url::parse(base).and_then(|b| b.join(value)) {
+        Ok(resolved) => resolved.to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn has(graph: &JsonLdGraph, predicate: &str, object: &Term) -> bool {
+        graph.triples.iter().any(|t| t.predicate == predicate && &t.object == object)
+    }
+
+    #[test]
+    fn test_schema_org_product_with_nested_offer() {
+        let block = json!({
+            "@context": "http://schema.org",
+            "@type": "Product",
+            "@id": "/products/42",
+            "name": "Widget",
+            "offers": {
+                "@type": "Offer",
+                "price": 19.99,
+                "priceCurrency": "EUR"
+            }
+        });
+        let graph = to_rdf(&[block], "https://shop.example/catalog/");
+
+        assert_eq!(graph.roots, vec![Term::iri("https://shop.example/products/42")]);
+        assert!(has(&graph, RDF_TYPE, &Term::iri("https://schema.org/Product")));
+        assert!(has(&graph, RDF_TYPE, &Term::iri("https://schema.org/Offer")));
+        assert!(has(
+            &graph,
+            "https://schema.org/price",
+            &Term::typed_literal("19.99", format!("{}double", XSD))
+        ));
+        let offer = graph
+            .triples
+            .iter()
+            .find(|t| t.predicate == "https://schema.org/offers")
+            .expect("offers triple");
+        assert!(matches!(offer.object, Term::BlankNode(_)));
+    }
+
+    #[test]
+    fn test_graph_language_and_typed_values() {
+        let block = json!({
+            "@context": {
+                "@vocab": "https://schema.org/",
+                "@language": "it",
+                "dc": "http://purl.org/dc/terms/",
+                "published": {"@id": "dc:issued", "@type": "http://www.w3.org/2001/XMLSchema#date"},
+                "homepage": {"@id": "url", "@type": "@id"}
+            },
+            "@graph": [
+                {"@id": "_:a", "@type": "Person", "name": "Maria", "homepage": "https://maria.example"},
+                {"@type": "Article", "author": {"@id": "_:a"}, "published": "2025-01-15",
+                 "headline": {"@value": "Hello", "@language": "en"}}
+            ]
+        });
+        let graph = to_rdf(&[block], "https://example.com/post");
+
+        assert_eq!(graph.roots.len(), 2);
+        assert!(has(&graph, "https://schema.org/name", &Term::language_literal("Maria", "it")));
+        assert!(has(&graph, "https://schema.org/headline", &Term::language_literal("Hello", "en")));
+        assert!(has(&graph, "https://schema.org/url", &Term::iri("https://maria.example")));
+        assert!(has(
+            &graph,
+            "http://purl.org/dc/terms/issued",
+            &Term::typed_literal("2025-01-15", format!("{}date", XSD))
+        ));
+        // The author reference points at the same blank node as the Person
+        let author = graph.triples.iter().find(|t| t.predicate == "https://schema.org/author");
+        assert_eq!(author.map(|t| &t.object), Some(&graph.roots[0]));
+    }
+
+    #[test]
+    fn test_missing_context_falls_back_to_schema_org() {
+        let graph = to_rdf(&[json!({"@type": "Person", "name": "John"})], "https://example.com");
+        assert!(has(&graph, RDF_TYPE, &Term::iri("https://schema.org/Person")));
+        assert!(has(&graph, "https://schema.org/name", &Term::literal("John")));
+    }
+
+    #[test]
+    fn test_blank_labels_are_scoped_per_block() {
+        let block = json!({"@id": "_:x", "name": "A"});
+        let graph = to_rdf(&[block.clone(), block], "https://example.com");
+        assert_eq!(graph.roots.len(), 2);
+        assert_ne!(graph.roots[0], graph.roots[1]);
+    }
+}
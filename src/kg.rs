@@ -2,6 +2,8 @@
 
 use oxigraph::model::*;
 use oxigraph::store::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 #[cfg(feature = "onnx-integration")]
 use std::collections::HashSet;
 use std::path::Path;
@@ -11,6 +13,66 @@ use crate::ml::embeddings::{EmbeddingModel, EmbeddingType};
 #[cfg(feature = "onnx-integration")]
 use crate::ml::inference::LinkPredictor;
 
+/// RDF term produced by the structured data extractors (JSON-LD, microdata, ...)
+///
+/// Blank node labels are only meaningful within a single batch passed to
+/// [`KnowledgeGraph::insert_triples`]; each batch gets fresh blank nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Term {
+    /// Absolute IRI
+    Iri(String),
+    /// Blank node label (without the `_:` prefix)
+    BlankNode(String),
+    /// Literal with an optional datatype IRI or language tag
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    /// Create an IRI term
+    pub fn iri(value: impl Into<String>) -> Self {
+        Term::Iri(value.into())
+    }
+
+    /// Create a plain string literal
+    pub fn literal(value: impl Into<String>) -> Self {
+        Term::Literal { value: value.into(), datatype: None, language: None }
+    }
+
+    /// Create a literal with an explicit datatype IRI
+    pub fn typed_literal(value: impl Into<String>, datatype: impl Into<String>) -> Self {
+        Term::Literal { value: value.into(), datatype: Some(datatype.into()), language: None }
+    }
+
+    /// Create a language-tagged literal
+    pub fn language_literal(value: impl Into<String>, language: impl Into<String>) -> Self {
+        Term::Literal { value: value.into(), datatype: None, language: Some(language.into()) }
+    }
+
+    /// Returns true if the term can be used in subject position
+    pub fn is_resource(&self) -> bool {
+        !matches!(self, Term::Literal { .. })
+    }
+}
+
+/// A single RDF statement produced by the structured data extractors
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: String,
+    pub object: Term,
+}
+
+impl Triple {
+    pub fn new(subject: Term, predicate: impl Into<String>, object: Term) -> Self {
+        Self { subject, predicate: predicate.into(), object }
+    }
+}
+
 /// Knowledge Graph wrapper
 pub struct KnowledgeGraph {
     store: Store,
@@ -669,6 +731,84 @@ impl KnowledgeGraph {
         Ok(())
     }
 
+    /// Insert a batch of extractor triples
+    ///
+    /// Blank node labels are mapped to fresh blank nodes for the whole batch, so
+    /// two pages using the same labels never collide in the store. Triples with
+    /// invalid IRIs or a literal subject are skipped (web data is messy), and the
+    /// number of triples actually inserted is returned.
+    pub fn insert_triples(
+        &mut self,
+        triples: &[Triple],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut blank_nodes: HashMap<String, BlankNode> = HashMap::new();
+        let mut inserted = 0usize;
+
+        for triple in triples {
+            let subject: Subject = match &triple.subject {
+                Term::Iri(iri) => match NamedNode::new(iri.as_str()) {
+                    Ok(node) => node.into(),
+                    Err(e) => {
+                        tracing::debug!("Skipping triple with invalid subject <{}>: {}", iri, e);
+                        continue;
+                    }
+                },
+                Term::BlankNode(label) => {
+                    blank_nodes.entry(label.clone()).or_default().clone().into()
+                }
+                Term::Literal { .. } => {
+                    tracing::debug!("Skipping triple with literal subject");
+                    continue;
+                }
+            };
+
+            let predicate = match NamedNode::new(triple.predicate.as_str()) {
+                Ok(node) => node,
+                Err(e) => {
+                    tracing::debug!(
+                        "Skipping triple with invalid predicate <{}>: {}",
+                        triple.predicate,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let object: oxigraph::model::Term = match &triple.object {
+                Term::Iri(iri) => match NamedNode::new(iri.as_str()) {
+                    Ok(node) => node.into(),
+                    Err(e) => {
+                        tracing::debug!("Skipping triple with invalid object <{}>: {}", iri, e);
+                        continue;
+                    }
+                },
+                Term::BlankNode(label) => {
+                    blank_nodes.entry(label.clone()).or_default().clone().into()
+                }
+                Term::Literal { value, datatype, language } => {
+                    if let Some(lang) = language {
+                        Literal::new_language_tagged_literal(value.as_str(), lang.as_str())
+                            .unwrap_or_else(|_| Literal::new_simple_literal(value.as_str()))
+                            .into()
+                    } else if let Some(dt) = datatype {
+                        match NamedNode::new(dt.as_str()) {
+                            Ok(dtype) => Literal::new_typed_literal(value.as_str(), dtype).into(),
+                            Err(_) => Literal::new_simple_literal(value.as_str()).into(),
+                        }
+                    } else {
+                        Literal::new_simple_literal(value.as_str()).into()
+                    }
+                }
+            };
+
+            let quad = Quad::new(subject, predicate, object, GraphName::DefaultGraph);
+            self.store.insert(&quad)?;
+            inserted += 1;
+        }
+
+        Ok(inserted)
+    }
+
     /// Helper to expand common namespace prefixes
     ///
     /// Supports: og:, twitter:, schema:, dcterms:, rdf:, rdfs:, xsd:
@@ -796,6 +936,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_insert_triples_fresh_blank_nodes() {
+        let mut kg = KnowledgeGraph::new();
+        let batch = vec![
+            Triple::new(
+                Term::iri("https://example.com"),
+                "https://schema.org/author",
+                Term::BlankNode("b0".to_string()),
+            ),
+            Triple::new(
+                Term::BlankNode("b0".to_string()),
+                "https://schema.org/name",
+                Term::language_literal("Jane", "en"),
+            ),
+            // Invalid predicate is skipped, not fatal
+            Triple::new(Term::iri("https://example.com"), "not an iri", Term::literal("x")),
+        ];
+
+        assert_eq!(kg.insert_triples(&batch).unwrap(), 2);
+        // Same labels in a second batch must not merge with the first one
+        assert_eq!(kg.insert_triples(&batch).unwrap(), 2);
+        assert_eq!(kg.list_triples().len(), 4);
+    }
+
     #[test]
     fn test_multiple_literals() {
         let mut kg = KnowledgeGraph::new();
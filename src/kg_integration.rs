@@ -10,8 +10,17 @@
 //! - Canonical URL for deduplication
 //! - Structured Open Graph and Twitter Card mapping
 
-use crate::kg::KnowledgeGraph;
+use crate::citation::Citation;
+use crate::documents::{DocumentInfo, DocumentKind};
+use crate::feeds::{Feed, FeedAuthor};
+use crate::jsonld;
+use crate::kg::{KnowledgeGraph, Term, Triple};
+use crate::links::{Link, LinkSource};
+use crate::microformats::{Mf2Document, Mf2Item, Mf2Value};
 use crate::models::{MicrodataSummary, SemanticSnapshot};
+use crate::parser::{MicrodataItem, MicrodataValue};
+use crate::tables::Table;
+use std::collections::HashSet;
 
 #[cfg(feature = "browser-automation")]
 use crate::browser::SemanticData;
@@ -24,9 +33,11 @@ pub fn insert_snapshot_to_kg(
     query: Option<&str>,
 ) -> Result<usize, Box<dyn std::error::Error>> {
     let mut count = 0usize;
+    // Tag literals with the declared language, or the detected one when `lang` is missing
+    let language = snapshot.effective_language();
 
     if let Some(title) = &snapshot.title {
-        if let Some(lang) = &snapshot.language {
+        if let Some(lang) = &language {
             kg.insert_language_literal(
                 base_url,
                 &KnowledgeGraph::expand_namespace("dcterms:title"),
@@ -40,7 +51,7 @@ pub fn insert_snapshot_to_kg(
     }
 
     if let Some(desc) = &snapshot.description {
-        if let Some(lang) = &snapshot.language {
+        if let Some(lang) = &language {
             kg.insert_language_literal(
                 base_url,
                 &KnowledgeGraph::expand_namespace("dcterms:description"),
@@ -62,7 +73,7 @@ pub fn insert_snapshot_to_kg(
         count += 1;
     }
 
-    if let Some(lang) = &snapshot.language {
+    if let Some(lang) = &language {
         kg.insert_literal(base_url, &KnowledgeGraph::expand_namespace("dcterms:language"), lang)?;
         count += 1;
     }
@@ -122,6 +133,15 @@ pub fn insert_snapshot_to_kg(
         count += 1;
     }
 
+    if !snapshot.json_ld.is_empty() {
+        let document_url = if snapshot.final_url.is_empty() {
+            base_url
+        } else {
+            snapshot.final_url.as_str()
+        };
+        count += insert_json_ld_to_kg(&snapshot.json_ld, kg, base_url, document_url)?;
+    }
+
     if !snapshot.microdata.is_empty() {
         kg.insert_typed_literal(
             base_url,
@@ -132,6 +152,34 @@ pub fn insert_snapshot_to_kg(
         count += 1;
     }
 
+    if !snapshot.microdata_items.is_empty() {
+        count += insert_microdata_to_kg(&snapshot.microdata_items, kg, base_url)?;
+    }
+
+    if !snapshot.rdfa.is_empty() {
+        count += kg.insert_triples(&snapshot.rdfa)?;
+    }
+
+    if !snapshot.microformats.items.is_empty() {
+        count += insert_microformats_to_kg(&snapshot.microformats, kg, base_url)?;
+    }
+
+    if !snapshot.links.is_empty() {
+        count += insert_links_to_kg(&snapshot.links, kg, base_url)?;
+    }
+
+    if let Some(feed) = &snapshot.feed {
+        count += insert_feed_to_kg(feed, kg, base_url)?;
+    }
+
+    if let Some(document) = &snapshot.document {
+        count += insert_document_to_kg(document, kg, base_url)?;
+    }
+
+    if let Some(citation) = &snapshot.citation {
+        count += insert_citation_to_kg(citation, kg, base_url)?;
+    }
+
     for MicrodataSummary { item_type, properties } in &snapshot.microdata {
         if !item_type.is_empty() {
             kg.insert_literal(
@@ -187,30 +235,1058 @@ pub fn insert_snapshot_to_kg(
     Ok(count)
 }
 
-#[cfg(feature = "browser-automation")]
-pub(crate) fn semantic_data_to_snapshot(data: &SemanticData) -> SemanticSnapshot {
-    let microdata = data
-        .microdata
+/// Convert JSON-LD blocks to RDF and insert them into the Knowledge Graph
+///
+/// Relative `@id` values are resolved against `document_url`. Every top-level
+/// node is linked from the page (`base_url`) with `schema:mainEntity`, unless
+/// the node describes the page itself.
+pub fn insert_json_ld_to_kg(
+    blocks: &[serde_json::Value],
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+    document_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let graph = jsonld::to_rdf(blocks, document_url);
+    let page = Term::iri(base_url);
+    let main_entity = KnowledgeGraph::expand_namespace("schema:mainEntity");
+
+    let mut triples = graph.triples;
+    for root in graph.roots {
+        if root != page && root != Term::iri(document_url) {
+            triples.push(Triple::new(page.clone(), main_entity.clone(), root));
+        }
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} JSON-LD triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// Convert microdata item trees to RDF and insert them into the Knowledge Graph
+///
+/// Follows the W3C "Microdata to RDF" mapping: items become their `itemid` or a
+/// blank node, each `itemtype` becomes an `rdf:type`, and property names are
+/// expanded against the vocabulary of the item type (inherited by untyped
+/// nested items, schema.org when nothing is declared). Top-level items are
+/// linked from the page with `schema:mainEntity`.
+pub fn insert_microdata_to_kg(
+    items: &[MicrodataItem],
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let page = Term::iri(base_url);
+    let main_entity = KnowledgeGraph::expand_namespace("schema:mainEntity");
+    let mut mapper = MicrodataMapper { triples: Vec::new(), next_blank: 0 };
+
+    for item in items {
+        let subject = mapper.item(item, "https://schema.org/");
+        if subject != page {
+            mapper.triples.push(Triple::new(page.clone(), main_entity.clone(), subject));
+        }
+    }
+
+    let inserted = kg.insert_triples(&mapper.triples)?;
+    tracing::debug!("Inserted {} microdata triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+struct MicrodataMapper {
+    triples: Vec<Triple>,
+    next_blank: usize,
+}
+
+impl MicrodataMapper {
+    fn item(&mut self, item: &MicrodataItem, inherited_vocab: &str) -> Term {
+        let subject = match item.item_id.as_deref() {
+            Some(id) if jsonld::is_absolute_iri(id) => Term::iri(jsonld::normalize_schema_org(id)),
+            _ => {
+                self.next_blank += 1;
+                Term::BlankNode(format!("microdata{}", self.next_blank))
+            }
+        };
+
+        let types: Vec<String> = item
+            .item_types()
+            .filter(|t| jsonld::is_absolute_iri(t))
+            .map(jsonld::normalize_schema_org)
+            .collect();
+        let vocab = match types.first() {
+            Some(first) => match first.rfind(['#', '/']) {
+                Some(pos) => first[..=pos].to_string(),
+                None => inherited_vocab.to_string(),
+            },
+            None => inherited_vocab.to_string(),
+        };
+
+        let rdf_type = KnowledgeGraph::expand_namespace("rdf:type");
+        for item_type in types {
+            self.triples.push(Triple::new(subject.clone(), rdf_type.clone(), Term::iri(item_type)));
+        }
+
+        // Sort names so the generated triples (and blank node labels) are deterministic
+        let mut names: Vec<&String> = item.properties.keys().collect();
+        names.sort();
+        for name in names {
+            let predicate = if jsonld::is_absolute_iri(name) {
+                jsonld::normalize_schema_org(name)
+            } else {
+                format!("{}{}", vocab, name)
+            };
+            for value in &item.properties[name] {
+                let object = match value {
+                    MicrodataValue::Text(text) => Term::literal(text.trim()),
+                    MicrodataValue::Url(url) if jsonld::is_absolute_iri(url) => Term::iri(url),
+                    MicrodataValue::Url(url) => Term::literal(url.as_str()),
+                    MicrodataValue::DateTime(value) => datetime_literal(value.trim()),
+                    MicrodataValue::Item(nested) => self.item(nested, &vocab),
+                };
+                self.triples.push(Triple::new(subject.clone(), predicate.clone(), object));
+            }
+        }
+
+        subject
+    }
+}
+
+/// Type a `<time>` value with the matching XSD datatype
+///
+/// Values are validated and normalized to the datatype's lexical form
+/// (`2025-03-01T18:00` becomes `2025-03-01T18:00:00`); anything that is not a
+/// valid ISO 8601 date, time or duration stays a plain literal.
+fn datetime_literal(value: &str) -> Term {
+    let typed = if let Some(duration) = xsd_duration(value) {
+        Some((duration, "xsd:duration"))
+    } else if let Some((date, time)) = value.split_once('T') {
+        xsd_date(date)
+            .zip(xsd_time(time))
+            .map(|(date, time)| (format!("{}T{}", date, time), "xsd:dateTime"))
+    } else if let Some(date) = xsd_date(value) {
+        Some((date, "xsd:date"))
+    } else if let Some(time) = xsd_time(value) {
+        Some((time, "xsd:time"))
+    } else {
+        let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+        match value.split_once('-') {
+            Some((year, month))
+                if digits(year, 4)
+                    && digits(month, 2)
+                    && (1..=12).contains(&month.parse().unwrap_or(0)) =>
+            {
+                Some((value.to_string(), "xsd:gYearMonth"))
+            }
+            None if digits(value, 4) => Some((value.to_string(), "xsd:gYear")),
+            _ => None,
+        }
+    };
+    match typed {
+        Some((lexical, datatype)) => {
+            Term::typed_literal(lexical, KnowledgeGraph::expand_namespace(datatype))
+        }
+        None => Term::literal(value),
+    }
+}
+
+/// Calendar date `YYYY-MM-DD`
+fn xsd_date(value: &str) -> Option<String> {
+    (value.len() == 10)
+        .then(|| chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        .flatten()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// `HH:MM[:SS[.fff]]` with an optional `Z`, `±HH`, `±HHMM` or `±HH:MM` offset,
+/// normalized to `HH:MM:SS[.fff]` and `Z`/`±HH:MM`
+fn xsd_time(value: &str) -> Option<String> {
+    let (clock, offset) = match value.find(['Z', 'z', '+', '-']) {
+        Some(index) => (&value[..index], Some(&value[index..])),
+        None => (value, None),
+    };
+    let time = ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveTime::parse_from_str(clock, format).ok())?;
+    let offset = match offset {
+        None => String::new(),
+        Some("Z" | "z") => "Z".to_string(),
+        Some(offset) => {
+            let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+            let colon = offset[1..].find(':');
+            if !matches!(digits.len(), 2 | 4)
+                || !digits.bytes().all(|b| b.is_ascii_digit())
+                || !(colon.is_none() || (colon == Some(2) && digits.len() == 4))
+            {
+                return None;
+            }
+            let (hours, minutes) = digits.split_at(2);
+            let minutes = if minutes.is_empty() { "00" } else { minutes };
+            if hours.parse::<u32>().ok()? > 14 || minutes.parse::<u32>().ok()? > 59 {
+                return None;
+            }
+            format!("{}{}:{}", &offset[..1], hours, minutes)
+        }
+    };
+    Some(format!("{}{}", time.format("%H:%M:%S%.f"), offset))
+}
+
+/// ISO 8601 duration such as `PT1H30M` or `P2W`; weeks become days because
+/// `xsd:duration` has no week component
+fn xsd_duration(value: &str) -> Option<String> {
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value),
+    };
+    let rest = rest.strip_prefix('P')?;
+    if let Some(weeks) = rest.strip_suffix('W') {
+        let weeks: u64 =
+            weeks.bytes().all(|b| b.is_ascii_digit()).then(|| weeks.parse().ok()).flatten()?;
+        return Some(format!("{}P{}D", sign, weeks.checked_mul(7)?));
+    }
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (rest, None),
+    };
+    // Designators must appear in order, each after a number; only seconds may be fractional
+    let valid = |part: &str, designators: &str| {
+        let mut order = designators.chars();
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || (c == '.' && designators == "HMS") {
+                number.push(c);
+            } else if number.is_empty() || !order.any(|d| d == c) {
+                return false;
+            } else {
+                if number.starts_with('.')
+                    || number.ends_with('.')
+                    || number.matches('.').count() > 1
+                {
+                    return false;
+                }
+                if number.contains('.') && c != 'S' {
+                    return false;
+                }
+                number.clear();
+            }
+        }
+        number.is_empty()
+    };
+    (valid(date, "YMD")
+        && time.map_or(true, |time| valid(time, "HMS"))
+        && (date, time) != ("", None))
+        .then(|| value.to_string())
+}
+
+/// Map microformats2 items to schema.org and insert them into the Knowledge Graph
+///
+/// `h-card`, `h-entry`, `h-event` and `h-adr` become `schema:Person`,
+/// `schema:Article`, `schema:Event` and `schema:PostalAddress` blank nodes;
+/// properties without a schema.org counterpart are dropped. Other item types
+/// are not mapped, but their children are. Mapped items that are not property
+/// values are linked from the page with `schema:mainEntity`.
+pub fn insert_microformats_to_kg(
+    document: &Mf2Document,
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let page = Term::iri(base_url);
+    let main_entity = KnowledgeGraph::expand_namespace("schema:mainEntity");
+    let mut mapper = Mf2Mapper { triples: Vec::new(), next_blank: 0 };
+
+    let mut pending: Vec<&Mf2Item> = document.items.iter().rev().collect();
+    while let Some(item) = pending.pop() {
+        if let Some(subject) = mapper.item(item) {
+            mapper.triples.push(Triple::new(page.clone(), main_entity.clone(), subject));
+        }
+        pending.extend(item.children.iter().rev());
+    }
+
+    let inserted = kg.insert_triples(&mapper.triples)?;
+    tracing::debug!("Inserted {} microformats triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// How an mf2 property value is turned into an RDF object
+#[derive(Clone, Copy)]
+enum Mf2Kind {
+    Text,
+    Url,
+    Email,
+    Date,
+}
+
+type Mf2Mapping = &'static [(&'static str, &'static str, Mf2Kind)];
+
+const H_CARD: Mf2Mapping = &[
+    ("name", "name", Mf2Kind::Text),
+    ("given-name", "givenName", Mf2Kind::Text),
+    ("family-name", "familyName", Mf2Kind::Text),
+    ("nickname", "alternateName", Mf2Kind::Text),
+    ("url", "url", Mf2Kind::Url),
+    ("photo", "image", Mf2Kind::Url),
+    ("email", "email", Mf2Kind::Email),
+    ("tel", "telephone", Mf2Kind::Text),
+    ("note", "description", Mf2Kind::Text),
+    ("org", "worksFor", Mf2Kind::Text),
+    ("job-title", "jobTitle", Mf2Kind::Text),
+    ("bday", "birthDate", Mf2Kind::Date),
+    ("adr", "address", Mf2Kind::Text),
+];
+
+const H_ENTRY: Mf2Mapping = &[
+    ("name", "headline", Mf2Kind::Text),
+    ("summary", "description", Mf2Kind::Text),
+    ("content", "articleBody", Mf2Kind::Text),
+    ("published", "datePublished", Mf2Kind::Date),
+    ("updated", "dateModified", Mf2Kind::Date),
+    ("author", "author", Mf2Kind::Text),
+    ("category", "keywords", Mf2Kind::Text),
+    ("url", "url", Mf2Kind::Url),
+    ("photo", "image", Mf2Kind::Url),
+    ("location", "contentLocation", Mf2Kind::Text),
+];
+
+const H_EVENT: Mf2Mapping = &[
+    ("name", "name", Mf2Kind::Text),
+    ("summary", "description", Mf2Kind::Text),
+    ("description", "description", Mf2Kind::Text),
+    ("start", "startDate", Mf2Kind::Date),
+    ("end", "endDate", Mf2Kind::Date),
+    ("duration", "duration", Mf2Kind::Date),
+    ("location", "location", Mf2Kind::Text),
+    ("category", "keywords", Mf2Kind::Text),
+    ("url", "url", Mf2Kind::Url),
+    ("photo", "image", Mf2Kind::Url),
+];
+
+const H_ADR: Mf2Mapping = &[
+    ("street-address", "streetAddress", Mf2Kind::Text),
+    ("locality", "addressLocality", Mf2Kind::Text),
+    ("region", "addressRegion", Mf2Kind::Text),
+    ("postal-code", "postalCode", Mf2Kind::Text),
+    ("country-name", "addressCountry", Mf2Kind::Text),
+];
+
+fn mf2_schema_type(item: &Mf2Item) -> Option<(&'static str, Mf2Mapping)> {
+    item.types.iter().find_map(|t| match t.as_str() {
+        "h-card" => Some(("Person", H_CARD)),
+        "h-entry" => Some(("Article", H_ENTRY)),
+        "h-event" => Some(("Event", H_EVENT)),
+        "h-adr" => Some(("PostalAddress", H_ADR)),
+        _ => None,
+    })
+}
+
+struct Mf2Mapper {
+    triples: Vec<Triple>,
+    next_blank: usize,
+}
+
+impl Mf2Mapper {
+    fn blank(&mut self) -> Term {
+        self.next_blank += 1;
+        Term::BlankNode(format!("mf2{}", self.next_blank))
+    }
+
+    /// Map an item, returning its subject, or `None` when the type is not mapped
+    fn item(&mut self, item: &Mf2Item) -> Option<Term> {
+        let (schema_type, mapping) = mf2_schema_type(item)?;
+        let subject = self.blank();
+        self.triples.push(Triple::new(
+            subject.clone(),
+            KnowledgeGraph::expand_namespace("rdf:type"),
+            Term::iri(format!("https://schema.org/{}", schema_type)),
+        ));
+        self.properties(&subject, item, mapping);
+
+        // h-card address parts given directly on the card
+        if schema_type == "Person" && H_ADR.iter().any(|(n, _, _)| item.properties.contains_key(*n))
+        {
+            let address = self.blank();
+            self.triples.push(Triple::new(
+                address.clone(),
+                KnowledgeGraph::expand_namespace("rdf:type"),
+                Term::iri("https://schema.org/PostalAddress"),
+            ));
+            self.properties(&address, item, H_ADR);
+            self.triples.push(Triple::new(
+                subject.clone(),
+                KnowledgeGraph::expand_namespace("schema:address"),
+                address,
+            ));
+        }
+
+        Some(subject)
+    }
+
+    fn properties(&mut self, subject: &Term, item: &Mf2Item, mapping: Mf2Mapping) {
+        for (name, property, kind) in mapping {
+            let Some(values) = item.properties.get(*name) else {
+                continue;
+            };
+            let predicate = format!("https://schema.org/{}", property);
+            for value in values {
+                let object = match value {
+                    // Nested microformats keep their structure when the type is known
+                    Mf2Value::Item(nested) => match self.item(nested) {
+                        Some(object) => object,
+                        None => match nested.value.as_deref().or(nested.first_text("name")) {
+                            Some(text) => Term::literal(text),
+                            None => continue,
+                        },
+                    },
+                    other => match other.as_str().map(str::trim) {
+                        Some(text) if !text.is_empty() => mf2_object(text, *kind),
+                        _ => continue,
+                    },
+                };
+                self.triples.push(Triple::new(subject.clone(), predicate.clone(), object));
+            }
+        }
+    }
+}
+
+fn mf2_object(value: &str, kind: Mf2Kind) -> Term {
+    match kind {
+        Mf2Kind::Text => Term::literal(value),
+        Mf2Kind::Url if jsonld::is_absolute_iri(value) => Term::iri(value),
+        Mf2Kind::Url => Term::literal(value),
+        Mf2Kind::Email => Term::literal(value.strip_prefix("mailto:").unwrap_or(value)),
+        // mf2 allows a space between date and time
+        Mf2Kind::Date => match value.split_once(' ') {
+            Some((date, time)) if date.len() == 10 => {
+                datetime_literal(&format!("{}T{}", date, time.trim()))
+            }
+            _ => datetime_literal(value),
+        },
+    }
+}
+
+const CSVW: &str = "http://www.w3.org/ns/csvw#";
+
+/// Insert data tables into the Knowledge Graph following the CSVW RDF mapping
+///
+/// Each table becomes a `csvw:Table` (with `csvw:url` the page) whose
+/// `csvw:row` entries are `csvw:Row` resources (`csvw:rownum` N counted per
+/// table, `csvw:url` `<page#table=T&row=N>`) that `csvw:describe` a blank
+/// node carrying the cells. Cell properties are `<page#column-name>`, and
+/// typed columns produce `xsd:integer`/`xsd:decimal`/`xsd:boolean` literals;
+/// empty cells are skipped.
+pub fn insert_tables_to_kg(
+    tables: &[Table],
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let page = url::Url::parse(base_url).ok();
+    let fragment_iri = |fragment: &str| match &page {
+        Some(page) => {
+            let mut iri = page.clone();
+            iri.set_fragment(Some(fragment));
+            iri.to_string()
+        }
+        None => format!("{}#{}", base_url, fragment),
+    };
+    let rdf_type = KnowledgeGraph::expand_namespace("rdf:type");
+    let xsd_integer = KnowledgeGraph::expand_namespace("xsd:integer");
+
+    let mut triples = Vec::new();
+    for (t, table) in tables.iter().enumerate() {
+        let table_node = Term::BlankNode(format!("table{}", t + 1));
+        triples.push(Triple::new(
+            table_node.clone(),
+            rdf_type.clone(),
+            Term::iri(format!("{}Table", CSVW)),
+        ));
+        triples.push(Triple::new(table_node.clone(), format!("{}url", CSVW), Term::iri(base_url)));
+        if let Some(caption) = &table.caption {
+            triples.push(Triple::new(
+                table_node.clone(),
+                KnowledgeGraph::expand_namespace("dcterms:title"),
+                Term::literal(caption.as_str()),
+            ));
+        }
+
+        let properties: Vec<String> = table.columns.iter().map(|c| fragment_iri(&c.name)).collect();
+        for (r, row) in table.rows.iter().enumerate() {
+            let row_node = Term::BlankNode(format!("table{}row{}", t + 1, r + 1));
+            let described = Term::BlankNode(format!("table{}row{}data", t + 1, r + 1));
+            triples.push(Triple::new(table_node.clone(), format!("{}row", CSVW), row_node.clone()));
+            triples.push(Triple::new(
+                row_node.clone(),
+                rdf_type.clone(),
+                Term::iri(format!("{}Row", CSVW)),
+            ));
+            triples.push(Triple::new(
+                row_node.clone(),
+                format!("{}rownum", CSVW),
+                Term::typed_literal((r + 1).to_string(), xsd_integer.clone()),
+            ));
+            triples.push(Triple::new(
+                row_node.clone(),
+                format!("{}url", CSVW),
+                Term::iri(fragment_iri(&format!("table={}&row={}", t + 1, r + 1))),
+            ));
+            triples.push(Triple::new(row_node, format!("{}describes", CSVW), described.clone()));
+
+            for ((value, column), property) in row.iter().zip(&table.columns).zip(&properties) {
+                let text = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let object = match column.datatype.xsd_datatype() {
+                    Some(datatype) => Term::typed_literal(text, datatype),
+                    None => Term::literal(text),
+                };
+                triples.push(Triple::new(described.clone(), property.clone(), object));
+            }
+        }
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} table triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// Namespace of the IANA link relations registry, used for registered `rel` values
+const IANA_RELATION: &str = "http://www.iana.org/assignments/relation/";
+
+/// Resolver prefix turning DOIs into IRIs
+const DOI_RESOLVER: &str = "https://doi.org/";
+
+/// Insert the outgoing links of a page into the Knowledge Graph
+///
+/// Every `<a>`/`<area>` target becomes `<page> schema:significantLink <target>`,
+/// and every `rel` token becomes an edge `<page> <rel> <target>`, where
+/// registered relations (`next`, `alternate`, `nofollow`, ...) expand to the
+/// IANA relation namespace and extension relations are used as-is when they are
+/// absolute IRIs. `hreflang` and `type` are recorded on the target as
+/// `dcterms:language` and `dcterms:format`. Relative targets are skipped.
+pub fn insert_links_to_kg(
+    links: &[Link],
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let significant_link = KnowledgeGraph::expand_namespace("schema:significantLink");
+    // Pages often repeat the same link (header, body, footer); keep each edge once
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut triples = Vec::new();
+    let mut push = |triples: &mut Vec<Triple>, subject: &str, predicate: String, object: Term| {
+        if seen.insert(format!("{} {} {:?}", subject, predicate, object)) {
+            triples.push(Triple::new(Term::iri(subject), predicate, object));
+        }
+    };
+
+    for link in links.iter().filter(|link| jsonld::is_absolute_iri(&link.url)) {
+        let target = Term::iri(link.url.as_str());
+        if link.source == LinkSource::Anchor {
+            push(&mut triples, base_url, significant_link.clone(), target.clone());
+        }
+        for rel in &link.rel {
+            let predicate = if jsonld::is_absolute_iri(rel) {
+                rel.clone()
+            } else {
+                format!("{}{}", IANA_RELATION, rel)
+            };
+            push(&mut triples, base_url, predicate, target.clone());
+        }
+        if let Some(hreflang) = &link.hreflang {
+            push(
+                &mut triples,
+                &link.url,
+                KnowledgeGraph::expand_namespace("dcterms:language"),
+                Term::literal(hreflang.as_str()),
+            );
+        }
+        if let Some(media_type) = &link.media_type {
+            push(
+                &mut triples,
+                &link.url,
+                KnowledgeGraph::expand_namespace("dcterms:format"),
+                Term::literal(media_type.as_str()),
+            );
+        }
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} link triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// Insert a parsed RSS/Atom/JSON Feed into the Knowledge Graph
+///
+/// The feed (its `feed_url`, or `base_url`) becomes a `schema:DataFeed` whose
+/// `schema:dataFeedElement` entries are `schema:BlogPosting` resources, named by
+/// their link (or absolute `id`), else by a `<feed#item=...>` fragment built
+/// from the guid or title. Authors are named by their URL, `mailto:` address or
+/// a `<feed#author=...>` fragment. Re-ingesting a feed therefore updates the
+/// same nodes, and the values this mapping writes on them are replaced rather
+/// than accumulated. Items carry headline, url, dates (`xsd:dateTime`),
+/// description, article body, keywords, image and `schema:Person` authors.
+pub fn insert_feed_to_kg(
+    feed: &Feed,
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let schema = |local: &str| KnowledgeGraph::expand_namespace(&format!("schema:{}", local));
+    let iri_or_literal = |value: &str| {
+        if jsonld::is_absolute_iri(value) {
+            Term::iri(value)
+        } else {
+            Term::literal(value)
+        }
+    };
+    let rdf_type = KnowledgeGraph::expand_namespace("rdf:type");
+    let feed_iri = feed.feed_url.as_deref().unwrap_or(base_url);
+    let feed_node = Term::iri(feed_iri);
+    let fragment = |key: &str, value: &str| {
+        let encoded: String = url::form_urlencoded::byte_serialize(value.as_bytes()).collect();
+        Term::iri(format!("{}#{}={}", feed_iri.split('#').next().unwrap_or_default(), key, encoded))
+    };
+
+    let mut triples = Vec::new();
+    let mut blank = 0;
+    let mut add_authors = |triples: &mut Vec<Triple>, subject: &Term, authors: &[FeedAuthor]| {
+        for author in authors {
+            let person = match (&author.url, &author.email, &author.name) {
+                (Some(url), _, _) if jsonld::is_absolute_iri(url) => Term::iri(url.as_str()),
+                (_, Some(email), _) => Term::iri(format!("mailto:{}", email)),
+                (_, _, Some(name)) => fragment("author", name),
+                _ => {
+                    blank += 1;
+                    Term::BlankNode(format!("feedauthor{}", blank))
+                }
+            };
+            triples.push(Triple::new(subject.clone(), schema("author"), person.clone()));
+            triples.push(Triple::new(
+                person.clone(),
+                rdf_type.clone(),
+                Term::iri(schema("Person")),
+            ));
+            if let Some(name) = &author.name {
+                triples.push(Triple::new(
+                    person.clone(),
+                    schema("name"),
+                    Term::literal(name.as_str()),
+                ));
+            }
+            if let Some(url) = &author.url {
+                triples.push(Triple::new(person.clone(), schema("url"), iri_or_literal(url)));
+            }
+            if let Some(email) = &author.email {
+                triples.push(Triple::new(person, schema("email"), Term::literal(email.as_str())));
+            }
+        }
+    };
+
+    triples.push(Triple::new(feed_node.clone(), rdf_type.clone(), Term::iri(schema("DataFeed"))));
+    if let Some(title) = &feed.title {
+        triples.push(Triple::new(feed_node.clone(), schema("name"), Term::literal(title.as_str())));
+    }
+    if let Some(description) = &feed.description {
+        triples.push(Triple::new(
+            feed_node.clone(),
+            schema("description"),
+            Term::literal(description.as_str()),
+        ));
+    }
+    if let Some(link) = &feed.link {
+        triples.push(Triple::new(feed_node.clone(), schema("url"), iri_or_literal(link)));
+    }
+    if let Some(language) = &feed.language {
+        triples.push(Triple::new(
+            feed_node.clone(),
+            schema("inLanguage"),
+            Term::literal(language.as_str()),
+        ));
+    }
+    if let Some(updated) = &feed.updated {
+        triples.push(Triple::new(
+            feed_node.clone(),
+            schema("dateModified"),
+            datetime_literal(updated),
+        ));
+    }
+    add_authors(&mut triples, &feed_node, &feed.authors);
+
+    for (i, item) in feed.items.iter().enumerate() {
+        let absolute =
+            [&item.link, &item.id].into_iter().flatten().find(|v| jsonld::is_absolute_iri(v));
+        let node = match (absolute, &item.id, &item.title) {
+            (Some(iri), _, _) => Term::iri(iri.as_str()),
+            (None, Some(guid), _) => fragment("item", guid),
+            (None, None, Some(title)) => fragment("item", title),
+            _ => Term::BlankNode(format!("feeditem{}", i + 1)),
+        };
+        triples.push(Triple::new(feed_node.clone(), schema("dataFeedElement"), node.clone()));
+        triples.push(Triple::new(node.clone(), rdf_type.clone(), Term::iri(schema("BlogPosting"))));
+
+        let literals = [
+            ("headline", &item.title),
+            ("identifier", &item.id),
+            ("description", &item.summary),
+            ("articleBody", &item.content),
+        ];
+        for (property, value) in literals {
+            if let Some(value) = value {
+                triples.push(Triple::new(
+                    node.clone(),
+                    schema(property),
+                    Term::literal(value.as_str()),
+                ));
+            }
+        }
+        for (property, value) in [("url", &item.link), ("image", &item.image)] {
+            if let Some(value) = value {
+                triples.push(Triple::new(node.clone(), schema(property), iri_or_literal(value)));
+            }
+        }
+        for (property, value) in
+            [("datePublished", &item.published), ("dateModified", &item.updated)]
+        {
+            if let Some(value) = value {
+                triples.push(Triple::new(node.clone(), schema(property), datetime_literal(value)));
+            }
+        }
+        for category in &item.categories {
+            triples.push(Triple::new(
+                node.clone(),
+                schema("keywords"),
+                Term::literal(category.as_str()),
+            ));
+        }
+        add_authors(&mut triples, &node, &item.authors);
+    }
+
+    // Drop the values written by a previous ingestion of the same nodes; the
+    // feed's `dataFeedElement` links are kept so older items stay reachable
+    let written: Vec<&Triple> = triples
         .iter()
-        .map(|item| MicrodataSummary {
-            item_type: item.item_type.clone(),
-            properties: item.properties.len(),
-        })
+        .filter(|t| t.predicate != rdf_type && t.predicate != schema("dataFeedElement"))
         .collect();
+    let mut subjects: Vec<&str> = Vec::new();
+    let mut predicates: Vec<&str> = Vec::new();
+    for triple in &written {
+        if let Term::Iri(subject) = &triple.subject {
+            if !subjects.contains(&subject.as_str()) {
+                subjects.push(subject);
+            }
+        }
+        if !predicates.contains(&triple.predicate.as_str()) {
+            predicates.push(&triple.predicate);
+        }
+    }
+    let sparql_iri = |iri: &&str| {
+        (!iri.contains(|c: char| c.is_whitespace() || "<>\"{}|^`\\".contains(c)))
+            .then(|| format!("<{}>", iri))
+    };
+    let subjects: Vec<String> = subjects.iter().filter_map(sparql_iri).collect();
+    let predicates: Vec<String> = predicates.iter().filter_map(sparql_iri).collect();
+    if !subjects.is_empty() && !predicates.is_empty() {
+        kg.update(&format!(
+            "DELETE {{ ?s ?p ?o }} WHERE {{ VALUES ?s {{ {} }} VALUES ?p {{ {} }} ?s ?p ?o }}",
+            subjects.join(" "),
+            predicates.join(" ")
+        ))
+        .map_err(|e| e.to_string())?;
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} feed triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// Insert the metadata of a non-HTML document into the Knowledge Graph
+///
+/// The document (`base_url`) gets its media type as `dcterms:format`, one
+/// `dcterms:creator` per author, `dcterms:created`/`dcterms:modified` dates and,
+/// for PDFs, `schema:numberOfPages`. Binary documents stop at the format; the
+/// rest are also typed `schema:DigitalDocument`.
+pub fn insert_document_to_kg(
+    document: &DocumentInfo,
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let subject = Term::iri(base_url);
+    let mut triples = vec![Triple::new(
+        subject.clone(),
+        KnowledgeGraph::expand_namespace("dcterms:format"),
+        Term::literal(document.media_type.as_str()),
+    )];
+    if document.kind != DocumentKind::Binary {
+        triples.push(Triple::new(
+            subject.clone(),
+            KnowledgeGraph::expand_namespace("rdf:type"),
+            Term::iri(KnowledgeGraph::expand_namespace("schema:DigitalDocument")),
+        ));
+    }
+    for author in &document.authors {
+        triples.push(Triple::new(
+            subject.clone(),
+            KnowledgeGraph::expand_namespace("dcterms:creator"),
+            Term::literal(author.as_str()),
+        ));
+    }
+    for (predicate, value) in
+        [("dcterms:created", &document.created), ("dcterms:modified", &document.modified)]
+    {
+        if let Some(value) = value {
+            triples.push(Triple::new(
+                subject.clone(),
+                KnowledgeGraph::expand_namespace(predicate),
+                datetime_literal(value),
+            ));
+        }
+    }
+    if let Some(pages) = document.pages {
+        triples.push(Triple::new(
+            subject.clone(),
+            KnowledgeGraph::expand_namespace("schema:numberOfPages"),
+            Term::typed_literal(pages.to_string(), KnowledgeGraph::expand_namespace("xsd:integer")),
+        ));
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} document triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+/// Insert a bibliographic record into the Knowledge Graph
+///
+/// The work becomes a `schema:ScholarlyArticle` named by its DOI resolver URL
+/// (`https://doi.org/...`) when it has one, so pages citing the same DOI share a
+/// node, and is linked from the page with `schema:mainEntity`; without a DOI
+/// the page itself is the article. Authors are `schema:Person` nodes with
+/// affiliations, the journal a `schema:Periodical` reached through
+/// `schema:PublicationVolume`/`schema:PublicationIssue`, and references with a
+/// DOI become `schema:citation` edges.
+///
+/// Every node has a stable IRI so ingesting the same record again adds
+/// nothing: authors are their ORCID, or `<article#author-N>` by position; the
+/// PDF is its URL, the periodical `urn:issn:...`, and the other nodes are
+/// fragments of the article IRI (`#publisher`, `#publicationvolume`, ...).
+pub fn insert_citation_to_kg(
+    citation: &Citation,
+    kg: &mut KnowledgeGraph,
+    base_url: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let schema = |local: &str| KnowledgeGraph::expand_namespace(&format!("schema:{}", local));
+    let rdf_type = KnowledgeGraph::expand_namespace("rdf:type");
+    let doi_iri = |doi: &str| Term::iri(format!("{}{}", DOI_RESOLVER, doi));
+    let article_iri = match &citation.doi {
+        Some(doi) => format!("{}{}", DOI_RESOLVER, doi),
+        None => base_url.split('#').next().unwrap_or_default().to_string(),
+    };
+    let article = Term::iri(article_iri.as_str());
+    let node = |fragment: &str| Term::iri(format!("{}#{}", article_iri, fragment));
+
+    let mut triples =
+        vec![Triple::new(article.clone(), rdf_type.clone(), Term::iri(schema("ScholarlyArticle")))];
+    if citation.doi.is_some() {
+        triples.push(Triple::new(Term::iri(base_url), schema("mainEntity"), article.clone()));
+        triples.push(Triple::new(article.clone(), schema("url"), Term::iri(base_url)));
+    }
+
+    let literals = [
+        ("headline", &citation.title),
+        ("name", &citation.title),
+        ("identifier", &citation.doi),
+        ("isbn", &citation.isbn),
+        ("pageStart", &citation.first_page),
+        ("pageEnd", &citation.last_page),
+        ("abstract", &citation.abstract_text),
+        ("inLanguage", &citation.language),
+    ];
+    for (property, value) in literals {
+        if let Some(value) = value {
+            triples.push(Triple::new(
+                article.clone(),
+                schema(property),
+                Term::literal(value.as_str()),
+            ));
+        }
+    }
+    for keyword in &citation.keywords {
+        triples.push(Triple::new(
+            article.clone(),
+            schema("keywords"),
+            Term::literal(keyword.as_str()),
+        ));
+    }
+    if let Some(date) = &citation.publication_date {
+        triples.push(Triple::new(article.clone(), schema("datePublished"), datetime_literal(date)));
+    }
+    if let Some(date) = &citation.online_date {
+        triples.push(Triple::new(article.clone(), schema("dateCreated"), datetime_literal(date)));
+    }
+    if let Some(url) = &citation.abstract_url {
+        triples.push(Triple::new(article.clone(), schema("url"), Term::iri(url.as_str())));
+    }
+    if let Some(publisher) = &citation.publisher {
+        let organization = node("publisher");
+        triples.push(Triple::new(article.clone(), schema("publisher"), organization.clone()));
+        triples.push(Triple::new(
+            organization.clone(),
+            rdf_type.clone(),
+            Term::iri(schema("Organization")),
+        ));
+        triples.push(Triple::new(organization, schema("name"), Term::literal(publisher.as_str())));
+    }
+    if let Some(pdf_url) = &citation.pdf_url {
+        let media = Term::iri(pdf_url.as_str());
+        triples.push(Triple::new(article.clone(), schema("encoding"), media.clone()));
+        triples.push(Triple::new(
+            media.clone(),
+            rdf_type.clone(),
+            Term::iri(schema("MediaObject")),
+        ));
+        triples.push(Triple::new(media.clone(), schema("contentUrl"), Term::iri(pdf_url.as_str())));
+        triples.push(Triple::new(
+            media,
+            schema("encodingFormat"),
+            Term::literal("application/pdf"),
+        ));
+    }
+
+    for (a, author) in citation.authors.iter().enumerate() {
+        let person = match &author.orcid {
+            Some(orcid) => Term::iri(orcid.as_str()),
+            None => node(&format!("author-{}", a + 1)),
+        };
+        triples.push(Triple::new(article.clone(), schema("author"), person.clone()));
+        triples.push(Triple::new(person.clone(), rdf_type.clone(), Term::iri(schema("Person"))));
+        triples.push(Triple::new(
+            person.clone(),
+            schema("name"),
+            Term::literal(author.name.as_str()),
+        ));
+        for (i, affiliation) in author.affiliations.iter().enumerate() {
+            let organization = node(&format!("author-{}-affiliation-{}", a + 1, i + 1));
+            triples.push(Triple::new(person.clone(), schema("affiliation"), organization.clone()));
+            triples.push(Triple::new(
+                organization.clone(),
+                rdf_type.clone(),
+                Term::iri(schema("Organization")),
+            ));
+            triples.push(Triple::new(
+                organization,
+                schema("name"),
+                Term::literal(affiliation.as_str()),
+            ));
+        }
+    }
+
+    // Article -> issue -> volume -> periodical, skipping the levels that are unknown
+    let mut part_of = article.clone();
+    let levels = [
+        ("PublicationIssue", "issueNumber", &citation.issue),
+        ("PublicationVolume", "volumeNumber", &citation.volume),
+    ];
+    for (kind, property, value) in levels {
+        if let Some(value) = value {
+            let level = node(&kind.to_ascii_lowercase());
+            triples.push(Triple::new(part_of, schema("isPartOf"), level.clone()));
+            triples.push(Triple::new(level.clone(), rdf_type.clone(), Term::iri(schema(kind))));
+            triples.push(Triple::new(
+                level.clone(),
+                schema(property),
+                Term::literal(value.as_str()),
+            ));
+            part_of = level;
+        }
+    }
+    if citation.journal.is_some() || !citation.issn.is_empty() {
+        let periodical = match citation.issn.first() {
+            Some(issn) => Term::iri(format!("urn:issn:{}", issn)),
+            None => node("periodical"),
+        };
+        triples.push(Triple::new(part_of, schema("isPartOf"), periodical.clone()));
+        triples.push(Triple::new(
+            periodical.clone(),
+            rdf_type.clone(),
+            Term::iri(schema("Periodical")),
+        ));
+        if let Some(journal) = &citation.journal {
+            triples.push(Triple::new(
+                periodical.clone(),
+                schema("name"),
+                Term::literal(journal.as_str()),
+            ));
+        }
+        for issn in &citation.issn {
+            triples.push(Triple::new(
+                periodical.clone(),
+                schema("issn"),
+                Term::literal(issn.as_str()),
+            ));
+        }
+    }
+    if let Some(conference) = &citation.conference {
+        let event = node("conference");
+        triples.push(Triple::new(article.clone(), schema("recordedAt"), event.clone()));
+        triples.push(Triple::new(event.clone(), rdf_type.clone(), Term::iri(schema("Event"))));
+        triples.push(Triple::new(event, schema("name"), Term::literal(conference.as_str())));
+    }
+    if let Some(institution) = &citation.institution {
+        let organization = node("institution");
+        triples.push(Triple::new(
+            article.clone(),
+            schema("sourceOrganization"),
+            organization.clone(),
+        ));
+        triples.push(Triple::new(
+            organization.clone(),
+            rdf_type.clone(),
+            Term::iri(schema("Organization")),
+        ));
+        triples.push(Triple::new(
+            organization,
+            schema("name"),
+            Term::literal(institution.as_str()),
+        ));
+    }
+
+    for reference in &citation.references {
+        let object = match (&reference.doi, &reference.title) {
+            (Some(doi), _) => doi_iri(doi),
+            (None, Some(title)) => Term::literal(title.as_str()),
+            (None, None) => Term::literal(reference.text.as_str()),
+        };
+        triples.push(Triple::new(article.clone(), schema("citation"), object));
+    }
+
+    let inserted = kg.insert_triples(&triples)?;
+    tracing::debug!("Inserted {} citation triples for {}", inserted, base_url);
+    Ok(inserted)
+}
+
+#[cfg(feature = "browser-automation")]
+pub(crate) fn semantic_data_to_snapshot(data: &SemanticData) -> SemanticSnapshot {
+    let microdata = data.microdata.iter().map(MicrodataSummary::from).collect();
 
     SemanticSnapshot {
         title: data.title.clone(),
         description: data.meta_description.clone(),
         language: data.language.clone(),
+        detected_language: crate::language::detect_language(&data.text_content),
         canonical_url: data.canonical_url.clone(),
         final_url: data.final_url.clone(),
         keywords: data.meta_keywords.clone(),
         open_graph: data.open_graph.clone(),
         twitter_card: data.twitter_card.clone(),
         json_ld_count: data.json_ld.len(),
+        json_ld: data.json_ld.clone(),
         microdata,
+        microdata_items: data.microdata.clone(),
+        rdfa: data.rdfa.clone(),
+        microformats: data.microformats.clone(),
+        links: data.links.clone(),
+        feed: None,
+        document: None,
+        article: data.article.clone(),
+        citation: data.citation.clone(),
+        markdown: data.markdown.clone(),
         text_preview: build_preview(&data.text_content),
         text_length: data.text_content.len(),
+        encoding: None,
         query_matches: Vec::new(),
     }
 }
@@ -252,6 +1328,343 @@ pub fn insert_semantic_data_to_kg(
 #[cfg(test)]
 mod tests {
 
+    #[test]
+    fn test_insert_json_ld_links_main_entity() {
+        use crate::kg::KnowledgeGraph;
+
+        let blocks = vec![serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "name": "Pancakes",
+            "author": {"@type": "Person", "name": "Ada"}
+        })];
+
+        let mut kg = KnowledgeGraph::new();
+        let count = super::insert_json_ld_to_kg(
+            &blocks,
+            &mut kg,
+            "https://example.com/pancakes",
+            "https://example.com/pancakes",
+        )
+        .unwrap();
+
+        // Recipe type + name, author link, Person type + name, mainEntity
+        assert_eq!(count, 6);
+        let results = kg
+            .query(
+                "SELECT ?name WHERE { <https://example.com/pancakes> <https://schema.org/mainEntity> ?r . \
+                 ?r <https://schema.org/author> ?a . ?a <https://schema.org/name> ?name }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("Ada"));
+    }
+
+    #[test]
+    fn test_insert_microdata_tree() {
+        use crate::kg::KnowledgeGraph;
+
+        let html = r#"<html><body>
+            <div itemscope itemtype="http://schema.org/Event" itemid="https://ev.example/e/1">
+              <span itemprop="name">Meetup</span>
+              <time itemprop="startDate" datetime="2025-03-01T18:00">March 1st</time>
+              <div itemprop="location" itemscope>
+                <span itemprop="name">Town Hall</span>
+              </div>
+            </div>
+            </body></html>"#;
+        let data = crate::parser::parse_html(html).unwrap();
+
+        let mut kg = KnowledgeGraph::new();
+        let count =
+            super::insert_microdata_to_kg(&data.microdata, &mut kg, "https://ev.example/").unwrap();
+
+        // type, name, startDate, location, location name, mainEntity
+        assert_eq!(count, 6);
+        let results = kg
+            .query(
+                "SELECT ?place WHERE { \
+                 <https://ev.example/e/1> a <https://schema.org/Event> ; \
+                   <https://schema.org/startDate> \"2025-03-01T18:00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> ; \
+                   <https://schema.org/location> ?l . \
+                 ?l <https://schema.org/name> ?place }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("Town Hall"));
+    }
+
+    #[test]
+    fn test_datetime_literal_lexical_forms() {
+        use super::datetime_literal;
+        use crate::kg::{KnowledgeGraph, Term};
+
+        let typed = |value: &str, lexical: &str, datatype: &str| {
+            assert_eq!(
+                datetime_literal(value),
+                Term::typed_literal(lexical, KnowledgeGraph::expand_namespace(datatype)),
+                "{}",
+                value
+            );
+        };
+        typed("2025-03-01T18:00", "2025-03-01T18:00:00", "xsd:dateTime");
+        typed("2025-03-01T18:00:05.25+0100", "2025-03-01T18:00:05.250+01:00", "xsd:dateTime");
+        typed("2025-03-01T18:00:00Z", "2025-03-01T18:00:00Z", "xsd:dateTime");
+        typed("2025-03-01", "2025-03-01", "xsd:date");
+        typed("2025-03", "2025-03", "xsd:gYearMonth");
+        typed("2025", "2025", "xsd:gYear");
+        typed("09:30", "09:30:00", "xsd:time");
+        typed("PT1H30M", "PT1H30M", "xsd:duration");
+        typed("P1Y2M3DT4H5M6.5S", "P1Y2M3DT4H5M6.5S", "xsd:duration");
+        typed("P2W", "P14D", "xsd:duration");
+
+        for value in [
+            "Pizza night",
+            "PT",
+            "P1H",
+            "2025-02-30",
+            "2025-13",
+            "2025-03-01T25:00",
+            "Thursday 18:00 CET",
+        ] {
+            assert_eq!(datetime_literal(value), Term::literal(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_insert_tables_as_csvw_rows() {
+        use crate::kg::KnowledgeGraph;
+
+        let html = r#"<table><caption>Prices</caption>
+            <tr><th>Product name</th><th>Price</th></tr>
+            <tr><td>Widget</td><td>9.5</td></tr>
+            <tr><td>Gadget</td><td></td></tr>
+            </table>"#;
+        let data = crate::parser::parse_html(html).unwrap();
+        assert_eq!(data.tables.len(), 1);
+
+        let mut kg = KnowledgeGraph::new();
+        let count =
+            super::insert_tables_to_kg(&data.tables, &mut kg, "https://shop.example/list").unwrap();
+
+        // table: type, url, title; 2 rows x (row, type, rownum, url, describes); 3 cells
+        assert_eq!(count, 3 + 10 + 3);
+        let results = kg
+            .query(
+                "PREFIX csvw: <http://www.w3.org/ns/csvw#> \
+                 SELECT ?price WHERE { \
+                 ?t a csvw:Table ; csvw:url <https://shop.example/list> ; csvw:row ?r . \
+                 ?r csvw:rownum 1 ; csvw:url <https://shop.example/list#table=1&row=1> ; \
+                    csvw:describes ?d . \
+                 ?d <https://shop.example/list#Product%20name> \"Widget\" ; \
+                    <https://shop.example/list#Price> ?price }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("9.5"));
+    }
+
+    #[test]
+    fn test_insert_feed_items_as_blog_postings() {
+        use crate::kg::KnowledgeGraph;
+
+        let rss = r#"<rss version="2.0"><channel>
+            <title>Notes</title><link>https://notes.example/</link>
+            <item>
+              <title>Launch</title><link>https://notes.example/launch</link>
+              <pubDate>Mon, 02 Jun 2025 09:00:00 +0000</pubDate>
+              <author>ann@notes.example (Ann)</author>
+            </item>
+            </channel></rss>"#;
+        let feed = crate::feeds::parse_feed(rss, Some("https://notes.example/rss")).unwrap();
+
+        let mut kg = KnowledgeGraph::new();
+        let count = super::insert_feed_to_kg(&feed, &mut kg, "https://notes.example/rss").unwrap();
+
+        // feed: type, name, url; item: element, type, headline, url, date; author: 4
+        assert_eq!(count, 3 + 5 + 4);
+        let results = kg
+            .query(
+                "PREFIX schema: <https://schema.org/> \
+                 SELECT ?name ?date WHERE { \
+                 <https://notes.example/rss> schema:dataFeedElement ?post . \
+                 ?post a schema:BlogPosting ; schema:datePublished ?date ; \
+                       schema:author/schema:name ?name }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("Ann"));
+        assert!(results[0].contains("2025-06-02T09:00:00"));
+
+        // Re-ingesting an updated feed replaces values instead of adding nodes
+        let rss = r#"<rss version="2.0"><channel>
+            <title>Notes</title><link>https://notes.example/</link>
+            <item>
+              <title>Launch (updated)</title><link>https://notes.example/launch</link>
+              <author>ann@notes.example (Ann)</author>
+            </item>
+            <item><guid isPermaLink="false">note-7</guid><title>Linkless</title></item>
+            </channel></rss>"#;
+        let feed = crate::feeds::parse_feed(rss, Some("https://notes.example/rss")).unwrap();
+        for _ in 0..2 {
+            super::insert_feed_to_kg(&feed, &mut kg, "https://notes.example/rss").unwrap();
+        }
+        let results = kg
+            .query(
+                "PREFIX schema: <https://schema.org/> \
+                 SELECT ?post ?headline WHERE { \
+                 <https://notes.example/rss> schema:dataFeedElement ?post . \
+                 ?post schema:headline ?headline }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|r| r.contains("Launch (updated)")));
+        assert!(results.iter().any(|r| r.contains("https://notes.example/rss#item=note-7")));
+        let authors = kg
+            .query("PREFIX schema: <https://schema.org/> SELECT DISTINCT ?a WHERE { ?p schema:author ?a }")
+            .unwrap();
+        assert_eq!(authors.len(), 1);
+        assert!(authors[0].contains("mailto:ann@notes.example"));
+    }
+
+    #[test]
+    fn test_insert_citation_as_scholarly_article() {
+        use crate::kg::KnowledgeGraph;
+
+        let html = r#"<html><head>
+            <meta name="citation_title" content="Attention Is All You Need">
+            <meta name="citation_author" content="Vaswani, Ashish">
+            <meta name="citation_author_institution" content="Google Brain">
+            <meta name="citation_journal_title" content="Advances in NeurIPS">
+            <meta name="citation_volume" content="30">
+            <meta name="citation_publication_date" content="2017/12/04">
+            <meta name="citation_doi" content="10.5555/3295222.3295349">
+            <meta name="citation_pdf_url" content="paper.pdf">
+            <meta name="citation_reference" content="citation_title=Adam; citation_doi=10.48550/arXiv.1412.6980">
+            </head><body></body></html>"#;
+        let data =
+            crate::parser::parse_html_with_base(html, Some("https://papers.example/3295349/"))
+                .unwrap();
+        let citation = data.citation.unwrap();
+
+        let mut kg = KnowledgeGraph::new();
+        let count =
+            super::insert_citation_to_kg(&citation, &mut kg, "https://papers.example/3295349/")
+                .unwrap();
+
+        // article: type, mainEntity, url, headline, name, identifier, date, citation;
+        // pdf: 4; author: 3 + affiliation 3; volume: 3; periodical: 3
+        assert_eq!(count, 8 + 4 + 6 + 3 + 3);
+        let results = kg
+            .query(
+                "PREFIX schema: <https://schema.org/> \
+                 SELECT ?author ?org ?journal ?pdf WHERE { \
+                 <https://papers.example/3295349/> schema:mainEntity ?a . \
+                 ?a a schema:ScholarlyArticle ; \
+                    schema:datePublished \"2017-12-04\"^^<http://www.w3.org/2001/XMLSchema#date> ; \
+                    schema:citation <https://doi.org/10.48550/arXiv.1412.6980> ; \
+                    schema:author ?p ; schema:encoding/schema:contentUrl ?pdf ; \
+                    schema:isPartOf/schema:isPartOf/schema:name ?journal . \
+                 ?p schema:name ?author ; schema:affiliation/schema:name ?org }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("Ashish Vaswani"));
+        assert!(results[0].contains("Google Brain"));
+        assert!(results[0].contains("Advances in NeurIPS"));
+        assert!(results[0].contains("https://papers.example/3295349/paper.pdf"));
+
+        // Stable IRIs: the same record again adds nothing
+        let before = kg.list_triples().len();
+        super::insert_citation_to_kg(&citation, &mut kg, "https://papers.example/3295349/")
+            .unwrap();
+        assert_eq!(kg.list_triples().len(), before);
+        let authors = kg
+            .query(
+                "PREFIX schema: <https://schema.org/> \
+                 SELECT ?p WHERE { <https://doi.org/10.5555/3295222.3295349> schema:author ?p }",
+            )
+            .unwrap();
+        assert_eq!(authors.len(), 1);
+        assert!(authors[0].contains("https://doi.org/10.5555/3295222.3295349#author-1"));
+    }
+
+    #[test]
+    fn test_insert_links_as_link_graph() {
+        use crate::kg::KnowledgeGraph;
+
+        let html = r#"<html><head>
+            <link rel="alternate" hreflang="de" href="/de/">
+            </head><body>
+            <a href="/docs">Docs</a> <a href="/docs">Documentation</a>
+            <a href="https://ads.example/" rel="nofollow">Ad</a>
+            </body></html>"#;
+        let data = crate::parser::parse_html_with_base(html, Some("https://a.example/")).unwrap();
+        assert_eq!(data.links.len(), 4);
+
+        let mut kg = KnowledgeGraph::new();
+        let count = super::insert_links_to_kg(&data.links, &mut kg, "https://a.example/").unwrap();
+
+        // alternate + language; docs once; ads significantLink + nofollow
+        assert_eq!(count, 2 + 1 + 2);
+        let results = kg
+            .query(
+                "SELECT ?page WHERE { \
+                 ?page <https://schema.org/significantLink> <https://a.example/docs> }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("https://a.example/"));
+        let results = kg
+            .query(
+                "SELECT ?lang WHERE { \
+                 <https://a.example/> <http://www.iana.org/assignments/relation/alternate> ?alt . \
+                 ?alt <http://purl.org/dc/terms/language> ?lang }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("de"));
+    }
+
+    #[test]
+    fn test_insert_microformats_entry_with_author() {
+        use crate::kg::KnowledgeGraph;
+
+        let html = r#"<html><body>
+            <div class="h-feed">
+              <article class="h-entry">
+                <h1 class="p-name">Release notes</h1>
+                <time class="dt-published" datetime="2025-05-02 10:30">May 2</time>
+                <a class="p-author h-card" href="https://ann.example">Ann</a>
+                <a class="u-url" href="/notes/1">link</a>
+              </article>
+            </div>
+            </body></html>"#;
+        let data = crate::parser::parse_html_with_base(html, Some("https://ann.example/")).unwrap();
+
+        let mut kg = KnowledgeGraph::new();
+        let count =
+            super::insert_microformats_to_kg(&data.microformats, &mut kg, "https://ann.example/")
+                .unwrap();
+
+        // Article: type, headline, datePublished, author, url; Person: type, name, url; mainEntity
+        assert_eq!(count, 9);
+        let results = kg
+            .query(
+                "SELECT ?name WHERE { \
+                 <https://ann.example/> <https://schema.org/mainEntity> ?a . \
+                 ?a a <https://schema.org/Article> ; \
+                   <https://schema.org/headline> \"Release notes\" ; \
+                   <https://schema.org/datePublished> \"2025-05-02T10:30:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> ; \
+                   <https://schema.org/url> <https://ann.example/notes/1> ; \
+                   <https://schema.org/author> ?p . \
+                 ?p a <https://schema.org/Person> ; <https://schema.org/name> ?name }",
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("Ann"));
+    }
+
     #[test]
     #[cfg(feature = "browser-automation")]
     fn test_insert_semantic_data_minimal() {
@@ -263,8 +1676,16 @@ mod tests {
             title: Some("Test Page".to_string()),
             json_ld: vec![],
             microdata: vec![],
+            rdfa: vec![],
+            microformats: Default::default(),
+            links: Vec::new(),
+            article: None,
+            markdown: String::new(),
             text_content: String::new(),
             screenshot: None,
+            pdf: None,
+            har: None,
+            storage_state: None,
             final_url: "https://example.com".to_string(),
             meta_description: Some("Test description".to_string()),
             meta_keywords: vec!["test".to_string(), "example".to_string()],
@@ -272,6 +1693,7 @@ mod tests {
             canonical_url: Some("https://example.com/canonical".to_string()),
             open_graph: HashMap::new(),
             twitter_card: HashMap::new(),
+            citation: None,
         };
 
         let mut kg = KnowledgeGraph::new();
@@ -305,8 +1727,16 @@ mod tests {
             title: Some("Test".to_string()),
             json_ld: vec![],
             microdata: vec![],
+            rdfa: vec![],
+            microformats: Default::default(),
+            links: Vec::new(),
+            article: None,
+            markdown: String::new(),
             text_content: String::new(),
             screenshot: None,
+            pdf: None,
+            har: None,
+            storage_state: None,
             final_url: "https://example.com".to_string(),
             meta_description: None,
             meta_keywords: vec![],
@@ -314,6 +1744,7 @@ mod tests {
             canonical_url: None,
             open_graph,
             twitter_card,
+            citation: None,
         };
 
         let mut kg = KnowledgeGraph::new();
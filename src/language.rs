@@ -0,0 +1,186 @@
+//! Language identification
+//!
+//! Many pages omit `<html lang>` or declare the CMS default instead of the
+//! language they are written in. [`detect_language`] identifies the language of
+//! the extracted text offline with trigram statistics (69 languages, via
+//! `whatlang`), and [`effective_language`] combines it with the declared tag to
+//! pick the language used for tagged literals in the knowledge graph and for
+//! choosing a NER model (see `annotator::extract_entities`).
+
+use serde::{Deserialize, Serialize};
+use whatlang::Lang;
+
+/// Text looked at by the detector; more doesn't improve accuracy
+const MAX_DETECTION_CHARS: usize = 4096;
+
+/// Language identified from text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// BCP 47 language tag: the ISO 639-1 code where one exists (`de`), the
+    /// ISO 639-3 code otherwise
+    pub code: String,
+    /// Detector confidence between 0.0 and 1.0
+    pub confidence: f64,
+    /// Whether the text was long and distinctive enough to rely on the result
+    pub reliable: bool,
+}
+
+/// Identify the language of a text; `None` when no script or language stands out
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let end = text.char_indices().nth(MAX_DETECTION_CHARS).map_or(text.len(), |(i, _)| i);
+    let info = whatlang::detect(&text[..end])?;
+    Some(DetectedLanguage {
+        code: bcp47(info.lang()).to_string(),
+        confidence: (info.confidence() * 100.0).round() / 100.0,
+        reliable: info.is_reliable(),
+    })
+}
+
+/// Language a document should be treated as: the declared tag (normalised),
+/// otherwise the detected language when detection is reliable
+pub fn effective_language(
+    declared: Option<&str>,
+    detected: Option<&DetectedLanguage>,
+) -> Option<String> {
+    declared
+        .and_then(normalize_tag)
+        .or_else(|| detected.filter(|d| d.reliable).map(|d| d.code.clone()))
+}
+
+/// Normalise a declared language tag into BCP 47 form (`en_US` -> `en-US`);
+/// `None` for empty or malformed values
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-");
+    let mut subtags = tag.split('-');
+    let primary = subtags.next()?;
+    if !(2..=8).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    if !subtags.all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        return None;
+    }
+    Some(tag)
+}
+
+/// Lowercased primary language subtag (`pt-BR` -> `pt`)
+pub fn primary_subtag(tag: &str) -> String {
+    tag.split(['-', '_']).next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+/// BCP 47 tag of a detected language
+fn bcp47(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let german = "Die Bundesregierung hat am Mittwoch neue Maßnahmen beschlossen, \
+                      um die Energiepreise für Haushalte und Unternehmen zu senken.";
+        let detected = detect_language(german).unwrap();
+        assert_eq!(detected.code, "de");
+        assert!(detected.reliable);
+        assert!(detected.confidence > 0.5 && detected.confidence <= 1.0);
+
+        let italian = "Il consiglio comunale ha approvato ieri sera il nuovo piano \
+                       regolatore, dopo una lunga discussione con i cittadini del quartiere.";
+        assert_eq!(detect_language(italian).unwrap().code, "it");
+        assert_eq!(
+            detect_language("東京は日本の首都であり、世界有数の大都市です。").unwrap().code,
+            "ja"
+        );
+
+        assert!(detect_language("").is_none());
+        assert!(detect_language("1234 5678 !!!").is_none());
+    }
+
+    #[test]
+    fn test_effective_language() {
+        let reliable = DetectedLanguage { code: "fr".into(), confidence: 0.9, reliable: true };
+        let unreliable = DetectedLanguage { code: "fr".into(), confidence: 0.1, reliable: false };
+
+        // The declaration wins; malformed declarations are ignored
+        assert_eq!(effective_language(Some("en_US"), Some(&reliable)).as_deref(), Some("en-US"));
+        assert_eq!(effective_language(Some(" "), Some(&reliable)).as_deref(), Some("fr"));
+        assert_eq!(effective_language(Some("english!"), Some(&reliable)).as_deref(), Some("fr"));
+        assert_eq!(effective_language(None, Some(&unreliable)), None);
+        assert_eq!(effective_language(None, None), None);
+
+        assert_eq!(primary_subtag("pt-BR"), "pt");
+        assert_eq!(normalize_tag("zh-Hant-TW").as_deref(), Some("zh-Hant-TW"));
+    }
+}
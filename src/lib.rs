@@ -4,20 +4,44 @@
 //! including HTML5 parsing, semantic annotation, knowledge graph building,
 //! and agent interactions.
 
+pub mod accessibility;
 pub mod annotator;
 pub mod api;
 pub mod api_client;
 pub mod auth;
 pub mod browser;
+pub mod browser_context;
+pub mod capture;
+pub mod charset;
+pub mod citation;
+pub mod crawler;
+pub mod discovery;
+pub mod documents;
+pub mod element_map;
+pub mod emulation;
 pub mod external;
+pub mod feeds;
 pub mod form_analyzer;
 pub mod form_interaction;
+pub mod har;
+pub mod jsonld;
 pub mod kg;
 pub mod kg_integration;
+pub mod language;
+pub mod links;
 pub mod llm;
+pub mod markdown;
+pub mod microformats;
 pub mod ml;
 pub mod models;
 pub mod observability;
+pub mod page_pool;
 pub mod parser;
+pub mod pdf;
+pub mod rdfa;
+pub mod readability;
+pub mod replay;
 pub mod security;
 pub mod smart_form_filler;
+pub mod storage_state;
+pub mod tables;
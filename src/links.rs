@@ -0,0 +1,186 @@
+//! Hyperlink extraction
+//!
+//! Collects the outgoing links of a document from `<a href>`, `<area href>` and
+//! `<link href>` elements, resolving them against `<base href>` and the document
+//! URL. Each link keeps its anchor text and `rel`/`hreflang`/`type` attributes so
+//! the knowledge graph can be queried as a link graph (see
+//! `kg_integration::insert_links_to_kg`).
+
+use crate::external::normalize_whitespace;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Element a link was found on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkSource {
+    /// `<a>` or `<area>` hyperlink in the document body
+    Anchor,
+    /// `<link>` element, usually in the document head
+    Link,
+}
+
+/// An outgoing link of a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    /// Target URL, absolute whenever a document URL or `<base href>` is known
+    pub url: String,
+    /// Anchor text, falling back to `aria-label`, `title` or image `alt` text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Lowercased `rel` tokens (`nofollow`, `next`, `alternate`, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rel: Vec<String>,
+    /// Language of the target (`hreflang`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hreflang: Option<String>,
+    /// Advisory media type of the target (`type`)
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    pub source: LinkSource,
+}
+
+impl Link {
+    /// Whether the link carries the given `rel` token (case-insensitive)
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+
+    /// Whether search engines and crawlers are asked not to follow the link
+    pub fn is_nofollow(&self) -> bool {
+        self.has_rel("nofollow") || self.has_rel("ugc") || self.has_rel("sponsored")
+    }
+
+    /// Whether the target is on the same host as `page`
+    pub fn is_internal(&self, page: &url::Url) -> bool {
+        url::Url::parse(&self.url).is_ok_and(|target| target.host_str() == page.host_str())
+    }
+}
+
+/// Extract the outgoing links of a document, in document order.
+///
+/// `base_url` is the URL the document was fetched from (after redirects).
+/// Same-document fragment links, `javascript:` and `data:` URLs are skipped;
+/// without any base, relative links are kept as written.
+pub fn extract_links(document: &Html, base_url: Option<&str>) -> Vec<Link> {
+    let Ok(selector) = Selector::parse("a[href], area[href], link[href]") else {
+        return Vec::new();
+    };
+    let base = crate::parser::document_base(document, base_url);
+
+    let mut links = Vec::new();
+    for element in document.select(&selector) {
+        let href = element.value().attr("href").unwrap_or_default().trim();
+        if href.is_empty() || href.starts_with('#') {
+            continue;
+        }
+        let url = match &base {
+            Some(base) => match base.join(href) {
+                Ok(url) => url.to_string(),
+                Err(_) => continue,
+            },
+            None => href.to_string(),
+        };
+        let scheme = url.split(':').next().unwrap_or_default().to_ascii_lowercase();
+        if scheme == "javascript" || scheme == "data" {
+            continue;
+        }
+
+        let source = if element.value().name() == "link" {
+            LinkSource::Link
+        } else {
+            LinkSource::Anchor
+        };
+
+        let text = match source {
+            LinkSource::Anchor => Some(normalize_whitespace(&element.text().collect::<String>()))
+                .filter(|t| !t.is_empty())
+                .or_else(|| attr_text(element.value().attr("aria-label")))
+                .or_else(|| attr_text(element.value().attr("title")))
+                .or_else(|| {
+                    Selector::parse("img[alt]").ok().and_then(|img| {
+                        element.select(&img).find_map(|i| attr_text(i.value().attr("alt")))
+                    })
+                })
+                .or_else(|| attr_text(element.value().attr("alt"))),
+            LinkSource::Link => attr_text(element.value().attr("title")),
+        };
+
+        let mut rel: Vec<String> = Vec::new();
+        for token in element.value().attr("rel").unwrap_or_default().split_ascii_whitespace() {
+            let token = token.to_ascii_lowercase();
+            if !rel.contains(&token) {
+                rel.push(token);
+            }
+        }
+
+        links.push(Link {
+            url,
+            text,
+            rel,
+            hreflang: attr_text(element.value().attr("hreflang")),
+            media_type: attr_text(element.value().attr("type")),
+            source,
+        });
+    }
+    links
+}
+
+fn attr_text(value: Option<&str>) -> Option<String> {
+    value.map(normalize_whitespace).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links_resolves_and_keeps_rel() {
+        let html = r##"
+            <html><head>
+              <base href="/blog/">
+              <link rel="alternate" hreflang="fr" href="https://example.com/fr/">
+              <link rel="Next" href="page/2">
+            </head><body>
+              <a href="post-1">First   post</a>
+              <a href="https://other.example/" rel="nofollow noopener">Sponsor</a>
+              <a href="#top">Top</a>
+              <a href="javascript:void(0)">Menu</a>
+              <a href="/"><img src="logo.png" alt="Home"></a>
+            </body></html>
+        "##;
+        let document = Html::parse_document(html);
+        let links = extract_links(&document, Some("https://example.com/index.html"));
+
+        let urls: Vec<&str> = links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/fr/",
+                "https://example.com/blog/page/2",
+                "https://example.com/blog/post-1",
+                "https://other.example/",
+                "https://example.com/",
+            ]
+        );
+        assert_eq!(links[0].source, LinkSource::Link);
+        assert_eq!(links[0].hreflang.as_deref(), Some("fr"));
+        assert!(links[1].has_rel("next"));
+        assert_eq!(links[2].text.as_deref(), Some("First post"));
+        assert!(links[3].is_nofollow());
+        assert_eq!(links[4].text.as_deref(), Some("Home"));
+
+        let page = url::Url::parse("https://example.com/index.html").unwrap();
+        assert!(links[2].is_internal(&page));
+        assert!(!links[3].is_internal(&page));
+    }
+
+    #[test]
+    fn test_extract_links_without_base_keeps_relative_hrefs() {
+        let document = Html::parse_document(r#"<a href="about.html">About</a>"#);
+        let links = extract_links(&document, None);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "about.html");
+        assert_eq!(links[0].source, LinkSource::Anchor);
+    }
+}
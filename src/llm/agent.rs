@@ -1,6 +1,6 @@
 #[cfg(feature = "browser-automation")]
 use super::browser_executor::BrowserExecutor;
-use super::provider::{LLMConfig, LLMProvider, LLMResult, Message};
+use super::provider::{ContentBlock, LLMConfig, LLMProvider, LLMResult, Message, MessageContent};
 use super::tools::ToolRegistry;
 use serde::{Deserialize, Serialize};
 
@@ -52,6 +52,10 @@ pub struct AgentResponse {
     pub error: Option<String>,
 }
 
+/// Tools that don't touch the page, so no screenshot is taken after them
+#[cfg(feature = "browser-automation")]
+const NON_BROWSER_TOOLS: &[&str] = &["query_kg", "store_memory", "predict_link", "discover_site"];
+
 /// Agent execution step
 #[derive(Debug, Clone)]
 struct AgentStep {
@@ -173,10 +177,19 @@ Be concise but thorough. Think step by step."#.to_string()
             info!("Agent iteration {}/{}", iterations, task.max_iterations);
 
             // Get LLM response
-            let response = self
-                .provider
-                .chat_completion_with_tools(messages.clone(), tools_json.clone(), &self.config)
-                .await?;
+            let response = if messages.iter().any(Message::has_vision_content) {
+                self.provider
+                    .vision_chat_completion_with_tools(
+                        messages.clone(),
+                        tools_json.clone(),
+                        &self.config,
+                    )
+                    .await?
+            } else {
+                self.provider
+                    .chat_completion_with_tools(messages.clone(), tools_json.clone(), &self.config)
+                    .await?
+            };
 
             // Parse the response
             let step = self.parse_response(&response.content)?;
@@ -205,7 +218,15 @@ Be concise but thorough. Think step by step."#.to_string()
 
                 // Add observation to conversation
                 messages.push(Message::assistant(&response.content));
-                messages.push(Message::user(format!("OBSERVATION: {}", observation)));
+                let observation = format!("OBSERVATION: {}", observation);
+                match self.vision_observation(action, &observation).await {
+                    Some(message) => {
+                        // Only the latest screenshot is worth its tokens
+                        drop_screenshots(&mut messages);
+                        messages.push(message);
+                    }
+                    None => messages.push(Message::user(observation)),
+                }
             } else {
                 // No action specified, add response and continue
                 messages.push(Message::assistant(&response.content));
@@ -213,6 +234,46 @@ Be concise but thorough. Think step by step."#.to_string()
         }
     }
 
+    /// Observation with a set-of-marks screenshot of the page, for vision
+    /// providers driving a real browser
+    #[cfg(feature = "browser-automation")]
+    async fn vision_observation(&self, action: &str, observation: &str) -> Option<Message> {
+        use super::browser_executor::ScreenshotMode;
+        use base64::{engine::general_purpose, Engine as _};
+
+        let browser = self.browser.as_ref()?;
+        if !self.provider.supports_vision() || NON_BROWSER_TOOLS.contains(&action) {
+            return None;
+        }
+        let screenshot = match browser.screenshot_with_mode(ScreenshotMode::SetOfMarks).await {
+            Ok(screenshot) => screenshot,
+            Err(e) => {
+                warn!("Set-of-marks screenshot failed: {}", e);
+                return None;
+            }
+        };
+        let marks = screenshot.marks.map(|m| m.to_text()).unwrap_or_default();
+
+        Some(Message::user_vision(vec![
+            ContentBlock::Text(format!(
+                "{}\n\nSCREENSHOT: interactive elements carry numbered marks; pass a mark \
+                 number as \"ref\" to browser tools.\n{}",
+                observation, marks
+            )),
+            ContentBlock::Image(super::provider::ImageContent {
+                image_url: super::provider::ImageSource::Base64 {
+                    media_type: "image/png".to_string(),
+                    data: general_purpose::STANDARD.encode(&screenshot.image),
+                },
+            }),
+        ]))
+    }
+
+    #[cfg(not(feature = "browser-automation"))]
+    async fn vision_observation(&self, _action: &str, _observation: &str) -> Option<Message> {
+        None
+    }
+
     fn parse_response(&self, content: &str) -> LLMResult<AgentStep> {
         let mut thought = String::new();
         let mut action: Option<String> = None;
@@ -427,6 +488,15 @@ Be concise but thorough. Think step by step."#.to_string()
             }
             "fill_form" => Ok("Form filled successfully".to_string()),
             "click_element" => {
+                if let Some(element_ref) =
+                    input.and_then(|v| v.get("ref")).and_then(|v| v.as_u64())
+                {
+                    return Ok(format!("Clicked element ref: {}", element_ref));
+                }
+                if let Some(node_id) = input.and_then(|v| v.get("node_id")).and_then(|v| v.as_i64())
+                {
+                    return Ok(format!("Clicked node: {}", node_id));
+                }
                 let selector = input
                     .and_then(|v| v.get("selector"))
                     .and_then(|v| v.as_str())
@@ -434,6 +504,12 @@ Be concise but thorough. Think step by step."#.to_string()
                 Ok(format!("Clicked element: {}", selector))
             }
             "get_page_content" => Ok("<html><body><h1>Example Page</h1></body></html>".to_string()),
+            "get_page_markdown" => Ok("# Example Page".to_string()),
+            "capture_page" => Ok("Captured screenshot (image/png, 48213 bytes)".to_string()),
+            "get_interactive_elements" => Ok("[1] link \"More information...\" @ 8,120 150x18\n".to_string()),
+            "get_accessibility_tree" => Ok("[1] RootWebArea \"Example Page\"\n  [5] heading \"Example Page\" (level=1)\n  [7] link \"More information...\"\n".to_string()),
+            "discover_site" => Ok(r#"{"root": "https://example.com/", "total_urls": 1, "urls": [{"loc": "https://example.com/", "priority": 1.0}]}"#.to_string()),
+            "extract_tables" => Ok(r#"[{"caption": null, "columns": [{"name": "Product", "datatype": "string"}], "records": [{"Product": "Widget"}]}]"#.to_string()),
             "extract_data" => Ok(r#"{"title": "Example", "price": "$99.99"}"#.to_string()),
             _ => {
                 warn!("Unknown tool: {}", tool_name);
@@ -443,6 +519,19 @@ Be concise but thorough. Think step by step."#.to_string()
     }
 }
 
+/// Replace the images of earlier observations with a placeholder
+fn drop_screenshots(messages: &mut [Message]) {
+    for message in messages {
+        if let MessageContent::Blocks(blocks) = &mut message.content {
+            for block in blocks.iter_mut() {
+                if matches!(block, ContentBlock::Image(_)) {
+                    *block = ContentBlock::Text("[earlier screenshot omitted]".to_string());
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,6 +556,29 @@ mod tests {
         println!("Agent result: {:?}", result);
     }
 
+    #[test]
+    fn test_drop_screenshots_keeps_text() {
+        let mut messages = vec![
+            Message::user("TASK: book a table"),
+            Message::user_vision(vec![
+                ContentBlock::Text("OBSERVATION: done\n[1] button \"Book\"".to_string()),
+                ContentBlock::Image(super::super::provider::ImageContent {
+                    image_url: super::super::provider::ImageSource::Base64 {
+                        media_type: "image/png".to_string(),
+                        data: "iVBORw0KGgo=".to_string(),
+                    },
+                }),
+            ]),
+        ];
+        drop_screenshots(&mut messages);
+
+        assert_eq!(
+            messages[1].content.to_string(),
+            "OBSERVATION: done\n[1] button \"Book\"\n[earlier screenshot omitted]"
+        );
+        assert_eq!(messages[0].text_content(), Some("TASK: book a table"));
+    }
+
     #[test]
     fn test_tools_include_memory_and_ml() {
         let registry = ToolRegistry::with_browser_tools();
@@ -1,4 +1,8 @@
 #[cfg(feature = "browser-automation")]
+use crate::capture::{Capture, PdfOptions, ScreenshotOptions};
+#[cfg(feature = "browser-automation")]
+use crate::element_map::ElementMap;
+#[cfg(feature = "browser-automation")]
 use crate::form_analyzer::FormAnalyzer;
 #[cfg(feature = "browser-automation")]
 use crate::llm::provider::{LLMError, LLMResult, ToolCall};
@@ -9,7 +13,29 @@ use chromiumoxide::Page;
 #[cfg(feature = "browser-automation")]
 use std::collections::HashMap;
 #[cfg(feature = "browser-automation")]
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+/// What `BrowserExecutor::screenshot_with_mode` draws on the page
+#[cfg(feature = "browser-automation")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScreenshotMode {
+    /// The page as rendered
+    #[default]
+    Plain,
+    /// Numbered boxes over the interactive elements in view ("set-of-marks"),
+    /// numbered with the same refs browser tools accept
+    SetOfMarks,
+}
+
+/// Screenshot with the marks drawn on it, if any
+#[cfg(feature = "browser-automation")]
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    /// PNG image data
+    pub image: Vec<u8>,
+    /// Mark -> element table for `ScreenshotMode::SetOfMarks`
+    pub marks: Option<ElementMap>,
+}
 
 /// Browser executor for LLM agents
 ///
@@ -19,6 +45,10 @@ use std::sync::Arc;
 pub struct BrowserExecutor {
     page: Arc<Page>,
     filler: Option<SmartFormFiller>,
+    /// Refs handed out by the last `get_interactive_elements` call
+    element_map: Mutex<Option<ElementMap>>,
+    /// Result of the last `capture_page` call, kept for the caller
+    last_capture: Mutex<Option<Capture>>,
 }
 
 #[cfg(feature = "browser-automation")]
@@ -27,7 +57,7 @@ impl BrowserExecutor {
     pub async fn new(page: Arc<Page>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let filler = SmartFormFiller::new(page.clone()).await.ok();
 
-        Ok(Self { page, filler })
+        Ok(Self { page, filler, element_map: Mutex::new(None), last_capture: Mutex::new(None) })
     }
 
     /// Navigate to URL
@@ -37,6 +67,8 @@ impl BrowserExecutor {
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("Navigating to: {}", url);
 
+        // Refs point into the old document
+        self.invalidate_refs();
         self.page.goto(url).await?;
         self.page.wait_for_navigation().await?;
 
@@ -101,6 +133,108 @@ impl BrowserExecutor {
         Ok(format!("Clicked element: {}", selector))
     }
 
+    /// Enumerate visible interactive elements and number them with refs that
+    /// browser tools accept instead of selectors until the page navigates
+    pub async fn get_interactive_elements(
+        &self,
+        format: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let map = crate::element_map::snapshot(&self.page).await?;
+
+        let output = match format {
+            "text" if map.elements.is_empty() => "No interactive elements found".to_string(),
+            "text" => map.to_text(),
+            "json" => serde_json::to_string_pretty(&map)?,
+            _ => return Err(format!("Unknown format: {}", format).into()),
+        };
+        *self.element_map.lock().map_err(|_| "Element map lock poisoned")? = Some(map);
+        Ok(output)
+    }
+
+    /// Backend DOM node id behind an element ref from the current page snapshot
+    pub async fn resolve_ref(
+        &self,
+        element_ref: u32,
+    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+        let current_url = self.page.url().await?.unwrap_or_default();
+        let mut guard = self.element_map.lock().map_err(|_| "Element map lock poisoned")?;
+        let map = guard
+            .as_ref()
+            .ok_or("No element refs for this page; call get_interactive_elements first")?;
+        if map.url != current_url {
+            *guard = None;
+            return Err(
+                "Element refs are stale (the page navigated); call get_interactive_elements again"
+                    .into(),
+            );
+        }
+        map.resolve(element_ref)
+            .ok_or_else(|| format!("Unknown element ref: {}", element_ref).into())
+    }
+
+    /// Forget the current element refs
+    fn invalidate_refs(&self) {
+        if let Ok(mut map) = self.element_map.lock() {
+            *map = None;
+        }
+    }
+
+    /// Click the accessibility-tree node with id `node_id`
+    pub async fn click_node(
+        &self,
+        node_id: i64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        tracing::info!("Clicking accessibility node: {}", node_id);
+
+        crate::accessibility::click_node(&self.page, node_id).await?;
+
+        Ok(format!("Clicked node: {}", node_id))
+    }
+
+    /// Fill the form control behind the accessibility-tree node `node_id`
+    pub async fn fill_node(
+        &self,
+        node_id: i64,
+        value: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        tracing::debug!("Filling accessibility node {}", node_id);
+
+        crate::accessibility::fill_node(&self.page, node_id, value).await?;
+
+        Ok(format!("Filled node: {}", node_id))
+    }
+
+    /// Visible text of the node with backend id `node_id`
+    pub async fn node_text(
+        &self,
+        node_id: i64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let text = crate::accessibility::call_on_node(
+            &self.page,
+            node_id,
+            "function() { return this.innerText ?? this.textContent; }",
+            vec![],
+        )
+        .await?;
+        Ok(text.as_str().unwrap_or_default().to_string())
+    }
+
+    /// Snapshot the page's pruned accessibility tree as a text outline or JSON
+    pub async fn get_accessibility_tree(
+        &self,
+        interactive_only: bool,
+        format: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let options = crate::accessibility::AccessibilityOptions { interactive_only };
+        let tree = crate::accessibility::snapshot(&self.page, &options).await?;
+
+        match format {
+            "text" => Ok(tree.to_text()),
+            "json" => Ok(serde_json::to_string_pretty(&tree)?),
+            _ => Err(format!("Unknown format: {}", format).into()),
+        }
+    }
+
     /// Get page content (HTML)
     pub async fn get_content(
         &self,
@@ -122,6 +256,100 @@ impl BrowserExecutor {
         }
     }
 
+    /// Render the page as Markdown within an approximate token budget
+    pub async fn get_markdown(
+        &self,
+        max_tokens: usize,
+        main_content_only: bool,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let html = self.page.content().await?;
+        let url = self.page.url().await?;
+
+        let document = scraper::Html::parse_document(&html);
+        let options = crate::markdown::MarkdownOptions {
+            max_tokens: Some(max_tokens),
+            main_content_only,
+            base_url: url,
+        };
+        Ok(crate::markdown::html_to_markdown(&document, &options).markdown)
+    }
+
+    /// Extract data tables, optionally limited to elements matching `selector`
+    pub async fn extract_tables(
+        &self,
+        selector: Option<&str>,
+        format: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let html = self.page.content().await?;
+        let document = scraper::Html::parse_document(&html);
+
+        let tables = match selector {
+            Some(selector) => {
+                let selector = scraper::Selector::parse(selector)
+                    .map_err(|e| format!("Invalid selector '{}': {:?}", selector, e))?;
+                document.select(&selector).flat_map(crate::tables::extract_tables_in).collect()
+            }
+            None => crate::tables::extract_tables(&document),
+        };
+        Self::format_tables(&tables, format)
+    }
+
+    /// Extract data tables inside the node with backend id `node_id`
+    pub async fn extract_tables_in_node(
+        &self,
+        node_id: i64,
+        format: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let html = crate::accessibility::call_on_node(
+            &self.page,
+            node_id,
+            "function() { return this.outerHTML; }",
+            vec![],
+        )
+        .await?;
+        let document = scraper::Html::parse_document(html.as_str().unwrap_or_default());
+        Self::format_tables(&crate::tables::extract_tables(&document), format)
+    }
+
+    fn format_tables(
+        tables: &[crate::tables::Table],
+        format: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        if tables.is_empty() {
+            return Ok("No data tables found".to_string());
+        }
+
+        match format {
+            "json" => {
+                let value: Vec<serde_json::Value> = tables
+                    .iter()
+                    .map(|table| {
+                        serde_json::json!({
+                            "caption": table.caption,
+                            "columns": table.columns,
+                            "records": table.records(),
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::to_string_pretty(&value)?)
+            }
+            "csv" => Ok(tables
+                .iter()
+                .enumerate()
+                .map(|(i, table)| {
+                    format!(
+                        "Table {}{}:\n{}",
+                        i + 1,
+                        table.caption.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default(),
+                        table.to_csv()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            _ => Err(format!("Unknown format: {}", format).into()),
+        }
+    }
+
     /// Extract structured data from page
     pub async fn extract_data(
         &self,
@@ -160,6 +388,62 @@ impl BrowserExecutor {
         Ok(screenshot)
     }
 
+    /// Take a screenshot, optionally with set-of-marks annotations
+    ///
+    /// With `ScreenshotMode::SetOfMarks` the element refs are refreshed, so a
+    /// vision model can answer with a mark number and pass it as `ref`.
+    pub async fn screenshot_with_mode(
+        &self,
+        mode: ScreenshotMode,
+    ) -> Result<Screenshot, Box<dyn std::error::Error + Send + Sync>> {
+        if mode == ScreenshotMode::Plain {
+            return Ok(Screenshot { image: self.screenshot().await?, marks: None });
+        }
+
+        let map = crate::element_map::snapshot(&self.page).await?;
+        let viewport: Vec<f64> =
+            self.page.evaluate("[window.innerWidth, window.innerHeight]").await?.into_value()?;
+        let marks = match viewport[..] {
+            [width, height] => map.in_viewport(width, height),
+            _ => map.clone(),
+        };
+
+        crate::element_map::draw_marks(&self.page, &marks).await?;
+        let image = self.screenshot().await;
+        // Always take the overlay down again, even if the capture failed
+        crate::element_map::clear_marks(&self.page).await?;
+
+        *self.element_map.lock().map_err(|_| "Element map lock poisoned")? = Some(map);
+        Ok(Screenshot { image: image?, marks: Some(marks) })
+    }
+
+    /// Take a screenshot of the viewport, the full page or one element
+    pub async fn capture_screenshot(
+        &self,
+        options: &ScreenshotOptions,
+    ) -> Result<Capture, Box<dyn std::error::Error + Send + Sync>> {
+        tracing::info!("Capturing {:?} screenshot", options.format);
+
+        let data = crate::capture::capture_screenshot(&self.page, options).await?;
+        Ok(Capture::screenshot(data, options.format))
+    }
+
+    /// Print the current page to PDF
+    pub async fn print_pdf(
+        &self,
+        options: &PdfOptions,
+    ) -> Result<Capture, Box<dyn std::error::Error + Send + Sync>> {
+        tracing::info!("Printing page to PDF");
+
+        let data = crate::capture::print_pdf(&self.page, options).await?;
+        Ok(Capture::pdf(data))
+    }
+
+    /// Take the screenshot or PDF produced by the last `capture_page` tool call
+    pub fn take_last_capture(&self) -> Option<Capture> {
+        self.last_capture.lock().ok()?.take()
+    }
+
     /// Wait for element to appear
     pub async fn wait_for_element(
         &self,
@@ -183,6 +467,64 @@ impl BrowserExecutor {
         Ok(format!("Element '{}' appeared", selector))
     }
 
+    /// Wait until the node with backend id `node_id` is rendered with a
+    /// non-empty box
+    pub async fn wait_for_node(
+        &self,
+        node_id: i64,
+        timeout_ms: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        tracing::debug!("Waiting for node: {}", node_id);
+
+        let timeout = std::time::Duration::from_millis(timeout_ms);
+
+        tokio::time::timeout(timeout, async {
+            loop {
+                let visible = crate::accessibility::call_on_node(
+                    &self.page,
+                    node_id,
+                    "function() { \
+                         if (!this.isConnected) throw new Error('node was removed'); \
+                         const rect = this.getBoundingClientRect(); \
+                         return rect.width > 0 && rect.height > 0 \
+                             && getComputedStyle(this).visibility !== 'hidden'; \
+                     }",
+                    vec![],
+                )
+                .await?;
+                if visible.as_bool().unwrap_or(false) {
+                    return Ok::<_, Box<dyn std::error::Error + Send + Sync>>(());
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+        })
+        .await??;
+
+        Ok(format!("Node {} is visible", node_id))
+    }
+
+    /// Index in document order of the form owning the node `node_id` (the
+    /// form itself, or the form a control belongs to)
+    pub async fn node_form_index(
+        &self,
+        node_id: i64,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let index = crate::accessibility::call_on_node(
+            &self.page,
+            node_id,
+            "function() { \
+                 const form = this.form ?? this.closest?.('form'); \
+                 return form ? Array.from(document.forms).indexOf(form) : -1; \
+             }",
+            vec![],
+        )
+        .await?;
+        index
+            .as_i64()
+            .and_then(|i| usize::try_from(i).ok())
+            .ok_or_else(|| format!("Node {} is not inside a form", node_id).into())
+    }
+
     /// Check if element exists on the page
     pub async fn element_exists(&self, selector: &str) -> bool {
         self.page.find_element(selector).await.is_ok()
@@ -384,6 +726,16 @@ impl BrowserExecutor {
         }
     }
 
+    /// Read an optional `ref` argument and resolve it to a backend node id
+    async fn ref_argument(&self, args: &serde_json::Value) -> LLMResult<Option<i64>> {
+        let Some(element_ref) = args.get("ref").and_then(|v| v.as_u64()) else {
+            return Ok(None);
+        };
+        let element_ref = u32::try_from(element_ref)
+            .map_err(|_| LLMError::InvalidResponse(format!("Invalid ref: {}", element_ref)))?;
+        self.resolve_ref(element_ref).await.map(Some).map_err(|e| LLMError::Api(e.to_string()))
+    }
+
     /// Execute a tool call from LLM
     pub async fn execute_tool(&self, tool_call: &ToolCall) -> LLMResult<String> {
         match tool_call.function.name.as_str() {
@@ -392,9 +744,15 @@ impl BrowserExecutor {
             "fill_form_field" => self.execute_fill_form_field(tool_call).await,
             "extract_text" => self.execute_extract_text(tool_call).await,
             "get_page_content" => self.execute_get_page_content(tool_call).await,
+            "get_page_markdown" => self.execute_get_page_markdown(tool_call).await,
+            "get_accessibility_tree" => self.execute_get_accessibility_tree(tool_call).await,
+            "get_interactive_elements" => self.execute_get_interactive_elements(tool_call).await,
+            "extract_tables" => self.execute_extract_tables(tool_call).await,
+            "discover_site" => self.execute_discover_site(tool_call).await,
             "wait_for_element" => self.execute_wait_for_element(tool_call).await,
             "get_current_url" => self.execute_get_current_url(tool_call).await,
             "get_page_title" => self.execute_get_page_title(tool_call).await,
+            "capture_page" => self.execute_capture_page(tool_call).await,
             "analyze_form" => self.execute_analyze_form(tool_call).await,
             "auto_fill_form" => self.execute_auto_fill_form(tool_call).await,
             "submit_form" => self.execute_submit_form(tool_call).await,
@@ -423,12 +781,22 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid click_element arguments: {}", e))
             })?;
 
-        let selector = args
-            .get("selector")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| LLMError::InvalidResponse("Missing 'selector' parameter".to_string()))?;
+        let node_id = match self.ref_argument(&args).await? {
+            Some(node_id) => Some(node_id),
+            None => args.get("node_id").and_then(|v| v.as_i64()),
+        };
+        let result = if let Some(node_id) = node_id {
+            self.click_node(node_id).await
+        } else {
+            let selector = args.get("selector").and_then(|v| v.as_str()).ok_or_else(|| {
+                LLMError::InvalidResponse(
+                    "Missing 'selector', 'ref' or 'node_id' parameter".to_string(),
+                )
+            })?;
+            self.click(selector).await
+        };
 
-        self.click(selector).await.map_err(|e| LLMError::Api(format!("Click failed: {}", e)))
+        result.map_err(|e| LLMError::Api(format!("Click failed: {}", e)))
     }
 
     async fn execute_fill_form_field(&self, tool_call: &ToolCall) -> LLMResult<String> {
@@ -437,15 +805,28 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid fill_form_field arguments: {}", e))
             })?;
 
-        let field_name = args.get("field_name").and_then(|v| v.as_str()).ok_or_else(|| {
-            LLMError::InvalidResponse("Missing 'field_name' parameter".to_string())
-        })?;
-
         let value = args
             .get("value")
             .and_then(|v| v.as_str())
             .ok_or_else(|| LLMError::InvalidResponse("Missing 'value' parameter".to_string()))?;
 
+        let node_id = match self.ref_argument(&args).await? {
+            Some(node_id) => Some(node_id),
+            None => args.get("node_id").and_then(|v| v.as_i64()),
+        };
+        if let Some(node_id) = node_id {
+            return self
+                .fill_node(node_id, value)
+                .await
+                .map_err(|e| LLMError::Api(format!("Form filling failed: {}", e)));
+        }
+
+        let field_name = args.get("field_name").and_then(|v| v.as_str()).ok_or_else(|| {
+            LLMError::InvalidResponse(
+                "Missing 'field_name', 'ref' or 'node_id' parameter".to_string(),
+            )
+        })?;
+
         let form_data = HashMap::from([(field_name.to_string(), value.to_string())]);
 
         self.fill_form(&form_data)
@@ -459,6 +840,13 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid extract_text arguments: {}", e))
             })?;
 
+        if let Some(node_id) = self.ref_argument(&args).await? {
+            return self
+                .node_text(node_id)
+                .await
+                .map_err(|e| LLMError::Api(format!("Text extraction failed: {}", e)));
+        }
+
         let selector = args
             .get("selector")
             .and_then(|v| v.as_str())
@@ -484,22 +872,160 @@ impl BrowserExecutor {
             .map_err(|e| LLMError::Api(format!("Content extraction failed: {}", e)))
     }
 
-    async fn execute_wait_for_element(&self, tool_call: &ToolCall) -> LLMResult<String> {
+    async fn execute_get_page_markdown(&self, tool_call: &ToolCall) -> LLMResult<String> {
         let args: serde_json::Value =
             serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
-                LLMError::InvalidResponse(format!("Invalid wait_for_element arguments: {}", e))
+                LLMError::InvalidResponse(format!("Invalid get_page_markdown arguments: {}", e))
             })?;
 
-        let selector = args
-            .get("selector")
+        let max_tokens = args
+            .get("max_tokens")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(crate::markdown::DEFAULT_SNAPSHOT_TOKENS);
+        let main_content_only =
+            args.get("main_content_only").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        self.get_markdown(max_tokens, main_content_only)
+            .await
+            .map_err(|e| LLMError::Api(format!("Markdown rendering failed: {}", e)))
+    }
+
+    async fn execute_capture_page(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!("Invalid capture_page arguments: {}", e))
+            })?;
+
+        let invalid = |e: serde_json::Error| {
+            LLMError::InvalidResponse(format!("Invalid capture_page arguments: {}", e))
+        };
+        let (capture, what) = if args.get("pdf").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let options: PdfOptions = serde_json::from_value(args).map_err(invalid)?;
+            (self.print_pdf(&options).await, "PDF")
+        } else {
+            let options: ScreenshotOptions = serde_json::from_value(args).map_err(invalid)?;
+            options.validate().map_err(LLMError::InvalidResponse)?;
+            let what = if options.selector.is_some() {
+                "element screenshot"
+            } else if options.full_page {
+                "full-page screenshot"
+            } else {
+                "screenshot"
+            };
+            (self.capture_screenshot(&options).await, what)
+        };
+        let capture = capture.map_err(|e| LLMError::Api(format!("Capture failed: {}", e)))?;
+
+        let summary =
+            format!("Captured {} ({}, {} bytes)", what, capture.media_type, capture.data.len());
+        *self
+            .last_capture
+            .lock()
+            .map_err(|_| LLMError::Api("Capture lock poisoned".to_string()))? = Some(capture);
+        Ok(summary)
+    }
+
+    async fn execute_get_interactive_elements(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!(
+                    "Invalid get_interactive_elements arguments: {}",
+                    e
+                ))
+            })?;
+
+        let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("text");
+
+        self.get_interactive_elements(format)
+            .await
+            .map_err(|e| LLMError::Api(format!("Listing interactive elements failed: {}", e)))
+    }
+
+    async fn execute_get_accessibility_tree(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!(
+                    "Invalid get_accessibility_tree arguments: {}",
+                    e
+                ))
+            })?;
+
+        let interactive_only =
+            args.get("interactive_only").and_then(|v| v.as_bool()).unwrap_or(false);
+        let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("text");
+
+        self.get_accessibility_tree(interactive_only, format)
+            .await
+            .map_err(|e| LLMError::Api(format!("Accessibility snapshot failed: {}", e)))
+    }
+
+    async fn execute_extract_tables(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!("Invalid extract_tables arguments: {}", e))
+            })?;
+
+        let selector = args.get("selector").and_then(|v| v.as_str());
+        let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("json");
+
+        let result = match self.ref_argument(&args).await? {
+            Some(node_id) => self.extract_tables_in_node(node_id, format).await,
+            None => self.extract_tables(selector, format).await,
+        };
+        result.map_err(|e| LLMError::Api(format!("Table extraction failed: {}", e)))
+    }
+
+    async fn execute_discover_site(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!("Invalid discover_site arguments: {}", e))
+            })?;
+
+        let url = args
+            .get("url")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| LLMError::InvalidResponse("Missing 'selector' parameter".to_string()))?;
+            .ok_or_else(|| LLMError::InvalidResponse("Missing 'url' parameter".to_string()))?;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(LLMError::InvalidResponse(
+                "URL must start with http:// or https://".to_string(),
+            ));
+        }
+        let max_urls = args.get("max_urls").and_then(|v| v.as_u64()).unwrap_or(100) as usize;
+
+        let site = crate::discovery::Site::discover(url)
+            .await
+            .map_err(|e| LLMError::Api(format!("Site discovery failed: {}", e)))?;
+        let urls: Vec<_> = site.urls().take(max_urls).collect();
+
+        Ok(serde_json::json!({
+            "root": site.root,
+            "sitemaps": site.sitemaps,
+            "crawl_delay_secs": site.crawl_delay().map(|d| d.as_secs_f64()),
+            "total_urls": site.urls().count(),
+            "urls": urls,
+        })
+        .to_string())
+    }
+
+    async fn execute_wait_for_element(&self, tool_call: &ToolCall) -> LLMResult<String> {
+        let args: serde_json::Value =
+            serde_json::from_str(&tool_call.function.arguments).map_err(|e| {
+                LLMError::InvalidResponse(format!("Invalid wait_for_element arguments: {}", e))
+            })?;
 
         let timeout_ms = args.get("timeout_ms").and_then(|v| v.as_u64()).unwrap_or(5000);
 
-        self.wait_for_element(selector, timeout_ms)
-            .await
-            .map_err(|e| LLMError::Api(format!("Wait for element failed: {}", e)))
+        let result = if let Some(node_id) = self.ref_argument(&args).await? {
+            self.wait_for_node(node_id, timeout_ms).await
+        } else {
+            let selector = args.get("selector").and_then(|v| v.as_str()).ok_or_else(|| {
+                LLMError::InvalidResponse("Missing 'selector' or 'ref' parameter".to_string())
+            })?;
+            self.wait_for_element(selector, timeout_ms).await
+        };
+
+        result.map_err(|e| LLMError::Api(format!("Wait for element failed: {}", e)))
     }
 
     async fn execute_get_current_url(&self, _tool_call: &ToolCall) -> LLMResult<String> {
@@ -520,7 +1046,14 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid analyze_form arguments: {}", e))
             })?;
 
-        let form_index = args.get("form_index").and_then(|v| v.as_u64()).map(|v| v as usize);
+        let form_index = match self.ref_argument(&args).await? {
+            Some(node_id) => Some(
+                self.node_form_index(node_id)
+                    .await
+                    .map_err(|e| LLMError::Api(format!("Form analysis failed: {}", e)))?,
+            ),
+            None => args.get("form_index").and_then(|v| v.as_u64()).map(|v| v as usize),
+        };
 
         self.analyze_forms(form_index)
             .await
@@ -553,6 +1086,13 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid submit_form arguments: {}", e))
             })?;
 
+        if let Some(node_id) = self.ref_argument(&args).await? {
+            return self
+                .click_node(node_id)
+                .await
+                .map_err(|e| LLMError::Api(format!("Form submission failed: {}", e)));
+        }
+
         let form_selector = args.get("form_selector").and_then(|v| v.as_str());
 
         let form_index = args.get("form_index").and_then(|v| v.as_u64()).map(|v| v as usize);
@@ -568,7 +1108,14 @@ impl BrowserExecutor {
                 LLMError::InvalidResponse(format!("Invalid get_form_fields arguments: {}", e))
             })?;
 
-        let form_index = args.get("form_index").and_then(|v| v.as_u64()).map(|v| v as usize);
+        let form_index = match self.ref_argument(&args).await? {
+            Some(node_id) => Some(
+                self.node_form_index(node_id)
+                    .await
+                    .map_err(|e| LLMError::Api(format!("Getting form fields failed: {}", e)))?,
+            ),
+            None => args.get("form_index").and_then(|v| v.as_u64()).map(|v| v as usize),
+        };
 
         self.get_form_fields(form_index)
             .await
@@ -32,4 +32,4 @@ pub use workflow::{
 };
 
 #[cfg(feature = "browser-automation")]
-pub use browser_executor::BrowserExecutor;
+pub use browser_executor::{BrowserExecutor, Screenshot, ScreenshotMode};
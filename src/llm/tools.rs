@@ -92,7 +92,9 @@ impl ToolRegistry {
             tool_type: "function".to_string(),
             function: FunctionDefinition {
                 name: "fill_form_field".to_string(),
-                description: "Fill a specific form field with a value".to_string(),
+                description: "Fill a specific form field with a value, found by name/hint, \
+                              element ref or accessibility node id"
+                    .to_string(),
                 parameters: ParametersSchema {
                     schema_type: "object".to_string(),
                     properties: {
@@ -105,6 +107,25 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the field in get_interactive_elements output"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "node_id".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Id of the field in get_accessibility_tree output \
+                                              (used instead of field_name)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props.insert(
                             "value".to_string(),
                             ToolParameter {
@@ -115,7 +136,7 @@ impl ToolRegistry {
                         );
                         props
                     },
-                    required: vec!["field_name".to_string(), "value".to_string()],
+                    required: vec!["value".to_string()],
                 },
             },
         });
@@ -125,7 +146,9 @@ impl ToolRegistry {
             tool_type: "function".to_string(),
             function: FunctionDefinition {
                 name: "click_element".to_string(),
-                description: "Click on an element matching the given selector".to_string(),
+                description: "Click on an element given by CSS selector, element ref or \
+                              accessibility node id"
+                    .to_string(),
                 parameters: ParametersSchema {
                     schema_type: "object".to_string(),
                     properties: {
@@ -138,9 +161,29 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description:
+                                    "Ref of the element to click in get_interactive_elements output"
+                                        .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "node_id".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Id of the element in get_accessibility_tree output \
+                                              (used instead of selector)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props
                     },
-                    required: vec!["selector".to_string()],
+                    required: vec![],
                 },
             },
         });
@@ -150,7 +193,9 @@ impl ToolRegistry {
             tool_type: "function".to_string(),
             function: FunctionDefinition {
                 name: "extract_text".to_string(),
-                description: "Extract text content from an element using CSS selector".to_string(),
+                description: "Extract text content from an element using CSS selector or \
+                              element ref"
+                    .to_string(),
                 parameters: ParametersSchema {
                     schema_type: "object".to_string(),
                     properties: {
@@ -164,9 +209,18 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the element to extract text from in get_interactive_elements output"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props
                     },
-                    required: vec!["selector".to_string()],
+                    required: vec![],
                 },
             },
         });
@@ -197,12 +251,208 @@ impl ToolRegistry {
             },
         });
 
+        // get_page_markdown tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "get_page_markdown".to_string(),
+                description: "Get the current page as compact Markdown (headings, lists, tables, \
+                              code); links are numbered references listed at the end"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "max_tokens".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Approximate token budget for the output \
+                                              (default: 2000)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "main_content_only".to_string(),
+                            ToolParameter {
+                                param_type: "boolean".to_string(),
+                                description: "Render only the main content area, skipping \
+                                              navigation and footers (default: false)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props
+                    },
+                    required: vec![],
+                },
+            },
+        });
+
+        // get_interactive_elements tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "get_interactive_elements".to_string(),
+                description: "List visible links, buttons, inputs and other interactive \
+                              elements with numeric refs, labels and bounding boxes; pass a ref \
+                              as 'ref' to other browser tools instead of a selector (refs expire \
+                              when the page navigates)"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "format".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "Output format: one 'text' line per element or \
+                                              'json' (default: text)"
+                                    .to_string(),
+                                enum_values: Some(vec!["text".to_string(), "json".to_string()]),
+                            },
+                        );
+                        props
+                    },
+                    required: vec![],
+                },
+            },
+        });
+
+        // get_accessibility_tree tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "get_accessibility_tree".to_string(),
+                description: "Get the page's accessibility tree: roles, names, values and states \
+                              of elements, each with an id usable as node_id in click_element \
+                              and fill_form_field"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "interactive_only".to_string(),
+                            ToolParameter {
+                                param_type: "boolean".to_string(),
+                                description: "List only buttons, links and form controls \
+                                              (default: false)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "format".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "Output format: indented 'text' outline or 'json' \
+                                              (default: text)"
+                                    .to_string(),
+                                enum_values: Some(vec!["text".to_string(), "json".to_string()]),
+                            },
+                        );
+                        props
+                    },
+                    required: vec![],
+                },
+            },
+        });
+
+        // discover_site tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "discover_site".to_string(),
+                description: "List a site's URLs from its robots.txt and sitemaps, with \
+                              lastmod and priority (only URLs robots.txt allows)"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "url".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "Any URL of the site".to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "max_urls".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Maximum number of URLs to return (default: 100)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props
+                    },
+                    required: vec!["url".to_string()],
+                },
+            },
+        });
+
+        // extract_tables tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "extract_tables".to_string(),
+                description: "Extract HTML tables as typed records (headers resolved, \
+                              rowspan/colspan expanded)"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "selector".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "CSS selector limiting extraction to matching \
+                                              tables or containers (default: whole page)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the table or container to extract from in get_interactive_elements output"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "format".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description:
+                                    "Output format: 'json' records or 'csv' (default: json)"
+                                        .to_string(),
+                                enum_values: Some(vec!["json".to_string(), "csv".to_string()]),
+                            },
+                        );
+                        props
+                    },
+                    required: vec![],
+                },
+            },
+        });
+
         // wait_for_element tool
         registry.register(ToolDefinition {
             tool_type: "function".to_string(),
             function: FunctionDefinition {
                 name: "wait_for_element".to_string(),
-                description: "Wait for an element to appear on the page".to_string(),
+                description: "Wait for an element given by CSS selector to appear, or for \
+                              an element ref to become visible"
+                    .to_string(),
                 parameters: ParametersSchema {
                     schema_type: "object".to_string(),
                     properties: {
@@ -215,6 +465,16 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the element to wait for in \
+                                              get_interactive_elements output"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props.insert(
                             "timeout_ms".to_string(),
                             ToolParameter {
@@ -226,7 +486,7 @@ impl ToolRegistry {
                         );
                         props
                     },
-                    required: vec!["selector".to_string()],
+                    required: vec![],
                 },
             },
         });
@@ -259,6 +519,92 @@ impl ToolRegistry {
             },
         });
 
+        // capture_page tool
+        registry.register(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "capture_page".to_string(),
+                description: "Save a screenshot (viewport, full page or one element) or a PDF \
+                              of the current page for the user"
+                    .to_string(),
+                parameters: ParametersSchema {
+                    schema_type: "object".to_string(),
+                    properties: {
+                        let mut props = HashMap::new();
+                        props.insert(
+                            "full_page".to_string(),
+                            ToolParameter {
+                                param_type: "boolean".to_string(),
+                                description: "Capture the whole scrollable page instead of \
+                                              the viewport (default: false)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "selector".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "CSS selector of a single element to capture"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "format".to_string(),
+                            ToolParameter {
+                                param_type: "string".to_string(),
+                                description: "Image format (default: png)".to_string(),
+                                enum_values: Some(vec![
+                                    "png".to_string(),
+                                    "jpeg".to_string(),
+                                    "webp".to_string(),
+                                ]),
+                            },
+                        );
+                        props.insert(
+                            "quality".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Compression quality 0-100, jpeg and webp only"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "device_scale_factor".to_string(),
+                            ToolParameter {
+                                param_type: "number".to_string(),
+                                description: "Pixels per CSS pixel, e.g. 2 for a high-DPI capture"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "pdf".to_string(),
+                            ToolParameter {
+                                param_type: "boolean".to_string(),
+                                description: "Print the page to PDF instead of taking a \
+                                              screenshot (default: false)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props.insert(
+                            "landscape".to_string(),
+                            ToolParameter {
+                                param_type: "boolean".to_string(),
+                                description: "PDF page orientation (default: false)".to_string(),
+                                enum_values: None,
+                            },
+                        );
+                        props
+                    },
+                    required: vec![],
+                },
+            },
+        });
+
         // analyze_form tool
         registry.register(ToolDefinition {
             tool_type: "function".to_string(),
@@ -277,6 +623,17 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the form, or of a field inside it, in \
+                                              get_interactive_elements output (used instead of \
+                                              form_index)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props
                     },
                     required: vec![],
@@ -335,6 +692,15 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the submit button in get_interactive_elements output"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props.insert(
                             "form_index".to_string(),
                             ToolParameter {
@@ -368,6 +734,17 @@ impl ToolRegistry {
                                 enum_values: None,
                             },
                         );
+                        props.insert(
+                            "ref".to_string(),
+                            ToolParameter {
+                                param_type: "integer".to_string(),
+                                description: "Ref of the form, or of a field inside it, in \
+                                              get_interactive_elements output (used instead of \
+                                              form_index)"
+                                    .to_string(),
+                                enum_values: None,
+                            },
+                        );
                         props
                     },
                     required: vec![],
@@ -555,6 +932,39 @@ mod tests {
         assert!(registry.get_tool("navigate_to").is_some());
         assert!(registry.get_tool("fill_form_field").is_some());
         assert!(registry.get_tool("click_element").is_some());
+        assert!(registry.get_tool("get_page_markdown").is_some());
+        assert!(registry.get_tool("extract_tables").is_some());
+        assert!(registry.get_tool("discover_site").is_some());
+        assert!(registry.get_tool("get_accessibility_tree").is_some());
+        assert!(registry.get_tool("capture_page").is_some());
+
+        // Elements can be targeted by accessibility node id instead of selector
+        let click = registry.get_tool("click_element").unwrap();
+        assert!(click.function.parameters.properties.contains_key("node_id"));
+        assert!(click.function.parameters.required.is_empty());
+        assert!(registry
+            .get_tool("wait_for_element")
+            .unwrap()
+            .function
+            .parameters
+            .required
+            .is_empty());
+
+        // ... or by element ref from get_interactive_elements
+        assert!(registry.get_tool("get_interactive_elements").is_some());
+        for name in [
+            "click_element",
+            "fill_form_field",
+            "extract_text",
+            "extract_tables",
+            "submit_form",
+            "wait_for_element",
+            "analyze_form",
+            "get_form_fields",
+        ] {
+            let tool = registry.get_tool(name).unwrap();
+            assert!(tool.function.parameters.properties.contains_key("ref"), "{}", name);
+        }
     }
 
     #[test]
@@ -0,0 +1,599 @@
+//! HTML to Markdown rendering for LLM consumption
+//!
+//! Renders headings, paragraphs, lists, tables, block quotes and code blocks to
+//! compact Markdown. Links become numbered references (`[text][3]`) listed at
+//! the end of the output, so the model can refer to them by index. Output is
+//! cut at block boundaries to stay within a token budget.
+
+use crate::external::normalize_whitespace;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Token budget used for `SemanticSnapshot.markdown`
+pub const DEFAULT_SNAPSHOT_TOKENS: usize = 2000;
+
+/// Marker appended when blocks were dropped to respect the token budget
+const TRUNCATION_MARKER: &str = "[... truncated]";
+
+/// Subtrees that never produce Markdown
+const SKIPPED_TAGS: &[&str] = &[
+    "button", "canvas", "head", "iframe", "noscript", "object", "script", "select", "style", "svg",
+    "template", "textarea",
+];
+
+/// Options for [`html_to_markdown`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarkdownOptions {
+    /// Approximate maximum size of the output in tokens (`None` for no limit)
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Render only the main landmark (`<main>`, `<article>`, `[role=main]`) when present
+    #[serde(default)]
+    pub main_content_only: bool,
+    /// URL of the document, used to resolve relative links
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// A link referenced from the Markdown output as `[text][index]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarkdownLink {
+    pub index: usize,
+    pub text: String,
+    pub url: String,
+}
+
+/// Markdown rendering of a page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarkdownPage {
+    /// Markdown text, including the link reference list
+    pub markdown: String,
+    /// Links referenced in `markdown`, by index
+    pub links: Vec<MarkdownLink>,
+    /// Estimated size of `markdown` in tokens
+    pub estimated_tokens: usize,
+    /// True if content was dropped to fit `max_tokens`
+    pub truncated: bool,
+}
+
+/// Rough token estimate (about four characters per token for English text)
+pub fn estimate_tokens(text: &str) -> usize {
+    (text.chars().count() + 3) / 4
+}
+
+/// Render a parsed HTML document to Markdown
+pub fn html_to_markdown(document: &Html, options: &MarkdownOptions) -> MarkdownPage {
+    let root = options
+        .main_content_only
+        .then(|| {
+            let selector = Selector::parse("main, article, [role='main']").ok()?;
+            document.select(&selector).next()
+        })
+        .flatten()
+        .or_else(|| Selector::parse("body").ok().and_then(|s| document.select(&s).next()))
+        .unwrap_or_else(|| document.root_element());
+
+    let mut renderer = Renderer {
+        base: crate::parser::document_base(document, options.base_url.as_deref()),
+        links: Vec::new(),
+        link_index: HashMap::new(),
+        block_links: Vec::new(),
+        blocks: Vec::new(),
+    };
+    renderer.render_blocks(root, &mut Context::default());
+
+    assemble(renderer, options.max_tokens)
+}
+
+/// Markdown used for `SemanticSnapshot.markdown`: the main content within
+/// [`DEFAULT_SNAPSHOT_TOKENS`]
+pub fn snapshot_markdown(document: &Html, base_url: &str) -> String {
+    let options = MarkdownOptions {
+        max_tokens: Some(DEFAULT_SNAPSHOT_TOKENS),
+        main_content_only: true,
+        base_url: Some(base_url.to_string()),
+    };
+    html_to_markdown(document, &options).markdown
+}
+
+/// Join rendered blocks and their link references, dropping blocks past the budget
+fn assemble(renderer: Renderer, max_tokens: Option<usize>) -> MarkdownPage {
+    let budget = max_tokens.unwrap_or(usize::MAX);
+    let marker_tokens = estimate_tokens(TRUNCATION_MARKER) + 1;
+
+    let mut body = String::new();
+    let mut kept_links: Vec<&MarkdownLink> = Vec::new();
+    let mut tokens = 0;
+    let mut truncated = false;
+
+    for (block, links) in &renderer.blocks {
+        let new_links: Vec<&MarkdownLink> = links
+            .iter()
+            .map(|i| &renderer.links[*i - 1])
+            .filter(|link| !kept_links.iter().any(|kept| kept.index == link.index))
+            .collect();
+        let cost = estimate_tokens(block)
+            + 1
+            + new_links
+                .iter()
+                .map(|link| estimate_tokens(&reference_line(link)) + 1)
+                .sum::<usize>();
+        if tokens + cost + marker_tokens > budget {
+            truncated = true;
+            break;
+        }
+        tokens += cost;
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(block);
+        kept_links.extend(new_links);
+    }
+
+    if truncated {
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(TRUNCATION_MARKER);
+    }
+    if !kept_links.is_empty() {
+        kept_links.sort_by_key(|link| link.index);
+        body.push_str("\n\n");
+        body.push_str(
+            &kept_links.iter().map(|link| reference_line(link)).collect::<Vec<_>>().join("\n"),
+        );
+    }
+
+    MarkdownPage {
+        estimated_tokens: estimate_tokens(&body),
+        markdown: body,
+        links: kept_links.into_iter().cloned().collect(),
+        truncated,
+    }
+}
+
+fn reference_line(link: &MarkdownLink) -> String {
+    format!("[{}]: {}", link.index, link.url)
+}
+
+/// Nesting state while rendering blocks
+#[derive(Default, Clone)]
+struct Context {
+    /// Prefix of every line (list indentation, `> ` for quotes)
+    prefix: String,
+}
+
+struct Renderer {
+    base: Option<url::Url>,
+    links: Vec<MarkdownLink>,
+    link_index: HashMap<String, usize>,
+    /// Links referenced by the block being rendered
+    block_links: Vec<usize>,
+    /// Rendered top-level blocks with the links they reference
+    blocks: Vec<(String, Vec<usize>)>,
+}
+
+impl Renderer {
+    fn push_block(&mut self, text: &str, context: &Context) {
+        let text = text.trim();
+        if text.is_empty() {
+            self.block_links.clear();
+            return;
+        }
+        let block = text
+            .lines()
+            .map(|line| format!("{}{}", context.prefix, line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.blocks.push((block, std::mem::take(&mut self.block_links)));
+    }
+
+    /// Render the children of `element` as a sequence of blocks
+    fn render_blocks(&mut self, element: ElementRef<'_>, context: &mut Context) {
+        let mut inline = String::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => inline.push_str(text),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    if is_hidden(child) {
+                        continue;
+                    }
+                    if is_block(child) {
+                        let pending = normalize_whitespace(&inline);
+                        inline.clear();
+                        self.push_block(&pending, context);
+                        self.render_block(child, context);
+                    } else {
+                        inline.push_str(&self.inline(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let pending = normalize_whitespace(&inline);
+        self.push_block(&pending, context);
+    }
+
+    fn render_block(&mut self, element: ElementRef<'_>, context: &mut Context) {
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let text = normalize_whitespace(&self.inline_children(element));
+                if !text.is_empty() {
+                    self.push_block(&format!("{} {}", "#".repeat(level), text), context);
+                }
+            }
+            "p" => {
+                let text = normalize_whitespace(&self.inline_children(element));
+                self.push_block(&text, context);
+            }
+            "pre" => {
+                let code = element.text().collect::<String>();
+                let language = code_language(element).unwrap_or_default();
+                let fence = if code.contains("```") { "~~~" } else { "```" };
+                self.push_block(
+                    &format!("{}{}\n{}\n{}", fence, language, code.trim_end_matches('\n'), fence),
+                    context,
+                );
+            }
+            "ul" | "ol" => {
+                let list = self.list(element, name == "ol", 0);
+                self.push_block(&list, context);
+            }
+            "table" => {
+                let table = self.table(element);
+                self.push_block(&table, context);
+            }
+            "blockquote" => {
+                let mut quoted = Context { prefix: format!("{}> ", context.prefix) };
+                self.render_blocks(element, &mut quoted);
+            }
+            "hr" => self.push_block("---", context),
+            _ => self.render_blocks(element, context),
+        }
+    }
+
+    fn list(&mut self, list: ElementRef<'_>, ordered: bool, depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+        let mut lines = Vec::new();
+        let mut number =
+            list.value().attr("start").and_then(|s| s.parse::<i64>().ok()).unwrap_or(1);
+        for item in list.children().filter_map(ElementRef::wrap) {
+            if item.value().name() != "li" || is_hidden(item) {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{}.", number)
+            } else {
+                "-".to_string()
+            };
+            number += 1;
+
+            let mut text = String::new();
+            let mut nested = Vec::new();
+            for child in item.children() {
+                match child.value() {
+                    Node::Text(t) => text.push_str(t),
+                    Node::Element(_) => {
+                        let Some(child) = ElementRef::wrap(child) else {
+                            continue;
+                        };
+                        if is_hidden(child) {
+                            continue;
+                        }
+                        match child.value().name() {
+                            "ul" | "ol" => nested.push(self.list(
+                                child,
+                                child.value().name() == "ol",
+                                depth + 1,
+                            )),
+                            name if is_block(child) && name != "p" && name != "div" => {
+                                text.push(' ');
+                                text.push_str(&self.inline_children(child));
+                            }
+                            _ => {
+                                text.push(' ');
+                                text.push_str(&self.inline(child));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            lines.push(
+                format!("{}{} {}", indent, marker, normalize_whitespace(&text))
+                    .trim_end()
+                    .to_string(),
+            );
+            lines.extend(nested.into_iter().filter(|n| !n.is_empty()));
+        }
+        lines.join("\n")
+    }
+
+    fn table(&mut self, table: ElementRef<'_>) -> String {
+        let Ok(row_selector) = Selector::parse("tr") else {
+            return String::new();
+        };
+        let rows: Vec<Vec<String>> = table
+            .select(&row_selector)
+            // Skip rows of nested tables
+            .filter(|row| {
+                row.ancestors().filter_map(ElementRef::wrap).find(|a| a.value().name() == "table")
+                    == Some(table)
+            })
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .map(|cell| {
+                        normalize_whitespace(&self.inline_children(cell)).replace('|', "\\|")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells: &Vec<String>| !cells.is_empty())
+            .collect();
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return String::new();
+        }
+        let line = |cells: &[String]| {
+            let mut padded: Vec<&str> = cells.iter().map(String::as_str).collect();
+            padded.resize(columns, "");
+            format!("| {} |", padded.join(" | "))
+        };
+        let mut lines = vec![line(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+        lines.extend(rows[1..].iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+
+    fn inline_children(&mut self, element: ElementRef<'_>) -> String {
+        let mut text = String::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(t) => text.push_str(t),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        if !is_hidden(child) {
+                            text.push_str(&self.inline(child));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        text
+    }
+
+    /// Render phrasing content (and flatten any block content found inside it)
+    fn inline(&mut self, element: ElementRef<'_>) -> String {
+        let attrs = element.value();
+        match attrs.name() {
+            "br" => " ".to_string(),
+            "img" => match attrs.attr("alt").map(str::trim) {
+                Some(alt) if !alt.is_empty() => format!("![{}]", alt),
+                _ => String::new(),
+            },
+            "code" | "kbd" | "samp" => {
+                let code = normalize_whitespace(&element.text().collect::<String>());
+                if code.is_empty() {
+                    String::new()
+                } else if code.contains('`') {
+                    format!("`` {} ``", code)
+                } else {
+                    format!("`{}`", code)
+                }
+            }
+            "strong" | "b" => wrap_emphasis(&self.inline_children(element), "**"),
+            "em" | "i" => wrap_emphasis(&self.inline_children(element), "*"),
+            "a" => {
+                let text = self.inline_children(element);
+                match attrs.attr("href").and_then(|href| self.link_target(href)) {
+                    Some(url) if !normalize_whitespace(&text).is_empty() => {
+                        let index = self.link(&url, &normalize_whitespace(&text));
+                        format!("[{}][{}]", normalize_whitespace(&text), index)
+                    }
+                    _ => text,
+                }
+            }
+            _ => {
+                let text = self.inline_children(element);
+                if is_block(element) {
+                    format!(" {} ", text)
+                } else {
+                    text
+                }
+            }
+        }
+    }
+
+    /// Absolute URL for a link, or `None` for fragment and script links
+    fn link_target(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        Some(match &self.base {
+            Some(base) => {
+                base.join(href).map(|u| u.to_string()).unwrap_or_else(|_| href.to_string())
+            }
+            None => href.to_string(),
+        })
+    }
+
+    fn link(&mut self, url: &str, text: &str) -> usize {
+        let index = match self.link_index.get(url) {
+            Some(index) => *index,
+            None => {
+                let index = self.links.len() + 1;
+                self.links.push(MarkdownLink {
+                    index,
+                    text: text.to_string(),
+                    url: url.to_string(),
+                });
+                self.link_index.insert(url.to_string(), index);
+                index
+            }
+        };
+        if !self.block_links.contains(&index) {
+            self.block_links.push(index);
+        }
+        index
+    }
+}
+
+fn wrap_emphasis(text: &str, marker: &str) -> String {
+    let trimmed = normalize_whitespace(text);
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    // Keep the surrounding spaces outside the markers
+    let lead = if text.starts_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    let trail = if text.ends_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    format!("{}{}{}{}{}", lead, marker, trimmed, marker, trail)
+}
+
+fn code_language(pre: ElementRef<'_>) -> Option<String> {
+    let code = Selector::parse("code").ok().and_then(|s| pre.select(&s).next());
+    [Some(pre), code].into_iter().flatten().find_map(|el| {
+        el.value().classes().find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+                .map(str::to_string)
+        })
+    })
+}
+
+fn is_block(element: ElementRef<'_>) -> bool {
+    matches!(
+        element.value().name(),
+        "address"
+            | "article"
+            | "aside"
+            | "blockquote"
+            | "body"
+            | "dd"
+            | "details"
+            | "div"
+            | "dl"
+            | "dt"
+            | "fieldset"
+            | "figcaption"
+            | "figure"
+            | "footer"
+            | "form"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "header"
+            | "hr"
+            | "li"
+            | "main"
+            | "nav"
+            | "ol"
+            | "p"
+            | "pre"
+            | "section"
+            | "summary"
+            | "table"
+            | "ul"
+    )
+}
+
+fn is_hidden(element: ElementRef<'_>) -> bool {
+    let attrs = element.value();
+    SKIPPED_TAGS.contains(&attrs.name())
+        || attrs.attr("hidden").is_some()
+        || attrs.attr("aria-hidden") == Some("true")
+        || attrs
+            .attr("style")
+            .is_some_and(|s| s.replace(' ', "").to_lowercase().contains("display:none"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(html: &str, max_tokens: Option<usize>) -> MarkdownPage {
+        let options = MarkdownOptions {
+            max_tokens,
+            base_url: Some("https://docs.example/guide/".to_string()),
+            ..Default::default()
+        };
+        html_to_markdown(&Html::parse_document(html), &options)
+    }
+
+    #[test]
+    fn test_structure_and_links() {
+        let page = render(
+            r#"<html><head><title>T</title><script>var x = 1;</script></head><body>
+            <h1>Getting <em>started</em></h1>
+            <p>Read the <a href="install">install guide</a> and the
+               <a href="https://docs.example/faq">FAQ</a>, then run <code>cargo build</code>.</p>
+            <ul><li>One</li><li>Two<ol><li>Nested</li></ol></li></ul>
+            <table><tr><th>Name</th><th>Value</th></tr><tr><td>a|b</td><td>1</td></tr></table>
+            <pre><code class="language-rust">fn main() {}
+</code></pre>
+            <blockquote><p>Quoted <a href="install">again</a></p></blockquote>
+            </body></html>"#,
+            None,
+        );
+
+        let expected = "# Getting *started*\n\n\
+            Read the [install guide][1] and the [FAQ][2], then run `cargo build`.\n\n\
+            - One\n- Two\n  1. Nested\n\n\
+            | Name | Value |\n| --- | --- |\n| a\\|b | 1 |\n\n\
+            ```rust\nfn main() {}\n```\n\n\
+            > Quoted [again][1]\n\n\
+            [1]: https://docs.example/guide/install\n\
+            [2]: https://docs.example/faq";
+        assert_eq!(page.markdown, expected);
+        assert_eq!(page.links.len(), 2);
+        assert_eq!(page.links[0].text, "install guide");
+        assert!(!page.truncated);
+    }
+
+    #[test]
+    fn test_token_budget_truncates_at_block_boundaries() {
+        let paragraphs: String = (0..50)
+            .map(|i| {
+                format!(
+                    "<p>Paragraph {} with <a href=\"/p{}\">a link</a> and some filler text.</p>",
+                    i, i
+                )
+            })
+            .collect();
+        let html = format!("<html><body>{}</body></html>", paragraphs);
+
+        let page = render(&html, Some(100));
+        assert!(page.truncated);
+        assert!(page.estimated_tokens <= 100, "{} tokens", page.estimated_tokens);
+        assert!(page.markdown.contains("Paragraph 0 "));
+        assert!(page.markdown.contains(TRUNCATION_MARKER));
+        // Only links of kept paragraphs are listed
+        let kept = page.markdown.matches("Paragraph ").count();
+        assert_eq!(page.links.len(), kept);
+        assert!(page.markdown.ends_with(&format!(
+            "[{}]: https://docs.example/p{}",
+            kept,
+            kept - 1
+        )));
+    }
+}
@@ -0,0 +1,673 @@
+//! Microformats2 extraction
+//!
+//! Parses `h-*` roots and `p-`/`u-`/`dt-`/`e-` properties into the canonical
+//! mf2 JSON structure (`items`, `rels`, `rel-urls`) described at
+//! <https://microformats.org/wiki/microformats2-parsing>, including implied
+//! `name`, `photo` and `url` properties and the basic value-class pattern.
+//! Backcompat (microformats1) class names are not recognised.
+
+use crate::parser::resolve_against;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Parsed microformats2 document, serialising to canonical mf2 JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mf2Document {
+    /// Top-level microformats in document order
+    pub items: Vec<Mf2Item>,
+    /// Link relations: rel value -> URLs
+    pub rels: BTreeMap<String, Vec<String>>,
+    /// URL -> details of the links pointing to it
+    #[serde(rename = "rel-urls")]
+    pub rel_urls: BTreeMap<String, Mf2RelUrl>,
+}
+
+/// A single microformat (`h-card`, `h-entry`, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mf2Item {
+    /// Root class names, e.g. `["h-card"]`
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    /// Property name (without prefix) -> values
+    pub properties: BTreeMap<String, Vec<Mf2Value>>,
+    /// `id` attribute of the root element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Plain value when the item is itself a property of its parent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Nested microformats that are not properties
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Mf2Item>,
+}
+
+impl Mf2Item {
+    /// Returns true if the item has the given root class (e.g. `"h-entry"`)
+    pub fn has_type(&self, item_type: &str) -> bool {
+        self.types.iter().any(|t| t == item_type)
+    }
+
+    /// First plain-text value of a property, if any
+    pub fn first_text(&self, property: &str) -> Option<&str> {
+        self.properties.get(property)?.iter().find_map(Mf2Value::as_str)
+    }
+}
+
+/// Property value in canonical mf2 JSON form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Mf2Value {
+    /// `p-*`, `u-*` and `dt-*` values
+    Text(String),
+    /// Nested microformat used as a property value
+    Item(Box<Mf2Item>),
+    /// `e-*` values
+    Html { html: String, value: String },
+    /// `u-photo` (and other image URLs) with alternative text
+    Image { value: String, alt: String },
+}
+
+impl Mf2Value {
+    /// Plain string form (`value` for embedded markup and images), `None` for items
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Mf2Value::Text(s) => Some(s),
+            Mf2Value::Html { value, .. } | Mf2Value::Image { value, .. } => Some(value),
+            Mf2Value::Item(_) => None,
+        }
+    }
+}
+
+/// Entry of the `rel-urls` map
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mf2RelUrl {
+    pub rels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hreflang: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+}
+
+/// Extract microformats2 items and rels from a parsed HTML document
+///
+/// `base_url` resolves relative `u-*` values and rel URLs; a `<base href>` in
+/// the document takes precedence.
+pub fn extract_microformats(document: &Html, base_url: Option<&str>) -> Mf2Document {
+    let parser = Parser { base: crate::parser::document_base(document, base_url) };
+    let mut result = Mf2Document::default();
+
+    parser.find_items(document.root_element(), &mut result.items);
+    parser.rels(document, &mut result);
+
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Prefix {
+    P,
+    U,
+    Dt,
+    E,
+}
+
+/// Explicit properties found while walking an item, for the implied-property rules
+#[derive(Default)]
+struct Seen {
+    nested: bool,
+    text: bool,
+    url: bool,
+}
+
+impl Seen {
+    fn record(&mut self, prefix: Prefix) {
+        match prefix {
+            Prefix::P | Prefix::E => self.text = true,
+            Prefix::U => self.url = true,
+            Prefix::Dt => {}
+        }
+    }
+}
+
+struct Parser {
+    base: Option<url::Url>,
+}
+
+impl Parser {
+    fn find_items(&self, element: ElementRef<'_>, items: &mut Vec<Mf2Item>) {
+        for child in element.children().filter_map(ElementRef::wrap) {
+            if root_classes(child).is_empty() {
+                self.find_items(child, items);
+            } else {
+                items.push(self.item(child));
+            }
+        }
+    }
+
+    fn item(&self, element: ElementRef<'_>) -> Mf2Item {
+        let mut item = Mf2Item {
+            types: root_classes(element),
+            id: element.value().id().map(str::to_string),
+            ..Default::default()
+        };
+        let mut seen = Seen::default();
+        self.walk(element, &mut item, &mut seen);
+        self.implied_properties(element, &mut item, &seen);
+        item
+    }
+
+    /// Collect properties from descendants, stopping at nested microformats
+    fn walk(&self, element: ElementRef<'_>, item: &mut Mf2Item, seen: &mut Seen) {
+        for child in element.children().filter_map(ElementRef::wrap) {
+            let properties = property_classes(child);
+            if !root_classes(child).is_empty() {
+                let mut nested = self.item(child);
+                if properties.is_empty() {
+                    item.children.push(nested);
+                    continue;
+                }
+                seen.nested = true;
+                for (prefix, name) in properties {
+                    nested.value = Some(match prefix {
+                        Prefix::P => nested
+                            .first_text("name")
+                            .map(str::to_string)
+                            .unwrap_or_else(|| self.text_value(child)),
+                        Prefix::U => nested
+                            .first_text("url")
+                            .map(str::to_string)
+                            .unwrap_or_else(|| self.url_value(child).0),
+                        Prefix::Dt => self.datetime_value(child),
+                        Prefix::E => text_content(child),
+                    });
+                    item.properties
+                        .entry(name)
+                        .or_default()
+                        .push(Mf2Value::Item(Box::new(nested.clone())));
+                }
+                continue;
+            }
+
+            for (prefix, name) in properties {
+                seen.record(prefix);
+                let value = match prefix {
+                    Prefix::P => Mf2Value::Text(self.text_value(child)),
+                    Prefix::U => match self.url_value(child) {
+                        (url, Some(alt)) => Mf2Value::Image { value: url, alt },
+                        (url, None) => Mf2Value::Text(url),
+                    },
+                    Prefix::Dt => Mf2Value::Text(self.datetime_value(child)),
+                    Prefix::E => Mf2Value::Html {
+                        html: child.inner_html().trim().to_string(),
+                        value: text_content(child),
+                    },
+                };
+                item.properties.entry(name).or_default().push(value);
+            }
+            self.walk(child, item, seen);
+        }
+    }
+
+    fn implied_properties(&self, element: ElementRef<'_>, item: &mut Mf2Item, seen: &Seen) {
+        if !item.properties.contains_key("name") && !seen.nested && !seen.text {
+            let name = implied_from(element, |el| match el.value().name() {
+                "img" | "area" => el.value().attr("alt").map(str::to_string),
+                "abbr" => el.value().attr("title").map(str::to_string),
+                _ => None,
+            })
+            .unwrap_or_else(|| text_content(element));
+            item.properties.insert("name".to_string(), vec![Mf2Value::Text(name)]);
+        }
+
+        if !item.properties.contains_key("photo") && !seen.nested && !seen.url {
+            let photo = implied_from(element, |el| match el.value().name() {
+                "img" => el.value().attr("src").map(|src| {
+                    let url = resolve_against(self.base.as_ref(), src);
+                    match el.value().attr("alt") {
+                        Some(alt) => Mf2Value::Image { value: url, alt: alt.to_string() },
+                        None => Mf2Value::Text(url),
+                    }
+                }),
+                "object" => el
+                    .value()
+                    .attr("data")
+                    .map(|d| Mf2Value::Text(resolve_against(self.base.as_ref(), d))),
+                _ => None,
+            });
+            if let Some(photo) = photo {
+                item.properties.insert("photo".to_string(), vec![photo]);
+            }
+        }
+
+        if !item.properties.contains_key("url") && !seen.nested && !seen.url {
+            let url = implied_from(element, |el| match el.value().name() {
+                "a" | "area" => {
+                    el.value().attr("href").map(|href| resolve_against(self.base.as_ref(), href))
+                }
+                _ => None,
+            });
+            if let Some(url) = url {
+                item.properties.insert("url".to_string(), vec![Mf2Value::Text(url)]);
+            }
+        }
+    }
+
+    /// `p-*` parsing
+    fn text_value(&self, element: ElementRef<'_>) -> String {
+        if let Some(value) = value_class(element) {
+            return value;
+        }
+        let attrs = element.value();
+        let attr = match attrs.name() {
+            "abbr" | "link" => attrs.attr("title"),
+            "data" | "input" => attrs.attr("value"),
+            "img" | "area" => attrs.attr("alt"),
+            _ => None,
+        };
+        attr.map(str::to_string).unwrap_or_else(|| text_content(element))
+    }
+
+    /// `u-*` parsing, returning the URL and the `alt` text for images
+    fn url_value(&self, element: ElementRef<'_>) -> (String, Option<String>) {
+        let attrs = element.value();
+        let url_attr = match attrs.name() {
+            "a" | "area" | "link" => attrs.attr("href"),
+            "img" | "audio" | "video" | "source" | "iframe" => attrs.attr("src"),
+            "object" => attrs.attr("data"),
+            _ => None,
+        };
+        if let Some(url) =
+            url_attr.or_else(|| attrs.attr("poster").filter(|_| attrs.name() == "video"))
+        {
+            let alt = if attrs.name() == "img" {
+                attrs.attr("alt").map(str::to_string)
+            } else {
+                None
+            };
+            return (resolve_against(self.base.as_ref(), url), alt);
+        }
+        if let Some(value) = value_class(element) {
+            return (value, None);
+        }
+        let fallback = match attrs.name() {
+            "abbr" => attrs.attr("title"),
+            "data" | "input" => attrs.attr("value"),
+            _ => None,
+        };
+        (fallback.map(str::to_string).unwrap_or_else(|| text_content(element)), None)
+    }
+
+    /// `dt-*` parsing
+    fn datetime_value(&self, element: ElementRef<'_>) -> String {
+        if let Some(value) = value_class_datetime(element) {
+            return value;
+        }
+        let attrs = element.value();
+        let attr = match attrs.name() {
+            "time" | "ins" | "del" => attrs.attr("datetime"),
+            "abbr" => attrs.attr("title"),
+            "data" | "input" => attrs.attr("value"),
+            _ => None,
+        };
+        attr.map(str::to_string).unwrap_or_else(|| text_content(element))
+    }
+
+    fn rels(&self, document: &Html, result: &mut Mf2Document) {
+        let Ok(selector) =
+            scraper::Selector::parse("a[rel][href], area[rel][href], link[rel][href]")
+        else {
+            return;
+        };
+        for element in document.select(&selector) {
+            let attrs = element.value();
+            let url = resolve_against(self.base.as_ref(), attrs.attr("href").unwrap_or(""));
+            let entry = result.rel_urls.entry(url.clone()).or_default();
+            for rel in attrs.attr("rel").unwrap_or("").split_whitespace() {
+                let rel = rel.to_lowercase();
+                let urls = result.rels.entry(rel.clone()).or_default();
+                if !urls.contains(&url) {
+                    urls.push(url.clone());
+                }
+                if !entry.rels.contains(&rel) {
+                    entry.rels.push(rel);
+                }
+            }
+            let text = text_content(element);
+            if entry.text.is_none() && !text.is_empty() {
+                entry.text = Some(text);
+            }
+            entry.hreflang = entry.hreflang.take().or(attrs.attr("hreflang").map(str::to_string));
+            entry.media = entry.media.take().or(attrs.attr("media").map(str::to_string));
+            entry.title = entry.title.take().or(attrs.attr("title").map(str::to_string));
+            entry.media_type = entry.media_type.take().or(attrs.attr("type").map(str::to_string));
+        }
+    }
+}
+
+/// Implied property lookup: the element itself, its only child, or its only grandchild
+fn implied_from<T>(
+    element: ElementRef<'_>,
+    extract: impl Fn(ElementRef<'_>) -> Option<T>,
+) -> Option<T> {
+    if let Some(value) = extract(element) {
+        return Some(value);
+    }
+    let child = only_child(element)?;
+    if let Some(value) = extract(child) {
+        return Some(value);
+    }
+    extract(only_child(child)?)
+}
+
+/// The single element child, unless it is itself a microformat
+fn only_child(element: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    let mut children = element.children().filter_map(ElementRef::wrap);
+    match (children.next(), children.next()) {
+        (Some(child), None) if root_classes(child).is_empty() => Some(child),
+        _ => None,
+    }
+}
+
+/// Value-class pattern: concatenated `.value` descendants (`value-title` uses `title`)
+fn value_class(element: ElementRef<'_>) -> Option<String> {
+    let values = value_class_parts(element, false);
+    (!values.is_empty()).then(|| values.concat())
+}
+
+/// Value-class pattern for `dt-*`: the first date, time and timezone among the
+/// `.value` parts joined as `date`, `T`, `time`, `timezone`
+fn value_class_datetime(element: ElementRef<'_>) -> Option<String> {
+    let values = value_class_parts(element, true);
+    if values.is_empty() {
+        return None;
+    }
+    let (mut date, mut time, mut zone) = (None, None, None);
+    for value in &values {
+        let value = value.trim();
+        if date.is_none() && is_vcp_date(value) {
+            date = Some(value.to_string());
+        } else if zone.is_none() && is_vcp_timezone(value) {
+            zone = Some(value.to_string());
+        } else if time.is_none() {
+            if let Some((t, z)) = parse_vcp_time(value) {
+                time = Some(t);
+                zone = zone.or(z);
+            }
+        }
+    }
+    Some(match (date, time) {
+        (Some(date), Some(time)) => format!("{}T{}{}", date, time, zone.unwrap_or_default()),
+        (Some(date), None) => date,
+        (None, Some(time)) => format!("{}{}", time, zone.unwrap_or_default()),
+        (None, None) => values.concat(),
+    })
+}
+
+/// Values of the `.value` / `.value-title` descendants; `datetime` also reads
+/// the `datetime` attribute of `time`, `ins` and `del`
+fn value_class_parts(element: ElementRef<'_>, datetime: bool) -> Vec<String> {
+    let mut values = Vec::new();
+    for node in element.descendants().skip(1).filter_map(ElementRef::wrap) {
+        let classes: Vec<&str> = node.value().classes().collect();
+        if classes.contains(&"value-title") {
+            values.push(node.value().attr("title").unwrap_or("").to_string());
+        } else if classes.contains(&"value") {
+            let attrs = node.value();
+            let value = match attrs.name() {
+                "img" | "area" => attrs.attr("alt").map(str::to_string),
+                "data" => attrs.attr("value").map(str::to_string),
+                "abbr" => attrs.attr("title").map(str::to_string),
+                "time" | "ins" | "del" if datetime => attrs.attr("datetime").map(str::to_string),
+                _ => None,
+            };
+            values.push(value.unwrap_or_else(|| text_content(node)));
+        }
+    }
+    values
+}
+
+/// `YYYY-MM-DD` or ordinal `YYYY-DDD`
+fn is_vcp_date(value: &str) -> bool {
+    let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+    match value.split('-').collect::<Vec<_>>()[..] {
+        [year, month, day] => digits(year, 4) && digits(month, 2) && digits(day, 2),
+        [year, ordinal] => digits(year, 4) && digits(ordinal, 3),
+        _ => false,
+    }
+}
+
+/// `Z`, `±HH`, `±HHMM` or `±HH:MM`
+fn is_vcp_timezone(value: &str) -> bool {
+    if value == "Z" {
+        return true;
+    }
+    let Some(offset) = value.strip_prefix(['+', '-']) else {
+        return false;
+    };
+    let digits: String = offset.chars().filter(|c| *c != ':').collect();
+    matches!(digits.len(), 2 | 4)
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && (offset.len() == digits.len() || offset.find(':') == Some(2))
+}
+
+/// `HH:MM[:SS]` with an optional timezone, or a 12-hour time such as `6pm` or
+/// `6:30 p.m.`, normalized to 24 hours
+fn parse_vcp_time(value: &str) -> Option<(String, Option<String>)> {
+    let lower = value.to_ascii_lowercase().replace('.', "");
+    let lower = lower.trim();
+    let (clock, pm) = if let Some(clock) = lower.strip_suffix("pm") {
+        (clock.trim(), Some(true))
+    } else if let Some(clock) = lower.strip_suffix("am") {
+        (clock.trim(), Some(false))
+    } else {
+        (lower, None)
+    };
+
+    // A trailing timezone only goes with 24-hour times
+    let (clock, zone) = match clock.find(['z', '+', '-']) {
+        Some(index) if pm.is_none() => {
+            let zone = clock[index..].to_ascii_uppercase();
+            is_vcp_timezone(&zone).then_some(())?;
+            (&clock[..index], Some(zone))
+        }
+        Some(_) => return None,
+        None => (clock, None),
+    };
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|p| p.is_empty() || p.len() > 2) {
+        return None;
+    }
+    let numbers: Vec<u32> = parts.iter().map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    let mut hour = numbers[0];
+    match pm {
+        Some(pm) if (1..=12).contains(&hour) => hour = hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        // 24-hour times need their minutes
+        None if numbers.len() < 2 => return None,
+        None => {}
+    }
+    let minute = numbers.get(1).copied().unwrap_or(0);
+    if hour > 23 || minute > 59 || numbers.get(2).is_some_and(|s| *s > 59) {
+        return None;
+    }
+    let time = match numbers.get(2) {
+        Some(second) => format!("{:02}:{:02}:{:02}", hour, minute, second),
+        None => format!("{:02}:{:02}", hour, minute),
+    };
+    Some((time, zone))
+}
+
+/// Text content without `<script>`/`<style>`, trimmed
+fn text_content(element: ElementRef<'_>) -> String {
+    let mut text = String::new();
+    for node in element.descendants() {
+        if let Some(t) = node.value().as_text() {
+            let inside_ignored = node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|el| matches!(el.value().name(), "script" | "style" | "template"));
+            if !inside_ignored {
+                text.push_str(t);
+            }
+        }
+    }
+    text.trim().to_string()
+}
+
+fn is_mf2_name(name: &str) -> bool {
+    // Lowercase letters and digits, hyphen-separated, at least one letter
+    !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && name.chars().any(|c| c.is_ascii_lowercase())
+}
+
+fn root_classes(element: ElementRef<'_>) -> Vec<String> {
+    let mut classes: Vec<String> = element
+        .value()
+        .classes()
+        .filter(|c| c.strip_prefix("h-").is_some_and(is_mf2_name))
+        .map(str::to_string)
+        .collect();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
+fn property_classes(element: ElementRef<'_>) -> Vec<(Prefix, String)> {
+    let mut properties = Vec::new();
+    for class in element.value().classes() {
+        let parsed = if let Some(name) = class.strip_prefix("p-") {
+            Some((Prefix::P, name))
+        } else if let Some(name) = class.strip_prefix("u-") {
+            Some((Prefix::U, name))
+        } else if let Some(name) = class.strip_prefix("dt-") {
+            Some((Prefix::Dt, name))
+        } else {
+            class.strip_prefix("e-").map(|name| (Prefix::E, name))
+        };
+        if let Some((prefix, name)) = parsed {
+            if is_mf2_name(name) && !properties.iter().any(|(p, n)| *p == prefix && n == name) {
+                properties.push((prefix, name.to_string()));
+            }
+        }
+    }
+    properties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mf2(html: &str) -> Mf2Document {
+        extract_microformats(&Html::parse_document(html), Some("https://blog.example/posts/1"))
+    }
+
+    #[test]
+    fn test_h_entry_with_nested_author() {
+        let doc = mf2(r#"<html><body>
+            <article class="h-entry" id="post">
+              <h1 class="p-name">Hello mf2</h1>
+              <a class="u-url" href="/posts/1">permalink</a>
+              <time class="dt-published" datetime="2024-06-01T09:00:00Z">June 1</time>
+              <div class="p-author h-card">
+                <img class="u-photo" src="/me.jpg" alt="Jane">
+                <a class="p-name u-url" href="https://jane.example">Jane Doe</a>
+              </div>
+              <div class="e-content"><p>First <b>post</b></p></div>
+              <a class="p-category" href="/tags/rust">rust</a>
+            </article>
+            </body></html>"#);
+
+        assert_eq!(doc.items.len(), 1);
+        let entry = &doc.items[0];
+        assert_eq!(entry.types, vec!["h-entry"]);
+        assert_eq!(entry.id.as_deref(), Some("post"));
+        assert_eq!(entry.first_text("name"), Some("Hello mf2"));
+        assert_eq!(entry.first_text("url"), Some("https://blog.example/posts/1"));
+        assert_eq!(entry.first_text("published"), Some("2024-06-01T09:00:00Z"));
+        assert_eq!(entry.first_text("category"), Some("rust"));
+        assert_eq!(
+            entry.properties["content"][0],
+            Mf2Value::Html {
+                html: "<p>First <b>post</b></p>".to_string(),
+                value: "First post".to_string()
+            }
+        );
+
+        let Mf2Value::Item(author) = &entry.properties["author"][0] else {
+            panic!("author should be a nested h-card");
+        };
+        assert_eq!(author.value.as_deref(), Some("Jane Doe"));
+        assert_eq!(author.first_text("url"), Some("https://jane.example/"));
+        assert_eq!(
+            author.properties["photo"][0],
+            Mf2Value::Image {
+                value: "https://blog.example/me.jpg".to_string(),
+                alt: "Jane".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_implied_properties_and_rels() {
+        let doc = mf2(r#"<html><head>
+            <link rel="me authn" href="https://github.com/jane">
+            </head><body>
+            <a class="h-card" href="/about"><img src="/avatar.png" alt="Jane Doe"></a>
+            <div class="h-event"><span class="p-name">Launch</span>
+              <time class="dt-start"><span class="value">2024-07-01</span> <span class="value">18:00</span></time>
+            </div>
+            </body></html>"#);
+
+        assert_eq!(doc.items.len(), 2);
+        let card = &doc.items[0];
+        assert_eq!(card.first_text("name"), Some("Jane Doe"));
+        assert_eq!(card.first_text("url"), Some("https://blog.example/about"));
+        assert_eq!(card.first_text("photo"), Some("https://blog.example/avatar.png"));
+
+        let event = &doc.items[1];
+        assert_eq!(event.first_text("start"), Some("2024-07-01T18:00"));
+        assert!(!event.properties.contains_key("url"));
+
+        assert_eq!(doc.rels["me"], vec!["https://github.com/jane"]);
+        assert_eq!(doc.rel_urls["https://github.com/jane"].rels, vec!["me", "authn"]);
+    }
+
+    #[test]
+    fn test_value_class_datetime() {
+        let doc = mf2(r#"<div class="h-event">
+            <span class="dt-start"><span class="value">2024-07-01</span><span class="value">18:00</span><span class="value">-0800</span></span>
+            <span class="dt-end"><time class="value" datetime="2024-07-01">July 1</time> at <abbr class="value" title="9:30pm">half past nine</abbr></span>
+            <span class="dt-updated"><span class="value">10:15:00Z</span> on <span class="value-title" title="2024-183"></span></span>
+            <span class="p-summary"><span class="value">2024-07-01</span><span class="value">18:00</span></span>
+            </div>"#);
+
+        let event = &doc.items[0];
+        assert_eq!(event.first_text("start"), Some("2024-07-01T18:00-0800"));
+        assert_eq!(event.first_text("end"), Some("2024-07-01T21:30"));
+        assert_eq!(event.first_text("updated"), Some("2024-183T10:15:00Z"));
+        assert_eq!(event.first_text("summary"), Some("2024-07-0118:00"));
+    }
+
+    #[test]
+    fn test_canonical_json_shape() {
+        let doc = mf2(r#"<div class="h-card"><span class="p-name">Ann</span></div>"#);
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "items": [{"type": ["h-card"], "properties": {"name": ["Ann"]}}],
+                "rels": {},
+                "rel-urls": {}
+            })
+        );
+    }
+}
@@ -1,3 +1,12 @@
+use crate::citation::Citation;
+use crate::documents::DocumentInfo;
+use crate::feeds::Feed;
+use crate::kg::Triple;
+use crate::language::DetectedLanguage;
+use crate::links::Link;
+use crate::microformats::Mf2Document;
+use crate::parser::MicrodataItem;
+use crate::readability::Article;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -28,6 +37,12 @@ pub struct MicrodataSummary {
     pub properties: usize,
 }
 
+impl From<&MicrodataItem> for MicrodataSummary {
+    fn from(item: &MicrodataItem) -> Self {
+        Self { item_type: item.item_type.clone(), properties: item.properties.len() }
+    }
+}
+
 /// Comprehensive snapshot of semantic information extracted from a web page.
 /// This struct aggregates metadata, structured data, and content analysis results.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,8 +51,13 @@ pub struct SemanticSnapshot {
     pub title: Option<String>,
     /// Page description from meta description, Open Graph, or content summarization.
     pub description: Option<String>,
-    /// Detected language of the page content (ISO 639-1 format, e.g., "en", "fr").
+    /// Language declared by the page (`<html lang>`, a feed's or XML document's
+    /// language), e.g. "en" or "fr-CA".
     pub language: Option<String>,
+    /// Language identified from the extracted text by trigram statistics, with
+    /// its confidence; `language` keeps what the page declares.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<DetectedLanguage>,
     /// Canonical URL as specified in the page's rel="canonical" link.
     pub canonical_url: Option<String>,
     /// Final resolved URL after redirects (the actual URL that was fetched).
@@ -51,17 +71,72 @@ pub struct SemanticSnapshot {
     pub twitter_card: HashMap<String, String>,
     /// Count of JSON-LD structured data objects found on the page.
     pub json_ld_count: usize,
+    /// Raw JSON-LD blocks found on the page, one entry per `<script>` tag.
+    /// Converted to RDF triples when the snapshot is inserted into the knowledge graph.
+    #[serde(default)]
+    pub json_ld: Vec<serde_json::Value>,
     /// List of Microdata items extracted from the page.
     pub microdata: Vec<MicrodataSummary>,
+    /// Full microdata item trees, converted to RDF triples when the snapshot
+    /// is inserted into the knowledge graph.
+    #[serde(default)]
+    pub microdata_items: Vec<MicrodataItem>,
+    /// RDFa triples extracted from the page, inserted as-is into the knowledge graph.
+    #[serde(default)]
+    pub rdfa: Vec<Triple>,
+    /// Microformats2 items (h-card, h-entry, h-event, ...) and rels in canonical
+    /// mf2 JSON form; known types are mapped to schema.org in the knowledge graph.
+    #[serde(default)]
+    pub microformats: Mf2Document,
+    /// Outgoing links with anchor text and `rel` values; mapped to link-graph
+    /// edges (`schema:significantLink`, IANA link relations) in the knowledge graph.
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Parsed feed when the URL served RSS, Atom or JSON Feed instead of HTML;
+    /// its items become `schema:BlogPosting` resources in the knowledge graph.
+    #[serde(default)]
+    pub feed: Option<Feed>,
+    /// Metadata of a PDF, JSON, XML or plain-text response (media type,
+    /// authors, page count); `None` for HTML pages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<DocumentInfo>,
+    /// Main article (byline, published date, lead image) found by readability
+    /// extraction; `text_preview` is built from its text when present.
+    #[serde(default)]
+    pub article: Option<Article>,
+    /// Bibliographic record from `citation_*`, `prism.*` and Dublin Core meta
+    /// tags, inserted as a `schema:ScholarlyArticle`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
+    /// Compact Markdown rendering of the main content for LLM context, with
+    /// numbered link references, capped at `markdown::DEFAULT_SNAPSHOT_TOKENS`.
+    #[serde(default)]
+    pub markdown: String,
     /// Short text preview of the page content for summarization purposes.
     /// Typically the first few sentences or paragraphs.
     pub text_preview: String,
     /// Total character count of extracted text content (UTF-8).
     pub text_length: usize,
+    /// Character encoding the document was decoded with (WHATWG name, e.g.
+    /// `Shift_JIS`), sniffed from the BOM, `Content-Type` or `<meta charset>`.
+    /// `None` when the page was rendered by the headless browser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
     /// Query matches found when a search query was applied to the content.
     pub query_matches: Vec<QueryMatch>,
 }
 
+impl SemanticSnapshot {
+    /// Language to treat the content as: the declared one, otherwise the
+    /// detected one when detection is reliable
+    pub fn effective_language(&self) -> Option<String> {
+        crate::language::effective_language(
+            self.language.as_deref(),
+            self.detected_language.as_ref(),
+        )
+    }
+}
+
 /// Result of a web page browsing operation.
 /// Combines a human-readable summary with detailed semantic analysis.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,4 +146,10 @@ pub struct BrowseOutcome {
     pub summary: String,
     /// Detailed semantic snapshot containing all extracted metadata and structured data.
     pub snapshot: SemanticSnapshot,
+    /// Screenshot of the rendered page, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<crate::capture::Capture>,
+    /// PDF rendering of the page, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf: Option<crate::capture::Capture>,
 }
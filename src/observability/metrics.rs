@@ -118,6 +118,21 @@ lazy_static! {
         "Number of active browser instances",
         &["pool_type"]
     ).expect("Failed to register ACTIVE_BROWSER_INSTANCES");
+
+    /// Browser pages by pool state (in_use, idle, waiting)
+    pub static ref BROWSER_POOL_PAGES: GaugeVec = register_gauge_vec!(
+        "semantic_browser_browser_pool_pages",
+        "Browser pool pages in use and idle, and requests waiting for one",
+        &["state"]
+    ).expect("Failed to register BROWSER_POOL_PAGES");
+
+    /// Time spent waiting for a free browser page
+    pub static ref BROWSER_POOL_WAIT_DURATION: HistogramVec = register_histogram_vec!(
+        "semantic_browser_browser_pool_wait_seconds",
+        "Time requests waited for a free browser page in seconds",
+        &[],
+        vec![0.001, 0.01, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0]
+    ).expect("Failed to register BROWSER_POOL_WAIT_DURATION");
 }
 
 // ===== MACHINE LEARNING METRICS =====
@@ -210,6 +225,18 @@ pub fn record_browser_operation(operation: &str, result: &str, duration: f64) {
     BROWSER_OPERATION_DURATION.with_label_values(&[operation]).observe(duration);
 }
 
+/// Update browser pool gauges from a metrics snapshot
+pub fn update_browser_pool(metrics: &crate::page_pool::PoolMetrics) {
+    BROWSER_POOL_PAGES.with_label_values(&["in_use"]).set(metrics.in_use as f64);
+    BROWSER_POOL_PAGES.with_label_values(&["idle"]).set(metrics.idle as f64);
+    BROWSER_POOL_PAGES.with_label_values(&["waiting"]).set(metrics.waiting as f64);
+}
+
+/// Record how long a request waited for a browser page
+pub fn record_browser_pool_wait(seconds: f64) {
+    BROWSER_POOL_WAIT_DURATION.with_label_values(&[]).observe(seconds);
+}
+
 /// Record ML inference metrics
 pub fn record_ml_inference(
     model_type: &str,
@@ -267,6 +294,7 @@ mod tests {
 
         // Test browser metrics
         record_browser_operation("browse", "success", 2.0);
+        record_browser_pool_wait(0.05);
 
         // Test ML metrics
         record_ml_inference("bert", "ner", "success", 0.1, Some(0.95));
@@ -0,0 +1,359 @@
+//! Bounded pool of reusable browser pages
+//!
+//! [`BrowserPool`](crate::browser::BrowserPool) checks a page out for every
+//! navigation instead of holding one browser lock for the whole request. A
+//! semaphore caps the pages in use at `size`; pages that finished cleanly are
+//! reset and kept idle for the next request. Callers queue for a free page,
+//! but only `max_waiting` of them and for at most `acquire_timeout`; beyond
+//! that they get a [`PoolError`] so load is shed instead of piling up.
+//!
+//! The pool is generic over what it holds, so the queueing and accounting are
+//! independent of Chromium.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Pool limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Maximum number of items checked out at once
+    pub size: usize,
+    /// Maximum number of callers waiting for a free item
+    pub max_waiting: usize,
+    /// How long a caller waits for a free item before giving up
+    pub acquire_timeout: Duration,
+}
+
+/// Backpressure errors returned by [`Pool::checkout`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolError {
+    /// Every item is in use and the wait queue is full
+    Saturated { waiting: usize, max_waiting: usize },
+    /// No item became free within the acquire timeout
+    Timeout(Duration),
+}
+
+impl std::fmt::Display for PoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolError::Saturated { waiting, max_waiting } => write!(
+                f,
+                "Browser pool is saturated: {} requests already waiting (limit {})",
+                waiting, max_waiting
+            ),
+            PoolError::Timeout(timeout) => {
+                write!(f, "Timed out after {:?} waiting for a free browser page", timeout)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
+
+/// Point-in-time pool usage and cumulative counters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoolMetrics {
+    pub size: usize,
+    pub in_use: usize,
+    pub idle: usize,
+    pub waiting: usize,
+    /// Successful checkouts
+    pub checkouts: u64,
+    /// Checkouts served with an idle item instead of a new one
+    pub reused: u64,
+    /// Checkouts refused because the wait queue was full
+    pub rejected: u64,
+    /// Checkouts that gave up after the acquire timeout
+    pub timed_out: u64,
+    /// Queue wait summed over all successful checkouts, in milliseconds
+    pub total_wait_ms: u64,
+    /// Longest queue wait of a successful checkout, in milliseconds
+    pub max_wait_ms: u64,
+}
+
+impl PoolMetrics {
+    /// Average queue wait of a successful checkout, in milliseconds
+    pub fn mean_wait_ms(&self) -> f64 {
+        if self.checkouts == 0 {
+            0.0
+        } else {
+            self.total_wait_ms as f64 / self.checkouts as f64
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    checkouts: u64,
+    reused: u64,
+    rejected: u64,
+    timed_out: u64,
+    total_wait_ms: u64,
+    max_wait_ms: u64,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    config: PoolConfig,
+    permits: Arc<Semaphore>,
+    idle: Mutex<Vec<T>>,
+    waiting: AtomicUsize,
+    counters: Mutex<Counters>,
+}
+
+impl<T> Inner<T> {
+    fn metrics(&self) -> PoolMetrics {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        PoolMetrics {
+            size: self.config.size,
+            in_use: self.config.size - self.permits.available_permits(),
+            idle: self.idle.lock().map(|idle| idle.len()).unwrap_or_default(),
+            waiting: self.waiting.load(Ordering::SeqCst),
+            checkouts: counters.checkouts,
+            reused: counters.reused,
+            rejected: counters.rejected,
+            timed_out: counters.timed_out,
+            total_wait_ms: counters.total_wait_ms,
+            max_wait_ms: counters.max_wait_ms,
+        }
+    }
+
+    fn count(&self, update: impl FnOnce(&mut Counters)) {
+        update(&mut self.counters.lock().unwrap_or_else(|e| e.into_inner()));
+        self.publish();
+    }
+
+    /// Mirror usage to the Prometheus gauges
+    fn publish(&self) {
+        #[cfg(feature = "observability")]
+        crate::observability::metrics::update_browser_pool(&self.metrics());
+    }
+}
+
+/// Bounded pool of reusable items, see the module docs
+#[derive(Debug)]
+pub struct Pool<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T> Pool<T> {
+    /// Empty pool; a `size` of 0 is treated as 1
+    pub fn new(mut config: PoolConfig) -> Self {
+        config.size = config.size.max(1);
+        Self {
+            inner: Arc::new(Inner {
+                permits: Arc::new(Semaphore::new(config.size)),
+                config,
+                idle: Mutex::new(Vec::new()),
+                waiting: AtomicUsize::new(0),
+                counters: Mutex::new(Counters::default()),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &PoolConfig {
+        &self.inner.config
+    }
+
+    /// Wait for a free slot
+    ///
+    /// The returned [`Checkout`] holds the slot until it is dropped; take the
+    /// idle item it offers or create a new one, and [`Checkout::release`] it
+    /// when it can be reused.
+    pub async fn checkout(&self) -> Result<Checkout<T>, PoolError> {
+        let inner = &self.inner;
+        let started = Instant::now();
+
+        let permit = match Arc::clone(&inner.permits).try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                // Join the queue, or refuse if it is full; the guard leaves it
+                // again, also when the caller's future is dropped
+                let max_waiting = inner.config.max_waiting;
+                let waiting = inner.waiting.fetch_add(1, Ordering::SeqCst);
+                let _queued = Queued(&inner.waiting);
+                if waiting >= max_waiting {
+                    inner.count(|c| c.rejected += 1);
+                    return Err(PoolError::Saturated { waiting, max_waiting });
+                }
+                let acquired = tokio::time::timeout(
+                    inner.config.acquire_timeout,
+                    Arc::clone(&inner.permits).acquire_owned(),
+                )
+                .await;
+                match acquired {
+                    // The semaphore is never closed
+                    Ok(permit) => permit.expect("pool semaphore closed"),
+                    Err(_) => {
+                        inner.count(|c| c.timed_out += 1);
+                        return Err(PoolError::Timeout(inner.config.acquire_timeout));
+                    }
+                }
+            }
+        };
+
+        let waited = started.elapsed();
+        let idle = inner.idle.lock().ok().and_then(|mut idle| idle.pop());
+        let wait_ms = waited.as_millis() as u64;
+        inner.count(|c| {
+            c.checkouts += 1;
+            c.reused += u64::from(idle.is_some());
+            c.total_wait_ms += wait_ms;
+            c.max_wait_ms = c.max_wait_ms.max(wait_ms);
+        });
+        #[cfg(feature = "observability")]
+        crate::observability::metrics::record_browser_pool_wait(waited.as_secs_f64());
+
+        Ok(Checkout { pool: Arc::clone(inner), idle, waited, permit: Some(permit) })
+    }
+
+    /// Current usage and counters
+    pub fn metrics(&self) -> PoolMetrics {
+        self.inner.metrics()
+    }
+
+    /// Remove and return every idle item, e.g. to close them on shutdown
+    pub fn drain_idle(&self) -> Vec<T> {
+        self.inner.idle.lock().map(|mut idle| std::mem::take(&mut *idle)).unwrap_or_default()
+    }
+}
+
+/// Place in the wait queue
+struct Queued<'a>(&'a AtomicUsize);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A checked-out pool slot
+///
+/// Dropping it frees the slot; an item taken from it is only kept for reuse
+/// if it is handed back with [`release`](Self::release).
+#[derive(Debug)]
+pub struct Checkout<T> {
+    pool: Arc<Inner<T>>,
+    idle: Option<T>,
+    waited: Duration,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<T> Checkout<T> {
+    /// Idle item left by a previous checkout, if there was one
+    pub fn take_idle(&mut self) -> Option<T> {
+        self.idle.take()
+    }
+
+    /// Time spent queueing for this slot
+    pub fn waited(&self) -> Duration {
+        self.waited
+    }
+
+    /// Free the slot and keep `item` for the next checkout
+    pub fn release(mut self, item: T) {
+        if let Ok(mut idle) = self.pool.idle.lock() {
+            idle.push(item);
+        }
+        // An untaken idle item goes back too
+        self.return_idle();
+    }
+
+    fn return_idle(&mut self) {
+        if let Some(item) = self.idle.take() {
+            if let Ok(mut idle) = self.pool.idle.lock() {
+                idle.push(item);
+            }
+        }
+    }
+}
+
+impl<T> Drop for Checkout<T> {
+    fn drop(&mut self) {
+        self.return_idle();
+        self.permit.take();
+        self.pool.publish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(size: usize, max_waiting: usize) -> Pool<u32> {
+        Pool::new(PoolConfig { size, max_waiting, acquire_timeout: Duration::from_millis(200) })
+    }
+
+    #[tokio::test]
+    async fn test_checkout_reuses_released_items() {
+        let pool = pool(2, 4);
+
+        let mut first = pool.checkout().await.unwrap();
+        assert_eq!(first.take_idle(), None);
+        let second = pool.checkout().await.unwrap();
+        assert_eq!(pool.metrics().in_use, 2);
+
+        first.release(7);
+        // Dropped without release: the slot is freed but nothing is kept
+        drop(second);
+        let metrics = pool.metrics();
+        assert_eq!((metrics.in_use, metrics.idle), (0, 1));
+
+        let mut third = pool.checkout().await.unwrap();
+        assert_eq!(third.take_idle(), Some(7));
+        drop(third);
+        assert_eq!(pool.metrics().idle, 0);
+
+        // An idle item that was never taken stays in the pool
+        pool.checkout().await.unwrap().release(8);
+        drop(pool.checkout().await.unwrap());
+        assert_eq!(pool.drain_idle(), vec![8]);
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics.checkouts, 5);
+        assert_eq!(metrics.reused, 2);
+        assert_eq!(metrics.size, 2);
+    }
+
+    #[tokio::test]
+    async fn test_backpressure() {
+        let pool = pool(1, 1);
+        let held = pool.checkout().await.unwrap();
+
+        // One caller may queue; it gets the slot once it is released
+        let queued = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.checkout().await.map(|c| c.waited()) }
+        });
+        while pool.metrics().waiting == 0 {
+            tokio::task::yield_now().await;
+        }
+        let err = pool.checkout().await.unwrap_err();
+        assert_eq!(err, PoolError::Saturated { waiting: 1, max_waiting: 1 });
+        assert!(err.to_string().contains("saturated"));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(held);
+        let waited = queued.await.unwrap().unwrap();
+        assert!(waited >= Duration::from_millis(20));
+
+        // Nobody releases: the waiter times out
+        let _held = pool.checkout().await.unwrap();
+        let err = pool.checkout().await.unwrap_err();
+        assert_eq!(err, PoolError::Timeout(Duration::from_millis(200)));
+
+        let metrics = pool.metrics();
+        assert_eq!((metrics.rejected, metrics.timed_out, metrics.waiting), (1, 1, 0));
+        assert!(metrics.max_wait_ms >= 20);
+        assert!(metrics.mean_wait_ms() > 0.0);
+    }
+}
@@ -1,5 +1,12 @@
-use scraper::{Html, Selector};
+use crate::citation::Citation;
+use crate::kg::Triple;
+use crate::links::Link;
+use crate::microformats::Mf2Document;
+use crate::tables::Table;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// Container for semantic data extracted from HTML content.
 /// This struct holds the results of parsing HTML for structured data including
@@ -15,19 +22,71 @@ pub struct SemanticData {
     /// List of JSON-LD objects extracted from script tags.
     /// JSON-LD is a structured data format commonly used for SEO and rich snippets.
     pub json_ld: Vec<serde_json::Value>,
+    /// RDFa triples found in the document (RDFa 1.1 Core / Lite).
+    #[serde(default)]
+    pub rdfa: Vec<Triple>,
+    /// Microformats2 items and rels, in canonical mf2 JSON form.
+    #[serde(default)]
+    pub microformats: Mf2Document,
+    /// Data tables with resolved headers and typed rows.
+    #[serde(default)]
+    pub tables: Vec<Table>,
+    /// Outgoing links (`<a>`, `<area>`, `<link>`) with anchor text and `rel` values.
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Bibliographic record from `citation_*`, `prism.*` and Dublin Core meta tags.
+    #[serde(default)]
+    pub citation: Option<Citation>,
 }
 
 /// Represents a single microdata item extracted from HTML.
 /// Microdata items are defined using itemscope and itemprop attributes,
 /// following the schema.org vocabulary for structured data.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MicrodataItem {
     /// The item type URL (e.g., "https://schema.org/Person").
-    /// Specifies the type of entity being described.
+    /// Specifies the type of entity being described. May hold several
+    /// space-separated types, see [`MicrodataItem::item_types`].
     pub item_type: String,
-    /// Map of property names to their values.
+    /// Global identifier of the item from the `itemid` attribute, resolved to an absolute URL.
+    #[serde(default)]
+    pub item_id: Option<String>,
+    /// Map of property names to their values, in tree order.
     /// Each property can have multiple values if multiple elements share the same itemprop.
-    pub properties: std::collections::HashMap<String, Vec<String>>,
+    pub properties: HashMap<String, Vec<MicrodataValue>>,
+}
+
+impl MicrodataItem {
+    /// Individual types listed in the `itemtype` attribute
+    pub fn item_types(&self) -> impl Iterator<Item = &str> {
+        self.item_type.split_whitespace()
+    }
+}
+
+/// Value of a microdata property, typed according to the element it comes from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicrodataValue {
+    /// Text content, or the `content`/`value` attribute of `meta`, `data` and `meter`
+    Text(String),
+    /// Absolute URL from `href`, `src` or `data` attributes
+    Url(String),
+    /// Date/time from a `time` element (`datetime` attribute or text content)
+    DateTime(String),
+    /// Nested item (element with both `itemprop` and `itemscope`)
+    Item(Box<MicrodataItem>),
+}
+
+impl MicrodataValue {
+    /// String form of the value, `None` for nested items
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MicrodataValue::Text(s) | MicrodataValue::Url(s) | MicrodataValue::DateTime(s) => {
+                Some(s)
+            }
+            MicrodataValue::Item(_) => None,
+        }
+    }
 }
 
 /// Parses HTML content and extracts semantic elements including titles, microdata, and JSON-LD.
@@ -52,6 +111,15 @@ pub struct MicrodataItem {
 /// assert_eq!(data.title, Some("Test".to_string()));
 /// ```
 pub fn parse_html(html: &str) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
+    parse_html_with_base(html, None)
+}
+
+/// Same as [`parse_html`], resolving relative URLs (microdata values, RDFa
+/// subjects and objects) against `base_url`, the URL the HTML was fetched from.
+pub fn parse_html_with_base(
+    html: &str,
+    base_url: Option<&str>,
+) -> Result<SemanticData, Box<dyn std::error::Error + Send + Sync>> {
     crate::security::validate_html_input(html)?;
     crate::security::log_action("parse_html", &format!("length: {}", html.len()));
     let document = Html::parse_document(html);
@@ -64,10 +132,25 @@ pub fn parse_html(html: &str) -> Result<SemanticData, Box<dyn std::error::Error
     // Extract JSON-LD
     let json_ld = extract_json_ld(&document)?;
 
-    // Extract microdata (simplified)
-    let microdata = extract_microdata(&document)?;
+    // Extract microdata; without a document URL only <base href> can resolve relative URLs
+    let microdata = extract_microdata(&document, base_url)?;
+
+    // Extract RDFa
+    let rdfa = crate::rdfa::extract_rdfa(&document, base_url);
+
+    // Extract microformats2
+    let microformats = crate::microformats::extract_microformats(&document, base_url);
 
-    Ok(SemanticData { title, microdata, json_ld })
+    // Extract data tables
+    let tables = crate::tables::extract_tables(&document);
+
+    // Extract outgoing links
+    let links = crate::links::extract_links(&document, base_url);
+
+    // Extract the bibliographic record of scholarly pages
+    let citation = crate::citation::extract_citation(&document, base_url);
+
+    Ok(SemanticData { title, microdata, json_ld, rdfa, microformats, tables, links, citation })
 }
 
 /// Extracts JSON-LD (JSON for Linking Data) structured data from HTML script tags.
@@ -103,54 +186,179 @@ fn extract_json_ld(
     Ok(json_ld)
 }
 
-/// Extracts microdata items from HTML using the microdata specification.
+/// Extracts microdata items from HTML following the WHATWG microdata specification.
 /// Microdata allows embedding structured data in HTML using attributes like
-/// itemscope, itemtype, and itemprop. This follows the schema.org vocabulary.
+/// itemscope, itemtype, itemprop, itemid and itemref. This follows the schema.org vocabulary.
 ///
-/// This is a simplified implementation that:
-/// - Finds all elements with itemscope attribute
-/// - Extracts the itemtype
-/// - Collects all itemprop values within the itemscope scope
+/// Only top-level items (elements with `itemscope` but no `itemprop`) are returned;
+/// nested items are reachable through [`MicrodataValue::Item`] property values.
+/// Properties are collected with the spec's crawling algorithm, so the properties of
+/// a nested item never leak into its parent, and `itemref` pulls in properties from
+/// elsewhere in the document. Cycles created by `itemref` are skipped.
 ///
 /// # Arguments
 /// * `document` - Parsed HTML document from the scraper crate
+/// * `base_url` - Document URL used to resolve relative URL values and `itemid`.
+///   A `<base href>` in the document takes precedence, as in browsers.
 ///
 /// # Returns
-/// A vector of MicrodataItem structs representing the extracted structured data.
-///
-/// # Limitations
-/// - Does not handle nested microdata scopes properly
-/// - Simple text extraction for property values (no URL or date parsing)
-/// - Does not validate schema.org compliance
-///
-/// # Note
-/// This is a basic implementation. For production use, consider a more robust
-/// microdata parser that handles the full specification.
-fn extract_microdata(
+/// A vector of top-level MicrodataItem trees.
+pub fn extract_microdata(
     document: &Html,
+    base_url: Option<&str>,
 ) -> Result<Vec<MicrodataItem>, Box<dyn std::error::Error + Send + Sync>> {
-    // Simplified: look for itemscope
-    let selector = Selector::parse("[itemscope]")
+    let itemscope_selector = Selector::parse("[itemscope]:not([itemprop])")
         .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-    let mut items = Vec::new();
+    let base = document_base(document, base_url);
 
-    for element in document.select(&selector) {
-        let item_type = element.value().attr("itemtype").unwrap_or("").to_string();
-        let mut properties = std::collections::HashMap::new();
-
-        // Find itemprop within
-        let prop_selector = Selector::parse("[itemprop]")
-            .map_err(|e| Box::<dyn std::error::Error + Send + Sync>::from(e.to_string()))?;
-        for prop in element.select(&prop_selector) {
-            let prop_name = prop.value().attr("itemprop").unwrap_or("").to_string();
-            let prop_value = prop.text().collect::<String>();
-            properties.entry(prop_name).or_insert(Vec::new()).push(prop_value);
+    let mut order = HashMap::new();
+    let mut ids = HashMap::new();
+    for (index, node) in document.root_element().descendants().enumerate() {
+        order.insert(node.id(), index);
+        if let Some(element) = ElementRef::wrap(node) {
+            if let Some(id) = element.value().id() {
+                ids.entry(id.to_string()).or_insert(element);
+            }
         }
+    }
 
-        items.push(MicrodataItem { item_type, properties });
+    let extractor = MicrodataExtractor { base, order, ids };
+    let mut ancestors = Vec::new();
+    Ok(document
+        .select(&itemscope_selector)
+        .map(|element| extractor.item(element, &mut ancestors))
+        .collect())
+}
+
+/// Base URL for resolving relative URLs in `document`
+///
+/// `base_url` is the URL the document was fetched from; a `<base href>` in the
+/// document takes precedence, as in browsers.
+pub(crate) fn document_base(document: &Html, base_url: Option<&str>) -> Option<url::Url> {
+    let document_url = base_url.and_then(|u| url::Url::parse(u).ok());
+    let base_href = Selector::parse("base[href]")
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .and_then(|base| base.value().attr("href").map(str::to_string));
+    match base_href {
+        Some(href) => match &document_url {
+            Some(doc) => doc.join(&href).ok(),
+            None => url::Url::parse(&href).ok(),
+        },
+        None => document_url,
     }
+}
+
+/// Resolve an attribute URL against `base`.
+///
+/// Leading and trailing ASCII whitespace is stripped, as HTML does for URL
+/// attributes, so an empty or blank value resolves to `base` itself (a
+/// same-document reference). Without a base, or when the value cannot be
+/// joined, the stripped value is returned unchanged.
+pub(crate) fn resolve_against(base: Option<&url::Url>, value: &str) -> String {
+    let value = value.trim_matches(|c: char| c.is_ascii_whitespace());
+    base.and_then(|base| base.join(value).ok())
+        .map(|url| url.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
 
-    Ok(items)
+struct MicrodataExtractor<'a> {
+    base: Option<url::Url>,
+    /// Tree order position of every node, used to sort crawled properties
+    order: HashMap<NodeId, usize>,
+    /// First element for each id, used to resolve `itemref`
+    ids: HashMap<String, ElementRef<'a>>,
+}
+
+impl<'a> MicrodataExtractor<'a> {
+    fn item(&self, element: ElementRef<'a>, ancestors: &mut Vec<NodeId>) -> MicrodataItem {
+        ancestors.push(element.id());
+
+        let attrs = element.value();
+        let item_type = attrs.attr("itemtype").unwrap_or("").trim().to_string();
+        let item_id = attrs.attr("itemid").map(|id| resolve_against(self.base.as_ref(), id));
+        let mut properties: HashMap<String, Vec<MicrodataValue>> = HashMap::new();
+
+        for prop in self.properties(element) {
+            let value = if prop.value().attr("itemscope").is_some() {
+                if ancestors.contains(&prop.id()) {
+                    tracing::debug!("Skipping microdata property that refers to an ancestor item");
+                    continue;
+                }
+                MicrodataValue::Item(Box::new(self.item(prop, ancestors)))
+            } else {
+                self.value(prop)
+            };
+
+            let mut names: Vec<&str> = Vec::new();
+            for name in prop.value().attr("itemprop").unwrap_or("").split_whitespace() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            for name in names {
+                properties.entry(name.to_string()).or_default().push(value.clone());
+            }
+        }
+
+        ancestors.pop();
+        MicrodataItem { item_type, item_id, properties }
+    }
+
+    /// The "crawl the properties" algorithm: children and itemref targets, without
+    /// descending into nested items, returned in tree order.
+    fn properties(&self, root: ElementRef<'a>) -> Vec<ElementRef<'a>> {
+        let mut pending: Vec<ElementRef<'a>> =
+            root.children().filter_map(ElementRef::wrap).collect();
+        if let Some(refs) = root.value().attr("itemref") {
+            for id in refs.split_whitespace() {
+                if let Some(element) = self.ids.get(id) {
+                    pending.push(*element);
+                }
+            }
+        }
+
+        let mut memory = HashSet::from([root.id()]);
+        let mut results = Vec::new();
+        while let Some(current) = pending.pop() {
+            if !memory.insert(current.id()) {
+                continue;
+            }
+            if current.value().attr("itemscope").is_none() {
+                pending.extend(current.children().filter_map(ElementRef::wrap));
+            }
+            if current.value().attr("itemprop").is_some_and(|p| !p.trim().is_empty()) {
+                results.push(current);
+            }
+        }
+
+        results.sort_by_key(|element| self.order.get(&element.id()).copied().unwrap_or(usize::MAX));
+        results
+    }
+
+    /// Property value per element type (WHATWG microdata, "values")
+    fn value(&self, element: ElementRef<'a>) -> MicrodataValue {
+        let attrs = element.value();
+        let attr = |name: &str| attrs.attr(name).unwrap_or("").to_string();
+        // A missing URL attribute gives the empty string, not the base
+        let url = |name: &str| {
+            attrs.attr(name).map(|v| resolve_against(self.base.as_ref(), v)).unwrap_or_default()
+        };
+        match attrs.name() {
+            "meta" => MicrodataValue::Text(attr("content")),
+            "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => {
+                MicrodataValue::Url(url("src"))
+            }
+            "a" | "area" | "link" => MicrodataValue::Url(url("href")),
+            "object" => MicrodataValue::Url(url("data")),
+            "data" | "meter" => MicrodataValue::Text(attr("value")),
+            "time" => match attrs.attr("datetime") {
+                Some(datetime) => MicrodataValue::DateTime(datetime.to_string()),
+                None => MicrodataValue::DateTime(element.text().collect()),
+            },
+            _ => MicrodataValue::Text(element.text().collect()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -172,4 +380,106 @@ mod tests {
         assert_eq!(result.title, Some("Test Page".to_string()));
         assert_eq!(result.json_ld.len(), 1);
     }
+
+    fn microdata(html: &str, base_url: Option<&str>) -> Vec<MicrodataItem> {
+        extract_microdata(&Html::parse_document(html), base_url).unwrap()
+    }
+
+    #[test]
+    fn test_microdata_nested_items_do_not_leak() {
+        let html = r#"
+        <div itemscope itemtype="https://schema.org/Book" itemid="/books/1">
+          <span itemprop="name">Dune</span>
+          <div itemprop="author" itemscope itemtype="https://schema.org/Person">
+            <span itemprop="name">Frank Herbert</span>
+            <a itemprop="url" href="/authors/herbert">profile</a>
+          </div>
+          <time itemprop="datePublished" datetime="1965-08-01">August 1965</time>
+          <meta itemprop="isbn" content="9780441013593">
+          <img itemprop="image" src="cover.jpg">
+        </div>
+        "#;
+        let items = microdata(html, Some("https://books.example/list/"));
+
+        assert_eq!(items.len(), 1, "nested items are not top-level");
+        let book = &items[0];
+        assert_eq!(book.item_id.as_deref(), Some("https://books.example/books/1"));
+        assert_eq!(book.properties["name"], vec![MicrodataValue::Text("Dune".to_string())]);
+        assert!(!book.properties.contains_key("url"), "author url must not leak into book");
+        assert_eq!(
+            book.properties["datePublished"],
+            vec![MicrodataValue::DateTime("1965-08-01".to_string())]
+        );
+        assert_eq!(book.properties["isbn"][0].as_str(), Some("9780441013593"));
+        assert_eq!(
+            book.properties["image"],
+            vec![MicrodataValue::Url("https://books.example/list/cover.jpg".to_string())]
+        );
+
+        let MicrodataValue::Item(author) = &book.properties["author"][0] else {
+            panic!("author should be a nested item");
+        };
+        assert_eq!(author.item_type, "https://schema.org/Person");
+        assert_eq!(author.properties["name"][0].as_str(), Some("Frank Herbert"));
+        assert_eq!(
+            author.properties["url"][0].as_str(),
+            Some("https://books.example/authors/herbert")
+        );
+    }
+
+    #[test]
+    fn test_microdata_itemref_and_multiple_names() {
+        let html = r#"
+        <div itemscope itemtype="https://schema.org/Product" itemref="price extra"></div>
+        <p id="price"><span itemprop="price offerPrice price">10</span></p>
+        <div id="extra" itemprop="brand">ACME</div>
+        "#;
+        let items = microdata(html, None);
+
+        assert_eq!(items.len(), 1);
+        let product = &items[0];
+        assert_eq!(product.properties["price"].len(), 1, "duplicate names count once");
+        assert_eq!(product.properties["offerPrice"][0].as_str(), Some("10"));
+        assert_eq!(product.properties["brand"][0].as_str(), Some("ACME"));
+    }
+
+    #[test]
+    fn test_microdata_itemref_cycle_is_skipped() {
+        let html = r#"
+        <div itemscope>
+          <div id="n" itemprop="child" itemscope itemref="m"><span itemprop="name">N</span></div>
+        </div>
+        <div id="m" itemprop="next" itemscope itemref="n"><span itemprop="name">M</span></div>
+        "#;
+        let items = microdata(html, None);
+
+        assert_eq!(items.len(), 1);
+        let MicrodataValue::Item(child) = &items[0].properties["child"][0] else {
+            panic!("child should be a nested item");
+        };
+        let MicrodataValue::Item(next) = &child.properties["next"][0] else {
+            panic!("next should be a nested item");
+        };
+        assert_eq!(next.properties["name"][0].as_str(), Some("M"));
+        // "n" is an ancestor of "m", so it is not embedded again
+        assert!(!next.properties.contains_key("child"));
+    }
+
+    #[test]
+    fn test_resolve_against() {
+        let base = url::Url::parse("https://site.example/dir/page").unwrap();
+        assert_eq!(resolve_against(Some(&base), " a.png\n"), "https://site.example/dir/a.png");
+        assert_eq!(resolve_against(Some(&base), ""), "https://site.example/dir/page");
+        assert_eq!(resolve_against(Some(&base), "  "), "https://site.example/dir/page");
+        assert_eq!(resolve_against(None, " /a.png "), "/a.png");
+        assert_eq!(resolve_against(None, ""), "");
+
+        // Missing URL attributes stay empty; empty ones point at the page
+        let html = r#"<div itemscope><a itemprop="home" href="">Home</a>
+            <img itemprop="image"><a itemprop="next" href=" /next ">Next</a></div>"#;
+        let items = microdata(html, Some("https://site.example/dir/page"));
+        assert_eq!(items[0].properties["home"][0].as_str(), Some("https://site.example/dir/page"));
+        assert_eq!(items[0].properties["image"][0].as_str(), Some(""));
+        assert_eq!(items[0].properties["next"][0].as_str(), Some("https://site.example/next"));
+    }
 }
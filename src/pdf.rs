@@ -0,0 +1,1084 @@
+//! Minimal PDF reader for metadata and text extraction
+//!
+//! Reads indirect objects (including compressed object streams), the
+//! document information dictionary and the page tree, and extracts text from
+//! page content streams and form XObjects. FlateDecode and ASCIIHexDecode
+//! streams are supported. Text of simple fonts is decoded as WinAnsi; composite
+//! (Type0) fonts need a `/ToUnicode` CMap. Encrypted documents only report
+//! their page count.
+
+use regex::bytes::Regex;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Deepest page tree / form XObject / array and dictionary nesting followed
+const MAX_NESTING: usize = 32;
+
+/// Largest decompressed stream accepted
+const MAX_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Decompressed bytes accepted across all streams of a document
+const MAX_DOCUMENT_INFLATE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Document information dictionary (`/Info`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    /// Application that created the original document
+    pub creator: Option<String>,
+    /// Application that produced the PDF
+    pub producer: Option<String>,
+    /// RFC 3339 creation date
+    pub created: Option<String>,
+    /// RFC 3339 modification date
+    pub modified: Option<String>,
+}
+
+/// A parsed PDF document
+#[derive(Debug, Clone, Default)]
+pub struct PdfDocument {
+    /// Version from the `%PDF-x.y` header
+    pub version: Option<String>,
+    pub info: PdfInfo,
+    /// Extracted text, one entry per page
+    pub pages: Vec<String>,
+    pub encrypted: bool,
+}
+
+impl PdfDocument {
+    /// Text of all pages, separated by blank lines
+    pub fn text(&self) -> String {
+        self.pages
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Whether the bytes start with the `%PDF-` signature (leading junk allowed)
+pub fn is_pdf(body: &[u8]) -> bool {
+    body.windows(5).take(1024).any(|w| w == b"%PDF-")
+}
+
+/// Parse a PDF file
+pub fn parse_pdf(body: &[u8]) -> Result<PdfDocument, Box<dyn std::error::Error + Send + Sync>> {
+    let header = body.windows(5).take(1024).position(|w| w == b"%PDF-").ok_or("Not a PDF file")?;
+    let version = body[header + 5..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit() || **b == b'.')
+        .map(|&b| b as char)
+        .collect::<String>();
+
+    let file = PdfFile::load(body);
+    let trailer = file.trailer().ok_or("PDF trailer not found")?;
+    let encrypted = trailer.get("Encrypt").is_some();
+
+    let info = match file.resolve(trailer.get("Info")) {
+        Some(Object::Dict(dict)) if !encrypted => PdfInfo {
+            title: text_entry(&file, dict, "Title"),
+            author: text_entry(&file, dict, "Author"),
+            subject: text_entry(&file, dict, "Subject"),
+            keywords: text_entry(&file, dict, "Keywords"),
+            creator: text_entry(&file, dict, "Creator"),
+            producer: text_entry(&file, dict, "Producer"),
+            created: text_entry(&file, dict, "CreationDate").and_then(|d| parse_date(&d)),
+            modified: text_entry(&file, dict, "ModDate").and_then(|d| parse_date(&d)),
+        },
+        _ => PdfInfo::default(),
+    };
+
+    let mut pages = Vec::new();
+    if let Some(Object::Dict(catalog)) = file.resolve(trailer.get("Root")) {
+        if let Some(root) = catalog.get("Pages") {
+            file.collect_pages(root, None, 0, &mut HashSet::new(), &mut pages);
+        }
+    }
+
+    let pages = pages
+        .into_iter()
+        .map(|(page, resources)| {
+            if encrypted {
+                return String::new();
+            }
+            let mut text = TextExtractor::default();
+            for content in file.page_contents(page) {
+                text.run(&file, &content, resources, 0);
+            }
+            text.finish()
+        })
+        .collect();
+
+    Ok(PdfDocument { version: (!version.is_empty()).then_some(version), info, pages, encrypted })
+}
+
+/// A PDF dictionary, keyed by name without the leading `/`
+type Dict = HashMap<String, Object>;
+
+/// A PDF object
+#[derive(Debug, Clone, PartialEq)]
+enum Object {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Name(String),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(Dict),
+    Ref(u32),
+    Stream(Dict, Vec<u8>),
+    /// Content stream operator (only produced when lexing content streams)
+    Operator(String),
+}
+
+impl Object {
+    fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Object::Dict(dict) | Object::Stream(dict, _) => Some(dict),
+            _ => None,
+        }
+    }
+
+    fn as_name(&self) -> Option<&str> {
+        match self {
+            Object::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Object::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Indirect objects of a file, by object number
+struct PdfFile {
+    objects: HashMap<u32, Object>,
+    trailers: Vec<Dict>,
+    /// Decompressed bytes still allowed, see [`MAX_DOCUMENT_INFLATE_BYTES`]
+    inflate_budget: std::cell::Cell<u64>,
+}
+
+impl PdfFile {
+    fn load(body: &[u8]) -> Self {
+        let object_re = Regex::new(r"(\d+)\s+\d+\s+obj\b").expect("valid regex");
+        let mut objects = HashMap::new();
+        let mut trailers = Vec::new();
+
+        // Later definitions (incremental updates) replace earlier ones
+        for captures in object_re.captures_iter(body) {
+            let whole = captures.get(0).expect("match");
+            let Some(id) = std::str::from_utf8(&captures[1]).ok().and_then(|s| s.parse().ok())
+            else {
+                continue;
+            };
+            let mut lexer = Lexer::new(body, whole.end());
+            if let Some(object) = lexer.object_with_stream() {
+                if let Object::Stream(dict, _) = &object {
+                    if dict.get("Type").and_then(Object::as_name) == Some("XRef") {
+                        trailers.push(dict.clone());
+                    }
+                }
+                objects.insert(id, object);
+            }
+        }
+
+        let trailer_re = Regex::new(r"trailer\s*<<").expect("valid regex");
+        for found in trailer_re.find_iter(body) {
+            let mut lexer = Lexer::new(body, found.start() + b"trailer".len());
+            if let Some(Object::Dict(dict)) = lexer.object() {
+                trailers.push(dict);
+            }
+        }
+
+        let mut file = PdfFile {
+            objects,
+            trailers,
+            inflate_budget: std::cell::Cell::new(MAX_DOCUMENT_INFLATE_BYTES),
+        };
+        file.load_object_streams();
+        file
+    }
+
+    /// Unpack compressed objects of `/Type /ObjStm` streams
+    fn load_object_streams(&mut self) {
+        let mut unpacked = Vec::new();
+        for object in self.objects.values() {
+            let Object::Stream(dict, _) = object else {
+                continue;
+            };
+            if dict.get("Type").and_then(Object::as_name) != Some("ObjStm") {
+                continue;
+            }
+            let Some(data) = decode_stream(object, &self.inflate_budget) else {
+                continue;
+            };
+            let count = dict.get("N").and_then(Object::as_number).unwrap_or(0.0) as usize;
+            let first = dict.get("First").and_then(Object::as_number).unwrap_or(0.0) as usize;
+            let mut header = Lexer::new(&data, 0);
+            for _ in 0..count {
+                let (Some(Object::Number(id)), Some(Object::Number(offset))) =
+                    (header.object(), header.object())
+                else {
+                    break;
+                };
+                let mut lexer = Lexer::new(&data, first + offset as usize);
+                if let Some(object) = lexer.object() {
+                    unpacked.push((id as u32, object));
+                }
+            }
+        }
+        for (id, object) in unpacked {
+            self.objects.entry(id).or_insert(object);
+        }
+    }
+
+    /// Last trailer that names a document catalog
+    fn trailer(&self) -> Option<&Dict> {
+        self.trailers.iter().rev().find(|t| t.contains_key("Root"))
+    }
+
+    /// Follow indirect references
+    fn resolve<'a>(&'a self, object: Option<&'a Object>) -> Option<&'a Object> {
+        let mut object = object?;
+        for _ in 0..MAX_NESTING {
+            match object {
+                Object::Ref(id) => object = self.objects.get(id)?,
+                _ => return Some(object),
+            }
+        }
+        None
+    }
+
+    fn resolve_dict<'a>(&'a self, object: Option<&'a Object>) -> Option<&'a Dict> {
+        self.resolve(object).and_then(Object::as_dict)
+    }
+
+    /// Walk the page tree in order, with inherited `/Resources`
+    fn collect_pages<'a>(
+        &'a self,
+        node: &'a Object,
+        inherited: Option<&'a Dict>,
+        depth: usize,
+        visited: &mut HashSet<u32>,
+        pages: &mut Vec<(&'a Dict, Option<&'a Dict>)>,
+    ) {
+        if depth > MAX_NESTING {
+            return;
+        }
+        if let Object::Ref(id) = node {
+            if !visited.insert(*id) {
+                return;
+            }
+        }
+        let Some(dict) = self.resolve_dict(Some(node)) else {
+            return;
+        };
+        let resources = self.resolve_dict(dict.get("Resources")).or(inherited);
+        match self.resolve(dict.get("Kids")) {
+            Some(Object::Array(kids)) => {
+                for kid in kids {
+                    self.collect_pages(kid, resources, depth + 1, visited, pages);
+                }
+            }
+            _ => pages.push((dict, resources)),
+        }
+    }
+
+    /// Decoded content streams of a page
+    fn page_contents(&self, page: &Dict) -> Vec<Vec<u8>> {
+        let streams = match self.resolve(page.get("Contents")) {
+            Some(Object::Array(items)) => {
+                items.iter().filter_map(|i| self.resolve(Some(i))).collect()
+            }
+            Some(stream) => vec![stream],
+            None => Vec::new(),
+        };
+        streams.into_iter().filter_map(|stream| self.decode(stream)).collect()
+    }
+
+    /// Decoded data of a stream, charged to the document's inflate budget
+    fn decode(&self, object: &Object) -> Option<Vec<u8>> {
+        decode_stream(object, &self.inflate_budget)
+    }
+}
+
+/// Text string entry of a dictionary
+fn text_entry(file: &PdfFile, dict: &Dict, key: &str) -> Option<String> {
+    match file.resolve(dict.get(key)) {
+        Some(Object::String(bytes)) => {
+            let text = decode_text_string(bytes);
+            let text = text.trim();
+            (!text.is_empty()).then(|| text.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Decode a PDF text string: UTF-16BE with a BOM, UTF-8 with a BOM (PDF 2.0),
+/// otherwise PDFDocEncoding (approximated by windows-1252)
+fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(b"\xFE\xFF") {
+        let units: Vec<u16> =
+            utf16.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Some(utf8) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// Convert a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`) to RFC 3339
+fn parse_date(date: &str) -> Option<String> {
+    let date = date.trim().trim_start_matches("D:");
+    let digits: String = date.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 4 {
+        return None;
+    }
+    let field = |start: usize, default: &str| -> String {
+        digits.get(start..start + 2).unwrap_or(default).to_string()
+    };
+    let rest = &date[digits.len()..];
+    let offset = match rest.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let parts: Vec<&str> =
+                rest[1..].split(|c: char| !c.is_ascii_digit()).filter(|p| !p.is_empty()).collect();
+            format!(
+                "{}{}:{}",
+                sign,
+                parts.first().copied().unwrap_or("00"),
+                parts.get(1).copied().unwrap_or("00")
+            )
+        }
+        _ => "Z".to_string(),
+    };
+    let rfc3339 = format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &digits[..4],
+        field(4, "01"),
+        field(6, "01"),
+        field(8, "00"),
+        field(10, "00"),
+        field(12, "00"),
+        offset
+    );
+    chrono::DateTime::parse_from_rfc3339(&rfc3339).ok().map(|d| d.to_rfc3339())
+}
+
+/// Apply the stream's filters; `None` for unsupported filters or once
+/// `budget` decompressed bytes are used up
+fn decode_stream(object: &Object, budget: &std::cell::Cell<u64>) -> Option<Vec<u8>> {
+    let Object::Stream(dict, data) = object else {
+        return None;
+    };
+    let filters = match dict.get("Filter") {
+        Some(Object::Name(name)) => vec![name.as_str()],
+        Some(Object::Array(names)) => names.iter().filter_map(Object::as_name).collect(),
+        _ => Vec::new(),
+    };
+    let mut data = data.clone();
+    for filter in filters {
+        data = match filter {
+            "FlateDecode" | "Fl" => {
+                let mut out = Vec::new();
+                let decoder = flate2::read::ZlibDecoder::new(data.as_slice());
+                // Truncated streams are common; keep what was inflated
+                let limit = MAX_STREAM_BYTES.min(budget.get());
+                let _ = decoder.take(limit).read_to_end(&mut out);
+                budget.set(budget.get().saturating_sub(out.len() as u64));
+                if out.is_empty() {
+                    return None;
+                }
+                out
+            }
+            "ASCIIHexDecode" | "AHx" => decode_hex(&data),
+            _ => return None,
+        };
+    }
+    Some(data)
+}
+
+fn decode_hex(data: &[u8]) -> Vec<u8> {
+    let digits: Vec<u8> = data
+        .iter()
+        .take_while(|&&b| b != b'>')
+        .filter_map(|&b| (b as char).to_digit(16).map(|d| d as u8))
+        .collect();
+    digits.chunks(2).map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0)).collect()
+}
+
+/// Tokenizer and parser for PDF objects and content streams
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else if is_whitespace(b) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// An object, followed by its stream data when it is a stream dictionary
+    fn object_with_stream(&mut self) -> Option<Object> {
+        let object = self.object()?;
+        let Object::Dict(dict) = object else {
+            return Some(object);
+        };
+        self.skip_whitespace();
+        if !self.data[self.pos..].starts_with(b"stream") {
+            return Some(Object::Dict(dict));
+        }
+        self.pos += b"stream".len();
+        if self.peek() == Some(b'\r') {
+            self.pos += 1;
+        }
+        if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        let declared = dict
+            .get("Length")
+            .and_then(Object::as_number)
+            .filter(|n| n.is_finite() && *n >= 0.0)
+            .and_then(|n| start.checked_add(n as usize));
+        let end = declared
+            .filter(|&end| {
+                end <= self.data.len() && {
+                    let mut after = Lexer::new(self.data, end);
+                    after.skip_whitespace();
+                    self.data[after.pos..].starts_with(b"endstream")
+                }
+            })
+            .or_else(|| {
+                let found = self.data[start..].windows(9).position(|w| w == b"endstream")?;
+                let mut end = start + found;
+                while end > start && matches!(self.data[end - 1], b'\r' | b'\n') {
+                    end -= 1;
+                }
+                Some(end)
+            })?;
+        self.pos = end;
+        Some(Object::Stream(dict, self.data[start..end].to_vec()))
+    }
+
+    fn object(&mut self) -> Option<Object> {
+        self.nested_object(0)
+    }
+
+    /// An object inside `depth` arrays or dictionaries; `None` past [`MAX_NESTING`]
+    fn nested_object(&mut self, depth: usize) -> Option<Object> {
+        // Stray closing delimiters carry no object
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                b')' | b'>' | b']' | b'{' | b'}' => self.pos += 1,
+                _ => break,
+            }
+        }
+        let b = self.peek()?;
+        match b {
+            b'<' | b'[' if depth >= MAX_NESTING => None,
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let mut dict = HashMap::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.data[self.pos..].starts_with(b">>") {
+                        self.pos += 2;
+                        return Some(Object::Dict(dict));
+                    }
+                    let Object::Name(key) = self.nested_object(depth + 1)? else {
+                        return None;
+                    };
+                    let value = self.nested_object(depth + 1)?;
+                    dict.insert(key, value);
+                }
+            }
+            b'<' => {
+                self.pos += 1;
+                let end = self.data[self.pos..].iter().position(|&b| b == b'>')? + self.pos;
+                let bytes = decode_hex(&self.data[self.pos..end]);
+                self.pos = end + 1;
+                Some(Object::String(bytes))
+            }
+            b'(' => Some(Object::String(self.literal_string())),
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek()? == b']' {
+                        self.pos += 1;
+                        return Some(Object::Array(items));
+                    }
+                    items.push(self.nested_object(depth + 1)?);
+                }
+            }
+            b'/' => {
+                self.pos += 1;
+                Some(Object::Name(self.name()))
+            }
+            b'+' | b'-' | b'.' | b'0'..=b'9' => {
+                let number = self.number()?;
+                // `id gen R` reference
+                let save = self.pos;
+                if number.fract() == 0.0 && number >= 0.0 {
+                    self.skip_whitespace();
+                    if self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                        let _generation = self.number();
+                        self.skip_whitespace();
+                        if self.peek() == Some(b'R')
+                            && self.data.get(self.pos + 1).map_or(true, |&b| is_delimiter(b))
+                        {
+                            self.pos += 1;
+                            return Some(Object::Ref(number as u32));
+                        }
+                    }
+                }
+                self.pos = save;
+                Some(Object::Number(number))
+            }
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|b| !is_delimiter(b)) {
+                    self.pos += 1;
+                }
+                let word = String::from_utf8_lossy(&self.data[start..self.pos]).into_owned();
+                Some(match word.as_str() {
+                    "true" => Object::Bool(true),
+                    "false" => Object::Bool(false),
+                    "null" => Object::Null,
+                    _ => Object::Operator(word),
+                })
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| matches!(b, b'+' | b'-' | b'.' | b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.pos]).ok()?.parse().ok().or(Some(0.0))
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = Vec::new();
+        while let Some(b) = self.peek().filter(|&b| !is_delimiter(b)) {
+            if b == b'#' {
+                let hex = self.data.get(self.pos + 1..self.pos + 3).unwrap_or_default();
+                if let Some(value) =
+                    std::str::from_utf8(hex).ok().and_then(|h| u8::from_str_radix(h, 16).ok())
+                {
+                    name.push(value);
+                    self.pos += 3;
+                    continue;
+                }
+            }
+            name.push(b);
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&name).into_owned()
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut out = Vec::new();
+        let mut depth = 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    out.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    out.push(b);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {
+                        break;
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'b' => out.push(8),
+                        b'f' => out.push(12),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        // Line continuation
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => out.push(other),
+                    }
+                }
+                _ => out.push(b),
+            }
+        }
+        out
+    }
+
+    /// Skip inline image data after the `ID` operator
+    fn skip_inline_image(&mut self) {
+        while self.pos + 2 < self.data.len() {
+            if is_whitespace(self.data[self.pos])
+                && &self.data[self.pos + 1..self.pos + 3] == b"EI"
+                && self.data.get(self.pos + 3).map_or(true, |&b| is_delimiter(b))
+            {
+                self.pos += 3;
+                return;
+            }
+            self.pos += 1;
+        }
+        self.pos = self.data.len();
+    }
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n' | b'\x0c' | b'\0')
+}
+
+fn is_delimiter(b: u8) -> bool {
+    is_whitespace(b)
+        || matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+/// Code to text mapping of a font's `/ToUnicode` CMap
+#[derive(Debug, Default)]
+struct ToUnicode {
+    /// Code length in bytes
+    width: usize,
+    map: HashMap<u32, String>,
+}
+
+impl ToUnicode {
+    fn parse(data: &[u8]) -> Self {
+        let mut cmap = ToUnicode { width: 1, map: HashMap::new() };
+        let mut lexer = Lexer::new(data, 0);
+        let mut operands: Vec<Object> = Vec::new();
+        while let Some(object) = lexer.object() {
+            let Object::Operator(op) = object else {
+                operands.push(object);
+                continue;
+            };
+            match op.as_str() {
+                "endcodespacerange" => {
+                    if let Some(Object::String(lo)) = operands.first() {
+                        cmap.width = lo.len().clamp(1, 4);
+                    }
+                }
+                "endbfchar" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let (Object::String(src), Object::String(dst)) = (&pair[0], &pair[1]) {
+                            cmap.width = cmap.width.max(src.len().min(4));
+                            cmap.map.insert(code(src), utf16_text(dst));
+                        }
+                    }
+                }
+                "endbfrange" => {
+                    for triple in operands.chunks_exact(3) {
+                        let (Object::String(lo), Object::String(hi)) = (&triple[0], &triple[1])
+                        else {
+                            continue;
+                        };
+                        cmap.width = cmap.width.max(lo.len().min(4));
+                        let (lo, hi) = (code(lo), code(hi));
+                        if hi < lo || hi - lo > 0xFFFF {
+                            continue;
+                        }
+                        match &triple[2] {
+                            Object::String(dst) => {
+                                let mut units: Vec<u16> = dst
+                                    .chunks_exact(2)
+                                    .map(|p| u16::from_be_bytes([p[0], p[1]]))
+                                    .collect();
+                                for c in lo..=hi {
+                                    cmap.map.insert(c, String::from_utf16_lossy(&units));
+                                    if let Some(last) = units.last_mut() {
+                                        *last = last.wrapping_add(1);
+                                    }
+                                }
+                            }
+                            Object::Array(dsts) => {
+                                for (c, dst) in (lo..=hi).zip(dsts) {
+                                    if let Object::String(dst) = dst {
+                                        cmap.map.insert(c, utf16_text(dst));
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if op.starts_with("begin") || op.starts_with("end") {
+                operands.clear();
+            }
+        }
+        cmap
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        bytes
+            .chunks(self.width)
+            .filter_map(|chunk| self.map.get(&code(chunk)).map(String::as_str))
+            .collect()
+    }
+}
+
+fn code(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, &b| (acc << 8) | u32::from(b))
+}
+
+fn utf16_text(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|p| u16::from_be_bytes([p[0], p[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// How the bytes of a shown string map to text
+enum FontDecoder {
+    /// Simple font without a CMap: one byte per glyph, WinAnsi
+    WinAnsi,
+    ToUnicode(ToUnicode),
+    /// Composite font without a CMap: glyph ids only, text unrecoverable
+    Unknown,
+}
+
+impl FontDecoder {
+    fn load(file: &PdfFile, font: &Dict) -> Self {
+        if let Some(stream) = file.resolve(font.get("ToUnicode")) {
+            if let Some(data) = file.decode(stream) {
+                return FontDecoder::ToUnicode(ToUnicode::parse(&data));
+            }
+        }
+        match font.get("Subtype").and_then(Object::as_name) {
+            Some("Type0") => FontDecoder::Unknown,
+            _ => FontDecoder::WinAnsi,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            FontDecoder::WinAnsi => {
+                encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned()
+            }
+            FontDecoder::ToUnicode(cmap) => cmap.decode(bytes),
+            FontDecoder::Unknown => String::new(),
+        }
+    }
+}
+
+/// Collects the text shown by content stream operators
+#[derive(Default)]
+struct TextExtractor {
+    text: String,
+    /// Fonts already loaded, by resource dictionary address and name
+    fonts: HashMap<(usize, String), std::rc::Rc<FontDecoder>>,
+    font: Option<std::rc::Rc<FontDecoder>>,
+    line_y: Option<f64>,
+}
+
+impl TextExtractor {
+    fn run(&mut self, file: &PdfFile, content: &[u8], resources: Option<&Dict>, depth: usize) {
+        let mut lexer = Lexer::new(content, 0);
+        let mut operands: Vec<Object> = Vec::new();
+        while let Some(object) = lexer.object() {
+            let Object::Operator(op) = object else {
+                operands.push(object);
+                continue;
+            };
+            match op.as_str() {
+                "Tf" => {
+                    if let Some(name) = operands.first().and_then(Object::as_name) {
+                        self.font = Some(self.font(file, resources, name));
+                    }
+                }
+                "Tj" => {
+                    if let Some(Object::String(s)) = operands.last() {
+                        self.show(s);
+                    }
+                }
+                "'" | "\"" => {
+                    self.newline();
+                    if let Some(Object::String(s)) = operands.last() {
+                        self.show(s);
+                    }
+                }
+                "TJ" => {
+                    if let Some(Object::Array(items)) = operands.last() {
+                        for item in items {
+                            match item {
+                                Object::String(s) => self.show(s),
+                                // Large negative kerning is a word gap
+                                Object::Number(n) if *n < -200.0 => self.space(),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "Td" | "TD" => {
+                    if operands.get(1).and_then(Object::as_number).is_some_and(|ty| ty != 0.0) {
+                        self.newline();
+                    } else {
+                        self.space();
+                    }
+                }
+                "Tm" => {
+                    let y = operands.get(5).and_then(Object::as_number);
+                    if self.line_y.is_some() && y != self.line_y {
+                        self.newline();
+                    } else {
+                        self.space();
+                    }
+                    self.line_y = y;
+                }
+                "T*" => self.newline(),
+                "BT" => self.line_y = None,
+                "ET" => self.newline(),
+                "ID" => lexer.skip_inline_image(),
+                "Do" if depth < MAX_NESTING => {
+                    if let Some(name) = operands.first().and_then(Object::as_name) {
+                        self.form(file, resources, name, depth);
+                    }
+                }
+                _ => {}
+            }
+            operands.clear();
+        }
+    }
+
+    /// Run a form XObject's content stream
+    fn form(&mut self, file: &PdfFile, resources: Option<&Dict>, name: &str, depth: usize) {
+        let Some(xobjects) = resources.and_then(|r| file.resolve_dict(r.get("XObject"))) else {
+            return;
+        };
+        let Some(stream @ Object::Stream(dict, _)) = file.resolve(xobjects.get(name)) else {
+            return;
+        };
+        if dict.get("Subtype").and_then(Object::as_name) != Some("Form") {
+            return;
+        }
+        if let Some(content) = file.decode(stream) {
+            let form_resources = file.resolve_dict(dict.get("Resources")).or(resources);
+            let font = self.font.take();
+            self.run(file, &content, form_resources, depth + 1);
+            self.font = font;
+        }
+    }
+
+    fn font(
+        &mut self,
+        file: &PdfFile,
+        resources: Option<&Dict>,
+        name: &str,
+    ) -> std::rc::Rc<FontDecoder> {
+        let key = (resources.map_or(0, |r| r as *const _ as usize), name.to_string());
+        if let Some(decoder) = self.fonts.get(&key) {
+            return decoder.clone();
+        }
+        let decoder = resources
+            .and_then(|r| file.resolve_dict(r.get("Font")))
+            .and_then(|fonts| file.resolve_dict(fonts.get(name)))
+            .map_or(FontDecoder::WinAnsi, |font| FontDecoder::load(file, font));
+        let decoder = std::rc::Rc::new(decoder);
+        self.fonts.insert(key, decoder.clone());
+        decoder
+    }
+
+    fn show(&mut self, bytes: &[u8]) {
+        let text = match &self.font {
+            Some(font) => font.decode(bytes),
+            None => FontDecoder::WinAnsi.decode(bytes),
+        };
+        self.text.push_str(&text);
+    }
+
+    fn space(&mut self) {
+        if !self.text.ends_with(char::is_whitespace) && !self.text.is_empty() {
+            self.text.push(' ');
+        }
+    }
+
+    fn newline(&mut self) {
+        while self.text.ends_with(' ') {
+            self.text.pop();
+        }
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+    }
+
+    fn finish(self) -> String {
+        self.text
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexer_objects() {
+        let data = br"<< /Type /Page /Parent 3 0 R /MediaBox [0 0 612.5 -1] /N#20ame (a\(b\)\101\
+c) /Hex <48656c6c6f> /Flag true >>";
+        let Some(Object::Dict(dict)) = Lexer::new(data, 0).object() else {
+            panic!("expected dictionary");
+        };
+        assert_eq!(dict["Type"], Object::Name("Page".to_string()));
+        assert_eq!(dict["Parent"], Object::Ref(3));
+        assert_eq!(
+            dict["MediaBox"],
+            Object::Array(vec![
+                Object::Number(0.0),
+                Object::Number(0.0),
+                Object::Number(612.5),
+                Object::Number(-1.0)
+            ])
+        );
+        assert_eq!(dict["N ame"], Object::String(b"a(b)Ac".to_vec()));
+        assert_eq!(dict["Hex"], Object::String(b"Hello".to_vec()));
+        assert_eq!(dict["Flag"], Object::Bool(true));
+    }
+
+    #[test]
+    fn test_lexer_hostile_input() {
+        // Long runs of stray delimiters and deep nesting must not exhaust the stack
+        let mut strays = vec![b')'; 100_000];
+        strays.extend_from_slice(b" 42");
+        assert_eq!(Lexer::new(&strays, 0).object(), Some(Object::Number(42.0)));
+        let nested = vec![b'['; 100_000];
+        assert_eq!(Lexer::new(&nested, 0).object(), None);
+        let dicts = b"<< /A ".repeat(100_000);
+        assert_eq!(Lexer::new(&dicts, 0).object(), None);
+
+        let shallow = format!("{}1{}", "[".repeat(MAX_NESTING), "]".repeat(MAX_NESTING));
+        assert!(Lexer::new(shallow.as_bytes(), 0).object().is_some());
+        let deep = format!("[{}", shallow);
+        assert_eq!(Lexer::new(deep.as_bytes(), 0).object(), None);
+
+        // Absurd or negative lengths fall back to scanning for `endstream`
+        for length in ["1000000000000000000000000000000", "-5", "18446744073709551615"] {
+            let data = format!("<< /Length {} >>\nstream\nabc\nendstream", length);
+            let Some(Object::Stream(_, bytes)) =
+                Lexer::new(data.as_bytes(), 0).object_with_stream()
+            else {
+                panic!("expected stream for /Length {}", length);
+            };
+            assert_eq!(bytes, b"abc");
+        }
+    }
+
+    #[test]
+    fn test_inflate_budget() {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &[b'x'; 1000]).unwrap();
+        let mut dict = HashMap::new();
+        dict.insert("Filter".to_string(), Object::Name("FlateDecode".to_string()));
+        let stream = Object::Stream(dict, encoder.finish().unwrap());
+
+        let budget = std::cell::Cell::new(1500);
+        assert_eq!(decode_stream(&stream, &budget).map(|d| d.len()), Some(1000));
+        assert_eq!(decode_stream(&stream, &budget).map(|d| d.len()), Some(500));
+        assert_eq!(budget.get(), 0);
+        assert_eq!(decode_stream(&stream, &budget), None);
+    }
+
+    #[test]
+    fn test_text_strings_and_dates() {
+        assert_eq!(decode_text_string(b"\xFE\xFF\x00C\x00a\x00f\x00\xE9"), "Café");
+        assert_eq!(decode_text_string(b"Caf\xE9 \x93quoted\x94"), "Café \u{201c}quoted\u{201d}");
+        assert_eq!(
+            parse_date("D:20240131120530+01'00'").as_deref(),
+            Some("2024-01-31T12:05:30+01:00")
+        );
+        assert_eq!(parse_date("D:2023").as_deref(), Some("2023-01-01T00:00:00+00:00"));
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_to_unicode_cmap() {
+        let cmap = ToUnicode::parse(
+            b"begincmap 1 begincodespacerange <0000> <FFFF> endcodespacerange
+              2 beginbfchar <0003> <0020> <0024> <00410042> endbfchar
+              1 beginbfrange <0010> <0012> <0061> endbfrange
+              1 beginbfrange <0020> <0021> [<00DF> <00E6>] endbfrange endcmap",
+        );
+        assert_eq!(cmap.width, 2);
+        assert_eq!(
+            cmap.decode(b"\x00\x10\x00\x11\x00\x12\x00\x03\x00\x24\x00\x20\x00\x21"),
+            "abc ABßæ"
+        );
+    }
+
+    #[test]
+    fn test_content_stream_text() {
+        let file = PdfFile {
+            objects: HashMap::new(),
+            trailers: Vec::new(),
+            inflate_budget: std::cell::Cell::new(MAX_DOCUMENT_INFLATE_BYTES),
+        };
+        let mut text = TextExtractor::default();
+        text.run(
+            &file,
+            b"BT /F1 12 Tf 72 720 Td (Hello, ) Tj [(W) 80 (orld) -300 (again)] TJ 0 -14 Td (Line two) Tj T* (three) Tj ET
+              BI /W 1 /H 1 ID \x00\xFF EI",
+            None,
+            0,
+        );
+        assert_eq!(text.finish(), "Hello, World again\nLine two\nthree");
+    }
+}
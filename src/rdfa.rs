@@ -0,0 +1,510 @@
+//! RDFa 1.1 extraction
+//!
+//! Implements the RDFa 1.1 Core processing sequence (section 7.5) for HTML,
+//! which covers RDFa Lite (`vocab`, `typeof`, `property`, `resource`,
+//! `prefix`) and the commonly used Core attributes (`about`, `rel`, `rev`,
+//! `href`, `src`, `content`, `datatype`, `lang`).
+//!
+//! The output is a flat list of [`Triple`]s ready for
+//! [`KnowledgeGraph::insert_triples`](crate::kg::KnowledgeGraph::insert_triples).
+//! Not supported: `inlist` (values are emitted as plain repeated properties),
+//! XML literals (serialised as text) and the `rdfa:usesVocabulary` triple.
+
+use crate::jsonld::{is_absolute_iri, normalize_schema_org};
+use crate::kg::{Term, Triple};
+use crate::parser::resolve_against;
+use scraper::{ElementRef, Html};
+use std::collections::HashMap;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// Prefixes of the RDFa 1.1 initial context most often found in the wild
+const INITIAL_PREFIXES: &[(&str, &str)] = &[
+    ("as", "https://www.w3.org/ns/activitystreams#"),
+    ("cc", "http://creativecommons.org/ns#"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dc11", "http://purl.org/dc/elements/1.1/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("gr", "http://purl.org/goodrelations/v1#"),
+    ("og", "http://ogp.me/ns#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfa", "http://www.w3.org/ns/rdfa#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("schema", "http://schema.org/"),
+    ("sioc", "http://rdfs.org/sioc/ns#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("vcard", "http://www.w3.org/2006/vcard/ns#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("xhv", "http://www.w3.org/1999/xhtml/vocab#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// Extract RDFa triples from a parsed HTML document
+///
+/// `base_url` is the document IRI: it is the default subject and resolves
+/// relative IRIs. A `<base href>` in the document takes precedence. Without
+/// any base, relative IRIs are kept as written and will be skipped on insert.
+pub fn extract_rdfa(document: &Html, base_url: Option<&str>) -> Vec<Triple> {
+    let base = crate::parser::document_base(document, base_url);
+    let mut processor = Processor { base, triples: Vec::new(), next_blank: 0 };
+
+    let context = EvaluationContext {
+        parent_subject: Term::iri(processor.base_iri()),
+        parent_object: None,
+        incomplete: Vec::new(),
+        language: None,
+        prefixes: INITIAL_PREFIXES
+            .iter()
+            .map(|(prefix, iri)| (prefix.to_string(), iri.to_string()))
+            .collect(),
+        vocab: None,
+    };
+    processor.element(document.root_element(), &context, true);
+
+    processor.triples
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+#[derive(Debug, Clone)]
+struct EvaluationContext {
+    parent_subject: Term,
+    parent_object: Option<Term>,
+    incomplete: Vec<(String, Direction)>,
+    language: Option<String>,
+    prefixes: HashMap<String, String>,
+    vocab: Option<String>,
+}
+
+struct Processor {
+    base: Option<url::Url>,
+    triples: Vec<Triple>,
+    next_blank: usize,
+}
+
+impl Processor {
+    fn base_iri(&self) -> String {
+        self.base.as_ref().map(|b| b.to_string()).unwrap_or_default()
+    }
+
+    fn fresh_blank(&mut self) -> Term {
+        self.next_blank += 1;
+        Term::BlankNode(format!("rdfa{}", self.next_blank))
+    }
+
+    /// Expand a CURIE, term or absolute IRI (`property`, `rel`, `typeof`, ...)
+    fn expand_term(&self, value: &str, ctx: &EvaluationContext) -> Option<Term> {
+        if let Some((prefix, reference)) = value.split_once(':') {
+            if prefix == "_" {
+                return Some(Term::BlankNode(format!("rdfa-{}", reference)));
+            }
+            if let Some(iri) = ctx.prefixes.get(&prefix.to_lowercase()) {
+                return Some(Term::iri(normalize_schema_org(&format!("{}{}", iri, reference))));
+            }
+            if is_absolute_iri(value) {
+                return Some(Term::iri(normalize_schema_org(value)));
+            }
+            return None;
+        }
+        ctx.vocab
+            .as_ref()
+            .map(|vocab| Term::iri(normalize_schema_org(&format!("{}{}", vocab, value))))
+    }
+
+    /// Expand a SafeCURIE, CURIE or relative IRI (`about`, `resource`)
+    fn expand_resource(&self, value: &str, ctx: &EvaluationContext) -> Option<Term> {
+        let value = value.trim();
+        if let Some(safe) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return if safe.contains(':') {
+                self.expand_term(safe, ctx)
+            } else {
+                None
+            };
+        }
+        if let Some((prefix, _)) = value.split_once(':') {
+            if prefix == "_" || ctx.prefixes.contains_key(&prefix.to_lowercase()) {
+                return self.expand_term(value, ctx);
+            }
+        }
+        Some(Term::iri(normalize_schema_org(&resolve_against(self.base.as_ref(), value))))
+    }
+
+    fn expand_list(&self, value: Option<&str>, ctx: &EvaluationContext) -> Vec<Term> {
+        value
+            .map(|v| v.split_whitespace().filter_map(|t| self.expand_term(t, ctx)).collect())
+            .unwrap_or_default()
+    }
+
+    fn element(&mut self, element: ElementRef<'_>, ctx: &EvaluationContext, is_root: bool) {
+        let attrs = element.value();
+        let attr = |name: &str| attrs.attr(name);
+
+        // Steps 2-4: vocabulary, prefix mappings and language
+        let mut local = ctx.clone();
+        if let Some(vocab) = attr("vocab") {
+            let vocab = vocab.trim();
+            local.vocab = if vocab.is_empty() {
+                None
+            } else {
+                Some(normalize_schema_org(&resolve_against(self.base.as_ref(), vocab)))
+            };
+        }
+        if let Some(prefix) = attr("prefix") {
+            let mut tokens = prefix.split_whitespace();
+            while let (Some(name), Some(iri)) = (tokens.next(), tokens.next()) {
+                if let Some(name) = name.strip_suffix(':') {
+                    if !name.is_empty() && name != "_" {
+                        local.prefixes.insert(name.to_lowercase(), iri.to_string());
+                    }
+                }
+            }
+        }
+        if let Some(lang) = attr("lang").or_else(|| attr("xml:lang")) {
+            local.language = if lang.is_empty() {
+                None
+            } else {
+                Some(lang.to_lowercase())
+            };
+        }
+
+        let property = attr("property");
+        let has_content_attrs = attr("content").is_some() || attr("datatype").is_some();
+        let about = attr("about").and_then(|v| self.expand_resource(v, &local));
+        let resource = attr("resource")
+            .and_then(|v| self.expand_resource(v, &local))
+            .or_else(|| attr("href").map(|v| Term::iri(resolve_against(self.base.as_ref(), v))))
+            .or_else(|| attr("src").map(|v| Term::iri(resolve_against(self.base.as_ref(), v))));
+        let has_typeof = attr("typeof").is_some();
+
+        // HTML+RDFa: with @property present, plain-term @rel/@rev values are ignored
+        let link_values = |name: &str| -> Option<&str> {
+            let value = attr(name)?;
+            if property.is_some() && !value.split_whitespace().any(|t| t.contains(':')) {
+                None
+            } else {
+                Some(value)
+            }
+        };
+        let rels = self.expand_list(link_values("rel"), &local);
+        let revs = self.expand_list(link_values("rev"), &local);
+        let has_links = link_values("rel").is_some() || link_values("rev").is_some();
+
+        let mut skip = false;
+        let mut new_subject: Option<Term>;
+        let mut current_object: Option<Term> = None;
+        let mut typed_resource: Option<Term> = None;
+
+        if !has_links {
+            // Step 5
+            if property.is_some() && !has_content_attrs {
+                new_subject = about.clone().or_else(|| {
+                    if is_root {
+                        Some(Term::iri(self.base_iri()))
+                    } else {
+                        ctx.parent_object.clone()
+                    }
+                });
+                if has_typeof {
+                    typed_resource = match (&about, is_root) {
+                        (Some(about), _) => Some(about.clone()),
+                        (None, true) => Some(Term::iri(self.base_iri())),
+                        (None, false) => {
+                            Some(resource.clone().unwrap_or_else(|| self.fresh_blank()))
+                        }
+                    };
+                    current_object = typed_resource.clone();
+                }
+            } else {
+                new_subject = about.clone().or_else(|| resource.clone());
+                if new_subject.is_none() {
+                    if is_root {
+                        new_subject = Some(Term::iri(self.base_iri()));
+                    } else if has_typeof {
+                        new_subject = Some(self.fresh_blank());
+                    } else if let Some(parent_object) = &ctx.parent_object {
+                        new_subject = Some(parent_object.clone());
+                        skip = property.is_none();
+                    }
+                }
+                if has_typeof {
+                    typed_resource = new_subject.clone();
+                }
+            }
+        } else {
+            // Step 6
+            new_subject = about.clone();
+            if has_typeof {
+                typed_resource = new_subject.clone();
+            }
+            if new_subject.is_none() {
+                new_subject = if is_root {
+                    Some(Term::iri(self.base_iri()))
+                } else {
+                    ctx.parent_object.clone()
+                };
+            }
+            current_object = resource.clone();
+            if current_object.is_none() && has_typeof && about.is_none() {
+                current_object = Some(self.fresh_blank());
+            }
+            if has_typeof && about.is_none() {
+                typed_resource = current_object.clone();
+            }
+        }
+
+        // Step 7: types
+        if let Some(typed) = &typed_resource {
+            for ty in self.expand_list(attr("typeof"), &local) {
+                self.triples.push(Triple::new(typed.clone(), RDF_TYPE, ty));
+            }
+        }
+
+        // Steps 9-10: links, complete or pending
+        let mut incomplete = Vec::new();
+        if let Some(subject) = &new_subject {
+            if let Some(object) = &current_object {
+                if has_links {
+                    for rel in &rels {
+                        if let Term::Iri(predicate) = rel {
+                            self.triples.push(Triple::new(
+                                subject.clone(),
+                                predicate.clone(),
+                                object.clone(),
+                            ));
+                        }
+                    }
+                    for rev in &revs {
+                        if let Term::Iri(predicate) = rev {
+                            self.triples.push(Triple::new(
+                                object.clone(),
+                                predicate.clone(),
+                                subject.clone(),
+                            ));
+                        }
+                    }
+                }
+            } else if has_links {
+                current_object = Some(self.fresh_blank());
+                for rel in &rels {
+                    if let Term::Iri(predicate) = rel {
+                        incomplete.push((predicate.clone(), Direction::Forward));
+                    }
+                }
+                for rev in &revs {
+                    if let Term::Iri(predicate) = rev {
+                        incomplete.push((predicate.clone(), Direction::Backward));
+                    }
+                }
+            }
+        }
+
+        // Step 11: property values
+        if let (Some(subject), Some(_)) = (&new_subject, property) {
+            let value =
+                self.property_value(element, &local, has_links, &resource, &about, &typed_resource);
+            for predicate in self.expand_list(property, &local) {
+                if let Term::Iri(predicate) = predicate {
+                    self.triples.push(Triple::new(subject.clone(), predicate, value.clone()));
+                }
+            }
+        }
+
+        // Step 12: complete the parent's pending links
+        if !skip {
+            if let Some(subject) = &new_subject {
+                for (predicate, direction) in &ctx.incomplete {
+                    let triple = match direction {
+                        Direction::Forward => Triple::new(
+                            ctx.parent_subject.clone(),
+                            predicate.clone(),
+                            subject.clone(),
+                        ),
+                        Direction::Backward => Triple::new(
+                            subject.clone(),
+                            predicate.clone(),
+                            ctx.parent_subject.clone(),
+                        ),
+                    };
+                    self.triples.push(triple);
+                }
+            }
+        }
+
+        // Step 13: recurse with the new evaluation context
+        let child_ctx = if skip {
+            EvaluationContext {
+                language: local.language,
+                prefixes: local.prefixes,
+                vocab: local.vocab,
+                ..ctx.clone()
+            }
+        } else {
+            let parent_subject = new_subject.clone().unwrap_or_else(|| ctx.parent_subject.clone());
+            EvaluationContext {
+                parent_object: current_object
+                    .or(new_subject)
+                    .or_else(|| Some(ctx.parent_subject.clone())),
+                parent_subject,
+                incomplete,
+                language: local.language,
+                prefixes: local.prefixes,
+                vocab: local.vocab,
+            }
+        };
+
+        for child in element.children().filter_map(ElementRef::wrap) {
+            self.element(child, &child_ctx, false);
+        }
+    }
+
+    fn property_value(
+        &self,
+        element: ElementRef<'_>,
+        ctx: &EvaluationContext,
+        has_links: bool,
+        resource: &Option<Term>,
+        about: &Option<Term>,
+        typed_resource: &Option<Term>,
+    ) -> Term {
+        let attrs = element.value();
+        let text = || {
+            attrs.attr("content").map(str::to_string).unwrap_or_else(|| element.text().collect())
+        };
+
+        if let Some(datatype) = attrs.attr("datatype") {
+            return match self.expand_term(datatype.trim(), ctx) {
+                Some(Term::Iri(datatype)) => Term::typed_literal(text(), datatype),
+                _ => Term::literal(text()),
+            };
+        }
+        if let Some(content) = attrs.attr("content") {
+            return self.plain_literal(content.to_string(), ctx);
+        }
+        if !has_links {
+            if let Some(resource) = resource {
+                return resource.clone();
+            }
+            if about.is_none() {
+                if let Some(typed) = typed_resource {
+                    return typed.clone();
+                }
+            }
+        }
+        self.plain_literal(element.text().collect(), ctx)
+    }
+
+    fn plain_literal(&self, value: String, ctx: &EvaluationContext) -> Term {
+        match &ctx.language {
+            Some(lang) => Term::language_literal(value, lang.clone()),
+            None => Term::literal(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rdfa(html: &str, base: &str) -> Vec<Triple> {
+        extract_rdfa(&Html::parse_document(html), Some(base))
+    }
+
+    fn has(triples: &[Triple], subject: &Term, predicate: &str, object: &Term) -> bool {
+        triples
+            .iter()
+            .any(|t| &t.subject == subject && t.predicate == predicate && &t.object == object)
+    }
+
+    #[test]
+    fn test_rdfa_lite_person() {
+        let html = r##"<html><body>
+            <div vocab="https://schema.org/" typeof="Person" resource="#me">
+              <span property="name">Alice</span>
+              <a property="url" href="https://alice.example">homepage</a>
+              <div property="address" typeof="PostalAddress">
+                <span property="addressLocality">Rome</span>
+              </div>
+            </div>
+            </body></html>"##;
+        let triples = rdfa(html, "https://example.org/about");
+        let me = Term::iri("https://example.org/about#me");
+
+        assert!(has(&triples, &me, RDF_TYPE, &Term::iri("https://schema.org/Person")));
+        assert!(has(&triples, &me, "https://schema.org/name", &Term::literal("Alice")));
+        assert!(has(&triples, &me, "https://schema.org/url", &Term::iri("https://alice.example/")));
+
+        let address = triples
+            .iter()
+            .find(|t| t.subject == me && t.predicate == "https://schema.org/address")
+            .map(|t| t.object.clone())
+            .expect("address triple");
+        assert!(matches!(address, Term::BlankNode(_)));
+        assert!(has(
+            &triples,
+            &address,
+            "https://schema.org/addressLocality",
+            &Term::literal("Rome")
+        ));
+    }
+
+    #[test]
+    fn test_rdfa_prefix_datatype_and_language() {
+        let html = r#"<html prefix="ex: http://example.com/ns#" lang="en"><head>
+            <title property="dc:title">Report</title>
+            <meta property="ex:published" content="2024-05-01" datatype="xsd:date">
+            </head><body>
+            <p about="/doc/2" property="dc:description" lang="fr">Rapport</p>
+            <link rel="stylesheet" href="style.css">
+            </body></html>"#;
+        let triples = rdfa(html, "https://gov.example/doc/1");
+        let doc = Term::iri("https://gov.example/doc/1");
+
+        assert!(has(
+            &triples,
+            &doc,
+            "http://purl.org/dc/terms/title",
+            &Term::language_literal("Report", "en")
+        ));
+        assert!(has(
+            &triples,
+            &doc,
+            "http://example.com/ns#published",
+            &Term::typed_literal("2024-05-01", "http://www.w3.org/2001/XMLSchema#date")
+        ));
+        assert!(has(
+            &triples,
+            &Term::iri("https://gov.example/doc/2"),
+            "http://purl.org/dc/terms/description",
+            &Term::language_literal("Rapport", "fr")
+        ));
+        // Plain HTML link types without a vocabulary produce nothing
+        assert_eq!(triples.len(), 3);
+    }
+
+    #[test]
+    fn test_rdfa_rel_with_pending_objects() {
+        let html = r#"<html><body>
+            <div about="http://lib.example/book" rel="dc:creator">
+              <span about="http://lib.example/a1"></span>
+              <span typeof="foaf:Person"><span property="foaf:name">Bob</span></span>
+            </div>
+            </body></html>"#;
+        let triples = rdfa(html, "http://lib.example/");
+        let book = Term::iri("http://lib.example/book");
+        let creators: Vec<&Triple> = triples
+            .iter()
+            .filter(|t| t.subject == book && t.predicate == "http://purl.org/dc/terms/creator")
+            .collect();
+
+        assert_eq!(creators.len(), 2);
+        assert!(creators.iter().any(|t| t.object == Term::iri("http://lib.example/a1")));
+    }
+}
@@ -0,0 +1,611 @@
+//! Main content extraction (boilerplate removal)
+//!
+//! A port of the core heuristics of Mozilla Readability: paragraph-like blocks
+//! are scored (commas, length), their scores propagate to the enclosing
+//! containers, and the best container (weighted by class/id hints and link
+//! density) plus its related siblings is taken as the article. Navigation,
+//! cookie banners, sidebars and footers are skipped before scoring.
+
+use crate::external::normalize_whitespace;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minimum article length (in characters) before falling back to the full page text
+const MIN_ARTICLE_CHARS: usize = 250;
+
+/// How many ancestors of a paragraph receive part of its score
+const SCORE_ANCESTOR_LEVELS: usize = 5;
+
+/// Class/id fragments of elements that are almost never main content
+const UNLIKELY_CANDIDATES: &[&str] = &[
+    "-ad-",
+    "ad-break",
+    "agegate",
+    "banner",
+    "breadcrumb",
+    "combx",
+    "comment",
+    "community",
+    "consent",
+    "cookie",
+    "disqus",
+    "footer",
+    "gdpr",
+    "header",
+    "legends",
+    "menu",
+    "modal",
+    "navbar",
+    "newsletter",
+    "pager",
+    "pagination",
+    "popup",
+    "related",
+    "remark",
+    "replies",
+    "share",
+    "shoutbox",
+    "sidebar",
+    "skyscraper",
+    "social",
+    "sponsor",
+    "subscribe",
+    "supplemental",
+];
+
+/// Class/id fragments that rescue an otherwise unlikely element
+const MAYBE_CANDIDATES: &[&str] = &["and", "article", "body", "column", "content", "main"];
+
+const POSITIVE_HINTS: &[&str] =
+    &["article", "blog", "body", "content", "entry", "main", "page", "post", "story", "text"];
+
+const NEGATIVE_HINTS: &[&str] = &[
+    "-ad-", "banner", "combx", "comment", "contact", "footer", "hidden", "masthead", "meta",
+    "outbrain", "promo", "related", "share", "shopping", "sidebar", "sponsor", "tags", "widget",
+];
+
+/// Elements whose subtree never contributes to the article
+const SKIPPED_TAGS: &[&str] = &[
+    "aside", "button", "dialog", "footer", "form", "header", "iframe", "nav", "noscript", "script",
+    "select", "style", "svg", "template",
+];
+
+const UNLIKELY_ROLES: &[&str] =
+    &["alert", "alertdialog", "banner", "complementary", "dialog", "menu", "menubar", "navigation"];
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Which text is used as a page's `text_content` (NER input, previews, LLM context)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentMode {
+    /// Main article text, falling back to the full page text when no article is found
+    #[default]
+    Readability,
+    /// All text of the main landmark (`<main>`, `<article>`, ...) or of `<body>`
+    FullText,
+}
+
+/// Main content of a page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    /// Article title (`og:title`, first `<h1>` or `<title>`)
+    pub title: Option<String>,
+    /// Author line, e.g. "Jane Doe"
+    pub byline: Option<String>,
+    /// Publication date as found in the page (usually ISO 8601)
+    pub published: Option<String>,
+    /// Absolute URL of the lead image
+    pub lead_image: Option<String>,
+    /// Article text, one paragraph per block separated by blank lines
+    pub text: String,
+}
+
+/// Text for `text_content` according to `mode`, plus the article when one was found
+pub fn extract_text(
+    document: &Html,
+    base_url: Option<&str>,
+    mode: ContentMode,
+) -> (String, Option<Article>) {
+    if mode == ContentMode::Readability {
+        if let Some(article) = extract_article(document, base_url) {
+            return (article.text.clone(), Some(article));
+        }
+    }
+    (full_text(document), None)
+}
+
+/// Full text of the main landmark (`<main>`, `<article>`, ...) or of `<body>`
+pub fn full_text(document: &Html) -> String {
+    let selectors = ["main", "article", "[role='main']", ".content", "#content"];
+    for selector_str in &selectors {
+        if let Ok(selector) = Selector::parse(selector_str) {
+            if let Some(element) = document.select(&selector).next() {
+                let text = normalize_whitespace(&element.text().collect::<Vec<_>>().join(" "));
+                if !text.is_empty() {
+                    return text;
+                }
+            }
+        }
+    }
+
+    if let Ok(body_selector) = Selector::parse("body") {
+        if let Some(body) = document.select(&body_selector).next() {
+            return normalize_whitespace(&body.text().collect::<Vec<_>>().join(" "));
+        }
+    }
+
+    String::new()
+}
+
+/// Extract the main article of a page, or `None` if no block of content stands out
+///
+/// `base_url` resolves a relative lead image; a `<base href>` in the document
+/// takes precedence.
+pub fn extract_article(document: &Html, base_url: Option<&str>) -> Option<Article> {
+    let body = Selector::parse("body").ok().and_then(|s| document.select(&s).next())?;
+
+    let mut scorer = Scorer::default();
+    scorer.walk(body);
+    let (top, _) = scorer.best()?;
+
+    let mut blocks = Vec::new();
+    for element in scorer.article_elements(top) {
+        collect_blocks(element, &mut blocks);
+    }
+    let text = blocks.join("\n\n");
+    if text.chars().count() < MIN_ARTICLE_CHARS {
+        return None;
+    }
+
+    let base = crate::parser::document_base(document, base_url);
+    Some(Article {
+        title: article_title(document, top),
+        byline: byline(document, top),
+        published: published(document, top),
+        lead_image: lead_image(document, top).map(|src| match &base {
+            Some(base) => base.join(&src).map(|u| u.to_string()).unwrap_or(src),
+            None => src,
+        }),
+        text,
+    })
+}
+
+#[derive(Default)]
+struct Scorer<'a> {
+    scores: HashMap<NodeId, f64>,
+    candidates: Vec<ElementRef<'a>>,
+}
+
+impl<'a> Scorer<'a> {
+    /// Score paragraph-like blocks and propagate the scores to their ancestors
+    fn walk(&mut self, element: ElementRef<'a>) {
+        for child in element.children().filter_map(ElementRef::wrap) {
+            if is_unlikely(child) {
+                continue;
+            }
+            let name = child.value().name();
+            let leaf = !has_block_children(child);
+            let paragraph = matches!(name, "p" | "pre")
+                || (leaf && matches!(name, "td" | "blockquote" | "div" | "section" | "li"));
+            if paragraph {
+                self.score_paragraph(child);
+            }
+            if !leaf {
+                self.walk(child);
+            }
+        }
+    }
+
+    fn score_paragraph(&mut self, paragraph: ElementRef<'a>) {
+        let text = inner_text(paragraph);
+        let length = text.chars().count();
+        if length < 25 {
+            return;
+        }
+        let commas = text.chars().filter(|c| matches!(c, ',' | '，' | '、')).count();
+        let score = 1.0 + commas as f64 + (length as f64 / 100.0).floor().min(3.0);
+
+        let ancestors = paragraph
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .take_while(|el| el.value().name() != "html")
+            .take(SCORE_ANCESTOR_LEVELS);
+        for (level, ancestor) in ancestors.enumerate() {
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                n => n as f64 * 3.0,
+            };
+            let entry = self.scores.entry(ancestor.id()).or_insert_with(|| {
+                self.candidates.push(ancestor);
+                initial_score(ancestor)
+            });
+            *entry += score / divider;
+        }
+    }
+
+    /// Candidate with the highest score after the link-density penalty
+    fn best(&self) -> Option<(ElementRef<'a>, f64)> {
+        self.candidates
+            .iter()
+            .map(|el| (*el, self.final_score(*el)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    fn final_score(&self, element: ElementRef<'_>) -> f64 {
+        self.scores.get(&element.id()).copied().unwrap_or(0.0) * (1.0 - link_density(element))
+    }
+
+    /// The top candidate plus the siblings that look like part of the same article
+    fn article_elements(&self, top: ElementRef<'a>) -> Vec<ElementRef<'a>> {
+        let Some(parent) = top.parent().and_then(ElementRef::wrap) else {
+            return vec![top];
+        };
+        let threshold = (self.final_score(top) * 0.2).max(10.0);
+        parent
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|sibling| {
+                if sibling.id() == top.id() {
+                    return true;
+                }
+                if is_unlikely(*sibling) {
+                    return false;
+                }
+                if self.scores.contains_key(&sibling.id())
+                    && self.final_score(*sibling) >= threshold
+                {
+                    return true;
+                }
+                if sibling.value().name() != "p" {
+                    return false;
+                }
+                let text = inner_text(*sibling);
+                let density = link_density(*sibling);
+                let length = text.chars().count();
+                (length > 80 && density < 0.25)
+                    || (length > 0 && density == 0.0 && text.ends_with(['.', '!', '?']))
+            })
+            .collect()
+    }
+}
+
+/// Starting score of a container: tag bias plus class/id weight
+fn initial_score(element: ElementRef<'_>) -> f64 {
+    let tag = match element.value().name() {
+        "div" | "article" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag + class_weight(element)
+}
+
+fn class_weight(element: ElementRef<'_>) -> f64 {
+    let mut weight = 0.0;
+    for hint in [element.value().attr("class"), element.value().id()].into_iter().flatten() {
+        let hint = hint.to_lowercase();
+        if NEGATIVE_HINTS.iter().any(|n| hint.contains(n)) {
+            weight -= 25.0;
+        }
+        if POSITIVE_HINTS.iter().any(|p| hint.contains(p)) {
+            weight += 25.0;
+        }
+    }
+    weight
+}
+
+fn is_unlikely(element: ElementRef<'_>) -> bool {
+    let attrs = element.value();
+    let name = attrs.name();
+    if SKIPPED_TAGS.contains(&name) || attrs.attr("hidden").is_some() {
+        return true;
+    }
+    if attrs.attr("aria-hidden") == Some("true")
+        || attrs
+            .attr("style")
+            .is_some_and(|s| s.replace(' ', "").to_lowercase().contains("display:none"))
+    {
+        return true;
+    }
+    if attrs.attr("role").is_some_and(|role| UNLIKELY_ROLES.contains(&role)) {
+        return true;
+    }
+    if matches!(name, "body" | "main" | "article" | "a") {
+        return false;
+    }
+    let hints = format!("{} {}", attrs.attr("class").unwrap_or(""), attrs.id().unwrap_or(""))
+        .to_lowercase();
+    UNLIKELY_CANDIDATES.iter().any(|u| hints.contains(u))
+        && !MAYBE_CANDIDATES.iter().any(|m| hints.contains(m))
+}
+
+fn has_block_children(element: ElementRef<'_>) -> bool {
+    element.children().filter_map(ElementRef::wrap).any(|c| BLOCK_TAGS.contains(&c.value().name()))
+}
+
+/// Append the text of `element` as paragraphs, dropping boilerplate and link lists
+fn collect_blocks(element: ElementRef<'_>, blocks: &mut Vec<String>) {
+    let mut inline = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => inline.push_str(text),
+            Node::Element(_) => {
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                if is_unlikely(child) {
+                    continue;
+                }
+                let name = child.value().name();
+                if name == "br" {
+                    inline.push(' ');
+                } else if BLOCK_TAGS.contains(&name) {
+                    push_block(&mut inline, blocks);
+                    if !is_junk_container(child) {
+                        collect_blocks(child, blocks);
+                    }
+                } else {
+                    inline.push(' ');
+                    inline.push_str(&inner_text(child));
+                    inline.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+    push_block(&mut inline, blocks);
+}
+
+fn push_block(inline: &mut String, blocks: &mut Vec<String>) {
+    let text = normalize_whitespace(inline);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    inline.clear();
+}
+
+/// Containers inside the article that are mostly links (tag clouds, "read next" lists)
+fn is_junk_container(element: ElementRef<'_>) -> bool {
+    if !matches!(element.value().name(), "div" | "section" | "ul" | "ol" | "table") {
+        return false;
+    }
+    if class_weight(element) < 0.0 {
+        return true;
+    }
+    let commas = inner_text(element).matches(',').count();
+    commas < 10 && link_density(element) > 0.33
+}
+
+fn link_density(element: ElementRef<'_>) -> f64 {
+    let total = inner_text(element).chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let links: usize = Selector::parse("a")
+        .map(|s| element.select(&s).map(|a| inner_text(a).chars().count()).sum())
+        .unwrap_or(0);
+    (links as f64 / total as f64).min(1.0)
+}
+
+/// Whitespace-normalised text without `<script>`/`<style>` content
+fn inner_text(element: ElementRef<'_>) -> String {
+    let mut text = String::new();
+    for node in element.descendants() {
+        if let Some(t) = node.value().as_text() {
+            let hidden = node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|el| el.id() != element.id())
+                .any(|el| {
+                    matches!(el.value().name(), "script" | "style" | "noscript" | "template")
+                });
+            if !hidden {
+                text.push_str(t);
+                text.push(' ');
+            }
+        }
+    }
+    normalize_whitespace(&text)
+}
+
+fn meta_content(document: &Html, selectors: &[&str]) -> Option<String> {
+    selectors.iter().find_map(|selector| {
+        let selector = Selector::parse(selector).ok()?;
+        document
+            .select(&selector)
+            .filter_map(|el| el.value().attr("content"))
+            .map(str::trim)
+            .find(|content| !content.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn article_title(document: &Html, top: ElementRef<'_>) -> Option<String> {
+    meta_content(document, &["meta[property='og:title']", "meta[name='twitter:title']"])
+        .or_else(|| {
+            let h1 = Selector::parse("h1").ok()?;
+            top.select(&h1).chain(document.select(&h1)).map(inner_text).find(|t| !t.is_empty())
+        })
+        .or_else(|| {
+            let title = Selector::parse("title").ok()?;
+            document.select(&title).map(inner_text).find(|t| !t.is_empty())
+        })
+}
+
+fn byline(document: &Html, top: ElementRef<'_>) -> Option<String> {
+    let from_markup = || {
+        let selector = Selector::parse(
+            "[rel='author'], [itemprop~='author'], [class*='byline'], [id*='byline'], \
+             [class*='author'], .p-author",
+        )
+        .ok()?;
+        top.select(&selector)
+            .chain(document.select(&selector))
+            .map(|el| {
+                // <span itemprop="author" itemscope><span itemprop="name">...</span></span>
+                let name = Selector::parse("[itemprop~='name']")
+                    .ok()
+                    .and_then(|s| el.select(&s).next())
+                    .map(inner_text);
+                name.unwrap_or_else(|| inner_text(el))
+            })
+            .map(|text| {
+                let trimmed = text.trim();
+                trimmed
+                    .strip_prefix("By ")
+                    .or_else(|| trimmed.strip_prefix("by "))
+                    .unwrap_or(trimmed)
+                    .to_string()
+            })
+            .find(|text| !text.is_empty() && text.chars().count() <= 100)
+    };
+    meta_content(document, &["meta[name='author']", "meta[property='article:author']"])
+        .filter(|author| !author.starts_with("http"))
+        .or_else(from_markup)
+}
+
+fn published(document: &Html, top: ElementRef<'_>) -> Option<String> {
+    meta_content(
+        document,
+        &[
+            "meta[property='article:published_time']",
+            "meta[itemprop='datePublished']",
+            "meta[name='date']",
+            "meta[name='pubdate']",
+            "meta[name='publish-date']",
+            "meta[name='dc.date']",
+            "meta[name='DC.date']",
+            "meta[name='dcterms.created']",
+        ],
+    )
+    .or_else(|| {
+        let selector = Selector::parse("time[datetime]").ok()?;
+        top.select(&selector)
+            .chain(document.select(&selector))
+            .filter_map(|el| el.value().attr("datetime"))
+            .map(str::trim)
+            .find(|value| !value.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn lead_image(document: &Html, top: ElementRef<'_>) -> Option<String> {
+    meta_content(
+        document,
+        &[
+            "meta[property='og:image']",
+            "meta[name='twitter:image']",
+            "meta[property='twitter:image']",
+        ],
+    )
+    .or_else(|| {
+        let selector = Selector::parse("img[src]").ok()?;
+        top.select(&selector)
+            .filter_map(|img| img.value().attr("src"))
+            .map(str::trim)
+            .find(|src| !src.is_empty() && !src.starts_with("data:"))
+            .map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>Rust 2.0 released | Daily Bits</title>
+        <meta name="author" content="Jane Doe">
+        </head><body>
+        <nav><a href="/">Home</a> <a href="/news">News</a> <a href="/about">About</a></nav>
+        <div class="cookie-banner">We use cookies to improve your experience. Accept all cookies?</div>
+        <div id="main-wrapper">
+          <article class="post">
+            <h1>Rust 2.0 released</h1>
+            <time datetime="2025-06-01T08:00:00Z">June 1</time>
+            <img src="/img/rust.png" alt="Ferris">
+            <p>The Rust team announced a new major release today, bringing faster compile
+               times, a reworked trait solver, and many quality-of-life improvements.</p>
+            <p>According to the release notes, existing code keeps compiling, while new
+               editions opt into the changed defaults, lints, and library additions.</p>
+            <p>Users can upgrade with rustup, and the documentation, book, and standard
+               library reference have been updated accordingly.</p>
+          </article>
+          <aside class="sidebar"><p>Subscribe to our newsletter for more news, tips, and deals.</p></aside>
+        </div>
+        <footer><p>Copyright 2025 Daily Bits, all rights reserved, terms, privacy.</p></footer>
+        </body></html>"#;
+
+    #[test]
+    fn test_extract_article_skips_boilerplate() {
+        let document = Html::parse_document(PAGE);
+        let article = extract_article(&document, Some("https://bits.example/news/rust")).unwrap();
+
+        assert!(article.text.starts_with("Rust 2.0 released"));
+        assert!(article.text.contains("reworked trait solver"));
+        assert!(article.text.contains("\n\nAccording to the release notes"));
+        for boilerplate in ["Home", "cookies", "newsletter", "Copyright"] {
+            assert!(!article.text.contains(boilerplate), "leaked {:?}", boilerplate);
+        }
+        assert_eq!(article.title.as_deref(), Some("Rust 2.0 released"));
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert_eq!(article.published.as_deref(), Some("2025-06-01T08:00:00Z"));
+        assert_eq!(article.lead_image.as_deref(), Some("https://bits.example/img/rust.png"));
+    }
+
+    #[test]
+    fn test_extract_text_modes() {
+        let document = Html::parse_document(PAGE);
+
+        let (text, article) = extract_text(&document, None, ContentMode::Readability);
+        assert!(article.is_some());
+        assert!(!text.contains("cookies"));
+
+        let (text, article) = extract_text(&document, None, ContentMode::FullText);
+        assert!(article.is_none());
+        assert!(text.contains("Rust 2.0 released"));
+
+        // Too little content for an article: fall back to the full text
+        let short = Html::parse_document("<html><body><p>Just a short note.</p></body></html>");
+        let (text, article) = extract_text(&short, None, ContentMode::Readability);
+        assert!(article.is_none());
+        assert_eq!(text, "Just a short note.");
+    }
+}
@@ -0,0 +1,625 @@
+//! Record and replay of network traffic
+//!
+//! Stores HTTP responses in an on-disk cassette (JSON) keyed by method, URL
+//! and request body, and serves them back later, so browse → extract → KG
+//! pipelines run deterministically without network access. The same cassette
+//! backs both the reqwest fallback ([`crate::external::fetch_document`]) and
+//! the headless browser, where requests are intercepted through the CDP
+//! `Fetch` domain.
+//!
+//! Configuration from the environment:
+//! - `NETWORK_CASSETTE`: cassette file; record/replay is off when unset
+//! - `NETWORK_REPLAY_MODE`: `replay` (cassette only, misses are errors),
+//!   `record` (always fetch, rewrite the cassette) or `auto` (default; replay
+//!   hits, fetch and append misses)
+//! - `NETWORK_REPLAY_MATCH`: `strict` (default) or `lenient`
+
+use base64::{engine::general_purpose, Engine as _};
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Cassette format version written by this module
+const CASSETTE_VERSION: u32 = 1;
+
+/// Response headers not worth replaying: the stored body is already decoded
+/// and its length is known
+const DROPPED_HEADERS: &[&str] = &["content-encoding", "content-length", "transfer-encoding"];
+
+/// Where responses come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayMode {
+    /// Always use the network and record every response, replacing the cassette
+    Record,
+    /// Only serve recorded responses; unrecorded requests fail
+    Replay,
+    /// Serve recorded responses and record the ones that are missing
+    #[default]
+    Auto,
+}
+
+impl FromStr for ReplayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            "auto" => Ok(Self::Auto),
+            other => Err(format!("Unknown replay mode: {}", other)),
+        }
+    }
+}
+
+/// How a request is matched against recorded ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// Same method, URL (fragment aside) and body
+    #[default]
+    Strict,
+    /// Same method and URL up to query parameter order, host case and a
+    /// trailing slash; bodies are ignored
+    Lenient,
+}
+
+impl FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            other => Err(format!("Unknown replay match mode: {}", other)),
+        }
+    }
+}
+
+/// Request or response body: a JSON string when it is UTF-8, otherwise
+/// `{"base64": "..."}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BodyRepr", into = "BodyRepr")]
+pub struct Body(pub Vec<u8>);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum BodyRepr {
+    Text(String),
+    Base64 { base64: String },
+}
+
+impl TryFrom<BodyRepr> for Body {
+    type Error = base64::DecodeError;
+
+    fn try_from(repr: BodyRepr) -> Result<Self, Self::Error> {
+        match repr {
+            BodyRepr::Text(text) => Ok(Body(text.into_bytes())),
+            BodyRepr::Base64 { base64 } => general_purpose::STANDARD.decode(base64).map(Body),
+        }
+    }
+}
+
+impl From<Body> for BodyRepr {
+    fn from(body: Body) -> Self {
+        match String::from_utf8(body.0) {
+            Ok(text) => BodyRepr::Text(text),
+            Err(e) => BodyRepr::Base64 { base64: general_purpose::STANDARD.encode(e.as_bytes()) },
+        }
+    }
+}
+
+impl Body {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Body::is_empty")]
+    pub body: Body,
+}
+
+impl RecordedRequest {
+    pub fn get(url: &str) -> Self {
+        Self { method: "GET".to_string(), url: url.to_string(), body: Body::default() }
+    }
+
+    fn matches(&self, other: &RecordedRequest, matching: MatchMode) -> bool {
+        if !self.method.eq_ignore_ascii_case(&other.method) {
+            return false;
+        }
+        match matching {
+            MatchMode::Strict => {
+                strip_fragment(&self.url) == strip_fragment(&other.url) && self.body == other.body
+            }
+            MatchMode::Lenient => normalize_url(&self.url) == normalize_url(&other.url),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// URL after redirects, when the client followed any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    #[serde(default)]
+    pub body: Body,
+}
+
+impl RecordedResponse {
+    /// First value of header `name`, case-insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// Recorded interactions, in the order they happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self { version: CASSETTE_VERSION, interactions: Vec::new() }
+    }
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data).map_err(std::io::Error::from)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    path: Option<PathBuf>,
+    mode: ReplayMode,
+    matching: MatchMode,
+    cassette: Cassette,
+    /// Interactions already served; identical requests replay in recorded order
+    served: Vec<bool>,
+}
+
+/// Shared handle to a cassette in record or replay mode
+#[derive(Debug, Clone)]
+pub struct Replay {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl Replay {
+    /// In-memory cassette that is never written to disk
+    pub fn new(cassette: Cassette, mode: ReplayMode, matching: MatchMode) -> Self {
+        Self::with_path(None, cassette, mode, matching)
+    }
+
+    /// Cassette backed by `path`; recordings are written back as they happen
+    ///
+    /// `Replay` requires the file to exist, `Record` starts from an empty
+    /// cassette and `Auto` loads the file if there is one.
+    pub fn open(
+        path: impl Into<PathBuf>,
+        mode: ReplayMode,
+        matching: MatchMode,
+    ) -> std::io::Result<Self> {
+        let path = path.into();
+        let cassette = match mode {
+            ReplayMode::Record => Cassette::default(),
+            ReplayMode::Replay => Cassette::load(&path)?,
+            ReplayMode::Auto if path.exists() => Cassette::load(&path)?,
+            ReplayMode::Auto => Cassette::default(),
+        };
+        Ok(Self::with_path(Some(path), cassette, mode, matching))
+    }
+
+    fn with_path(
+        path: Option<PathBuf>,
+        cassette: Cassette,
+        mode: ReplayMode,
+        matching: MatchMode,
+    ) -> Self {
+        let served = vec![false; cassette.interactions.len()];
+        Self { state: Arc::new(Mutex::new(ReplayState { path, mode, matching, cassette, served })) }
+    }
+
+    /// Cassette configured by `NETWORK_CASSETTE` and friends, if any
+    pub fn from_env() -> Option<Self> {
+        #[allow(clippy::disallowed_methods)]
+        let path = std::env::var("NETWORK_CASSETTE").ok().filter(|p| !p.is_empty())?;
+        #[allow(clippy::disallowed_methods)]
+        let mode = std::env::var("NETWORK_REPLAY_MODE").ok();
+        #[allow(clippy::disallowed_methods)]
+        let matching = std::env::var("NETWORK_REPLAY_MATCH").ok();
+
+        let mode = mode.map(|m| m.parse::<ReplayMode>()).transpose();
+        let matching = matching.map(|m| m.parse::<MatchMode>()).transpose();
+        let (mode, matching) = match (mode, matching) {
+            (Ok(mode), Ok(matching)) => (mode.unwrap_or_default(), matching.unwrap_or_default()),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!("Network replay disabled: {}", e);
+                return None;
+            }
+        };
+        match Self::open(&path, mode, matching) {
+            Ok(replay) => {
+                tracing::info!("Network {:?} mode with cassette {}", mode, path);
+                Some(replay)
+            }
+            Err(e) => {
+                tracing::warn!("Network replay disabled, cannot open cassette {}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.state.lock().map(|s| s.mode).unwrap_or_default()
+    }
+
+    /// Copy of the current cassette
+    pub fn cassette(&self) -> Cassette {
+        self.state.lock().map(|s| s.cassette.clone()).unwrap_or_default()
+    }
+
+    /// Recorded response for `request`
+    ///
+    /// `Ok(None)` means the request should go to the network and be
+    /// [`record`](Self::record)ed; an error means it must not.
+    pub fn lookup(&self, request: &RecordedRequest) -> Result<Option<RecordedResponse>, String> {
+        let mut state = self.state.lock().map_err(|_| "Replay state lock poisoned")?;
+        if state.mode == ReplayMode::Record {
+            return Ok(None);
+        }
+
+        let matching = state.matching;
+        let candidates: Vec<usize> = state
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.request.matches(request, matching))
+            .map(|(index, _)| index)
+            .collect();
+        // Next unserved match, or the last one again once all were served
+        let hit =
+            candidates.iter().copied().find(|&i| !state.served[i]).or(candidates.last().copied());
+        match hit {
+            Some(index) => {
+                state.served[index] = true;
+                Ok(Some(state.cassette.interactions[index].response.clone()))
+            }
+            None if state.mode == ReplayMode::Replay => {
+                Err(format!("No recorded response for {} {}", request.method, request.url))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Add a response fetched from the network and write the cassette
+    ///
+    /// Cookies and credentials (see [`crate::har::REDACTED_HEADERS`]) are
+    /// redacted so cassettes can be checked in.
+    pub fn record(&self, request: RecordedRequest, mut response: RecordedResponse) {
+        drop_encoding_headers(&mut response.headers);
+        for (name, value) in &mut response.headers {
+            if crate::har::REDACTED_HEADERS.iter().any(|h| h.eq_ignore_ascii_case(name)) {
+                *value = crate::har::REDACTED.to_string();
+            }
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.mode == ReplayMode::Replay {
+            return;
+        }
+        state.cassette.interactions.push(Interaction { request, response });
+        state.served.push(true);
+        if let Some(path) = &state.path {
+            if let Err(e) = state.cassette.save(path) {
+                tracing::warn!("Failed to write cassette {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Process-wide cassette from the environment (see the module docs)
+pub fn global() -> Option<&'static Replay> {
+    static GLOBAL: OnceLock<Option<Replay>> = OnceLock::new();
+    GLOBAL.get_or_init(Replay::from_env).as_ref()
+}
+
+/// Remove the headers that described the body before it was decoded
+fn drop_encoding_headers(headers: &mut Vec<(String, String)>) {
+    headers.retain(|(name, _)| {
+        !DROPPED_HEADERS.iter().any(|dropped| dropped.eq_ignore_ascii_case(name))
+    });
+}
+
+fn strip_fragment(url: &str) -> &str {
+    url.split('#').next().unwrap_or(url)
+}
+
+/// URL with sorted query parameters, no fragment and no trailing slash
+fn normalize_url(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return strip_fragment(url).to_string();
+    };
+    parsed.set_fragment(None);
+    let path = parsed.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        parsed.set_path(path.trim_end_matches('/'));
+    }
+    let mut pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+    pairs.sort();
+    if pairs.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    parsed.to_string()
+}
+
+/// Serve `page`'s requests from `replay` and record the ones it misses
+///
+/// Requests are paused through the CDP `Fetch` domain: hits are fulfilled
+/// from the cassette; misses continue to the network and are recorded when
+/// their response arrives, or fail in `Replay` mode.
+#[cfg(feature = "browser-automation")]
+pub async fn intercept(
+    page: &Page,
+    replay: Replay,
+) -> Result<Interception, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::fetch::{
+        EnableParams, EventRequestPaused, RequestPattern, RequestStage,
+    };
+    use futures::StreamExt;
+
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    let stages = [RequestStage::Request, RequestStage::Response];
+    let patterns = stages
+        .into_iter()
+        .map(|stage| RequestPattern {
+            url_pattern: Some("*".to_string()),
+            resource_type: None,
+            request_stage: Some(stage),
+        })
+        .collect();
+    page.execute(EnableParams { patterns: Some(patterns), handle_auth_requests: None }).await?;
+
+    let page = page.clone();
+    let task = tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            if let Err(e) = handle_paused(&page, &replay, &event).await {
+                tracing::warn!("Replay interception failed for {}: {}", event.request.url, e);
+            }
+        }
+    });
+    Ok(Interception { task })
+}
+
+/// Running [`intercept`]ion of a page
+#[cfg(feature = "browser-automation")]
+pub struct Interception {
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "browser-automation")]
+impl Interception {
+    /// Stop pausing the page's requests
+    pub async fn stop(self, page: &Page) {
+        use chromiumoxide::cdp::browser_protocol::fetch::DisableParams;
+
+        // Disable first so nothing is left paused without a handler
+        if let Err(e) = page.execute(DisableParams::default()).await {
+            tracing::debug!("Failed to disable request interception: {}", e);
+        }
+        self.task.abort();
+    }
+}
+
+#[cfg(feature = "browser-automation")]
+async fn handle_paused(
+    page: &Page,
+    replay: &Replay,
+    event: &chromiumoxide::cdp::browser_protocol::fetch::EventRequestPaused,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::fetch::{
+        ContinueRequestParams, FailRequestParams, FulfillRequestParams, GetResponseBodyParams,
+        HeaderEntry,
+    };
+    use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+
+    let body: Vec<u8> = event
+        .request
+        .post_data_entries
+        .iter()
+        .flatten()
+        .filter_map(|entry| entry.bytes.as_ref())
+        .filter_map(|bytes| general_purpose::STANDARD.decode(AsRef::<str>::as_ref(bytes)).ok())
+        .flatten()
+        .collect();
+    let request = RecordedRequest {
+        method: event.request.method.clone(),
+        url: event.request.url.clone(),
+        body: Body(body),
+    };
+    let fulfill = |response: &RecordedResponse| {
+        // Redacted values from a cassette are not worth sending
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(_, value)| value != crate::har::REDACTED)
+            .map(|(name, value)| HeaderEntry::new(name, value))
+            .collect();
+        let mut params = FulfillRequestParams::new(event.request_id.clone(), response.status);
+        params.response_headers = Some(headers);
+        params.body = Some(general_purpose::STANDARD.encode(&response.body.0).into());
+        params
+    };
+
+    match event.response_status_code {
+        // Request stage: serve from the cassette or let it through
+        None if event.response_error_reason.is_none() => match replay.lookup(&request) {
+            Ok(Some(response)) => {
+                page.execute(fulfill(&response)).await?;
+            }
+            Ok(None) => {
+                page.execute(ContinueRequestParams::new(event.request_id.clone())).await?;
+            }
+            Err(e) => {
+                tracing::debug!("{}", e);
+                page.execute(FailRequestParams::new(
+                    event.request_id.clone(),
+                    ErrorReason::InternetDisconnected,
+                ))
+                .await?;
+            }
+        },
+        // Response stage: record what the network returned and pass it on
+        Some(status) => {
+            // Redirects and some errors have no body
+            let body =
+                match page.execute(GetResponseBodyParams::new(event.request_id.clone())).await {
+                    Ok(body) if body.result.base64_encoded => {
+                        general_purpose::STANDARD.decode(&body.result.body).unwrap_or_default()
+                    }
+                    Ok(body) => body.result.body.clone().into_bytes(),
+                    Err(_) => Vec::new(),
+                };
+            let mut response = RecordedResponse {
+                status: status as u16,
+                headers: event
+                    .response_headers
+                    .iter()
+                    .flatten()
+                    .map(|h| (h.name.clone(), h.value.clone()))
+                    .collect(),
+                final_url: None,
+                body: Body(body),
+            };
+            // The body was decoded by the browser, so the page must not be told
+            // it is still compressed; cookies pass through unredacted
+            drop_encoding_headers(&mut response.headers);
+            replay.record(request, response.clone());
+            page.execute(fulfill(&response)).await?;
+        }
+        // Network error at the response stage: nothing to record
+        None => {
+            page.execute(ContinueRequestParams::new(event.request_id.clone())).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> RecordedResponse {
+        RecordedResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            final_url: None,
+            body: Body(body.as_bytes().to_vec()),
+        }
+    }
+
+    #[test]
+    fn test_replay_matching() {
+        let mut post = RecordedRequest::get("https://api.example.com/search");
+        post.method = "POST".to_string();
+        post.body = Body(br#"{"q":"rust"}"#.to_vec());
+        let cassette = Cassette {
+            version: CASSETTE_VERSION,
+            interactions: vec![
+                Interaction {
+                    request: RecordedRequest::get("https://example.com/list?b=2&a=1"),
+                    response: response("first"),
+                },
+                Interaction {
+                    request: RecordedRequest::get("https://example.com/list?b=2&a=1"),
+                    response: response("second"),
+                },
+                Interaction { request: post.clone(), response: response("results") },
+            ],
+        };
+
+        let strict = Replay::new(cassette.clone(), ReplayMode::Replay, MatchMode::Strict);
+        let list = RecordedRequest::get("https://example.com/list?b=2&a=1#top");
+        // Repeated requests replay in recorded order, then stick to the last
+        for expected in ["first", "second", "second"] {
+            let hit = strict.lookup(&list).unwrap().unwrap();
+            assert_eq!(hit.body.0, expected.as_bytes());
+        }
+        assert!(strict.lookup(&post).unwrap().is_some());
+        let mut other_body = post.clone();
+        other_body.body = Body(br#"{"q":"go"}"#.to_vec());
+        assert!(strict.lookup(&other_body).is_err());
+        assert!(strict.lookup(&RecordedRequest::get("https://example.com/list?a=1&b=2")).is_err());
+
+        let lenient = Replay::new(cassette, ReplayMode::Replay, MatchMode::Lenient);
+        assert!(lenient.lookup(&other_body).unwrap().is_some());
+        let reordered = RecordedRequest::get("https://EXAMPLE.com/list/?a=1&b=2");
+        assert_eq!(lenient.lookup(&reordered).unwrap().unwrap().body.0, b"first");
+        assert!(lenient.lookup(&RecordedRequest::get("https://example.com/other")).is_err());
+    }
+
+    #[test]
+    fn test_replay_recording_and_format() {
+        let auto = Replay::new(Cassette::default(), ReplayMode::Auto, MatchMode::Strict);
+        let request = RecordedRequest::get("https://example.com/");
+        assert_eq!(auto.lookup(&request), Ok(None));
+
+        let mut fetched = response("<html></html>");
+        fetched.headers.push(("Content-Encoding".to_string(), "gzip".to_string()));
+        fetched.headers.push(("Set-Cookie".to_string(), "sid=secret; HttpOnly".to_string()));
+        auto.record(request.clone(), fetched);
+        let hit = auto.lookup(&request).unwrap().unwrap();
+        assert_eq!(hit.header("content-type"), Some("text/html"));
+        assert_eq!(hit.header("content-encoding"), None);
+        // Credentials never reach the cassette
+        assert_eq!(hit.header("set-cookie"), Some(crate::har::REDACTED));
+
+        // Record mode always goes to the network
+        let record = Replay::new(auto.cassette(), ReplayMode::Record, MatchMode::Strict);
+        assert_eq!(record.lookup(&request), Ok(None));
+
+        // Text bodies stay readable in the file, binary ones are base64
+        let mut cassette = auto.cassette();
+        cassette.interactions[0].response.body = Body(vec![0x89, 0x50, 0xff]);
+        let json = serde_json::to_value(&cassette).unwrap();
+        assert_eq!(json["interactions"][0]["response"]["body"]["base64"], "iVD/");
+        assert!(json["interactions"][0]["request"].get("body").is_none());
+        let back: Cassette = serde_json::from_value(json).unwrap();
+        assert_eq!(back, cassette);
+
+        assert_eq!("Replay".parse::<ReplayMode>(), Ok(ReplayMode::Replay));
+        assert!("sometimes".parse::<ReplayMode>().is_err());
+        assert_eq!("lenient".parse::<MatchMode>(), Ok(MatchMode::Lenient));
+    }
+}
@@ -0,0 +1,479 @@
+//! Cookie jar and web storage import/export
+//!
+//! A [`StorageState`] holds every cookie of a browser context (with domain,
+//! path, expiry and flags) plus the `localStorage` and `sessionStorage` of
+//! origins. Its JSON form is Playwright's `storageState` format, so states can
+//! be exchanged with Playwright scripts; cookies can also be read from and
+//! written to Netscape `cookies.txt` files as used by curl and wget.
+//!
+//! With the `browser-automation` feature [`export`] captures the state of a
+//! page and [`import`] applies one to a page before it navigates.
+
+#[cfg(feature = "browser-automation")]
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Header line of Netscape cookie files
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// Line prefix curl uses to mark HttpOnly cookies
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// `SameSite` attribute of a cookie
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    Strict,
+    /// Also what browsers assume when the attribute is missing
+    #[default]
+    Lax,
+    None,
+}
+
+/// A cookie with all the attributes needed to restore it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Host of a host-only cookie, or `.example.com` for one sent to subdomains too
+    pub domain: String,
+    pub path: String,
+    /// Expiry in seconds since the Unix epoch, -1 for a session cookie
+    pub expires: f64,
+    pub http_only: bool,
+    pub secure: bool,
+    #[serde(default)]
+    pub same_site: SameSite,
+}
+
+impl Cookie {
+    /// Session cookie for `domain` and path `/`
+    pub fn new(name: &str, value: &str, domain: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: -1.0,
+            http_only: false,
+            secure: false,
+            same_site: SameSite::default(),
+        }
+    }
+
+    pub fn is_session(&self) -> bool {
+        self.expires < 0.0
+    }
+
+    /// Whether the cookie is sent to `host`: its own host, or a subdomain of a
+    /// domain cookie
+    pub fn matches_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let domain = self.domain.to_ascii_lowercase();
+        match domain.strip_prefix('.') {
+            Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+            None => host == domain,
+        }
+    }
+
+    /// Whether the cookie expired before `now` (seconds since the Unix epoch)
+    pub fn is_expired(&self, now: f64) -> bool {
+        !self.is_session() && self.expires <= now
+    }
+}
+
+/// A `localStorage` or `sessionStorage` entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+/// Web storage of one origin
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginState {
+    /// Scheme, host and port, e.g. `https://example.com`
+    pub origin: String,
+    #[serde(default)]
+    pub local_storage: Vec<NameValue>,
+    /// Not part of Playwright's format, which ignores it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_storage: Vec<NameValue>,
+}
+
+/// Cookies and per-origin web storage, see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageState {
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+    #[serde(default)]
+    pub origins: Vec<OriginState>,
+}
+
+impl StorageState {
+    /// Read a Playwright-compatible storage state JSON file
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data).map_err(std::io::Error::from)
+    }
+
+    /// Write the state as Playwright-compatible JSON
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    /// State with only the cookies of a Netscape cookie file
+    pub fn load_netscape(path: &Path) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self { cookies: parse_netscape(&text)?, origins: Vec::new() })
+    }
+
+    /// Write the cookies as a Netscape cookie file; web storage is not included
+    pub fn save_netscape(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, to_netscape(&self.cookies))
+    }
+
+    /// Drop the cookies that expired before `now` (seconds since the Unix epoch)
+    pub fn remove_expired(&mut self, now: f64) {
+        self.cookies.retain(|cookie| !cookie.is_expired(now));
+    }
+
+    /// Web storage of `origin`, if the state has any
+    pub fn origin(&self, origin: &str) -> Option<&OriginState> {
+        self.origins.iter().find(|o| o.origin == origin)
+    }
+}
+
+/// Seconds since the Unix epoch, the unit of cookie expiry
+pub fn unix_now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default()
+}
+
+/// Parse a Netscape `cookies.txt` file
+///
+/// Each line holds tab-separated domain, include-subdomains flag, path,
+/// secure flag, expiry (0 for session cookies), name and value. Lines starting
+/// with `#HttpOnly_` are HttpOnly cookies; other `#` lines are comments.
+pub fn parse_netscape(text: &str) -> Result<Vec<Cookie>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut cookies = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |what: &str| format!("Invalid cookie on line {}: {}", index + 1, what);
+
+        let fields: Vec<&str> = line.splitn(7, '\t').collect();
+        let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+            return Err(invalid("expected 7 tab-separated fields").into());
+        };
+        let flag = |value: &str, field: &str| match value {
+            "TRUE" => Ok(true),
+            "FALSE" => Ok(false),
+            _ => Err(invalid(&format!("{} must be TRUE or FALSE", field))),
+        };
+        let include_subdomains = flag(subdomains, "include-subdomains flag")?;
+        let secure = flag(secure, "secure flag")?;
+        let expires: i64 = expires.parse().map_err(|_| invalid("expiry is not a number"))?;
+
+        // The flag is authoritative; cookie domains mark it with a leading dot
+        let host = domain.trim_start_matches('.');
+        let domain = if include_subdomains {
+            format!(".{}", host)
+        } else {
+            host.to_string()
+        };
+        cookies.push(Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain,
+            path: path.to_string(),
+            expires: if expires == 0 { -1.0 } else { expires as f64 },
+            http_only,
+            secure,
+            same_site: SameSite::default(),
+        });
+    }
+    Ok(cookies)
+}
+
+/// Render cookies as a Netscape `cookies.txt` file; `SameSite` is not representable
+pub fn to_netscape(cookies: &[Cookie]) -> String {
+    let flag = |value: bool| if value { "TRUE" } else { "FALSE" };
+    let mut text = format!("{}\n\n", NETSCAPE_HEADER);
+    for cookie in cookies {
+        let expires = if cookie.is_session() {
+            0
+        } else {
+            cookie.expires as i64
+        };
+        text.push_str(&format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            if cookie.http_only {
+                HTTP_ONLY_PREFIX
+            } else {
+                ""
+            },
+            cookie.domain,
+            flag(cookie.domain.starts_with('.')),
+            cookie.path,
+            flag(cookie.secure),
+            expires,
+            cookie.name,
+            cookie.value
+        ));
+    }
+    text
+}
+
+/// Reads the web storage of the page's origin
+#[cfg(feature = "browser-automation")]
+const EXPORT_STORAGE_JS: &str = r#"(() => {
+    const dump = (storage) => {
+        const items = [];
+        for (let i = 0; i < storage.length; i++) {
+            const name = storage.key(i);
+            items.push({ name, value: storage.getItem(name) });
+        }
+        return items;
+    };
+    try {
+        return {
+            origin: location.origin,
+            localStorage: dump(localStorage),
+            sessionStorage: dump(sessionStorage),
+        };
+    } catch (e) {
+        return null;
+    }
+})()"#;
+
+#[cfg(feature = "browser-automation")]
+impl From<chromiumoxide::cdp::browser_protocol::network::Cookie> for Cookie {
+    fn from(cookie: chromiumoxide::cdp::browser_protocol::network::Cookie) -> Self {
+        use chromiumoxide::cdp::browser_protocol::network::CookieSameSite;
+
+        let same_site = match cookie.same_site {
+            Some(CookieSameSite::Strict) => SameSite::Strict,
+            Some(CookieSameSite::None) => SameSite::None,
+            Some(CookieSameSite::Lax) | None => SameSite::Lax,
+        };
+        Self {
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            expires: if cookie.session { -1.0 } else { cookie.expires },
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site,
+        }
+    }
+}
+
+#[cfg(feature = "browser-automation")]
+impl From<&Cookie> for chromiumoxide::cdp::browser_protocol::network::CookieParam {
+    fn from(cookie: &Cookie) -> Self {
+        use chromiumoxide::cdp::browser_protocol::network::{CookieSameSite, TimeSinceEpoch};
+
+        let mut param = Self::new(cookie.name.clone(), cookie.value.clone());
+        param.domain = Some(cookie.domain.clone());
+        param.path = Some(cookie.path.clone());
+        param.secure = Some(cookie.secure);
+        param.http_only = Some(cookie.http_only);
+        param.same_site = Some(match cookie.same_site {
+            SameSite::Strict => CookieSameSite::Strict,
+            SameSite::Lax => CookieSameSite::Lax,
+            SameSite::None => CookieSameSite::None,
+        });
+        if !cookie.is_session() {
+            param.expires = Some(TimeSinceEpoch::new(cookie.expires));
+        }
+        param
+    }
+}
+
+/// Capture every cookie of the page's browser context and the web storage of
+/// the page's current origin
+#[cfg(feature = "browser-automation")]
+pub async fn export(page: &Page) -> Result<StorageState, Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::storage::GetCookiesParams;
+
+    // Storage.getCookies covers all domains, Network.getCookies only the page's URLs
+    let cookies = page.execute(GetCookiesParams::default()).await?;
+    let cookies = cookies.result.cookies.into_iter().map(Cookie::from).collect();
+
+    let storage: Option<OriginState> = page.evaluate(EXPORT_STORAGE_JS).await?.into_value()?;
+    // Blank and opaque-origin pages have no storage worth keeping
+    let origins = storage
+        .filter(|s| s.origin != "null")
+        .filter(|s| !s.local_storage.is_empty() || !s.session_storage.is_empty())
+        .into_iter()
+        .collect();
+    Ok(StorageState { cookies, origins })
+}
+
+/// Apply `state` to the page's browser context before it navigates
+///
+/// Cookies that already expired are skipped. Web storage is written by
+/// loading each origin with its requests answered by an empty document, so
+/// nothing reaches the network; the page is left on `about:blank`.
+/// `sessionStorage` only lives in this page.
+///
+/// The state lands in the whole context, so the page should be in one of its
+/// own (see [`crate::browser_context`]) and not be reused afterwards.
+#[cfg(feature = "browser-automation")]
+pub async fn import(
+    page: &Page,
+    state: &StorageState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::fetch::{
+        DisableParams, EnableParams, EventRequestPaused, FulfillRequestParams, RequestPattern,
+        RequestStage,
+    };
+    use chromiumoxide::cdp::browser_protocol::network::{CookieParam, SetCookiesParams};
+    use futures::StreamExt;
+
+    let now = unix_now();
+    let cookies: Vec<CookieParam> =
+        state.cookies.iter().filter(|c| !c.is_expired(now)).map(CookieParam::from).collect();
+    if !cookies.is_empty() {
+        page.execute(SetCookiesParams::new(cookies)).await?;
+    }
+    if state.origins.is_empty() {
+        return Ok(());
+    }
+
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    let pattern = RequestPattern {
+        url_pattern: Some("*".to_string()),
+        resource_type: None,
+        request_stage: Some(RequestStage::Request),
+    };
+    page.execute(EnableParams { patterns: Some(vec![pattern]), handle_auth_requests: None })
+        .await?;
+    let responder = tokio::spawn({
+        let page = page.clone();
+        async move {
+            while let Some(event) = paused.next().await {
+                let mut params = FulfillRequestParams::new(event.request_id.clone(), 200);
+                params.body = Some(String::new().into());
+                if let Err(e) = page.execute(params).await {
+                    tracing::debug!("Failed to answer {}: {}", event.request.url, e);
+                }
+            }
+        }
+    });
+
+    let result = async {
+        for origin in &state.origins {
+            let script = format!(
+                "(state => {{\
+                   for (const {{ name, value }} of state.localStorage) localStorage.setItem(name, value);\
+                   for (const {{ name, value }} of state.sessionStorage || []) sessionStorage.setItem(name, value);\
+                 }})({})",
+                serde_json::to_string(origin)?
+            );
+            page.goto(origin.origin.as_str()).await?;
+            page.evaluate(script).await?;
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+    }
+    .await;
+
+    if let Err(e) = page.execute(DisableParams::default()).await {
+        tracing::debug!("Failed to disable request interception: {}", e);
+    }
+    responder.abort();
+    result?;
+    page.goto("about:blank").await?;
+    tracing::debug!(
+        "Imported {} cookies and storage of {} origins",
+        state.cookies.len(),
+        state.origins.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let text = "# Netscape HTTP Cookie File\n\
+                    # https://curl.se/docs/http-cookies.html\n\
+                    \n\
+                    .example.com\tTRUE\t/\tTRUE\t1893456000\tsid\tabc=123\n\
+                    #HttpOnly_login.example.com\tFALSE\t/account\tFALSE\t0\ttoken\t\r\n";
+        let cookies = parse_netscape(text).unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        let sid = &cookies[0];
+        assert_eq!((sid.domain.as_str(), sid.path.as_str()), (".example.com", "/"));
+        assert_eq!(sid.value, "abc=123");
+        assert!(sid.secure && !sid.http_only && !sid.is_session());
+        assert_eq!(sid.expires, 1893456000.0);
+
+        let token = &cookies[1];
+        assert_eq!(token.domain, "login.example.com");
+        assert_eq!(token.value, "");
+        assert!(token.http_only && token.is_session());
+
+        let written = to_netscape(&cookies);
+        assert!(written.starts_with(NETSCAPE_HEADER));
+        assert!(
+            written.contains("#HttpOnly_login.example.com\tFALSE\t/account\tFALSE\t0\ttoken\t\n")
+        );
+        assert_eq!(parse_netscape(&written).unwrap(), cookies);
+
+        let err = parse_netscape("example.com\tMAYBE\t/\tFALSE\t0\ta\tb").unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+        assert!(parse_netscape("example.com\tTRUE\t/").is_err());
+    }
+
+    #[test]
+    fn test_playwright_storage_state() {
+        let json = r#"{
+            "cookies": [{
+                "name": "sid", "value": "abc", "domain": ".example.com", "path": "/",
+                "expires": -1, "httpOnly": true, "secure": true, "sameSite": "Strict"
+            }, {
+                "name": "old", "value": "x", "domain": "example.com", "path": "/",
+                "expires": 1000, "httpOnly": false, "secure": false, "sameSite": "None"
+            }],
+            "origins": [{
+                "origin": "https://example.com",
+                "localStorage": [{ "name": "theme", "value": "dark" }]
+            }]
+        }"#;
+        let mut state: StorageState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.cookies[0].same_site, SameSite::Strict);
+        assert!(state.cookies[0].http_only && state.cookies[0].is_session());
+        let origin = state.origin("https://example.com").unwrap();
+        assert_eq!(origin.local_storage[0].value, "dark");
+        assert!(origin.session_storage.is_empty());
+
+        // Written back in the same shape, without the sessionStorage extension
+        let value = serde_json::to_value(&state).unwrap();
+        assert_eq!(value["cookies"][0]["httpOnly"], true);
+        assert_eq!(value["cookies"][1]["sameSite"], "None");
+        assert!(value["origins"][0].get("sessionStorage").is_none());
+
+        let sid = &state.cookies[0];
+        assert!(sid.matches_host("example.com") && sid.matches_host("www.Example.com"));
+        assert!(!sid.matches_host("notexample.com"));
+        let old = &state.cookies[1];
+        assert!(old.matches_host("example.com") && !old.matches_host("www.example.com"));
+
+        state.remove_expired(unix_now());
+        assert_eq!(state.cookies.len(), 1);
+        assert_eq!(state.cookies[0].name, "sid");
+    }
+}
@@ -0,0 +1,491 @@
+//! HTML table extraction
+//!
+//! Builds the cell grid of each `<table>` following the HTML table model
+//! (`rowspan`/`colspan`, `rowspan="0"`), resolves column names from `<thead>`
+//! or leading rows of column header cells (multi-row headers are joined with
+//! " / "), marks columns of row headers, and infers a datatype per column so rows can be returned as typed JSON
+//! records or CSV. Tables marked `role="presentation"` are skipped.
+
+use crate::external::normalize_whitespace;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Upper bounds from the HTML table processing model
+const MAX_COLSPAN: usize = 1000;
+const MAX_ROWSPAN: usize = 65534;
+
+/// Datatype inferred for a column from its non-empty cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Integer,
+    Decimal,
+    Boolean,
+    String,
+}
+
+impl ColumnType {
+    /// XSD datatype IRI used when the column is mapped to RDF
+    pub fn xsd_datatype(&self) -> Option<&'static str> {
+        match self {
+            ColumnType::Integer => Some("http://www.w3.org/2001/XMLSchema#integer"),
+            ColumnType::Decimal => Some("http://www.w3.org/2001/XMLSchema#decimal"),
+            ColumnType::Boolean => Some("http://www.w3.org/2001/XMLSchema#boolean"),
+            ColumnType::String => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub datatype: ColumnType,
+    /// The body cells of this column are row headers (`<th scope="row">`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub row_header: bool,
+}
+
+/// A data table with typed rows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    /// `<caption>` text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// `id` attribute of the `<table>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub columns: Vec<Column>,
+    /// One value per column: numbers, booleans, strings, or `null` for empty cells
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Rows as JSON objects keyed by column name
+    pub fn records(&self) -> Vec<Map<String, Value>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                self.columns.iter().map(|c| c.name.clone()).zip(row.iter().cloned()).collect()
+            })
+            .collect()
+    }
+
+    /// RFC 4180 CSV with a header line
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| csv_field(&c.name)).collect();
+        csv.push_str(&header.join(","));
+        csv.push_str("\r\n");
+        for row in &self.rows {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    Value::String(s) => csv_field(s),
+                    other => csv_field(&other.to_string()),
+                })
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+}
+
+/// Extract every data table in the document
+pub fn extract_tables(document: &Html) -> Vec<Table> {
+    extract_tables_in(document.root_element())
+}
+
+/// Extract the data tables inside `root` (including `root` itself if it is a table)
+pub fn extract_tables_in(root: ElementRef<'_>) -> Vec<Table> {
+    let Ok(selector) = Selector::parse("table") else {
+        return Vec::new();
+    };
+    let own = (root.value().name() == "table").then_some(root);
+    own.into_iter().chain(root.select(&selector)).filter_map(table).collect()
+}
+
+#[derive(Clone)]
+struct Cell {
+    text: String,
+    header: bool,
+    /// `scope` of a `<th>`; `None` is the `auto` state
+    scope: Option<Scope>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Row,
+    Col,
+    RowGroup,
+    ColGroup,
+}
+
+impl Cell {
+    /// Header for the cells below it: a `<th>` not scoped to its row
+    fn column_header(&self) -> bool {
+        self.header && !matches!(self.scope, Some(Scope::Row | Scope::RowGroup))
+    }
+
+    /// Header for the cells beside it: a `<th>` not scoped to its column
+    fn row_header(&self) -> bool {
+        self.header && !matches!(self.scope, Some(Scope::Col | Scope::ColGroup))
+    }
+}
+
+fn table(element: ElementRef<'_>) -> Option<Table> {
+    if matches!(element.value().attr("role"), Some("presentation" | "none")) {
+        return None;
+    }
+
+    let rows = own_rows(element);
+    let (grid, thead_rows) = build_grid(&rows);
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return None;
+    }
+
+    // Header rows: the <thead>, otherwise leading rows of column headers
+    let header_rows = if thead_rows > 0 {
+        thead_rows
+    } else {
+        grid.iter().take_while(|row| is_header_row(row)).count()
+    };
+
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for column in 0..width {
+        let mut parts: Vec<&str> = Vec::new();
+        for row in &grid[..header_rows] {
+            if let Some(Some(cell)) = row.get(column) {
+                if !cell.text.is_empty() && parts.last() != Some(&cell.text.as_str()) {
+                    parts.push(&cell.text);
+                }
+            }
+        }
+        let name = if parts.is_empty() {
+            format!("column_{}", column + 1)
+        } else {
+            parts.join(" / ")
+        };
+        let mut unique = name.clone();
+        let mut n = 2;
+        while names.contains(&unique) {
+            unique = format!("{}_{}", name, n);
+            n += 1;
+        }
+        names.push(unique);
+    }
+
+    let body: Vec<&Vec<Option<Cell>>> = grid[header_rows..]
+        .iter()
+        .filter(|row| row.iter().flatten().any(|cell| !cell.text.is_empty()))
+        .collect();
+    let texts: Vec<Vec<String>> = body
+        .iter()
+        .map(|row| {
+            (0..width)
+                .map(|c| row.get(c).cloned().flatten().map(|cell| cell.text).unwrap_or_default())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    // Row header columns: every body cell in them is a row header
+    let row_header = |c: usize| {
+        let mut cells =
+            body.iter().filter_map(|row| row.get(c).and_then(Option::as_ref)).peekable();
+        cells.peek().is_some() && cells.all(Cell::row_header)
+    };
+
+    let columns: Vec<Column> = names
+        .into_iter()
+        .enumerate()
+        .map(|(c, name)| Column {
+            name,
+            datatype: infer_type(texts.iter().map(|row| &row[c])),
+            row_header: row_header(c),
+        })
+        .collect();
+    let rows = texts
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(&columns)
+                .map(|(text, column)| typed_value(text, column.datatype))
+                .collect()
+        })
+        .collect();
+
+    let caption = Selector::parse("caption")
+        .ok()
+        .and_then(|s| element.select(&s).find(|c| nearest_table(*c) == Some(element)))
+        .map(|c| normalize_whitespace(&c.text().collect::<String>()))
+        .filter(|c| !c.is_empty());
+
+    Some(Table { caption, id: element.value().id().map(str::to_string), columns, rows })
+}
+
+/// A row of column headers, possibly after an empty corner `<td>`
+fn is_header_row(row: &[Option<Cell>]) -> bool {
+    let mut cells =
+        row.iter().skip_while(|cell| cell.as_ref().is_some_and(|c| !c.header && c.text.is_empty()));
+    let mut any = false;
+    cells.all(|cell| {
+        any = true;
+        cell.as_ref().is_some_and(Cell::column_header)
+    }) && any
+}
+
+/// `<tr>` elements of this table (not of nested tables), in document order
+fn own_rows<'a>(table: ElementRef<'a>) -> Vec<ElementRef<'a>> {
+    let Ok(selector) = Selector::parse("tr") else {
+        return Vec::new();
+    };
+    table.select(&selector).filter(|row| nearest_table(*row) == Some(table)).collect()
+}
+
+fn nearest_table(element: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    element.ancestors().filter_map(ElementRef::wrap).find(|a| a.value().name() == "table")
+}
+
+/// Lay cells out on a grid, expanding spans; also returns the number of `<thead>` rows
+fn build_grid(rows: &[ElementRef<'_>]) -> (Vec<Vec<Option<Cell>>>, usize) {
+    let mut grid: Vec<Vec<Option<Cell>>> = vec![Vec::new(); rows.len()];
+    let section = |row: &ElementRef<'_>| {
+        row.parent().and_then(ElementRef::wrap).map(|p| p.value().name().to_string())
+    };
+    let thead_rows = rows.iter().take_while(|row| section(row).as_deref() == Some("thead")).count();
+
+    for (r, row) in rows.iter().enumerate() {
+        // Rows left in the same row group, for rowspan="0"
+        let group_end = rows[r..]
+            .iter()
+            .position(|other| other.parent().map(|p| p.id()) != row.parent().map(|p| p.id()))
+            .map_or(rows.len(), |offset| r + offset);
+
+        let mut column = 0;
+        for cell in row.children().filter_map(ElementRef::wrap) {
+            let name = cell.value().name();
+            if name != "td" && name != "th" {
+                continue;
+            }
+            while grid[r].get(column).is_some_and(Option::is_some) {
+                column += 1;
+            }
+            let span =
+                |attr: &str| cell.value().attr(attr).and_then(|v| v.trim().parse::<usize>().ok());
+            let colspan = span("colspan").unwrap_or(1).clamp(1, MAX_COLSPAN);
+            let rowspan = match span("rowspan") {
+                Some(0) => group_end - r,
+                Some(n) => n.min(MAX_ROWSPAN),
+                None => 1,
+            }
+            .max(1);
+
+            let scope = match cell.value().attr("scope").map(|s| s.trim().to_ascii_lowercase()) {
+                Some(s) if s == "row" => Some(Scope::Row),
+                Some(s) if s == "col" => Some(Scope::Col),
+                Some(s) if s == "rowgroup" => Some(Scope::RowGroup),
+                Some(s) if s == "colgroup" => Some(Scope::ColGroup),
+                _ => None,
+            };
+            let value = Cell {
+                text: normalize_whitespace(&cell.text().collect::<Vec<_>>().join(" ")),
+                header: name == "th",
+                scope,
+            };
+            for grid_row in grid.iter_mut().skip(r).take(rowspan) {
+                if grid_row.len() < column + colspan {
+                    grid_row.resize(column + colspan, None);
+                }
+                for slot in &mut grid_row[column..column + colspan] {
+                    *slot = Some(value.clone());
+                }
+            }
+            column += colspan;
+        }
+    }
+
+    (grid, thead_rows)
+}
+
+fn infer_type<'a>(values: impl Iterator<Item = &'a String>) -> ColumnType {
+    let mut datatype: Option<ColumnType> = None;
+    for value in values.filter(|v| !v.is_empty()) {
+        let cell_type = if parse_boolean(value).is_some() {
+            ColumnType::Boolean
+        } else {
+            match parse_number(value) {
+                Some(Number::Integer(_)) => ColumnType::Integer,
+                Some(Number::Decimal(_)) => ColumnType::Decimal,
+                None => return ColumnType::String,
+            }
+        };
+        datatype = Some(match (datatype, cell_type) {
+            (None, t) => t,
+            (Some(a), b) if a == b => a,
+            (Some(ColumnType::Integer), ColumnType::Decimal)
+            | (Some(ColumnType::Decimal), ColumnType::Integer) => ColumnType::Decimal,
+            _ => return ColumnType::String,
+        });
+    }
+    datatype.unwrap_or(ColumnType::String)
+}
+
+fn typed_value(text: &str, datatype: ColumnType) -> Value {
+    if text.is_empty() {
+        return Value::Null;
+    }
+    match datatype {
+        ColumnType::Boolean => parse_boolean(text).map(Value::Bool),
+        ColumnType::Integer | ColumnType::Decimal => match parse_number(text) {
+            Some(Number::Integer(n)) if datatype == ColumnType::Integer => Some(Value::from(n)),
+            Some(Number::Integer(n)) => serde_json::Number::from_f64(n as f64).map(Value::Number),
+            Some(Number::Decimal(f)) => serde_json::Number::from_f64(f).map(Value::Number),
+            None => None,
+        },
+        ColumnType::String => None,
+    }
+    .unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+fn parse_boolean(text: &str) -> Option<bool> {
+    match text.to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+enum Number {
+    Integer(i64),
+    Decimal(f64),
+}
+
+/// Parse `1234`, `-1,234,567`, `3.14` or `.5` (thousands separators must be well formed)
+fn parse_number(text: &str) -> Option<Number> {
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (int_part, frac_part) = match unsigned.split_once('.') {
+        Some((int_part, frac)) => (int_part, Some(frac)),
+        None => (unsigned, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    let int_ok = if int_part.contains(',') {
+        let mut groups = int_part.split(',');
+        let first = groups.next().unwrap_or("");
+        digits(first) && first.len() <= 3 && groups.all(|g| g.len() == 3 && digits(g))
+    } else {
+        digits(int_part) || (int_part.is_empty() && frac_part.is_some())
+    };
+    if !int_ok || frac_part.is_some_and(|f| !digits(f)) {
+        return None;
+    }
+
+    let cleaned: String = text.chars().filter(|c| *c != ',' && *c != '+').collect();
+    match frac_part {
+        None => cleaned.parse().ok().map(Number::Integer),
+        Some(_) => cleaned.parse::<f64>().ok().filter(|f| f.is_finite()).map(Number::Decimal),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_spans_and_multi_row_headers() {
+        let html = r#"<table id="sales">
+            <caption>Quarterly sales</caption>
+            <thead>
+              <tr><th rowspan="2">Region</th><th colspan="2">2024</th></tr>
+              <tr><th>Units</th><th>Revenue</th></tr>
+            </thead>
+            <tbody>
+              <tr><th scope="row" rowspan="2">North</th><td>1,200</td><td>10.5</td></tr>
+              <tr><td>800</td><td>7</td></tr>
+              <tr><td>South</td><td></td><td>3.25</td></tr>
+            </tbody>
+            </table>"#;
+        let tables = extract_tables(&Html::parse_fragment(html));
+
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!(table.caption.as_deref(), Some("Quarterly sales"));
+        assert_eq!(table.id.as_deref(), Some("sales"));
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Region", "2024 / Units", "2024 / Revenue"]);
+        let types: Vec<ColumnType> = table.columns.iter().map(|c| c.datatype).collect();
+        assert_eq!(types, [ColumnType::String, ColumnType::Integer, ColumnType::Decimal]);
+
+        assert_eq!(table.rows[0], vec![json!("North"), json!(1200), json!(10.5)]);
+        assert_eq!(table.rows[1], vec![json!("North"), json!(800), json!(7.0)]);
+        assert_eq!(table.rows[2], vec![json!("South"), Value::Null, json!(3.25)]);
+        assert_eq!(table.records()[1]["2024 / Units"], json!(800));
+        // "South" is a <td>, so the first column is not all row headers
+        assert!(table.columns.iter().all(|c| !c.row_header));
+    }
+
+    #[test]
+    fn test_header_scope_and_corner_cell() {
+        let html = r#"<table>
+            <tr><td></td><th scope="col">Mon</th><th scope="col">Tue</th></tr>
+            <tr><th scope="row">Open</th><td>9</td><td>10</td></tr>
+            <tr><th scope="row">Close</th><td>17</td><td>18</td></tr>
+            </table>
+            <table>
+            <tr><th scope="row">Name</th><th>Ada</th></tr>
+            <tr><th scope="row">Born</th><td>1815</td></tr>
+            </table>"#;
+        let tables = extract_tables(&Html::parse_fragment(html));
+
+        assert_eq!(tables.len(), 2);
+        let hours = &tables[0];
+        let names: Vec<&str> = hours.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["column_1", "Mon", "Tue"]);
+        assert!(hours.columns[0].row_header);
+        assert!(!hours.columns[1].row_header);
+        assert_eq!(hours.rows[0], vec![json!("Open"), json!(9), json!(10)]);
+        assert_eq!(hours.rows.len(), 2);
+
+        // A row of <th scope="row"> cells is data, not a header row
+        let person = &tables[1];
+        assert_eq!(person.columns[0].name, "column_1");
+        assert!(person.columns[0].row_header);
+        assert_eq!(person.rows.len(), 2);
+        assert_eq!(person.rows[0], vec![json!("Name"), json!("Ada")]);
+    }
+
+    #[test]
+    fn test_headerless_nested_and_layout_tables() {
+        let html = r#"<table role="presentation"><tr><td>
+              <table><tr><td>a, "b"</td><td>yes</td></tr><tr><td>c</td><td>no</td></tr></table>
+            </td></tr></table>"#;
+        let tables = extract_tables(&Html::parse_fragment(html));
+
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!(table.columns[0].name, "column_1");
+        assert_eq!(table.columns[1].datatype, ColumnType::Boolean);
+        assert_eq!(table.to_csv(), "column_1,column_2\r\n\"a, \"\"b\"\"\",true\r\nc,false\r\n");
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert!(matches!(parse_number("-1,234,567"), Some(Number::Integer(-1234567))));
+        assert!(matches!(parse_number(".5"), Some(Number::Decimal(f)) if f == 0.5));
+        assert!(parse_number("1,23").is_none());
+        assert!(parse_number("12a").is_none());
+        assert!(parse_number("1.2.3").is_none());
+        assert!(parse_number("").is_none());
+    }
+}
@@ -43,7 +43,7 @@ fn core_pipeline_roundtrip() {
         };
 
         for (prop, values) in &item.properties {
-            for value in values {
+            for value in values.iter().filter_map(|v| v.as_str()) {
                 statements.push(format!(
                     "<{subject}> <http://schema.org/{prop}> \"{value}\" .",
                     subject = subject,
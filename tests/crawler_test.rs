@@ -0,0 +1,189 @@
+//! Crawler tests against a local axum fixture site
+//!
+//! The fixture server counts hits per path and tracks the number of requests
+//! in flight, so politeness limits and deduplication can be asserted.
+
+use axum::{extract::State, http::Uri, response::Html, routing::get, Router};
+use semantic_browser::crawler::{CrawlOptions, CrawlStatus, Crawler};
+use semantic_browser::kg::KnowledgeGraph;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[derive(Clone, Default)]
+struct Fixture {
+    hits: Arc<StdMutex<HashMap<String, usize>>>,
+    in_flight: Arc<AtomicUsize>,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl Fixture {
+    fn hits(&self, path: &str) -> usize {
+        self.hits.lock().unwrap().get(path).copied().unwrap_or(0)
+    }
+}
+
+fn page(title: &str, extra_head: &str, body: &str) -> String {
+    format!("<html><head><title>{}</title>{}</head><body>{}</body></html>", title, extra_head, body)
+}
+
+async fn serve(State(fixture): State<Fixture>, uri: Uri) -> (axum::http::StatusCode, Html<String>) {
+    let path = uri.path().to_string();
+    *fixture.hits.lock().unwrap().entry(path.clone()).or_default() += 1;
+    let now = fixture.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    fixture.max_in_flight.fetch_max(now, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    fixture.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+    let html = match path.as_str() {
+        "/" => page(
+            "Home",
+            "",
+            r##"<a href="/a">A</a> <a href="/b?utm_source=news">B</a> <a href="b">B again</a>
+               <a href="/a#top">A again</a> <a href="/private/secret">Secret</a>
+               <a href="https://other.example/">Elsewhere</a>
+               <a href="/sponsored" rel="sponsored nofollow">Ad</a>
+               <a href="mailto:someone@example.com">Mail</a>"##,
+        ),
+        "/a" => page("Page A", "", r#"<a href="/c">C</a> <a href="/">Home</a>"#),
+        "/b" => page(
+            "Page B",
+            r#"<meta name="robots" content="nofollow">"#,
+            r#"<a href="/hidden">Hidden</a>"#,
+        ),
+        "/c" => page("Page C", "", r#"<a href="/d">Too deep</a>"#),
+        "/d" | "/hidden" | "/sponsored" | "/private/secret" => page("Unreachable", "", ""),
+        _ => return (axum::http::StatusCode::NOT_FOUND, Html(String::new())),
+    };
+    (axum::http::StatusCode::OK, Html(html))
+}
+
+async fn start_fixture() -> (String, Fixture) {
+    let fixture = Fixture::default();
+    let app = Router::new()
+        .route("/robots.txt", get(|| async { "User-agent: *\nDisallow: /private/\n" }))
+        .fallback(serve)
+        .with_state(fixture.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}/", addr), fixture)
+}
+
+fn options() -> CrawlOptions {
+    CrawlOptions { delay: Duration::from_millis(20), ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_crawl_fixture_site() {
+    let (root, fixture) = start_fixture().await;
+    let kg = Arc::new(Mutex::new(KnowledgeGraph::new()));
+
+    let crawler = Crawler::new(&[root.as_str()], options()).unwrap();
+    let handle = crawler.handle();
+    let report = crawler.run(Arc::clone(&kg)).await.unwrap();
+
+    assert_eq!(report.status, CrawlStatus::Completed);
+    let mut crawled: Vec<&str> =
+        report.pages.iter().map(|p| p.url.trim_start_matches(root.as_str())).collect();
+    crawled.sort();
+    assert_eq!(crawled, vec!["", "a", "b", "c"]);
+    assert!(report.pages.iter().all(|p| p.is_success()));
+    assert_eq!(report.disallowed, 1);
+    assert_eq!(report.queued, 0);
+
+    // Deduplicated, polite and within depth, scope, robots.txt and nofollow limits
+    for path in ["/", "/a", "/b", "/c"] {
+        assert_eq!(fixture.hits(path), 1, "{} fetched once", path);
+    }
+    for path in ["/d", "/hidden", "/sponsored", "/private/secret"] {
+        assert_eq!(fixture.hits(path), 0, "{} never fetched", path);
+    }
+    assert_eq!(fixture.max_in_flight.load(Ordering::SeqCst), 1);
+
+    let progress = handle.progress();
+    assert_eq!(progress.status, CrawlStatus::Completed);
+    assert_eq!(progress.pages_crawled, 4);
+    assert!(progress.triples_inserted > 0);
+
+    let kg = kg.lock().await;
+    let titles = kg.query("SELECT ?t WHERE { ?page <http://purl.org/dc/terms/title> ?t }").unwrap();
+    assert_eq!(titles.len(), 4);
+    assert!(titles.iter().any(|t| t.contains("Page C")));
+}
+
+#[tokio::test]
+async fn test_crawl_resumes_from_state_file() {
+    let (root, fixture) = start_fixture().await;
+    let kg = Arc::new(Mutex::new(KnowledgeGraph::new()));
+    let state_path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join(format!("crawl-state-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&state_path);
+
+    let first = CrawlOptions { max_pages: 2, state_path: Some(state_path.clone()), ..options() };
+    let report = Crawler::new(&[root.as_str()], first).unwrap().run(Arc::clone(&kg)).await.unwrap();
+    assert_eq!(report.pages.len(), 2);
+    assert!(report.queued > 0);
+    assert!(state_path.exists());
+
+    let second = CrawlOptions { state_path: Some(state_path.clone()), ..options() };
+    let report =
+        Crawler::new(&[root.as_str()], second).unwrap().run(Arc::clone(&kg)).await.unwrap();
+    assert_eq!(report.status, CrawlStatus::Completed);
+    assert_eq!(report.pages.len(), 4);
+    assert_eq!(report.queued, 0);
+    for path in ["/", "/a", "/b", "/c"] {
+        assert_eq!(fixture.hits(path), 1, "{} not fetched again after resuming", path);
+    }
+
+    std::fs::remove_file(&state_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_crawl_checks_redirect_targets() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let app = Router::new()
+        .route("/robots.txt", get(|| async { "User-agent: *\nDisallow: /private/\n" }))
+        .route(
+            "/",
+            get(|| async {
+                Html(page("Home", "", r#"<a href="/moved">Moved</a> <a href="/away">Away</a>"#))
+            }),
+        )
+        .route("/moved", get(|| async { axum::response::Redirect::permanent("/private/page") }))
+        .route(
+            "/away",
+            get(move || async move {
+                axum::response::Redirect::temporary(&format!("http://localhost:{}/landing", port))
+            }),
+        )
+        .route("/private/page", get(|| async { Html(page("Secret", "", "")) }))
+        .route("/landing", get(|| async { Html(page("Landing", "", "")) }));
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let root = format!("http://127.0.0.1:{}/", port);
+    let kg = Arc::new(Mutex::new(KnowledgeGraph::new()));
+
+    let report =
+        Crawler::new(&[root.as_str()], options()).unwrap().run(Arc::clone(&kg)).await.unwrap();
+
+    assert_eq!(report.pages.len(), 3);
+    assert_eq!(report.disallowed, 1);
+    let error = |path: &str| {
+        let url = format!("{}{}", root, path);
+        report.pages.iter().find(|p| p.url == url).and_then(|p| p.error.clone()).unwrap_or_default()
+    };
+    assert!(error("moved").contains("robots.txt disallows"), "{}", error("moved"));
+    assert!(error("away").contains("out of the crawl scope"), "{}", error("away"));
+
+    let kg = kg.lock().await;
+    let titles = kg.query("SELECT ?t WHERE { ?page <http://purl.org/dc/terms/title> ?t }").unwrap();
+    assert_eq!(titles.len(), 1);
+    assert!(titles[0].contains("Home"));
+}
@@ -0,0 +1,137 @@
+//! Site discovery tests against the fixture site in `tests/fixtures/site`
+//!
+//! The fixture directory is served through `file://` URLs (opted into with
+//! `DiscoveryOptions::allow_file_urls`), so robots.txt, the sitemap index and
+//! the gzipped sitemap are read without a network. Sitemap scoping runs
+//! against a local axum server.
+
+use axum::{routing::get, Router};
+use semantic_browser::discovery::{DiscoveryOptions, Site};
+use std::time::Duration;
+
+fn fixture_site_url() -> String {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/site/");
+    url::Url::from_directory_path(dir).expect("absolute fixture path").to_string()
+}
+
+fn fixture_options() -> DiscoveryOptions {
+    DiscoveryOptions { allow_file_urls: true, ..Default::default() }
+}
+
+const PAGES_SITEMAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>/about</loc></url>
+</urlset>"#;
+
+const SITEMAP_INDEX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>file:///etc/hostname</loc></sitemap>
+  <sitemap><loc>https://other.example/sitemap.xml</loc></sitemap>
+  <sitemap><loc>/pages.xml</loc></sitemap>
+</sitemapindex>"#;
+
+/// Local site whose robots.txt and sitemap index point outside the site
+async fn start_server() -> String {
+    let app = Router::new()
+        .route(
+            "/robots.txt",
+            get(|| async {
+                "User-agent: *\nAllow: /\n\nSitemap: file:///etc/passwd\n\
+                 Sitemap: ftp://127.0.0.1/sitemap.xml\nSitemap: /sitemap.xml\n"
+            }),
+        )
+        .route("/sitemap.xml", get(|| async { SITEMAP_INDEX }))
+        .route("/pages.xml", get(|| async { PAGES_SITEMAP }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/", addr)
+}
+
+#[tokio::test]
+async fn test_discover_fixture_site() {
+    let site = Site::discover_with(&fixture_site_url(), fixture_options())
+        .await
+        .expect("discovery should succeed");
+
+    // Index, pages and gzipped posts; the missing sitemap is skipped
+    assert_eq!(site.sitemaps.len(), 3);
+    assert!(site.robots.sitemaps.iter().any(|s| s == "sitemap_index.xml"));
+    assert_eq!(site.crawl_delay(), Some(Duration::from_millis(1500)));
+
+    let urls: Vec<&str> = site.urls().map(|entry| entry.loc.as_str()).collect();
+    assert_eq!(
+        urls,
+        vec![
+            "https://site.example/",
+            "https://site.example/about",
+            "https://site.example/posts/hello",
+            "https://site.example/drafts/published-2025",
+        ]
+    );
+
+    let home = site.urls().next().unwrap();
+    assert_eq!(home.lastmod.as_deref(), Some("2025-05-01"));
+    assert_eq!(home.priority, Some(1.0));
+    let post = site.urls().find(|e| e.loc.ends_with("/posts/hello")).unwrap();
+    assert_eq!(post.lastmod.as_deref(), Some("2025-04-20T10:00:00+00:00"));
+
+    assert!(!site.is_allowed("https://site.example/drafts/secret"));
+}
+
+#[tokio::test]
+async fn test_discover_respects_agent_group_and_limits() {
+    let options = DiscoveryOptions {
+        user_agent: "SlowBot/1.0".to_string(),
+        max_sitemaps: 2,
+        ..fixture_options()
+    };
+    let site = Site::discover_with(&fixture_site_url(), options).await.unwrap();
+
+    // Index and pages sitemap only
+    assert_eq!(site.sitemaps.len(), 2);
+    assert_eq!(site.crawl_delay(), None);
+    assert_eq!(site.urls().count(), 0);
+}
+
+#[tokio::test]
+async fn test_fetch_robots_failure_disallows_everything() {
+    // Nothing listens on the reserved port, so the request itself fails
+    let client = reqwest::Client::new();
+    let robots_url = url::Url::parse("http://127.0.0.1:9/robots.txt").unwrap();
+    let robots = semantic_browser::discovery::fetch_robots(&client, &robots_url).await;
+
+    let page = url::Url::parse("http://127.0.0.1:9/index.html").unwrap();
+    assert!(!robots.is_url_allowed("SemanticBrowser", &page));
+
+    // A missing file still allows everything
+    let root = start_server().await;
+    let missing = url::Url::parse(&format!("{}no-such-dir/robots.txt", root)).unwrap();
+    let robots = semantic_browser::discovery::fetch_robots(&client, &missing).await;
+    assert!(robots.is_url_allowed("SemanticBrowser", &page));
+}
+
+#[tokio::test]
+async fn test_discover_skips_sitemaps_outside_the_site() {
+    let root = start_server().await;
+    let site = Site::discover(&root).await.unwrap();
+
+    // file://, ftp:// and other hosts are never fetched
+    assert!(site.robots.sitemaps.iter().any(|s| s == "file:///etc/passwd"));
+    assert_eq!(site.sitemaps, vec![format!("{}sitemap.xml", root), format!("{}pages.xml", root)]);
+    let urls: Vec<&str> = site.urls().map(|entry| entry.loc.as_str()).collect();
+    assert_eq!(urls, vec![format!("{}about", root)]);
+}
+
+#[tokio::test]
+async fn test_discover_requires_opt_in_for_file_urls() {
+    let err = Site::discover(&fixture_site_url()).await.unwrap_err();
+    assert!(err.to_string().contains("Unsupported URL scheme"), "{}", err);
+
+    // fetch_document never reads local files
+    let client = reqwest::Client::new();
+    let robots = url::Url::parse(&format!("{}robots.txt", fixture_site_url())).unwrap();
+    assert!(semantic_browser::external::fetch_document(&client, robots.as_str()).await.is_err());
+}
@@ -0,0 +1,178 @@
+//! Non-HTML document extraction: PDF, JSON, XML and plain text snapshots and
+//! their knowledge graph triples
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use semantic_browser::documents::DocumentKind;
+use semantic_browser::external::extract_document;
+use semantic_browser::kg::KnowledgeGraph;
+use semantic_browser::kg_integration::insert_snapshot_to_kg;
+use semantic_browser::pdf::parse_pdf;
+use std::io::Write;
+
+/// Build a two-page PDF with a compressed content stream and an Info dictionary
+fn sample_pdf() -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(
+            b"BT /F1 18 Tf 72 720 Td (Quarterly report) Tj 0 -24 Td (Revenue grew by 12%.) Tj ET",
+        )
+        .unwrap();
+    let page_one = encoder.finish().unwrap();
+    let page_two = b"BT /F1 12 Tf 72 720 Td (Appendix) Tj ET".to_vec();
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 /Resources << /Font << /F1 5 0 R >> >> >>"
+            .to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Contents 6 0 R >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Contents 7 0 R >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+    ];
+    for (content, filter) in [(page_one, " /Filter /FlateDecode"), (page_two, "")] {
+        let mut object =
+            format!("<< /Length {}{} >>\nstream\n", content.len(), filter).into_bytes();
+        object.extend_from_slice(&content);
+        object.extend_from_slice(b"\nendstream");
+        objects.push(object);
+    }
+    objects.push(
+        b"<< /Title (Q3 Report) /Author (Ada Lovelace; Charles Babbage) /Keywords (finance, q3) \
+          /Producer (Handmade) /CreationDate (D:20240131120530Z) >>"
+            .to_vec(),
+    );
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            objects.len(),
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+#[test]
+fn test_parse_pdf_pages_and_info() {
+    let pdf = parse_pdf(&sample_pdf()).unwrap();
+    assert_eq!(pdf.version.as_deref(), Some("1.4"));
+    assert!(!pdf.encrypted);
+    assert_eq!(pdf.pages, vec!["Quarterly report\nRevenue grew by 12%.", "Appendix"]);
+    assert_eq!(pdf.info.title.as_deref(), Some("Q3 Report"));
+    assert_eq!(pdf.info.author.as_deref(), Some("Ada Lovelace; Charles Babbage"));
+    assert_eq!(pdf.info.created.as_deref(), Some("2024-01-31T12:05:30+00:00"));
+
+    assert!(parse_pdf(b"%PDF-1.7\nnot really a pdf").is_err());
+}
+
+#[test]
+fn test_pdf_snapshot_and_kg_triples() {
+    let url = "https://example.com/reports/q3.pdf";
+    let snapshot =
+        extract_document(&sample_pdf(), Some("application/pdf"), url.to_string(), "revenue")
+            .unwrap();
+
+    assert_eq!(snapshot.title.as_deref(), Some("Q3 Report"));
+    assert_eq!(snapshot.keywords, vec!["finance", "q3"]);
+    assert!(snapshot.markdown.starts_with("# Q3 Report\n\nQuarterly report"));
+    assert!(!snapshot.query_matches.is_empty());
+    let document = snapshot.document.as_ref().unwrap();
+    assert_eq!(document.kind, DocumentKind::Pdf);
+    assert_eq!(document.pages, Some(2));
+    assert_eq!(document.authors, vec!["Ada Lovelace", "Charles Babbage"]);
+    assert_eq!(document.generator.as_deref(), Some("Handmade"));
+
+    let mut kg = KnowledgeGraph::new();
+    insert_snapshot_to_kg(&snapshot, &mut kg, url, None).unwrap();
+    let results = kg
+        .query(&format!(
+            "SELECT ?format ?creator ?pages ?created WHERE {{
+               <{url}> <http://purl.org/dc/terms/format> ?format ;
+                       <http://purl.org/dc/terms/creator> ?creator ;
+                       <https://schema.org/numberOfPages> ?pages ;
+                       <http://purl.org/dc/terms/created> ?created .
+             }}"
+        ))
+        .unwrap();
+    assert_eq!(results.len(), 2, "one row per creator: {:?}", results);
+    assert!(results.iter().all(|r| r.contains("String(\"application/pdf\")")));
+    assert!(results.iter().any(|r| r.contains("Charles Babbage")));
+    assert!(results.iter().all(|r| r.contains("2024-01-31T12:05:30Z")));
+    assert!(results.iter().all(|r| r.contains("value: \"2\"") && r.contains("XMLSchema#integer")));
+}
+
+#[test]
+fn test_json_xml_and_text_snapshots() {
+    // Untyped JSON-LD is sniffed and its graph ends up in the KG
+    let json =
+        br#"{"@context": "https://schema.org", "@type": "Dataset", "name": "Rainfall 2024"}"#;
+    let url = "https://example.com/data/rainfall.json";
+    let snapshot = extract_document(json, None, url.to_string(), "").unwrap();
+    assert_eq!(snapshot.title.as_deref(), Some("Rainfall 2024"));
+    assert_eq!(snapshot.json_ld_count, 1);
+    assert!(snapshot.markdown.starts_with("```json\n"));
+    let document = snapshot.document.as_ref().unwrap();
+    assert_eq!(
+        (document.kind, document.media_type.as_str()),
+        (DocumentKind::Json, "application/json")
+    );
+
+    let mut kg = KnowledgeGraph::new();
+    insert_snapshot_to_kg(&snapshot, &mut kg, url, None).unwrap();
+    let datasets = kg.query("SELECT ?d WHERE { ?d a <https://schema.org/Dataset> }").unwrap();
+    assert_eq!(datasets.len(), 1);
+
+    // Legacy-encoded XML is decoded from its declaration
+    let xml = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><note><title>Caf\xE9</title><body>Ciao</body></note>";
+    let snapshot =
+        extract_document(xml, Some("text/xml"), "https://example.com/note.xml".into(), "").unwrap();
+    assert_eq!(snapshot.title.as_deref(), Some("Café"));
+    assert_eq!(snapshot.encoding.as_deref(), Some("windows-1252"));
+    assert_eq!(snapshot.document.unwrap().kind, DocumentKind::Xml);
+
+    // Feeds served as XML still get a feed snapshot
+    let rss =
+        br#"<?xml version="1.0"?><rss version="2.0"><channel><title>News</title></channel></rss>"#;
+    let snapshot =
+        extract_document(rss, Some("application/xml"), "https://example.com/rss".into(), "")
+            .unwrap();
+    assert!(snapshot.feed.is_some());
+    assert!(snapshot.document.is_none());
+
+    let text = b"README\n\nInstall with cargo.\n";
+    let snapshot = extract_document(
+        text,
+        Some("text/plain; charset=utf-8"),
+        "https://example.com/README".into(),
+        "cargo",
+    )
+    .unwrap();
+    assert_eq!(snapshot.title.as_deref(), Some("README"));
+    assert_eq!(snapshot.document.unwrap().media_type, "text/plain");
+    assert_eq!(snapshot.query_matches.len(), 1);
+
+    // Binary documents only record their media type
+    let snapshot =
+        extract_document(b"\x89PNG\r\n", Some("image/png"), "https://example.com/a.png".into(), "")
+            .unwrap();
+    assert_eq!(snapshot.text_length, 0);
+    assert_eq!(snapshot.document.unwrap().kind, DocumentKind::Binary);
+}
@@ -44,6 +44,38 @@ fn test_html_parsing_integration() {
     assert!(product.item_type.contains("Product"));
 }
 
+#[test]
+fn test_rdfa_parsing_to_kg_integration() {
+    let html = r#"
+        <html>
+        <head><title>Catalogue</title></head>
+        <body vocab="https://schema.org/">
+            <div typeof="Book" resource="/items/42">
+                <span property="name">Il nome della rosa</span>
+                <span property="author" typeof="Person"><span property="name">Umberto Eco</span></span>
+            </div>
+        </body>
+        </html>
+    "#;
+
+    let data = parser::parse_html_with_base(html, Some("https://library.example/catalogue"))
+        .expect("Failed to parse HTML");
+    assert_eq!(data.rdfa.len(), 5);
+
+    let mut kg = kg::KnowledgeGraph::new();
+    assert_eq!(kg.insert_triples(&data.rdfa).unwrap(), 5);
+
+    let results = kg
+        .query(
+            "SELECT ?author WHERE { \
+             <https://library.example/items/42> a <https://schema.org/Book> ; \
+               <https://schema.org/author>/<https://schema.org/name> ?author }",
+        )
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].contains("Umberto Eco"));
+}
+
 #[test]
 fn test_entity_extraction_integration() {
     let html = r#"
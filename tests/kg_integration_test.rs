@@ -165,8 +165,16 @@ fn test_metadata_count_insertion() {
         title: Some("Test".to_string()),
         json_ld: vec![serde_json::json!({"@type": "Thing"})],
         microdata: vec![],
+        rdfa: vec![],
+        microformats: Default::default(),
+        links: Vec::new(),
+        article: None,
+        markdown: String::new(),
         text_content: String::new(),
         screenshot: None,
+        pdf: None,
+        har: None,
+        storage_state: None,
         final_url: "https://example.com".to_string(),
         meta_description: None,
         meta_keywords: vec![],
@@ -174,6 +182,7 @@ fn test_metadata_count_insertion() {
         canonical_url: None,
         open_graph: HashMap::new(),
         twitter_card: HashMap::new(),
+        citation: None,
     };
 
     let mut kg = KnowledgeGraph::new();
@@ -201,8 +210,16 @@ fn test_og_twitter_insertion() {
         title: Some("Test".to_string()),
         json_ld: vec![],
         microdata: vec![],
+        rdfa: vec![],
+        microformats: Default::default(),
+        links: Vec::new(),
+        article: None,
+        markdown: String::new(),
         text_content: String::new(),
         screenshot: None,
+        pdf: None,
+        har: None,
+        storage_state: None,
         final_url: "https://example.com".to_string(),
         meta_description: None,
         meta_keywords: vec![],
@@ -210,6 +227,7 @@ fn test_og_twitter_insertion() {
         canonical_url: None,
         open_graph: og,
         twitter_card: twitter,
+        citation: None,
     };
 
     let mut kg = KnowledgeGraph::new();
@@ -227,3 +245,87 @@ fn test_og_twitter_insertion() {
     assert!(has_og, "Should have Open Graph triples");
     assert!(has_twitter, "Should have Twitter Card triples");
 }
+
+/// Test JSON-LD blocks from parsed HTML become queryable schema.org triples
+#[test]
+fn test_json_ld_snapshot_to_sparql() {
+    use semantic_browser::kg_integration::insert_snapshot_to_kg;
+    use semantic_browser::models::SemanticSnapshot;
+
+    let html = r##"<html><head><title>Event</title>
+        <script type="application/ld+json">
+        {"@context": "http://schema.org", "@type": "Event", "@id": "#event",
+         "name": "RustConf", "startDate": "2025-09-02",
+         "location": {"@type": "Place", "name": "Seattle"}}
+        </script></head><body></body></html>"##;
+    let data = semantic_browser::parser::parse_html(html).expect("Failed to parse HTML");
+
+    let snapshot = SemanticSnapshot {
+        title: data.title.clone(),
+        description: None,
+        language: None,
+        detected_language: None,
+        canonical_url: None,
+        final_url: "https://conf.example/2025".to_string(),
+        keywords: vec![],
+        open_graph: Default::default(),
+        twitter_card: Default::default(),
+        json_ld_count: data.json_ld.len(),
+        json_ld: data.json_ld.clone(),
+        microdata: vec![],
+        rdfa: vec![],
+        microformats: Default::default(),
+        links: Vec::new(),
+        feed: None,
+        document: None,
+        article: None,
+        citation: None,
+        markdown: String::new(),
+        microdata_items: vec![],
+        text_preview: String::new(),
+        text_length: 0,
+        encoding: None,
+        query_matches: vec![],
+    };
+
+    let mut kg = KnowledgeGraph::new();
+    insert_snapshot_to_kg(&snapshot, &mut kg, "https://conf.example/2025", None)
+        .expect("Failed to insert");
+
+    let query = "SELECT ?place WHERE { \
+        <https://conf.example/2025> <https://schema.org/mainEntity> <https://conf.example/2025#event> . \
+        <https://conf.example/2025#event> a <https://schema.org/Event> ; \
+            <https://schema.org/location> ?l . \
+        ?l <https://schema.org/name> ?place }";
+    let results = kg.query(query).expect("Failed to query");
+    assert_eq!(results.len(), 1);
+    assert!(results[0].contains("Seattle"));
+}
+
+/// Pages without `<html lang>` get literals tagged with the detected language
+#[test]
+fn test_detected_language_tags_literals() {
+    use semantic_browser::external::extract_document;
+    use semantic_browser::kg_integration::insert_snapshot_to_kg;
+
+    let html = "<html><head><title>Neue Maßnahmen beschlossen</title></head><body><p>\
+                Die Bundesregierung hat am Mittwoch neue Maßnahmen beschlossen, um die \
+                Energiepreise für Haushalte und Unternehmen zu senken. Die Opposition \
+                kritisierte den Entwurf als unzureichend und forderte weitere Schritte.\
+                </p></body></html>";
+    let url = "https://news.example/artikel";
+    let snapshot =
+        extract_document(html.as_bytes(), Some("text/html"), url.to_string(), "").unwrap();
+    assert_eq!(snapshot.language, None);
+    assert_eq!(snapshot.detected_language.as_ref().map(|d| d.code.as_str()), Some("de"));
+    assert_eq!(snapshot.effective_language().as_deref(), Some("de"));
+
+    let mut kg = KnowledgeGraph::new();
+    insert_snapshot_to_kg(&snapshot, &mut kg, url, None).expect("Failed to insert");
+    let query = format!(
+        "SELECT ?title WHERE {{ <{url}> <http://purl.org/dc/terms/title> ?title ; \
+         <http://purl.org/dc/terms/language> \"de\" . FILTER(lang(?title) = \"de\") }}"
+    );
+    let results = kg.query(&query).expect("Failed to query");
+    assert_eq!(results.len(), 1, "{:?}", results);
+}
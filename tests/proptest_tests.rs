@@ -400,6 +400,10 @@ proptest! {
             timeout_secs,
             pool_size,
             user_data_dir: Some(format!("/tmp/semantic-browser-proptest-{}", pool_size)),
+            pool_max_waiting: pool_size * 8,
+            pool_acquire_timeout_secs: timeout_secs,
+            session_idle_timeout_secs: timeout_secs * 10,
+            emulation_profiles_path: None,
         };
 
         // Serialize
@@ -0,0 +1,85 @@
+//! Offline browse → extract → KG tests driven by network cassettes
+//!
+//! The first test replays a checked-in cassette; the second records one against
+//! a local axum fixture server and then replays it without touching the server.
+
+use axum::{extract::State, response::Html, routing::get, Router};
+use semantic_browser::external::browse_over_http;
+use semantic_browser::kg::KnowledgeGraph;
+use semantic_browser::kg_integration::insert_snapshot_to_kg;
+use semantic_browser::replay::{MatchMode, Replay, ReplayMode};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn cassette(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cassettes").join(name)
+}
+
+#[tokio::test]
+async fn test_replay_cassette_into_kg() {
+    let replay =
+        Replay::open(cassette("conference.json"), ReplayMode::Replay, MatchMode::Strict).unwrap();
+
+    let url = "https://conf.example/2025";
+    let outcome = browse_over_http(url, "seattle", Some(&replay)).await.unwrap();
+    assert_eq!(outcome.snapshot.title.as_deref(), Some("RustConf 2025"));
+    assert_eq!(outcome.snapshot.final_url, url);
+
+    let mut kg = KnowledgeGraph::new();
+    let count = insert_snapshot_to_kg(&outcome.snapshot, &mut kg, url, Some("seattle")).unwrap();
+    assert!(count > 0);
+    let results = kg
+        .query(r#"SELECT ?s ?p WHERE { ?s ?p ?o FILTER(CONTAINS(STR(?o), "Seattle")) }"#)
+        .unwrap();
+    assert!(!results.is_empty(), "JSON-LD location should reach the graph");
+
+    // Replay mode never falls through to the network
+    let err = browse_over_http("https://conf.example/2024", "", Some(&replay)).await.unwrap_err();
+    assert!(err.to_string().contains("No recorded response"), "{}", err);
+    // Strict matching keys on the exact URL
+    assert!(browse_over_http("https://conf.example/2025/", "", Some(&replay)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_record_then_replay_offline() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route(
+            "/talks",
+            get(|State(hits): State<Arc<AtomicUsize>>| async move {
+                hits.fetch_add(1, Ordering::SeqCst);
+                Html(
+                    "<html lang=\"en\"><head><title>Talks</title>\
+                     <meta name=\"description\" content=\"Schedule of talks\"></head>\
+                     <body><h1>Talks</h1><p>Zero-cost async in practice.</p></body></html>",
+                )
+            }),
+        )
+        .with_state(Arc::clone(&hits));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("replay_test_talks.json");
+    let _ = std::fs::remove_file(&path);
+
+    let url = format!("http://{}/talks?day=2&track=async", addr);
+    let recorder = Replay::open(&path, ReplayMode::Record, MatchMode::Strict).unwrap();
+    let recorded = browse_over_http(&url, "async", Some(&recorder)).await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    assert_eq!(recorder.cassette().interactions.len(), 1);
+    assert!(path.exists(), "recording is written as it happens");
+
+    // Lenient matching ignores query parameter order and a trailing slash
+    let replay = Replay::open(&path, ReplayMode::Replay, MatchMode::Lenient).unwrap();
+    let variant = format!("http://{}/talks/?track=async&day=2", addr);
+    let replayed = browse_over_http(&variant, "async", Some(&replay)).await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 1, "replay must not hit the server");
+    assert_eq!(replayed.snapshot.title.as_deref(), Some("Talks"));
+    assert_eq!(replayed.snapshot.title, recorded.snapshot.title);
+    assert_eq!(replayed.snapshot.description, recorded.snapshot.description);
+    assert_eq!(replayed.snapshot.text_preview, recorded.snapshot.text_preview);
+}
@@ -21,6 +21,7 @@ async fn test_rate_limiting_concurrent() {
     let state = AppState {
         kg: Arc::new(Mutex::new(KnowledgeGraph::new())),
         rate_limits: Arc::new(Mutex::new(HashMap::new())),
+        crawls: Arc::new(Mutex::new(HashMap::new())),
     };
 
     let ip = "127.0.0.1";
@@ -49,6 +50,7 @@ async fn test_rate_limiting_time_window() {
     let state = AppState {
         kg: Arc::new(Mutex::new(KnowledgeGraph::new())),
         rate_limits: Arc::new(Mutex::new(HashMap::new())),
+        crawls: Arc::new(Mutex::new(HashMap::new())),
     };
 
     let ip = "127.0.0.2";